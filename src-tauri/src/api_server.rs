@@ -0,0 +1,398 @@
+use crate::db::{self, InventoryVoucher, InventoryVoucherFilters, Item, MasterListQuery, Site};
+use crate::encryption;
+use crate::lan;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{IpAddr, UdpSocket};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// JSON endpoints over the same operations as commands.rs, for tools that read balances or post
+// vouchers without the desktop UI. The server always works against the open company.
+const OPENAPI_DOCUMENT: &str = include_str!("openapi.json");
+// Big enough for the largest attachment a client uploads base64-encoded, plus the JSON around it
+const MAX_BODY_BYTES: u64 = (db::MAX_ATTACHMENT_BYTES as u64).div_ceil(3) * 4 + 1024 * 1024;
+// Query parameters named attr.<name> filter on custom attributes
+const ATTRIBUTE_PARAM_PREFIX: &str = "attr.";
+
+#[derive(Default)]
+pub struct ApiServer(Mutex<ServerState>);
+
+#[derive(Default)]
+struct ServerState {
+    running: Option<RunningServer>,
+    error: Option<String>,
+}
+
+struct RunningServer {
+    server: Arc<Server>,
+    address: String,
+    thread: JoinHandle<()>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiServerStatus {
+    pub is_running: bool,
+    pub address: Option<String>,
+    // Address other computers use, when the server listens on the network
+    pub lan_address: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Paging {
+    page: Option<i64>,
+    limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct StockBalanceQuery {
+    item_name: Option<String>,
+    site_id: Option<i64>,
+    category_id: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct MovementQuery {
+    item_id: Option<i64>,
+    site_id: Option<i64>,
+    voucher_type_id: Option<i64>,
+    category_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+}
+
+// Status code and JSON body (None for 204), or status code and error message
+type Reply = Result<(u16, Option<Value>), (u16, String)>;
+
+fn status_of(state: &ServerState) -> ApiServerStatus {
+    let address = state.running.as_ref().map(|r| r.address.clone());
+    let lan_address = address
+        .as_deref()
+        .and_then(|a| a.strip_prefix("0.0.0.0:"))
+        .and_then(|port| Some(format!("{}:{}", local_ip()?, port)));
+    ApiServerStatus {
+        is_running: state.running.is_some(),
+        address,
+        lan_address,
+        error: state.error.clone(),
+    }
+}
+
+// The address of the interface that would carry outside traffic. Connecting a UDP socket only
+// picks the route; nothing is sent.
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    Some(socket.local_addr().ok()?.ip()).filter(|ip| !ip.is_unspecified())
+}
+
+// Stop the server and start it again from the open company's settings. Called at startup and
+// whenever the settings, the open company or its lock change.
+pub fn restart(app: &AppHandle) -> ApiServerStatus {
+    let Some(state) = app.try_state::<ApiServer>() else {
+        return ApiServerStatus {
+            is_running: false,
+            address: None,
+            lan_address: None,
+            error: None,
+        };
+    };
+    let mut state = state.0.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(running) = &state.running {
+        // A client changed the settings; the server can only stop after answering it
+        if running.thread.thread().id() == std::thread::current().id() {
+            let app = app.clone();
+            std::thread::spawn(move || restart(&app));
+            return status_of(&state);
+        }
+    }
+    if let Some(running) = state.running.take() {
+        running.server.unblock();
+        let _ = running.thread.join();
+    }
+    state.error = None;
+
+    if encryption::is_locked(app) {
+        return status_of(&state);
+    }
+    match db::get_settings(app) {
+        Ok(settings) if settings.api_enabled => {
+            let host = if settings.api_allow_lan {
+                "0.0.0.0"
+            } else {
+                "127.0.0.1"
+            };
+            let address = format!("{}:{}", host, settings.api_port);
+            match Server::http(&address) {
+                Ok(server) => {
+                    let server = Arc::new(server);
+                    let thread = {
+                        let app = app.clone();
+                        let server = server.clone();
+                        std::thread::spawn(move || serve(&app, &server))
+                    };
+                    state.running = Some(RunningServer {
+                        server,
+                        address,
+                        thread,
+                    });
+                }
+                Err(e) => state.error = Some(format!("Could not listen on {}: {}", address, e)),
+            }
+        }
+        Ok(_) => {}
+        Err(e) => state.error = Some(e.to_string()),
+    }
+    status_of(&state)
+}
+
+// Requests are answered one at a time until the server is unblocked by restart
+fn serve(app: &AppHandle, server: &Server) {
+    let json_header = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    for mut request in server.incoming_requests() {
+        let response = match handle(app, &mut request) {
+            Ok((status, Some(body))) => Response::from_string(body.to_string())
+                .with_status_code(status)
+                .with_header(json_header.clone()),
+            Ok((status, None)) => Response::from_string("").with_status_code(status),
+            Err((status, message)) => {
+                Response::from_string(json!({ "error": message }).to_string())
+                    .with_status_code(status)
+                    .with_header(json_header.clone())
+            }
+        };
+        let _ = request.respond(response);
+    }
+}
+
+// ============================================================================
+// Request Helpers
+// ============================================================================
+
+fn ok<T: Serialize>(result: rusqlite::Result<T>) -> Reply {
+    let value = result.map_err(db_error)?;
+    let body = serde_json::to_value(value).map_err(|e| (500, e.to_string()))?;
+    Ok((200, Some(body)))
+}
+
+fn created(result: rusqlite::Result<i64>) -> Reply {
+    let id = result.map_err(db_error)?;
+    Ok((201, Some(json!({ "id": id }))))
+}
+
+fn no_content<T>(result: rusqlite::Result<T>) -> Reply {
+    result.map_err(db_error)?;
+    Ok((204, None))
+}
+
+fn db_error(e: rusqlite::Error) -> (u16, String) {
+    match e {
+        rusqlite::Error::QueryReturnedNoRows => (404, "Not found".to_string()),
+        e => (400, e.to_string()),
+    }
+}
+
+fn parse_id(segment: &str) -> Result<i64, (u16, String)> {
+    segment
+        .parse()
+        .map_err(|_| (404, format!("'{}' is not a valid id", segment)))
+}
+
+fn query<T: DeserializeOwned>(query: &str) -> Result<T, (u16, String)> {
+    serde_urlencoded::from_str(query).map_err(|e| (400, format!("Invalid query: {}", e)))
+}
+
+fn attribute_filters(query: &str) -> Result<Option<HashMap<String, String>>, (u16, String)> {
+    let pairs: Vec<(String, String)> = self::query(query)?;
+    let attributes: HashMap<String, String> = pairs
+        .into_iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(ATTRIBUTE_PARAM_PREFIX)
+                .map(|name| (name.to_string(), value))
+        })
+        .collect();
+    Ok(Some(attributes).filter(|a| !a.is_empty()))
+}
+
+fn master_query(query_string: &str) -> Result<MasterListQuery, (u16, String)> {
+    let mut list_query: MasterListQuery = query(query_string)?;
+    list_query.attributes = attribute_filters(query_string)?;
+    Ok(list_query)
+}
+
+fn body<T: DeserializeOwned>(request: &mut Request) -> Result<T, (u16, String)> {
+    let mut data = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| (400, e.to_string()))?;
+    if data.len() as u64 > MAX_BODY_BYTES {
+        return Err((413, "Request body is too large".to_string()));
+    }
+    serde_json::from_slice(&data).map_err(|e| (400, format!("Invalid JSON body: {}", e)))
+}
+
+// Compares every byte so the time taken does not reveal how much of the token matched
+fn token_matches(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn authorize(app: &AppHandle, request: &Request) -> Result<(), (u16, String)> {
+    let expected = db::get_settings(app)
+        .map_err(|e| (503, e.to_string()))?
+        .api_token
+        .ok_or((401, "No API token is configured".to_string()))?;
+    let provided = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .map(str::trim);
+    match provided {
+        Some(token) if token_matches(token, &expected) => Ok(()),
+        _ => Err((401, "Missing or invalid API token".to_string())),
+    }
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+fn handle(app: &AppHandle, request: &mut Request) -> Reply {
+    let url = request.url().to_string();
+    let (path, query_string) = url.split_once('?').unwrap_or((&url, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let method = request.method().clone();
+
+    if method == Method::Get && segments == ["api", "openapi.json"] {
+        let mut document: Value =
+            serde_json::from_str(OPENAPI_DOCUMENT).map_err(|e| (500, e.to_string()))?;
+        if let Some(host) = request.headers().iter().find(|h| h.field.equiv("Host")) {
+            document["servers"] = json!([{ "url": format!("http://{}", host.value) }]);
+        }
+        return Ok((200, Some(document)));
+    }
+    authorize(app, request)?;
+
+    match (&method, segments.as_slice()) {
+        (Method::Get, ["api", "ping"]) => {
+            let settings = db::get_settings(app).map_err(db_error)?;
+            Ok((200, Some(json!({ "company_name": settings.company_name }))))
+        }
+        (Method::Post, ["api", "rpc", command]) => {
+            let args: Value = body(request)?;
+            let value = lan::dispatch(app, command, &args).map_err(|e| (400, e))?;
+            Ok((200, Some(value)))
+        }
+        (Method::Get, ["api", "items"]) => ok(db::get_all_items(app, master_query(query_string)?)),
+        (Method::Post, ["api", "items"]) => created(db::create_item(app, body(request)?)),
+        (Method::Put, ["api", "items", id]) => {
+            let mut item: Item = body(request)?;
+            item.id = Some(parse_id(id)?);
+            no_content(db::update_item(app, item))
+        }
+        (Method::Get, ["api", "items", id, "stock"]) => {
+            ok(db::get_item_stock_by_sites(app, parse_id(id)?))
+        }
+        (Method::Get, ["api", "brands"]) => {
+            ok(db::get_all_brands(app, master_query(query_string)?))
+        }
+        (Method::Get, ["api", "models"]) => {
+            ok(db::get_all_models(app, master_query(query_string)?))
+        }
+        (Method::Get, ["api", "sites"]) => ok(db::get_all_sites(app, master_query(query_string)?)),
+        (Method::Post, ["api", "sites"]) => created(db::create_site(app, body(request)?)),
+        (Method::Put, ["api", "sites", id]) => {
+            let mut site: Site = body(request)?;
+            site.id = Some(parse_id(id)?);
+            no_content(db::update_site(app, site))
+        }
+        (Method::Get, ["api", "sites", id, "stock"]) => {
+            ok(db::get_site_stock_balances(app, parse_id(id)?))
+        }
+        (Method::Get, ["api", "suppliers"]) => ok(db::get_all_suppliers(app)),
+        (Method::Get, ["api", "voucher-types"]) => ok(db::get_all_inventory_transaction_types(app)),
+        (Method::Get, ["api", "vouchers"]) => {
+            let mut filters: InventoryVoucherFilters = query(query_string)?;
+            filters.attributes = attribute_filters(query_string)?;
+            let paging: Paging = query(query_string)?;
+            ok(db::get_inventory_vouchers(
+                app,
+                filters,
+                paging.page.unwrap_or(1),
+                paging.limit.unwrap_or(-1),
+            ))
+        }
+        (Method::Post, ["api", "vouchers"]) => {
+            created(db::create_inventory_voucher(app, body(request)?))
+        }
+        (Method::Get, ["api", "vouchers", id]) => ok(db::get_inventory_voucher(app, parse_id(id)?)),
+        (Method::Put, ["api", "vouchers", id]) => {
+            let mut voucher: InventoryVoucher = body(request)?;
+            voucher.id = Some(parse_id(id)?);
+            no_content(db::update_inventory_voucher(app, voucher))
+        }
+        (Method::Delete, ["api", "vouchers", id]) => {
+            no_content(db::delete_inventory_voucher(app, parse_id(id)?))
+        }
+        (Method::Get, ["api", "stock-balances"]) => {
+            let filters: StockBalanceQuery = query(query_string)?;
+            let paging: Paging = query(query_string)?;
+            ok(db::get_stock_balances(
+                app,
+                filters.item_name,
+                filters.site_id,
+                filters.category_id,
+                attribute_filters(query_string)?,
+                paging.page.unwrap_or(1),
+                paging.limit.unwrap_or(-1),
+            ))
+        }
+        (Method::Get, ["api", "stock-movements"]) => {
+            let filters: MovementQuery = query(query_string)?;
+            let paging: Paging = query(query_string)?;
+            ok(db::get_stock_movement_history(
+                app,
+                filters.item_id,
+                filters.site_id,
+                filters.voucher_type_id,
+                filters.category_id,
+                attribute_filters(query_string)?,
+                filters.from_date,
+                filters.to_date,
+                paging.page.unwrap_or(1),
+                paging.limit.unwrap_or(-1),
+            ))
+        }
+        _ => Err((404, format!("No endpoint for {} {}", method, path))),
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_api_server_status(app: AppHandle) -> ApiServerStatus {
+    match app.try_state::<ApiServer>() {
+        Some(state) => status_of(&state.0.lock().unwrap_or_else(PoisonError::into_inner)),
+        None => ApiServerStatus {
+            is_running: false,
+            address: None,
+            lan_address: None,
+            error: None,
+        },
+    }
+}
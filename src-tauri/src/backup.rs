@@ -0,0 +1,486 @@
+use crate::db::{self, company_data_dir, get_db_conn, DB_FILE_NAME, SCHEMA_VERSION};
+use crate::encryption;
+use chrono::{Datelike, Local, NaiveDateTime, Timelike};
+use rusqlite::backup::Backup;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use zip::result::ZipResult;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+// Backups cover the company database; attachment files stay in the company folder
+const BACKUPS_DIR: &str = "backups";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const REQUIRED_TABLES: [&str; 4] = [
+    "items",
+    "sites",
+    "inventory_vouchers",
+    "inventory_voucher_items",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BackupKind {
+    Manual,
+    Automatic,
+    // Taken just before a restore replaces the database
+    PreRestore,
+}
+
+impl BackupKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            BackupKind::Manual => "backup-",
+            BackupKind::Automatic => "auto-",
+            BackupKind::PreRestore => "pre-restore-",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    pub kind: BackupKind,
+    pub compressed: bool,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+// Outcome of the latest scheduled run; a failure stays until a later run gets through
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BackupStatus {
+    pub error: Option<String>,
+    pub failed_at: Option<String>,
+}
+
+#[derive(Default)]
+pub struct BackupScheduler(Mutex<BackupStatus>);
+
+fn backups_dir(app: &AppHandle) -> PathBuf {
+    company_data_dir(app).join(BACKUPS_DIR)
+}
+
+// Kind and time come from the file name, e.g. auto-20260801-020000.zip
+fn backup_info(path: &Path) -> Option<BackupInfo> {
+    let file_name = path.file_name()?.to_str()?.to_string();
+    let (stem, compressed) = match file_name.rsplit_once('.')? {
+        (stem, "zip") => (stem, true),
+        (stem, "db") => (stem, false),
+        _ => return None,
+    };
+    let (kind, rest) = [
+        BackupKind::PreRestore,
+        BackupKind::Automatic,
+        BackupKind::Manual,
+    ]
+    .into_iter()
+    .find_map(|kind| stem.strip_prefix(kind.prefix()).map(|rest| (kind, rest)))?;
+    let created_at = NaiveDateTime::parse_from_str(rest.get(..15)?, TIMESTAMP_FORMAT).ok()?;
+    Some(BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        file_name,
+        kind,
+        compressed,
+        size_bytes: path.metadata().ok()?.len(),
+        created_at: created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+}
+
+fn all_backups(app: &AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let dir = backups_dir(app);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut backups: Vec<BackupInfo> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| backup_info(&entry.path()))
+        .collect();
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then(b.file_name.cmp(&a.file_name))
+    });
+    Ok(backups)
+}
+
+// Copy a database into a new file with SQLite's online backup API, so writes in progress are
+// safe. SQLCipher only copies between databases with the same passphrase.
+fn copy_database(
+    source: &Connection,
+    target: &Path,
+    passphrase: Option<&str>,
+) -> rusqlite::Result<()> {
+    let mut target = Connection::open(target)?;
+    if let Some(passphrase) = passphrase {
+        target.pragma_update(None, "key", passphrase)?;
+    }
+    let backup = Backup::new(source, &mut target)?;
+    backup.run_to_completion(100, Duration::ZERO, None)
+}
+
+// Backups of an encrypted company are encrypted with its passphrase
+fn create_backup(app: &AppHandle, kind: BackupKind, compress: bool) -> Result<BackupInfo, String> {
+    let dir = backups_dir(app);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let stamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+    let extension = if compress { "zip" } else { "db" };
+    let mut path = dir.join(format!("{}{}.{}", kind.prefix(), stamp, extension));
+    let mut counter = 2;
+    while path.exists() {
+        path = dir.join(format!(
+            "{}{}-{}.{}",
+            kind.prefix(),
+            stamp,
+            counter,
+            extension
+        ));
+        counter += 1;
+    }
+
+    let snapshot = dir.join(format!(".snapshot-{}.db", uuid::Uuid::new_v4()));
+    let result = get_db_conn(app)
+        .and_then(|conn| copy_database(&conn, &snapshot, encryption::passphrase(app).as_deref()))
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            if compress {
+                write_zip(&snapshot, &path).map_err(|e| e.to_string())
+            } else {
+                std::fs::rename(&snapshot, &path).map_err(|e| e.to_string())
+            }
+        });
+    let _ = std::fs::remove_file(&snapshot);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&path);
+        return Err(format!("Backup failed: {}", e));
+    }
+    backup_info(&path).ok_or_else(|| "Backup file could not be read back".to_string())
+}
+
+// ============================================================================
+// Zip archives holding the single database file
+// ============================================================================
+
+fn write_zip(source: &Path, target: &Path) -> ZipResult<()> {
+    let now = Local::now();
+    let modified = zip::DateTime::from_date_and_time(
+        now.year().clamp(1980, 2107) as u16,
+        now.month() as u8,
+        now.day() as u8,
+        now.hour() as u8,
+        now.minute() as u8,
+        now.second() as u8,
+    )
+    .unwrap_or_default();
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(modified)
+        .large_file(true);
+
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(target)?));
+    zip.start_file(DB_FILE_NAME, options)?;
+    std::io::copy(&mut BufReader::new(File::open(source)?), &mut zip)?;
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+// Extract the first .db entry; reading it to the end checks it against its crc
+fn extract_zip(source: &Path, target: &Path) -> Result<(), String> {
+    let invalid = || "The backup archive is damaged or not a Stock Star backup".to_string();
+    let file = File::open(source).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).map_err(|_| invalid())?;
+    let index = (0..archive.len())
+        .find(|&i| {
+            archive
+                .name_for_index(i)
+                .is_some_and(|name| name.ends_with(".db"))
+        })
+        .ok_or_else(invalid)?;
+    let mut entry = archive.by_index(index).map_err(|_| invalid())?;
+
+    let mut out = BufWriter::new(File::create(target).map_err(|e| e.to_string())?);
+    std::io::copy(&mut entry, &mut out).map_err(|_| invalid())?;
+    out.flush().map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Restore
+// ============================================================================
+
+// The copy must pass SQLite's integrity check, contain the core tables and not come from a newer schema
+fn validate_backup_db(path: &Path, passphrase: Option<&str>) -> Result<Connection, String> {
+    let conn = encryption::open_database(path, passphrase).map_err(|e| e.to_string())?;
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|_| "The backup is not a valid database".to_string())?;
+    if integrity != "ok" {
+        return Err(format!(
+            "The backup failed the integrity check: {}",
+            integrity
+        ));
+    }
+    let version: i64 = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "The backup was made by a newer version of the app (schema {}, this app supports up to {})",
+            version, SCHEMA_VERSION
+        ));
+    }
+    for table in REQUIRED_TABLES {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+                [table],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err("The backup is not a Stock Star database".to_string());
+        }
+    }
+    Ok(conn)
+}
+
+// Replaces the company database with a validated backup. The current data is backed up first
+// and returned, and the restored database is upgraded to the current schema. An encrypted backup
+// is opened with the given passphrase, or the open company's, and takes the company's encryption.
+pub fn restore_from(
+    app: &AppHandle,
+    file_name: &str,
+    passphrase: Option<String>,
+) -> Result<BackupInfo, String> {
+    let dir = backups_dir(app);
+    let path = dir.join(file_name);
+    let backup = match backup_info(&path) {
+        Some(backup) if !file_name.contains(['/', '\\']) && path.is_file() => backup,
+        _ => return Err(format!("Backup '{}' does not exist", file_name)),
+    };
+
+    let live_key = encryption::passphrase(app);
+    let candidate = dir.join(format!(".restore-{}.db", uuid::Uuid::new_v4()));
+    let rekeyed = candidate.with_extension("rekeyed.db");
+    let result = (|| {
+        if backup.compressed {
+            extract_zip(&path, &candidate)?;
+        } else {
+            std::fs::copy(&path, &candidate).map_err(|e| e.to_string())?;
+        }
+        let source_key = if encryption::is_encrypted(&candidate) {
+            let key = passphrase
+                .filter(|p| !p.is_empty())
+                .or_else(|| live_key.clone());
+            Some(key.ok_or("The backup is encrypted; enter the passphrase it was made with")?)
+        } else {
+            None
+        };
+        let mut source = validate_backup_db(&candidate, source_key.as_deref())?;
+        if source_key != live_key {
+            encryption::export_database(&source, &rekeyed, live_key.as_deref())
+                .map_err(|e| format!("Restore failed: {}", e))?;
+            source = encryption::open_database(&rekeyed, live_key.as_deref())
+                .map_err(|e| e.to_string())?;
+        }
+
+        let settings = db::get_settings(app).map_err(|e| e.to_string())?;
+        let safety = create_backup(app, BackupKind::PreRestore, settings.compress_backups)?;
+        let mut live = get_db_conn(app).map_err(|e| e.to_string())?;
+        Backup::new(&source, &mut live)
+            .and_then(|backup| backup.run_to_completion(100, Duration::ZERO, None))
+            .map_err(|e| format!("Restore failed: {}", e))?;
+        drop(live);
+        db::init_company_db(&company_data_dir(app), live_key.as_deref())
+            .map_err(|e| e.to_string())?;
+        Ok(safety)
+    })();
+    let _ = std::fs::remove_file(&candidate);
+    let _ = std::fs::remove_file(&rekeyed);
+    result
+}
+
+// ============================================================================
+// Scheduled Backups
+// ============================================================================
+
+// Takes the daily automatic backup when the newest one is a day old, then prunes old ones
+pub fn run_scheduled_backup(app: &AppHandle) -> Result<Option<BackupInfo>, String> {
+    if encryption::is_locked(app) {
+        return Ok(None);
+    }
+    let settings = db::get_settings(app).map_err(|e| e.to_string())?;
+    if !settings.daily_backup {
+        return Ok(None);
+    }
+    let automatic: Vec<BackupInfo> = all_backups(app)?
+        .into_iter()
+        .filter(|b| b.kind == BackupKind::Automatic)
+        .collect();
+    let due_before = (Local::now() - chrono::Duration::days(1))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    if automatic.first().is_some_and(|b| b.created_at > due_before) {
+        return Ok(None);
+    }
+
+    let backup = create_backup(app, BackupKind::Automatic, settings.compress_backups)?;
+    let keep = settings.backup_retention_count.max(1) as usize;
+    let mut automatic: Vec<BackupInfo> = all_backups(app)?
+        .into_iter()
+        .filter(|b| b.kind == BackupKind::Automatic)
+        .collect();
+    for old in automatic.split_off(keep.min(automatic.len())) {
+        let _ = std::fs::remove_file(&old.path);
+    }
+    Ok(Some(backup))
+}
+
+// Checks hourly for the open company, so a missed day is caught up soon after the app starts
+pub fn start_backup_scheduler(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let status = match run_scheduled_backup(&app) {
+            Ok(_) => BackupStatus::default(),
+            Err(e) => BackupStatus {
+                error: Some(e),
+                failed_at: Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+            },
+        };
+        if let Some(state) = app.try_state::<BackupScheduler>() {
+            *state.0.lock().unwrap_or_else(PoisonError::into_inner) = status;
+        }
+        std::thread::sleep(SCHEDULE_INTERVAL);
+    });
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+// Backs up the open company now; compression defaults to the backup setting
+#[tauri::command]
+pub fn backup_database(app: AppHandle, compress: Option<bool>) -> Result<BackupInfo, String> {
+    let compress = match compress {
+        Some(compress) => compress,
+        None => {
+            db::get_settings(&app)
+                .map_err(|e| e.to_string())?
+                .compress_backups
+        }
+    };
+    create_backup(&app, BackupKind::Manual, compress)
+}
+
+// Backups of the open company, newest first
+#[tauri::command]
+pub fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    all_backups(&app)
+}
+
+// Whether the last automatic backup failed, and why
+#[tauri::command]
+pub fn get_backup_status(app: AppHandle) -> BackupStatus {
+    app.try_state::<BackupScheduler>()
+        .map(|state| {
+            state
+                .0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        })
+        .unwrap_or_default()
+}
+
+// The passphrase is only needed for an encrypted backup made under a different passphrase
+#[tauri::command]
+pub fn restore_backup(
+    app: AppHandle,
+    file_name: String,
+    passphrase: Option<String>,
+) -> Result<BackupInfo, String> {
+    restore_from(&app, &file_name, passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A scratch folder under the system temp dir, removed when dropped
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Scratch {
+            let dir =
+                std::env::temp_dir().join(format!("stock-star-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Scratch(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn zipped(scratch: &Scratch, contents: &[u8]) -> PathBuf {
+        let source = scratch.0.join("source.db");
+        let archive = scratch.0.join("backup.zip");
+        std::fs::write(&source, contents).unwrap();
+        write_zip(&source, &archive).unwrap();
+        archive
+    }
+
+    fn sample() -> Vec<u8> {
+        (0..200_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn zip_round_trip_keeps_the_database() {
+        let scratch = Scratch::new("zip-round-trip");
+        let archive = zipped(&scratch, &sample());
+        let restored = scratch.0.join("restored.db");
+        extract_zip(&archive, &restored).unwrap();
+        assert_eq!(std::fs::read(&restored).unwrap(), sample());
+    }
+
+    #[test]
+    fn truncated_zip_is_rejected() {
+        let scratch = Scratch::new("zip-truncated");
+        let archive = zipped(&scratch, &sample());
+        let bytes = std::fs::read(&archive).unwrap();
+        for length in [0, 10, bytes.len() / 2, bytes.len() - 10] {
+            std::fs::write(&archive, &bytes[..length]).unwrap();
+            assert!(extract_zip(&archive, &scratch.0.join("restored.db")).is_err());
+        }
+    }
+
+    #[test]
+    fn corrupt_zip_is_rejected() {
+        let scratch = Scratch::new("zip-corrupt");
+        let archive = zipped(&scratch, &sample());
+        let mut bytes = std::fs::read(&archive).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xFF;
+        std::fs::write(&archive, &bytes).unwrap();
+        assert!(extract_zip(&archive, &scratch.0.join("restored.db")).is_err());
+    }
+
+    #[test]
+    fn zip_without_a_database_is_rejected() {
+        let scratch = Scratch::new("zip-no-database");
+        let archive = scratch.0.join("backup.zip");
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        zip.start_file("notes.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"not a backup").unwrap();
+        zip.finish().unwrap();
+        assert!(extract_zip(&archive, &scratch.0.join("restored.db")).is_err());
+    }
+}
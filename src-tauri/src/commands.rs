@@ -1,7 +1,10 @@
 use crate::db::{
-    self, Brand, ImportItem, InventoryTransactionType, InventoryVoucher, InventoryVoucherDisplay,
-    Item, Model, Site,
+    self, AttributeDefinition, Brand, ImportItem, InventoryTransactionType, InventoryVoucher,
+    InventoryVoucherDisplay, InventoryVoucherFilters, Item, ItemBarcode, ItemCategory,
+    ItemComponent, ItemSearchFilters, MasterListQuery, MaterialRequisition, Model, Project,
+    PurchaseOrder, RequisitionApproval, Settings, Site, Supplier, VoucherAttachment,
 };
+use std::collections::HashMap;
 use tauri::{command, AppHandle};
 
 // Item Commands
@@ -11,8 +14,22 @@ pub fn create_item(app: AppHandle, item: Item) -> Result<i64, String> {
 }
 
 #[command]
-pub fn get_items(app: AppHandle) -> Result<Vec<Item>, String> {
-    db::get_all_items(&app).map_err(|e| e.to_string())
+pub fn get_items(
+    app: AppHandle,
+    query: Option<MasterListQuery>,
+) -> Result<db::PaginatedResponse<Item>, String> {
+    db::get_all_items(&app, query.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn search_items(
+    app: AppHandle,
+    query: String,
+    filters: Option<ItemSearchFilters>,
+    page: i64,
+    limit: i64,
+) -> Result<db::PaginatedResponse<Item>, String> {
+    db::search_items(&app, query, filters, page, limit).map_err(|e| e.to_string())
 }
 
 #[command]
@@ -21,7 +38,7 @@ pub fn update_item(app: AppHandle, item: Item) -> Result<(), String> {
 }
 
 #[command]
-pub fn delete_item(app: AppHandle, id: i64) -> Result<(), String> {
+pub fn delete_item(app: AppHandle, id: i64) -> Result<db::DeleteOutcome, String> {
     db::delete_item(&app, id).map_err(|e| e.to_string())
 }
 
@@ -30,6 +47,47 @@ pub fn import_items(app: AppHandle, items: Vec<ImportItem>) -> Result<(), String
     db::import_items(&app, items).map_err(|e| e.to_string())
 }
 
+#[command]
+pub fn export_items(app: AppHandle) -> Result<Vec<ImportItem>, String> {
+    db::export_items(&app).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_item_components(app: AppHandle, kit_item_id: i64) -> Result<Vec<ItemComponent>, String> {
+    db::get_item_components(&app, kit_item_id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_item_barcodes(app: AppHandle, item_id: i64) -> Result<Vec<ItemBarcode>, String> {
+    db::get_item_barcodes(&app, item_id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn lookup_item_by_barcode(app: AppHandle, barcode: String) -> Result<Option<Item>, String> {
+    db::lookup_item_by_barcode(&app, barcode).map_err(|e| e.to_string())
+}
+
+// Item Category Commands
+#[command]
+pub fn create_item_category(app: AppHandle, category: ItemCategory) -> Result<i64, String> {
+    db::create_item_category(&app, category).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_item_categories(app: AppHandle) -> Result<Vec<ItemCategory>, String> {
+    db::get_item_categories(&app).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn update_item_category(app: AppHandle, category: ItemCategory) -> Result<(), String> {
+    db::update_item_category(&app, category).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn delete_item_category(app: AppHandle, id: i64) -> Result<(), String> {
+    db::delete_item_category(&app, id).map_err(|e| e.to_string())
+}
+
 // Brand Commands
 #[command]
 pub fn create_brand(app: AppHandle, brand: Brand) -> Result<i64, String> {
@@ -37,8 +95,11 @@ pub fn create_brand(app: AppHandle, brand: Brand) -> Result<i64, String> {
 }
 
 #[command]
-pub fn get_brands(app: AppHandle) -> Result<Vec<Brand>, String> {
-    db::get_all_brands(&app).map_err(|e| e.to_string())
+pub fn get_brands(
+    app: AppHandle,
+    query: Option<MasterListQuery>,
+) -> Result<db::PaginatedResponse<Brand>, String> {
+    db::get_all_brands(&app, query.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
 #[command]
@@ -47,7 +108,7 @@ pub fn update_brand(app: AppHandle, brand: Brand) -> Result<(), String> {
 }
 
 #[command]
-pub fn delete_brand(app: AppHandle, id: i64) -> Result<(), String> {
+pub fn delete_brand(app: AppHandle, id: i64) -> Result<db::DeleteOutcome, String> {
     db::delete_brand(&app, id).map_err(|e| e.to_string())
 }
 
@@ -58,8 +119,16 @@ pub fn create_model(app: AppHandle, model: Model) -> Result<i64, String> {
 }
 
 #[command]
-pub fn get_models(app: AppHandle) -> Result<Vec<Model>, String> {
-    db::get_all_models(&app).map_err(|e| e.to_string())
+pub fn get_models(
+    app: AppHandle,
+    query: Option<MasterListQuery>,
+) -> Result<db::PaginatedResponse<Model>, String> {
+    db::get_all_models(&app, query.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_models_by_brand(app: AppHandle, brand_id: i64) -> Result<Vec<Model>, String> {
+    db::get_models_by_brand(&app, brand_id).map_err(|e| e.to_string())
 }
 
 #[command]
@@ -68,7 +137,7 @@ pub fn update_model(app: AppHandle, model: Model) -> Result<(), String> {
 }
 
 #[command]
-pub fn delete_model(app: AppHandle, id: i64) -> Result<(), String> {
+pub fn delete_model(app: AppHandle, id: i64) -> Result<db::DeleteOutcome, String> {
     db::delete_model(&app, id).map_err(|e| e.to_string())
 }
 
@@ -79,8 +148,11 @@ pub fn create_site(app: AppHandle, site: Site) -> Result<i64, String> {
 }
 
 #[command]
-pub fn get_sites(app: AppHandle) -> Result<Vec<Site>, String> {
-    db::get_all_sites(&app).map_err(|e| e.to_string())
+pub fn get_sites(
+    app: AppHandle,
+    query: Option<MasterListQuery>,
+) -> Result<db::PaginatedResponse<Site>, String> {
+    db::get_all_sites(&app, query.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
 #[command]
@@ -89,10 +161,202 @@ pub fn update_site(app: AppHandle, site: Site) -> Result<(), String> {
 }
 
 #[command]
-pub fn delete_site(app: AppHandle, id: i64) -> Result<(), String> {
+pub fn delete_site(app: AppHandle, id: i64) -> Result<db::DeleteOutcome, String> {
     db::delete_site(&app, id).map_err(|e| e.to_string())
 }
 
+// Master Usage and Merge Commands
+#[command]
+pub fn get_master_usage(
+    app: AppHandle,
+    entity: String,
+    id: i64,
+) -> Result<db::MasterUsage, String> {
+    db::get_master_usage(&app, entity, id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn merge_masters(
+    app: AppHandle,
+    entity: String,
+    source_id: i64,
+    target_id: i64,
+) -> Result<(), String> {
+    db::merge_masters(&app, entity, source_id, target_id).map_err(|e| e.to_string())
+}
+
+// Custom Attribute Commands
+#[command]
+pub fn create_attribute_definition(
+    app: AppHandle,
+    definition: AttributeDefinition,
+) -> Result<i64, String> {
+    db::create_attribute_definition(&app, definition).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_attribute_definitions(
+    app: AppHandle,
+    entity: Option<String>,
+) -> Result<Vec<AttributeDefinition>, String> {
+    db::get_attribute_definitions(&app, entity).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn update_attribute_definition(
+    app: AppHandle,
+    definition: AttributeDefinition,
+) -> Result<(), String> {
+    db::update_attribute_definition(&app, definition).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn delete_attribute_definition(app: AppHandle, id: i64) -> Result<(), String> {
+    db::delete_attribute_definition(&app, id).map_err(|e| e.to_string())
+}
+
+// Supplier Commands
+#[command]
+pub fn create_supplier(app: AppHandle, supplier: Supplier) -> Result<i64, String> {
+    db::create_supplier(&app, supplier).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_suppliers(app: AppHandle) -> Result<Vec<Supplier>, String> {
+    db::get_all_suppliers(&app).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn update_supplier(app: AppHandle, supplier: Supplier) -> Result<(), String> {
+    db::update_supplier(&app, supplier).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn delete_supplier(app: AppHandle, id: i64) -> Result<db::DeleteOutcome, String> {
+    db::delete_supplier(&app, id).map_err(|e| e.to_string())
+}
+
+// Project Commands
+#[command]
+pub fn create_project(app: AppHandle, project: Project) -> Result<i64, String> {
+    db::create_project(&app, project).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_projects(app: AppHandle, site_id: Option<i64>) -> Result<Vec<Project>, String> {
+    db::get_all_projects(&app, site_id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn update_project(app: AppHandle, project: Project) -> Result<(), String> {
+    db::update_project(&app, project).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn delete_project(app: AppHandle, id: i64) -> Result<(), String> {
+    db::delete_project(&app, id).map_err(|e| e.to_string())
+}
+
+// Purchase Order Commands
+#[command]
+pub fn create_purchase_order(app: AppHandle, order: PurchaseOrder) -> Result<i64, String> {
+    db::create_purchase_order(&app, order).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_purchase_orders(
+    app: AppHandle,
+    supplier_id: Option<i64>,
+    status: Option<String>,
+    page: i64,
+    limit: i64,
+) -> Result<db::PaginatedResponse<db::PurchaseOrderDisplay>, String> {
+    db::get_purchase_orders(&app, supplier_id, status, page, limit).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_purchase_order(app: AppHandle, id: i64) -> Result<PurchaseOrder, String> {
+    db::get_purchase_order(&app, id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn update_purchase_order(app: AppHandle, order: PurchaseOrder) -> Result<(), String> {
+    db::update_purchase_order(&app, order).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn cancel_purchase_order(app: AppHandle, id: i64) -> Result<(), String> {
+    db::cancel_purchase_order(&app, id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn delete_purchase_order(app: AppHandle, id: i64) -> Result<(), String> {
+    db::delete_purchase_order(&app, id).map_err(|e| e.to_string())
+}
+
+// Material Requisition Commands
+#[command]
+pub fn create_material_requisition(
+    app: AppHandle,
+    requisition: MaterialRequisition,
+) -> Result<i64, String> {
+    db::create_material_requisition(&app, requisition).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_material_requisitions(
+    app: AppHandle,
+    site_id: Option<i64>,
+    status: Option<String>,
+    page: i64,
+    limit: i64,
+) -> Result<db::PaginatedResponse<db::MaterialRequisitionDisplay>, String> {
+    db::get_material_requisitions(&app, site_id, status, page, limit).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_material_requisition(app: AppHandle, id: i64) -> Result<MaterialRequisition, String> {
+    db::get_material_requisition(&app, id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn update_material_requisition(
+    app: AppHandle,
+    requisition: MaterialRequisition,
+) -> Result<(), String> {
+    db::update_material_requisition(&app, requisition).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn approve_material_requisition(
+    app: AppHandle,
+    id: i64,
+    approvals: Vec<RequisitionApproval>,
+    approved_by: Option<i64>,
+) -> Result<(), String> {
+    db::approve_material_requisition(&app, id, approvals, approved_by).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn reject_material_requisition(
+    app: AppHandle,
+    id: i64,
+    reason: Option<String>,
+    rejected_by: Option<i64>,
+) -> Result<(), String> {
+    db::reject_material_requisition(&app, id, reason, rejected_by).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn cancel_material_requisition(app: AppHandle, id: i64) -> Result<(), String> {
+    db::cancel_material_requisition(&app, id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn delete_material_requisition(app: AppHandle, id: i64) -> Result<(), String> {
+    db::delete_material_requisition(&app, id).map_err(|e| e.to_string())
+}
+
 // Inventory Transaction Type Commands
 #[command]
 pub fn get_inventory_transaction_types(
@@ -112,8 +376,10 @@ pub fn get_inventory_vouchers(
     app: AppHandle,
     page: i64,
     limit: i64,
+    filters: Option<InventoryVoucherFilters>,
 ) -> Result<db::PaginatedResponse<InventoryVoucherDisplay>, String> {
-    db::get_inventory_vouchers(&app, page, limit).map_err(|e| e.to_string())
+    db::get_inventory_vouchers(&app, filters.unwrap_or_default(), page, limit)
+        .map_err(|e| e.to_string())
 }
 
 #[command]
@@ -131,6 +397,38 @@ pub fn delete_inventory_voucher(app: AppHandle, id: i64) -> Result<(), String> {
     db::delete_inventory_voucher(&app, id).map_err(|e| e.to_string())
 }
 
+#[command]
+pub fn add_voucher_attachment(
+    app: AppHandle,
+    voucher_id: i64,
+    file_name: String,
+    data: String,
+    description: Option<String>,
+) -> Result<VoucherAttachment, String> {
+    db::add_voucher_attachment(&app, voucher_id, file_name, data, description)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_voucher_attachments(
+    app: AppHandle,
+    voucher_id: i64,
+) -> Result<Vec<VoucherAttachment>, String> {
+    db::get_voucher_attachments(&app, voucher_id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_voucher_attachment_file(app: AppHandle, id: i64) -> Result<String, String> {
+    db::voucher_attachment_path(&app, id)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn delete_voucher_attachment(app: AppHandle, id: i64) -> Result<(), String> {
+    db::delete_voucher_attachment(&app, id).map_err(|e| e.to_string())
+}
+
 #[command]
 pub fn get_stock_balance(app: AppHandle, site_id: i64, item_id: i64) -> Result<f64, String> {
     db::get_stock_balance(&app, site_id, item_id).map_err(|e| e.to_string())
@@ -141,10 +439,21 @@ pub fn get_stock_balances(
     app: AppHandle,
     item_name: Option<String>,
     site_id: Option<i64>,
+    category_id: Option<i64>,
+    item_attributes: Option<HashMap<String, String>>,
     page: i64,
     limit: i64,
 ) -> Result<db::PaginatedResponse<db::StockBalance>, String> {
-    db::get_stock_balances(&app, item_name, site_id, page, limit).map_err(|e| e.to_string())
+    db::get_stock_balances(
+        &app,
+        item_name,
+        site_id,
+        category_id,
+        item_attributes,
+        page,
+        limit,
+    )
+    .map_err(|e| e.to_string())
 }
 
 #[command]
@@ -169,6 +478,8 @@ pub fn get_stock_movement_history(
     item_id: Option<i64>,
     site_id: Option<i64>,
     voucher_type_id: Option<i64>,
+    category_id: Option<i64>,
+    item_attributes: Option<HashMap<String, String>>,
     from_date: Option<String>,
     to_date: Option<String>,
     page: i64,
@@ -179,6 +490,8 @@ pub fn get_stock_movement_history(
         item_id,
         site_id,
         voucher_type_id,
+        category_id,
+        item_attributes,
         from_date,
         to_date,
         page,
@@ -187,7 +500,115 @@ pub fn get_stock_movement_history(
     .map_err(|e| e.to_string())
 }
 
+#[command]
+pub fn get_purchase_register(
+    app: AppHandle,
+    supplier_id: Option<i64>,
+    item_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    page: i64,
+    limit: i64,
+) -> Result<db::PaginatedResponse<db::PurchaseRegisterEntry>, String> {
+    db::get_purchase_register(&app, supplier_id, item_id, from_date, to_date, page, limit)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_purchase_summary(
+    app: AppHandle,
+    group_by: String,
+    supplier_id: Option<i64>,
+    item_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<Vec<db::PurchaseSummary>, String> {
+    db::get_purchase_summary(&app, group_by, supplier_id, item_id, from_date, to_date)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_outstanding_purchase_orders(
+    app: AppHandle,
+    supplier_id: Option<i64>,
+    item_id: Option<i64>,
+    site_id: Option<i64>,
+) -> Result<Vec<db::OutstandingPurchaseOrderLine>, String> {
+    db::get_outstanding_purchase_orders(&app, supplier_id, item_id, site_id)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_requisition_status_report(
+    app: AppHandle,
+    site_id: Option<i64>,
+    status: Option<String>,
+    pending_only: bool,
+) -> Result<Vec<db::RequisitionStatusLine>, String> {
+    db::get_requisition_status_report(&app, site_id, status, pending_only)
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_project_consumption(
+    app: AppHandle,
+    group_by: String,
+    project_id: Option<i64>,
+    site_id: Option<i64>,
+    item_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<Vec<db::ProjectConsumptionSummary>, String> {
+    db::get_project_consumption(
+        &app, group_by, project_id, site_id, item_id, from_date, to_date,
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_category_stock_summary(
+    app: AppHandle,
+    site_id: Option<i64>,
+) -> Result<Vec<db::CategoryTotal>, String> {
+    db::get_category_stock_summary(&app, site_id).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_category_consumption(
+    app: AppHandle,
+    project_id: Option<i64>,
+    site_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<Vec<db::CategoryTotal>, String> {
+    db::get_category_consumption(&app, project_id, site_id, from_date, to_date)
+        .map_err(|e| e.to_string())
+}
+
 #[command]
 pub fn get_dashboard_stats(app: AppHandle) -> Result<db::DashboardStats, String> {
     db::get_dashboard_stats(&app).map_err(|e| e.to_string())
 }
+
+#[command]
+pub fn get_settings(app: AppHandle) -> Result<Settings, String> {
+    db::get_settings(&app).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn update_settings(app: AppHandle, settings: Settings) -> Result<Settings, String> {
+    let settings = db::update_settings(&app, settings).map_err(|e| e.to_string())?;
+    crate::api_server::restart(&app);
+    Ok(settings)
+}
+
+// Integrity Commands
+#[command]
+pub fn check_integrity(app: AppHandle) -> Result<db::IntegrityReport, String> {
+    db::check_integrity(&app).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn rebuild_stock_movements(app: AppHandle) -> Result<i64, String> {
+    db::rebuild_stock_movements(&app).map_err(|e| e.to_string())
+}
@@ -0,0 +1,309 @@
+use crate::api_server;
+use crate::db::{self, DB_FILE_NAME};
+use crate::encryption::{self, DatabaseKey};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use tauri::{AppHandle, Manager};
+
+// The registry lists every company; each company keeps its own database in its folder
+const REGISTRY_FILE_NAME: &str = "companies.db";
+const MAX_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Company {
+    pub id: i64,
+    pub name: String,
+    // Relative to the app data dir; empty for the company that predates the registry
+    pub folder: String,
+    pub is_archived: bool,
+    pub is_active: bool,
+    pub created_at: Option<String>,
+    pub last_opened_at: Option<String>,
+}
+
+// The company every command works against
+#[derive(Default)]
+pub struct ActiveCompany(Mutex<Option<Company>>);
+
+fn app_data_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .expect("failed to get app data dir")
+}
+
+fn active_company(app: &AppHandle) -> Option<Company> {
+    let state = app.try_state::<ActiveCompany>()?;
+    let company = state.0.lock().unwrap_or_else(PoisonError::into_inner);
+    company.clone()
+}
+
+// Falls back to the app data dir itself before a company has been opened
+pub fn active_data_dir(app: &AppHandle) -> PathBuf {
+    let root = app_data_dir(app);
+    match active_company(app) {
+        Some(company) => root.join(company.folder),
+        None => root,
+    }
+}
+
+fn registry_conn(app: &AppHandle) -> Result<Connection, String> {
+    let root = app_data_dir(app);
+    std::fs::create_dir_all(&root).map_err(|e| e.to_string())?;
+    let conn = Connection::open(root.join(REGISTRY_FILE_NAME)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS companies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            folder TEXT NOT NULL UNIQUE,
+            is_archived BOOLEAN DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            last_opened_at DATETIME
+        )",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+fn map_company(row: &rusqlite::Row, active_id: Option<i64>) -> rusqlite::Result<Company> {
+    let id: i64 = row.get(0)?;
+    Ok(Company {
+        id,
+        name: row.get(1)?,
+        folder: row.get(2)?,
+        is_archived: row.get(3)?,
+        is_active: active_id == Some(id),
+        created_at: row.get(4)?,
+        last_opened_at: row.get(5)?,
+    })
+}
+
+fn get_company(app: &AppHandle, conn: &Connection, id: i64) -> Result<Company, String> {
+    let active_id = active_company(app).map(|c| c.id);
+    conn.query_row(
+        "SELECT id, name, folder, is_archived, created_at, last_opened_at
+         FROM companies WHERE id = ?1",
+        params![id],
+        |row| map_company(row, active_id),
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Company {} does not exist", id))
+}
+
+// Trimmed name that no other company uses (case-insensitive)
+fn validate_name(conn: &Connection, name: &str, id: Option<i64>) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Company name is required".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "Company name must be at most {} characters",
+            MAX_NAME_LENGTH
+        ));
+    }
+    let taken: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM companies WHERE LOWER(name) = LOWER(?1) AND id != ?2)",
+            params![name, id.unwrap_or(0)],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if taken {
+        return Err(format!("A company named '{}' already exists", name));
+    }
+    Ok(name.to_string())
+}
+
+// Register the existing database as the first company on first start, then open the company
+// used most recently
+pub fn init_companies(app: &AppHandle) -> Result<(), String> {
+    let conn = registry_conn(app)?;
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM companies", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if count == 0 {
+        // An encrypted database cannot be read yet, so it is registered under the default name
+        let root = app_data_dir(app);
+        let settings = if encryption::is_encrypted(&root.join(DB_FILE_NAME)) {
+            db::Settings::default()
+        } else {
+            db::init_company_db(&root, None).map_err(|e| e.to_string())?;
+            Connection::open(root.join(DB_FILE_NAME))
+                .and_then(|c| db::load_settings(&c))
+                .map_err(|e| e.to_string())?
+        };
+        conn.execute(
+            "INSERT INTO companies (name, folder) VALUES (?1, '')",
+            params![settings.company_name],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let id: i64 = conn
+        .query_row(
+            "SELECT id FROM companies WHERE is_archived = 0
+             ORDER BY last_opened_at IS NULL, last_opened_at DESC, id
+             LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    app.manage(ActiveCompany::default());
+    app.manage(DatabaseKey::default());
+    open_company(app.clone(), id).map(|_| ())
+}
+
+#[tauri::command]
+pub fn list_companies(
+    app: AppHandle,
+    include_archived: Option<bool>,
+) -> Result<Vec<Company>, String> {
+    let conn = registry_conn(&app)?;
+    let active_id = active_company(&app).map(|c| c.id);
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, folder, is_archived, created_at, last_opened_at
+             FROM companies
+             WHERE ?1 OR is_archived = 0
+             ORDER BY name COLLATE NOCASE",
+        )
+        .map_err(|e| e.to_string())?;
+    let companies = stmt
+        .query_map(params![include_archived.unwrap_or(false)], |row| {
+            map_company(row, active_id)
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(companies)
+}
+
+#[tauri::command]
+pub fn get_active_company(app: AppHandle) -> Result<Company, String> {
+    active_company(&app).ok_or_else(|| "No company is open".to_string())
+}
+
+// Creates the company with an empty database. The trial clock carries over from the open
+// company so a new company does not start a fresh trial; licenses are activated per company.
+#[tauri::command]
+pub fn create_company(app: AppHandle, name: String) -> Result<Company, String> {
+    let mut conn = registry_conn(&app)?;
+    let name = validate_name(&conn, &name, None)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let folder = format!("companies/{}", uuid::Uuid::new_v4());
+    tx.execute(
+        "INSERT INTO companies (name, folder) VALUES (?1, ?2)",
+        params![name, folder],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = tx.last_insert_rowid();
+
+    let data_dir = app_data_dir(&app).join(&folder);
+    let setup = || -> rusqlite::Result<()> {
+        db::init_company_db(&data_dir, None)?;
+        let company_conn = Connection::open(data_dir.join(DB_FILE_NAME))?;
+        company_conn.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES ('company_name', ?1)",
+            params![serde_json::Value::from(name.as_str()).to_string()],
+        )?;
+        let first_run_at: Option<String> = db::get_db_conn(&app)?
+            .query_row(
+                "SELECT value FROM system_metadata WHERE key = 'first_run_at'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(first_run_at) = first_run_at {
+            company_conn.execute(
+                "INSERT OR REPLACE INTO system_metadata (key, value) VALUES ('first_run_at', ?1)",
+                params![first_run_at],
+            )?;
+        }
+        Ok(())
+    };
+    if let Err(e) = setup() {
+        let _ = std::fs::remove_dir_all(&data_dir);
+        return Err(e.to_string());
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    get_company(&app, &conn, id)
+}
+
+// Makes the company the target of every command, upgrading its database first. An encrypted
+// company stays locked, and is upgraded once it is unlocked.
+#[tauri::command]
+pub fn open_company(app: AppHandle, id: i64) -> Result<Company, String> {
+    let conn = registry_conn(&app)?;
+    let company = get_company(&app, &conn, id)?;
+    if company.is_archived {
+        return Err(format!(
+            "Company '{}' is archived; restore it before opening",
+            company.name
+        ));
+    }
+    // The open company's passphrase does not unlock another one, but stays until the switch is done
+    let switching = active_company(&app).map(|c| c.id) != Some(id);
+    let data_dir = app_data_dir(&app).join(&company.folder);
+    let passphrase = if switching {
+        None
+    } else {
+        encryption::passphrase(&app)
+    };
+    if passphrase.is_some() || !encryption::is_encrypted(&data_dir.join(DB_FILE_NAME)) {
+        db::init_company_db(&data_dir, passphrase.as_deref()).map_err(|e| e.to_string())?;
+    }
+    conn.execute(
+        "UPDATE companies SET last_opened_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut company = get_company(&app, &conn, id)?;
+    company.is_active = true;
+    let state = app.state::<ActiveCompany>();
+    *state.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(company.clone());
+    if switching {
+        encryption::set_passphrase(&app, None);
+    }
+    api_server::restart(&app);
+    Ok(company)
+}
+
+#[tauri::command]
+pub fn rename_company(app: AppHandle, id: i64, name: String) -> Result<Company, String> {
+    let conn = registry_conn(&app)?;
+    get_company(&app, &conn, id)?;
+    let name = validate_name(&conn, &name, Some(id))?;
+    conn.execute(
+        "UPDATE companies SET name = ?1 WHERE id = ?2",
+        params![name, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let company = get_company(&app, &conn, id)?;
+    if company.is_active {
+        let state = app.state::<ActiveCompany>();
+        *state.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(company.clone());
+    }
+    Ok(company)
+}
+
+// Archived companies keep their data but are hidden from the switcher and cannot be opened
+#[tauri::command]
+pub fn archive_company(app: AppHandle, id: i64, archived: bool) -> Result<Company, String> {
+    let conn = registry_conn(&app)?;
+    let company = get_company(&app, &conn, id)?;
+    if archived && company.is_active {
+        return Err("The open company cannot be archived; open another company first".to_string());
+    }
+    conn.execute(
+        "UPDATE companies SET is_archived = ?1 WHERE id = ?2",
+        params![archived, id],
+    )
+    .map_err(|e| e.to_string())?;
+    get_company(&app, &conn, id)
+}
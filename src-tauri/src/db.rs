@@ -1,6 +1,9 @@
-use rusqlite::{params, Connection, Result};
+use base64::{engine::general_purpose, Engine as _};
+use rusqlite::{params, Connection, OptionalExtension, Result, Transaction, TransactionBehavior};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
 
 // ============================================================================
 // Data Models
@@ -10,12 +13,16 @@ use tauri::{AppHandle, Manager};
 pub struct Brand {
     pub id: Option<i64>,
     pub name: String,
+    pub is_active: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Model {
     pub id: Option<i64>,
     pub name: String,
+    pub brand_id: Option<i64>,
+    pub brand_name: Option<String>,
+    pub is_active: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,7 +34,67 @@ pub struct Item {
     pub model_id: Option<i64>,
     pub brand_name: Option<String>,
     pub model_name: Option<String>,
+    pub category_id: Option<i64>,
+    pub category_name: Option<String>,
+    pub aliases: Option<String>,
     pub is_active: bool,
+    pub is_kit: Option<bool>,
+    pub components: Option<Vec<ItemComponent>>,
+    pub barcodes: Option<Vec<ItemBarcode>>,
+    pub attributes: Option<AttributeValues>,
+}
+
+// Common query for the item, brand, model and site lists.
+// Filters that do not apply to a list are ignored; omitting page/limit (or limit -1) returns every row.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MasterListQuery {
+    pub search: Option<String>,
+    pub is_active: Option<bool>,
+    pub brand_id: Option<i64>,
+    pub model_id: Option<i64>,
+    pub category_id: Option<i64>,
+    pub attributes: Option<HashMap<String, String>>,
+    pub sort_by: Option<String>,
+    pub sort_dir: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ItemSearchFilters {
+    pub category_id: Option<i64>,
+    pub brand_id: Option<i64>,
+    pub model_id: Option<i64>,
+    pub is_active: Option<bool>,
+    pub attributes: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemCategory {
+    pub id: Option<i64>,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub path: Option<String>,
+    pub depth: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemComponent {
+    pub id: Option<i64>,
+    pub kit_item_id: Option<i64>,
+    pub component_item_id: i64,
+    pub component_code: Option<String>,
+    pub component_name: Option<String>,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemBarcode {
+    pub id: Option<i64>,
+    pub item_id: Option<i64>,
+    pub barcode: String,
+    pub barcode_type: String, // "EAN" (manufacturer) or "Internal"
+    pub is_primary: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -38,6 +105,48 @@ pub struct Site {
     pub address: Option<String>,
     pub r#type: String,
     pub is_active: bool,
+    pub attributes: Option<AttributeValues>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Supplier {
+    pub id: Option<i64>,
+    pub code: String,
+    pub name: String,
+    pub gstin: Option<String>,
+    pub contact_person: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub address: Option<String>,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Project {
+    pub id: Option<i64>,
+    pub code: String,
+    pub name: String,
+    pub site_id: i64,
+    pub site_name: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub status: String,
+    pub description: Option<String>,
+}
+
+// Custom attribute values keyed by attribute name
+pub type AttributeValues = BTreeMap<String, String>;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    pub id: Option<i64>,
+    pub entity: String, // "item", "site" or "voucher"
+    pub name: String,
+    pub data_type: String, // "text", "number", "date" or "enum"
+    pub is_required: bool,
+    pub options: Option<Vec<String>>,
+    pub sort_order: Option<i64>,
+    pub is_active: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,11 +158,25 @@ pub struct InventoryTransactionType {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InventoryVoucher {
     pub id: Option<i64>,
+    pub uuid: Option<String>, // Same on every node the voucher is synced to
     pub transaction_number: Option<String>,
     pub voucher_date: String,
     pub source_site_id: Option<i64>,
     pub destination_site_id: Option<i64>,
     pub voucher_type_id: i64,
+    pub supplier_id: Option<i64>,
+    pub bill_number: Option<String>,
+    pub bill_date: Option<String>,
+    pub purchase_order_id: Option<i64>,
+    pub requisition_id: Option<i64>,
+    pub original_voucher_id: Option<i64>,
+    pub project_id: Option<i64>,
+    pub activity_code: Option<String>,
+    pub challan_number: Option<String>,
+    pub vehicle_number: Option<String>,
+    pub driver_name: Option<String>,
+    pub receiver_name: Option<String>,
+    pub attributes: Option<AttributeValues>,
     pub items: Vec<InventoryVoucherItem>,
     pub remarks: Option<String>,
     pub created_at: Option<String>,
@@ -63,9 +186,30 @@ pub struct InventoryVoucher {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InventoryVoucherItem {
     pub id: Option<i64>,
+    pub uuid: Option<String>,
     pub inventory_voucher_id: Option<i64>,
     pub item_id: i64,
     pub quantity: f64,
+    pub purchase_order_item_id: Option<i64>,
+    pub requisition_item_id: Option<i64>,
+    pub original_voucher_item_id: Option<i64>,
+    pub kit_item_id: Option<i64>,
+    pub expand_kit: Option<bool>,
+    pub remarks: Option<String>,
+    pub returned_quantity: Option<f64>,
+    pub net_quantity: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VoucherAttachment {
+    pub id: i64,
+    pub voucher_id: i64,
+    pub file_name: String,
+    pub mime_type: Option<String>,
+    pub size_bytes: i64,
+    pub description: Option<String>,
+    pub path: String, // Absolute path of the stored copy
+    pub created_at: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -74,6 +218,25 @@ pub struct PaginatedResponse<T> {
     pub total_count: i64,
 }
 
+// Filters for the voucher list. Dates are inclusive and the item matches lines of that item or of kits
+// expanded from it. Without a sort field the newest vouchers come first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InventoryVoucherFilters {
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub voucher_type_id: Option<i64>,
+    pub source_site_id: Option<i64>,
+    pub destination_site_id: Option<i64>,
+    pub item_id: Option<i64>,
+    pub transaction_number: Option<String>,
+    pub remarks: Option<String>,
+    pub created_by: Option<i64>,
+    pub status: Option<String>,
+    pub attributes: Option<HashMap<String, String>>,
+    pub sort_by: Option<String>,
+    pub sort_dir: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InventoryVoucherDisplay {
     pub id: i64,
@@ -85,10 +248,210 @@ pub struct InventoryVoucherDisplay {
     pub destination_site_name: Option<String>,
     pub voucher_type_id: i64,
     pub voucher_type_name: String,
+    pub supplier_id: Option<i64>,
+    pub supplier_name: Option<String>,
+    pub bill_number: Option<String>,
+    pub bill_date: Option<String>,
+    pub purchase_order_id: Option<i64>,
+    pub purchase_order_number: Option<String>,
+    pub requisition_id: Option<i64>,
+    pub requisition_number: Option<String>,
+    pub original_voucher_id: Option<i64>,
+    pub original_transaction_number: Option<String>,
+    pub project_id: Option<i64>,
+    pub project_code: Option<String>,
+    pub project_name: Option<String>,
+    pub activity_code: Option<String>,
+    pub challan_number: Option<String>,
+    pub vehicle_number: Option<String>,
+    pub attributes: Option<AttributeValues>,
+    pub remarks: Option<String>,
+    pub created_at: String,
+    pub created_by: Option<i64>,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseOrder {
+    pub id: Option<i64>,
+    pub po_number: Option<String>,
+    pub po_date: String,
+    pub supplier_id: i64,
+    pub delivery_site_id: Option<i64>,
+    pub expected_date: Option<String>,
+    pub status: Option<String>,
+    pub items: Vec<PurchaseOrderItem>,
+    pub remarks: Option<String>,
+    pub created_at: Option<String>,
+    pub created_by: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseOrderItem {
+    pub id: Option<i64>,
+    pub purchase_order_id: Option<i64>,
+    pub item_id: i64,
+    pub ordered_quantity: f64,
+    pub rate: Option<f64>,
+    pub received_quantity: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseOrderDisplay {
+    pub id: i64,
+    pub po_number: String,
+    pub po_date: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub delivery_site_id: Option<i64>,
+    pub delivery_site_name: Option<String>,
+    pub expected_date: Option<String>,
+    pub status: String,
+    pub ordered_quantity: f64,
+    pub received_quantity: f64,
+    pub order_value: f64,
+    pub remarks: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutstandingPurchaseOrderLine {
+    pub purchase_order_id: i64,
+    pub po_number: String,
+    pub po_date: String,
+    pub expected_date: Option<String>,
+    pub status: String,
+    pub supplier_id: i64,
+    pub supplier_name: String,
+    pub delivery_site_id: Option<i64>,
+    pub delivery_site_name: Option<String>,
+    pub purchase_order_item_id: i64,
+    pub item_id: i64,
+    pub item_code: String,
+    pub item_name: String,
+    pub ordered_quantity: f64,
+    pub received_quantity: f64,
+    pub pending_quantity: f64,
+    pub rate: Option<f64>,
+    pub pending_value: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MaterialRequisition {
+    pub id: Option<i64>,
+    pub requisition_number: Option<String>,
+    pub requisition_date: String,
+    pub site_id: i64,
+    pub needed_by: Option<String>,
+    pub status: Option<String>,
+    pub requested_by: Option<String>,
+    pub items: Vec<MaterialRequisitionItem>,
+    pub remarks: Option<String>,
+    pub approved_by: Option<i64>,
+    pub approved_at: Option<String>,
+    pub rejection_reason: Option<String>,
+    pub created_at: Option<String>,
+    pub created_by: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MaterialRequisitionItem {
+    pub id: Option<i64>,
+    pub requisition_id: Option<i64>,
+    pub item_id: i64,
+    pub requested_quantity: f64,
+    pub approved_quantity: Option<f64>,
+    pub fulfilled_quantity: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MaterialRequisitionDisplay {
+    pub id: i64,
+    pub requisition_number: String,
+    pub requisition_date: String,
+    pub site_id: i64,
+    pub site_name: String,
+    pub needed_by: Option<String>,
+    pub status: String,
+    pub requested_by: Option<String>,
+    pub requested_quantity: f64,
+    pub approved_quantity: f64,
+    pub fulfilled_quantity: f64,
     pub remarks: Option<String>,
     pub created_at: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequisitionApproval {
+    pub requisition_item_id: i64,
+    pub approved_quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequisitionStatusLine {
+    pub requisition_id: i64,
+    pub requisition_number: String,
+    pub requisition_date: String,
+    pub needed_by: Option<String>,
+    pub status: String,
+    pub site_id: i64,
+    pub site_code: String,
+    pub site_name: String,
+    pub requisition_item_id: i64,
+    pub item_id: i64,
+    pub item_code: String,
+    pub item_name: String,
+    pub requested_quantity: f64,
+    pub approved_quantity: Option<f64>,
+    pub fulfilled_quantity: f64,
+    pub pending_quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectConsumptionSummary {
+    pub group_key: String,
+    pub group_name: String,
+    pub voucher_count: i64,
+    pub used_quantity: f64,
+    pub damaged_quantity: f64,
+    pub total_quantity: f64,
+    pub total_value: Option<f64>,
+    pub unvalued_quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MasterReference {
+    pub label: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MasterUsage {
+    pub entity: String,
+    pub id: i64,
+    pub name: String,
+    pub in_use: bool,
+    pub references: Vec<MasterReference>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum DeleteOutcome {
+    Deleted,
+    Deactivated,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryTotal {
+    pub category_id: Option<i64>,
+    pub category_name: String,
+    pub parent_id: Option<i64>,
+    pub path: String,
+    pub depth: i64,
+    pub item_count: i64,
+    pub total_quantity: f64,
+    pub total_value: Option<f64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StockMovement {
     pub id: Option<i64>,
@@ -137,11 +500,114 @@ pub struct StockMovementHistory {
     pub created_at: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseRegisterEntry {
+    pub voucher_id: i64,
+    pub transaction_number: String,
+    pub voucher_date: String,
+    pub supplier_id: Option<i64>,
+    pub supplier_name: Option<String>,
+    pub bill_number: Option<String>,
+    pub bill_date: Option<String>,
+    pub item_id: i64,
+    pub item_code: String,
+    pub item_name: String,
+    pub brand_name: Option<String>,
+    pub model_name: Option<String>,
+    pub site_id: Option<i64>,
+    pub site_name: Option<String>,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurchaseSummary {
+    pub group_key: String,
+    pub group_name: String,
+    pub voucher_count: i64,
+    pub total_quantity: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DashboardStats {
     pub active_items_count: i64,
     pub active_sites_count: i64,
     pub recent_transactions_count: i64,
+    pub fiscal_year_start: String,
+    pub fiscal_year_transactions_count: i64,
+}
+
+// A problem found by check_integrity. Repairable issues are fixed by rebuild_stock_movements.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub check: String,
+    pub message: String,
+    pub voucher_id: Option<i64>,
+    pub repairable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub is_ok: bool,
+    pub voucher_count: i64,
+    pub movement_count: i64,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+// Company profile and application preferences. Every field has a default, so a partial payload
+// or a database without stored settings still yields a complete set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub company_name: String,
+    pub company_address: Option<String>,
+    pub company_phone: Option<String>,
+    pub company_email: Option<String>,
+    pub gstin: Option<String>,
+    // data:image/png or data:image/jpeg URL
+    pub logo: Option<String>,
+    pub date_format: String,
+    pub default_godown_id: Option<i64>,
+    // 1 = January ... 12 = December
+    pub fiscal_year_start_month: u32,
+    pub quantity_decimals: u32,
+    // Prepended to new voucher numbers, e.g. "SS-" gives SS-1, SS-2, ...
+    pub voucher_number_prefix: Option<String>,
+    pub daily_backup: bool,
+    // Automatic backups kept; older ones are deleted after each new automatic backup
+    pub backup_retention_count: u32,
+    pub compress_backups: bool,
+    // The HTTP API serves the open company; see api_server.rs
+    pub api_enabled: bool,
+    pub api_port: u16,
+    // Listen on every network interface instead of only this computer
+    pub api_allow_lan: bool,
+    // Bearer token clients must send; generated when the API is enabled without one
+    pub api_token: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            company_name: "My Company".to_string(),
+            company_address: None,
+            company_phone: None,
+            company_email: None,
+            gstin: None,
+            logo: None,
+            date_format: "DD-MM-YYYY".to_string(),
+            default_godown_id: None,
+            fiscal_year_start_month: 4,
+            quantity_decimals: 2,
+            voucher_number_prefix: None,
+            daily_backup: true,
+            backup_retention_count: 7,
+            compress_backups: true,
+            api_enabled: false,
+            api_port: 8787,
+            api_allow_lan: false,
+            api_token: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -150,46 +616,68 @@ pub struct ImportItem {
     pub name: String,
     pub brand_name: String,
     pub model_name: String,
+    pub attributes: Option<AttributeValues>,
 }
 
 // ============================================================================
 // Database Connection
 // ============================================================================
 
+pub const DB_FILE_NAME: &str = "stock-star.db";
+
+// Stored in PRAGMA user_version. Bump whenever create_tables or migrate_tables change the schema.
+pub const SCHEMA_VERSION: i64 = 2;
+
+// Folder of the active company: its database, attachments and generated documents live here
+pub fn company_data_dir(app: &AppHandle) -> PathBuf {
+    crate::companies::active_data_dir(app)
+}
+
+// Encrypted databases are opened with the passphrase they were unlocked with
 pub fn get_db_conn(app: &AppHandle) -> Result<Connection> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .expect("failed to get app data dir");
-    let db_path = app_data_dir.join("stock-star.db");
-    Connection::open(db_path)
+    let db_path = company_data_dir(app).join(DB_FILE_NAME);
+    if crate::encryption::is_locked(app) {
+        return Err(validation_error(
+            "The database is locked; unlock it with its passphrase",
+        ));
+    }
+    crate::encryption::open_database(&db_path, crate::encryption::passphrase(app).as_deref())
 }
 
 // ============================================================================
 // Database Initialization
 // ============================================================================
 
-pub fn init_db(app: &AppHandle) -> Result<()> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .expect("failed to get app data dir");
-
-    if !app_data_dir.exists() {
-        std::fs::create_dir_all(&app_data_dir).expect("failed to create app data dir");
-    }
+// Create or upgrade the database in a company folder, unlocking it first when it is encrypted
+pub fn init_company_db(data_dir: &Path, passphrase: Option<&str>) -> Result<()> {
+    std::fs::create_dir_all(data_dir).map_err(|e| {
+        validation_error(format!(
+            "Could not create the company folder {}: {}",
+            data_dir.display(),
+            e
+        ))
+    })?;
 
-    let db_path = app_data_dir.join("stock-star.db");
-    let conn = Connection::open(db_path)?;
+    let db_path = data_dir.join(DB_FILE_NAME);
+    let conn = crate::encryption::open_database(&db_path, passphrase)?;
+    prepare_database(&conn)
+}
 
+// Bring an open connection to the current schema
+pub fn prepare_database(conn: &Connection) -> Result<()> {
     // Enable foreign keys
     conn.execute("PRAGMA foreign_keys = ON;", [])?;
 
     // Create tables
-    create_tables(&conn)?;
+    create_tables(conn)?;
+
+    // Add columns introduced after the first release
+    migrate_tables(conn)?;
 
     // Seed initial data
-    seed_transaction_types(&conn)?;
+    seed_transaction_types(conn)?;
+
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
 
     Ok(())
 }
@@ -225,6 +713,41 @@ fn create_tables(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_categories (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            parent_id INTEGER,
+            FOREIGN KEY(parent_id) REFERENCES item_categories(id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_components (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kit_item_id INTEGER NOT NULL,
+            component_item_id INTEGER NOT NULL,
+            quantity REAL NOT NULL,
+            FOREIGN KEY(kit_item_id) REFERENCES items(id),
+            FOREIGN KEY(component_item_id) REFERENCES items(id),
+            UNIQUE(kit_item_id, component_item_id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_barcodes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL,
+            barcode TEXT NOT NULL UNIQUE,
+            barcode_type TEXT NOT NULL DEFAULT 'Internal',
+            is_primary BOOLEAN NOT NULL DEFAULT 0,
+            FOREIGN KEY(item_id) REFERENCES items(id)
+        )",
+        [],
+    )?;
+
     conn.execute(
         "CREATE TABLE IF NOT EXISTS users (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -247,6 +770,103 @@ fn create_tables(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS suppliers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            code TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            gstin TEXT,
+            contact_person TEXT,
+            phone TEXT,
+            email TEXT,
+            address TEXT,
+            is_active BOOLEAN DEFAULT 1
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS purchase_orders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            po_number TEXT NOT NULL UNIQUE,
+            po_date TEXT NOT NULL,
+            supplier_id INTEGER NOT NULL,
+            delivery_site_id INTEGER,
+            expected_date TEXT,
+            status TEXT NOT NULL DEFAULT 'Open',
+            remarks TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            created_by INTEGER,
+            updated_at DATETIME,
+            FOREIGN KEY(supplier_id) REFERENCES suppliers(id),
+            FOREIGN KEY(delivery_site_id) REFERENCES sites(id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS purchase_order_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            purchase_order_id INTEGER NOT NULL,
+            item_id INTEGER NOT NULL,
+            ordered_quantity REAL NOT NULL,
+            rate REAL,
+            FOREIGN KEY(purchase_order_id) REFERENCES purchase_orders(id),
+            FOREIGN KEY(item_id) REFERENCES items(id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS material_requisitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            requisition_number TEXT NOT NULL UNIQUE,
+            requisition_date TEXT NOT NULL,
+            site_id INTEGER NOT NULL,
+            needed_by TEXT,
+            status TEXT NOT NULL DEFAULT 'Pending',
+            requested_by TEXT,
+            remarks TEXT,
+            approved_by INTEGER,
+            approved_at DATETIME,
+            rejection_reason TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            created_by INTEGER,
+            updated_at DATETIME,
+            FOREIGN KEY(site_id) REFERENCES sites(id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS material_requisition_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            requisition_id INTEGER NOT NULL,
+            item_id INTEGER NOT NULL,
+            requested_quantity REAL NOT NULL,
+            approved_quantity REAL,
+            FOREIGN KEY(requisition_id) REFERENCES material_requisitions(id),
+            FOREIGN KEY(item_id) REFERENCES items(id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            code TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            site_id INTEGER NOT NULL,
+            start_date TEXT,
+            end_date TEXT,
+            status TEXT NOT NULL DEFAULT 'Active',
+            description TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(site_id) REFERENCES sites(id)
+        )",
+        [],
+    )?;
+
     conn.execute(
         "CREATE TABLE IF NOT EXISTS inventory_transaction_types (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -307,8 +927,58 @@ fn create_tables(conn: &Connection) -> Result<()> {
     )?;
 
     conn.execute(
-        "CREATE TABLE IF NOT EXISTS system_metadata (
-            key TEXT PRIMARY KEY,
+        "CREATE TABLE IF NOT EXISTS attribute_definitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity TEXT NOT NULL,
+            name TEXT NOT NULL,
+            data_type TEXT NOT NULL DEFAULT 'text',
+            is_required BOOLEAN NOT NULL DEFAULT 0,
+            options TEXT,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            is_active BOOLEAN NOT NULL DEFAULT 1,
+            UNIQUE(entity, name)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attribute_values (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            definition_id INTEGER NOT NULL,
+            entity_id INTEGER NOT NULL,
+            value TEXT NOT NULL,
+            FOREIGN KEY(definition_id) REFERENCES attribute_definitions(id),
+            UNIQUE(definition_id, entity_id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS voucher_attachments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            voucher_id INTEGER NOT NULL,
+            file_name TEXT NOT NULL,
+            stored_name TEXT NOT NULL,
+            mime_type TEXT,
+            size_bytes INTEGER NOT NULL,
+            description TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(voucher_id) REFERENCES inventory_vouchers(id)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS system_metadata (
+            key TEXT PRIMARY KEY,
             value TEXT NOT NULL
         )",
         [],
@@ -317,6 +987,380 @@ fn create_tables(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+fn migrate_tables(conn: &Connection) -> Result<()> {
+    // Supplier and bill reference on purchase vouchers
+    add_column_if_missing(
+        conn,
+        "inventory_vouchers",
+        "supplier_id",
+        "INTEGER REFERENCES suppliers(id)",
+    )?;
+    add_column_if_missing(conn, "inventory_vouchers", "bill_number", "TEXT")?;
+    add_column_if_missing(conn, "inventory_vouchers", "bill_date", "TEXT")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_inventory_vouchers_supplier
+         ON inventory_vouchers(supplier_id, bill_number)",
+        [],
+    )?;
+
+    // Goods receipt against purchase orders
+    add_column_if_missing(
+        conn,
+        "inventory_vouchers",
+        "purchase_order_id",
+        "INTEGER REFERENCES purchase_orders(id)",
+    )?;
+    add_column_if_missing(
+        conn,
+        "inventory_voucher_items",
+        "purchase_order_item_id",
+        "INTEGER REFERENCES purchase_order_items(id)",
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_inventory_voucher_items_po_item
+         ON inventory_voucher_items(purchase_order_item_id)",
+        [],
+    )?;
+
+    // Site transfers fulfilling material requisitions
+    add_column_if_missing(
+        conn,
+        "inventory_vouchers",
+        "requisition_id",
+        "INTEGER REFERENCES material_requisitions(id)",
+    )?;
+    add_column_if_missing(
+        conn,
+        "inventory_voucher_items",
+        "requisition_item_id",
+        "INTEGER REFERENCES material_requisition_items(id)",
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_inventory_voucher_items_requisition_item
+         ON inventory_voucher_items(requisition_item_id)",
+        [],
+    )?;
+
+    // Returns against an original voucher and its lines
+    add_column_if_missing(
+        conn,
+        "inventory_vouchers",
+        "original_voucher_id",
+        "INTEGER REFERENCES inventory_vouchers(id)",
+    )?;
+    add_column_if_missing(
+        conn,
+        "inventory_voucher_items",
+        "original_voucher_item_id",
+        // Deferred so an edited voucher can re-insert its lines under the same ids
+        "INTEGER REFERENCES inventory_voucher_items(id) DEFERRABLE INITIALLY DEFERRED",
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_inventory_voucher_items_original_item
+         ON inventory_voucher_items(original_voucher_item_id)",
+        [],
+    )?;
+
+    // Usage and damage booked to projects
+    add_column_if_missing(
+        conn,
+        "inventory_vouchers",
+        "project_id",
+        "INTEGER REFERENCES projects(id)",
+    )?;
+    add_column_if_missing(conn, "inventory_vouchers", "activity_code", "TEXT")?;
+
+    // Item category tree
+    add_column_if_missing(
+        conn,
+        "items",
+        "category_id",
+        "INTEGER REFERENCES item_categories(id)",
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_items_category ON items(category_id)",
+        [],
+    )?;
+
+    // Models optionally belong to a brand. When the column is first added, models whose
+    // items all carry the same brand are assigned to it.
+    if add_column_if_missing(conn, "models", "brand_id", "INTEGER REFERENCES brands(id)")? {
+        conn.execute(
+            "UPDATE models SET brand_id = (
+                SELECT MIN(i.brand_id) FROM items i WHERE i.model_id = models.id
+             )
+             WHERE (
+                SELECT COUNT(DISTINCT i.brand_id) FROM items i
+                WHERE i.model_id = models.id AND i.brand_id IS NOT NULL
+             ) = 1
+             AND NOT EXISTS (
+                SELECT 1 FROM items i WHERE i.model_id = models.id AND i.brand_id IS NULL
+             )",
+            [],
+        )?;
+    }
+
+    // Brands and models can be deactivated like items and sites
+    add_column_if_missing(conn, "brands", "is_active", "BOOLEAN DEFAULT 1")?;
+    add_column_if_missing(conn, "models", "is_active", "BOOLEAN DEFAULT 1")?;
+
+    // Component lines exploded from a kit
+    add_column_if_missing(
+        conn,
+        "inventory_voucher_items",
+        "kit_item_id",
+        "INTEGER REFERENCES items(id)",
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_inventory_vouchers_project
+         ON inventory_vouchers(project_id, activity_code)",
+        [],
+    )?;
+
+    // Alternate names items are known by, searchable alongside code and name
+    add_column_if_missing(conn, "items", "aliases", "TEXT")?;
+    create_item_search_index(conn)?;
+
+    // External references on vouchers (delivery challan, vehicle, driver, receiver) and line notes
+    add_column_if_missing(conn, "inventory_vouchers", "challan_number", "TEXT")?;
+    add_column_if_missing(conn, "inventory_vouchers", "vehicle_number", "TEXT")?;
+    add_column_if_missing(conn, "inventory_vouchers", "driver_name", "TEXT")?;
+    add_column_if_missing(conn, "inventory_vouchers", "receiver_name", "TEXT")?;
+    add_column_if_missing(conn, "inventory_voucher_items", "remarks", "TEXT")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_voucher_attachments_voucher
+         ON voucher_attachments(voucher_id)",
+        [],
+    )?;
+
+    // Ids that are the same on every node and a log of changes to send to other nodes
+    create_change_log(conn)?;
+
+    Ok(())
+}
+
+// Tables whose rows are synced between nodes by sync.rs
+pub const SYNCED_TABLES: &[&str] = &[
+    "item_categories",
+    "brands",
+    "models",
+    "sites",
+    "suppliers",
+    "projects",
+    "items",
+    "inventory_vouchers",
+];
+
+// Every synced row gets a uuid, filled in by trigger when a row is inserted without one. Triggers
+// record each insert, update and delete in change_log under this database's node id; sync.rs
+// reassigns the changes it imports to the node they came from.
+fn create_change_log(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS change_log (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity TEXT NOT NULL,
+            entity_uuid TEXT NOT NULL,
+            operation TEXT NOT NULL, -- 'upsert' or 'delete'
+            node_id TEXT NOT NULL,
+            changed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_change_log_entity ON change_log(entity, entity_uuid);
+
+        CREATE TABLE IF NOT EXISTS sync_peers (
+            node_id TEXT PRIMARY KEY,
+            node_name TEXT NOT NULL,
+            last_exported_seq INTEGER NOT NULL DEFAULT 0,
+            last_exported_at DATETIME,
+            last_imported_seq INTEGER NOT NULL DEFAULT 0,
+            last_imported_at DATETIME
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_conflicts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity TEXT NOT NULL,
+            entity_uuid TEXT NOT NULL,
+            node_id TEXT NOT NULL,
+            message TEXT NOT NULL,
+            local_values TEXT,
+            remote_values TEXT,
+            detected_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            resolved_at DATETIME
+        );
+
+        INSERT OR IGNORE INTO system_metadata (key, value)
+        VALUES ('node_id', lower(hex(randomblob(16))));",
+    )?;
+
+    for table in SYNCED_TABLES {
+        add_column_if_missing(conn, table, "uuid", "TEXT")?;
+        conn.execute_batch(&format!(
+            "UPDATE {table} SET uuid = lower(hex(randomblob(16))) WHERE uuid IS NULL;
+
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_{table}_uuid ON {table}(uuid);
+
+            CREATE TRIGGER IF NOT EXISTS {table}_log_insert AFTER INSERT ON {table} BEGIN
+                UPDATE {table} SET uuid = lower(hex(randomblob(16)))
+                WHERE id = new.id AND uuid IS NULL;
+                INSERT INTO change_log (entity, entity_uuid, operation, node_id)
+                SELECT '{table}', uuid, 'upsert',
+                    (SELECT value FROM system_metadata WHERE key = 'node_id')
+                FROM {table} WHERE id = new.id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS {table}_log_update AFTER UPDATE ON {table}
+            WHEN old.uuid IS NOT NULL BEGIN
+                INSERT INTO change_log (entity, entity_uuid, operation, node_id)
+                VALUES ('{table}', new.uuid, 'upsert',
+                    (SELECT value FROM system_metadata WHERE key = 'node_id'));
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS {table}_log_delete AFTER DELETE ON {table} BEGIN
+                INSERT INTO change_log (entity, entity_uuid, operation, node_id)
+                VALUES ('{table}', old.uuid, 'delete',
+                    (SELECT value FROM system_metadata WHERE key = 'node_id'));
+            END;",
+            table = table
+        ))?;
+    }
+
+    // Voucher lines travel inside their voucher but carry a uuid of their own, so a synced edit
+    // can tell which line is which and a return can name the line it returns
+    add_column_if_missing(conn, "inventory_voucher_items", "uuid", "TEXT")?;
+    conn.execute_batch(
+        "UPDATE inventory_voucher_items SET uuid = lower(hex(randomblob(16))) WHERE uuid IS NULL;
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_inventory_voucher_items_uuid
+            ON inventory_voucher_items(uuid);
+
+        CREATE TRIGGER IF NOT EXISTS inventory_voucher_items_uuid
+        AFTER INSERT ON inventory_voucher_items WHEN new.uuid IS NULL BEGIN
+            UPDATE inventory_voucher_items SET uuid = lower(hex(randomblob(16))) WHERE id = new.id;
+        END;",
+    )?;
+
+    Ok(())
+}
+
+// Full-text index over item code, name, brand, model and aliases (rowid = item id).
+// Triggers keep it in sync with every write to items and with brand/model renames.
+fn create_item_search_index(conn: &Connection) -> Result<()> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'item_search')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS item_search USING fts5(
+            code, name, brand, model, aliases,
+            tokenize = 'unicode61 remove_diacritics 2',
+            prefix = '2 3'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS item_search_insert AFTER INSERT ON items BEGIN
+            INSERT INTO item_search (rowid, code, name, brand, model, aliases)
+            SELECT new.id, new.code, new.name,
+                (SELECT name FROM brands WHERE id = new.brand_id),
+                (SELECT name FROM models WHERE id = new.model_id),
+                new.aliases;
+        END;
+
+        -- Only the indexed columns: the change log fills in the uuid of a row that is still being
+        -- inserted, which must not index it a second time
+        DROP TRIGGER IF EXISTS item_search_update;
+        CREATE TRIGGER item_search_update
+        AFTER UPDATE OF code, name, brand_id, model_id, aliases ON items BEGIN
+            DELETE FROM item_search WHERE rowid = old.id;
+            INSERT INTO item_search (rowid, code, name, brand, model, aliases)
+            SELECT new.id, new.code, new.name,
+                (SELECT name FROM brands WHERE id = new.brand_id),
+                (SELECT name FROM models WHERE id = new.model_id),
+                new.aliases;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS item_search_delete AFTER DELETE ON items BEGIN
+            DELETE FROM item_search WHERE rowid = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS item_search_brand_rename AFTER UPDATE OF name ON brands BEGIN
+            UPDATE item_search SET brand = new.name
+            WHERE rowid IN (SELECT id FROM items WHERE brand_id = new.id);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS item_search_model_rename AFTER UPDATE OF name ON models BEGIN
+            UPDATE item_search SET model = new.name
+            WHERE rowid IN (SELECT id FROM items WHERE model_id = new.id);
+        END;",
+    )?;
+
+    if !exists {
+        conn.execute(
+            "INSERT INTO item_search (rowid, code, name, brand, model, aliases)
+             SELECT i.id, i.code, i.name, b.name, m.name, i.aliases
+             FROM items i
+             LEFT JOIN brands b ON i.brand_id = b.id
+             LEFT JOIN models m ON i.model_id = m.id",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Returns true when the column was added by this call
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?;
+
+    if columns.iter().any(|c| c == column) {
+        return Ok(false);
+    }
+
+    conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+        [],
+    )?;
+    Ok(true)
+}
+
+// Builds an error for business rule violations. It is reported as a
+// constraint failure so callers see the message instead of a generic error.
+pub fn validation_error(message: impl Into<String>) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+        Some(message.into()),
+    )
+}
+
+// Runs part of a transaction so that a failure only undoes that part
+pub fn with_savepoint<T>(tx: &rusqlite::Transaction, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    tx.execute_batch("SAVEPOINT partial")?;
+    match f() {
+        Ok(value) => {
+            tx.execute_batch("RELEASE partial")?;
+            Ok(value)
+        }
+        Err(e) => {
+            tx.execute_batch("ROLLBACK TO partial; RELEASE partial")?;
+            Err(e)
+        }
+    }
+}
+
 fn seed_transaction_types(conn: &Connection) -> Result<()> {
     let types = [
         "Purchase Inward",
@@ -327,6 +1371,9 @@ fn seed_transaction_types(conn: &Connection) -> Result<()> {
         "Material Usage",
         "Stock Adjustment",
         "Damaged Stock",
+        "Purchase Return",
+        "Return from Site",
+        "Kit Assembly",
     ];
     for t in types {
         // Use INSERT OR IGNORE to be safe, ensuring missing types are added
@@ -339,60 +1386,278 @@ fn seed_transaction_types(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+// ============================================================================
+// Master List Helpers
+// ============================================================================
+
+// LIKE pattern for the query's search text, if any
+fn list_search_pattern(query: &MasterListQuery) -> Option<String> {
+    query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| format!("%{}%", s))
+}
+
+// ORDER BY clause for a master list. Sort fields map onto the given columns (the first one is the
+// default) so that nothing from the request is spliced into the SQL; the id keeps paging stable.
+fn list_order_sql(
+    sort_by: Option<&str>,
+    sort_dir: Option<&str>,
+    columns: &[(&str, &str)],
+    id_column: &str,
+) -> Result<String> {
+    let column = match sort_by.filter(|s| !s.is_empty()) {
+        None => columns[0].1,
+        Some(field) => columns
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, column)| *column)
+            .ok_or_else(|| validation_error(format!("Cannot sort by '{}'", field)))?,
+    };
+    let direction = sort_direction(sort_dir)?;
+    Ok(format!(
+        "ORDER BY {} {}, {} {}",
+        column, direction, id_column, direction
+    ))
+}
+
+fn sort_direction(sort_dir: Option<&str>) -> Result<&'static str> {
+    match sort_dir {
+        None | Some("") => Ok("ASC"),
+        Some(dir) if dir.eq_ignore_ascii_case("asc") => Ok("ASC"),
+        Some(dir) if dir.eq_ignore_ascii_case("desc") => Ok("DESC"),
+        Some(dir) => Err(validation_error(format!(
+            "Invalid sort direction '{}'",
+            dir
+        ))),
+    }
+}
+
+fn list_page(query: &MasterListQuery) -> (i64, i64) {
+    match query.limit {
+        None | Some(-1) => (-1, 0),
+        Some(limit) => (limit, (query.page.unwrap_or(1).max(1) - 1) * limit),
+    }
+}
+
 // ============================================================================
 // Brand Operations
 // ============================================================================
 
 pub fn create_brand(app: &AppHandle, brand: Brand) -> Result<i64> {
     let conn = get_db_conn(app)?;
-    conn.execute("INSERT INTO brands (name) VALUES (?1)", params![brand.name])?;
+    conn.execute(
+        "INSERT INTO brands (name, is_active) VALUES (?1, COALESCE(?2, 1))",
+        params![brand.name, brand.is_active],
+    )?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn get_all_brands(app: &AppHandle) -> Result<Vec<Brand>> {
+pub fn get_all_brands(app: &AppHandle, query: MasterListQuery) -> Result<PaginatedResponse<Brand>> {
     let conn = get_db_conn(app)?;
-    let mut stmt = conn.prepare("SELECT id, name FROM brands")?;
-    let rows = stmt.query_map([], |row| {
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(search) = list_search_pattern(&query) {
+        where_clauses.push("name LIKE ?".to_string());
+        params_vec.push(Box::new(search));
+    }
+    if let Some(active) = query.is_active {
+        where_clauses.push("COALESCE(is_active, 1) = ?".to_string());
+        params_vec.push(Box::new(active));
+    }
+
+    let order = list_order_sql(
+        query.sort_by.as_deref(),
+        query.sort_dir.as_deref(),
+        &[
+            ("name", "name COLLATE NOCASE"),
+            ("is_active", "COALESCE(is_active, 1)"),
+        ],
+        "id",
+    )?;
+
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let total_count: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM brands WHERE {}",
+            where_clauses.join(" AND ")
+        ),
+        &param_refs[..],
+        |row| row.get(0),
+    )?;
+
+    let (limit_val, offset) = list_page(&query);
+    params_vec.push(Box::new(limit_val));
+    params_vec.push(Box::new(offset));
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, name, is_active FROM brands WHERE {} {} LIMIT ? OFFSET ?",
+        where_clauses.join(" AND "),
+        order
+    ))?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
         Ok(Brand {
             id: Some(row.get(0)?),
             name: row.get(1)?,
+            is_active: Some(row.get(2).unwrap_or(true)),
         })
     })?;
-    rows.collect()
+    let items = rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginatedResponse { items, total_count })
 }
 
 pub fn update_brand(app: &AppHandle, brand: Brand) -> Result<()> {
     let conn = get_db_conn(app)?;
     conn.execute(
-        "UPDATE brands SET name = ?1 WHERE id = ?2",
-        params![brand.name, brand.id],
+        "UPDATE brands SET name = ?1, is_active = COALESCE(?2, is_active) WHERE id = ?3",
+        params![brand.name, brand.is_active, brand.id],
     )?;
     Ok(())
 }
 
-pub fn delete_brand(app: &AppHandle, id: i64) -> Result<()> {
-    let conn = get_db_conn(app)?;
-    conn.execute("DELETE FROM brands WHERE id = ?1", params![id])?;
-    Ok(())
+pub fn delete_brand(app: &AppHandle, id: i64) -> Result<DeleteOutcome> {
+    delete_master(app, "brand", id)
 }
 
 // ============================================================================
 // Model Operations
 // ============================================================================
 
+// A model that belongs to a brand can only be used with that brand
+pub fn validate_brand_model(
+    conn: &Connection,
+    brand_id: Option<i64>,
+    model_id: Option<i64>,
+) -> Result<Option<i64>> {
+    let model_id = match model_id {
+        Some(mid) => mid,
+        None => return Ok(brand_id),
+    };
+
+    let (model_name, model_brand_id, model_brand_name): (String, Option<i64>, Option<String>) =
+        conn.query_row(
+            "SELECT m.name, m.brand_id, b.name
+             FROM models m
+             LEFT JOIN brands b ON m.brand_id = b.id
+             WHERE m.id = ?1",
+            params![model_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+    match (brand_id, model_brand_id) {
+        // Fill the brand from the model when the item has none
+        (None, model_brand) => Ok(model_brand),
+        (Some(bid), Some(model_brand)) if bid != model_brand => Err(validation_error(format!(
+            "Model '{}' belongs to brand '{}'",
+            model_name,
+            model_brand_name.unwrap_or_default()
+        ))),
+        (brand, _) => Ok(brand),
+    }
+}
+
 pub fn create_model(app: &AppHandle, model: Model) -> Result<i64> {
     let conn = get_db_conn(app)?;
-    conn.execute("INSERT INTO models (name) VALUES (?1)", params![model.name])?;
+    conn.execute(
+        "INSERT INTO models (name, brand_id, is_active) VALUES (?1, ?2, COALESCE(?3, 1))",
+        params![model.name, model.brand_id, model.is_active],
+    )?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn get_all_models(app: &AppHandle) -> Result<Vec<Model>> {
+pub fn get_all_models(app: &AppHandle, query: MasterListQuery) -> Result<PaginatedResponse<Model>> {
     let conn = get_db_conn(app)?;
-    let mut stmt = conn.prepare("SELECT id, name FROM models")?;
-    let rows = stmt.query_map([], |row| {
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(search) = list_search_pattern(&query) {
+        where_clauses.push("(m.name LIKE ? OR b.name LIKE ?)".to_string());
+        params_vec.push(Box::new(search.clone()));
+        params_vec.push(Box::new(search));
+    }
+    if let Some(bid) = query.brand_id {
+        where_clauses.push("m.brand_id = ?".to_string());
+        params_vec.push(Box::new(bid));
+    }
+    if let Some(active) = query.is_active {
+        where_clauses.push("COALESCE(m.is_active, 1) = ?".to_string());
+        params_vec.push(Box::new(active));
+    }
+
+    let order = list_order_sql(
+        query.sort_by.as_deref(),
+        query.sort_dir.as_deref(),
+        &[
+            ("name", "m.name COLLATE NOCASE"),
+            ("brand", "b.name COLLATE NOCASE"),
+            ("is_active", "COALESCE(m.is_active, 1)"),
+        ],
+        "m.id",
+    )?;
+
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let total_count: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM models m LEFT JOIN brands b ON m.brand_id = b.id WHERE {}",
+            where_clauses.join(" AND ")
+        ),
+        &param_refs[..],
+        |row| row.get(0),
+    )?;
+
+    let (limit_val, offset) = list_page(&query);
+    params_vec.push(Box::new(limit_val));
+    params_vec.push(Box::new(offset));
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT m.id, m.name, m.brand_id, b.name, m.is_active
+         FROM models m
+         LEFT JOIN brands b ON m.brand_id = b.id
+         WHERE {}
+         {}
+         LIMIT ? OFFSET ?",
+        where_clauses.join(" AND "),
+        order
+    ))?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        Ok(Model {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            brand_id: row.get(2)?,
+            brand_name: row.get(3)?,
+            is_active: Some(row.get(4).unwrap_or(true)),
+        })
+    })?;
+    let items = rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginatedResponse { items, total_count })
+}
+
+// Models that can be used with the brand: its own models and models not tied to any brand
+pub fn get_models_by_brand(app: &AppHandle, brand_id: i64) -> Result<Vec<Model>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare(
+        "SELECT m.id, m.name, m.brand_id, b.name, m.is_active
+         FROM models m
+         LEFT JOIN brands b ON m.brand_id = b.id
+         WHERE m.brand_id = ?1 OR m.brand_id IS NULL
+         ORDER BY m.name",
+    )?;
+    let rows = stmt.query_map(params![brand_id], |row| {
         Ok(Model {
             id: Some(row.get(0)?),
             name: row.get(1)?,
+            brand_id: row.get(2)?,
+            brand_name: row.get(3)?,
+            is_active: Some(row.get(4).unwrap_or(true)),
         })
     })?;
     rows.collect()
@@ -400,86 +1665,593 @@ pub fn get_all_models(app: &AppHandle) -> Result<Vec<Model>> {
 
 pub fn update_model(app: &AppHandle, model: Model) -> Result<()> {
     let conn = get_db_conn(app)?;
+
+    if let Some(bid) = model.brand_id {
+        let conflicting: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM items WHERE model_id = ?1 AND brand_id IS NOT NULL AND brand_id != ?2",
+            params![model.id, bid],
+            |row| row.get(0),
+        )?;
+        if conflicting > 0 {
+            return Err(validation_error(format!(
+                "{} item(s) use this model with a different brand",
+                conflicting
+            )));
+        }
+    }
+
     conn.execute(
-        "UPDATE models SET name = ?1 WHERE id = ?2",
-        params![model.name, model.id],
+        "UPDATE models SET name = ?1, brand_id = ?2, is_active = COALESCE(?3, is_active) WHERE id = ?4",
+        params![model.name, model.brand_id, model.is_active, model.id],
     )?;
     Ok(())
 }
 
-pub fn delete_model(app: &AppHandle, id: i64) -> Result<()> {
-    let conn = get_db_conn(app)?;
-    conn.execute("DELETE FROM models WHERE id = ?1", params![id])?;
-    Ok(())
+pub fn delete_model(app: &AppHandle, id: i64) -> Result<DeleteOutcome> {
+    delete_master(app, "model", id)
 }
 
 // ============================================================================
 // Item Operations
 // ============================================================================
 
-pub fn create_item(app: &AppHandle, item: Item) -> Result<i64> {
-    let conn = get_db_conn(app)?;
-    conn.execute(
-        "INSERT INTO items (code, name, brand_id, model_id, is_active) VALUES (?1, ?2, ?3, ?4, ?5)",
+pub fn create_item(app: &AppHandle, mut item: Item) -> Result<i64> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    item.brand_id = validate_brand_model(&tx, item.brand_id, item.model_id)?;
+    tx.execute(
+        "INSERT INTO items (code, name, brand_id, model_id, category_id, aliases, is_active) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         params![
             item.code,
             item.name,
             item.brand_id,
             item.model_id,
+            item.category_id,
+            item.aliases,
             item.is_active
         ],
     )?;
-    Ok(conn.last_insert_rowid())
-}
+    let item_id = tx.last_insert_rowid();
 
-pub fn get_all_items(app: &AppHandle) -> Result<Vec<Item>> {
-    let conn = get_db_conn(app)?;
-    let mut stmt = conn.prepare(
-        "SELECT 
-            i.id, i.code, i.name, i.brand_id, i.model_id, i.is_active,
-            b.name as brand_name,
-            m.name as model_name
-         FROM items i
-         LEFT JOIN brands b ON i.brand_id = b.id
-         LEFT JOIN models m ON i.model_id = m.id",
-    )?;
-    let rows = stmt.query_map([], |row| {
-        Ok(Item {
-            id: Some(row.get(0)?),
-            code: row.get(1)?,
-            name: row.get(2)?,
-            brand_id: row.get(3)?,
-            model_id: row.get(4)?,
-            is_active: row.get(5).unwrap_or(true),
-            brand_name: row.get(6)?,
-            model_name: row.get(7)?,
-        })
-    })?;
-    rows.collect()
+    if let Some(components) = &item.components {
+        save_item_components(&tx, item_id, components)?;
+    }
+    if let Some(barcodes) = &item.barcodes {
+        save_item_barcodes(&tx, item_id, barcodes)?;
+    }
+    save_attributes(&tx, "item", item_id, item.attributes.as_ref(), true)?;
+
+    tx.commit()?;
+    Ok(item_id)
 }
 
-pub fn update_item(app: &AppHandle, item: Item) -> Result<()> {
+// Items filtered by category (including subcategories), brand, model, status and attributes.
+// Search text goes through push_item_search; without an explicit sort, matches are ranked as in search_items.
+pub fn get_all_items(app: &AppHandle, query: MasterListQuery) -> Result<PaginatedResponse<Item>> {
     let conn = get_db_conn(app)?;
-    conn.execute(
-        "UPDATE items SET code = ?1, name = ?2, brand_id = ?3, model_id = ?4, is_active = ?5 WHERE id = ?6",
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    let search_text = query.search.as_deref().unwrap_or("").trim().to_string();
+    let search = push_item_search(&conn, &search_text, &mut where_clauses, &mut params_vec)?;
+
+    if let Some(cid) = query.category_id {
+        where_clauses.push(items_in_category_sql("i.id"));
+        params_vec.push(Box::new(cid));
+    }
+    if let Some(bid) = query.brand_id {
+        where_clauses.push("i.brand_id = ?".to_string());
+        params_vec.push(Box::new(bid));
+    }
+    if let Some(mid) = query.model_id {
+        where_clauses.push("i.model_id = ?".to_string());
+        params_vec.push(Box::new(mid));
+    }
+    if let Some(active) = query.is_active {
+        where_clauses.push("i.is_active = ?".to_string());
+        params_vec.push(Box::new(active));
+    }
+    push_attribute_filters(
+        "item",
+        "i.id",
+        &query.attributes,
+        &mut where_clauses,
+        &mut params_vec,
+    );
+
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let total_count: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM items i WHERE {}",
+            where_clauses.join(" AND ")
+        ),
+        &param_refs[..],
+        |row| row.get(0),
+    )?;
+
+    // Substring matches have no bm25 score and are ordered by name
+    let order = if !search_text.is_empty() && query.sort_by.as_deref().unwrap_or("").is_empty() {
+        params_vec.push(Box::new(search_text));
+        match search {
+            Some(search) => {
+                params_vec.push(Box::new(search));
+                "ORDER BY (i.code = ? COLLATE NOCASE) DESC,
+                    COALESCE((SELECT bm25(item_search, 10.0, 5.0, 2.0, 2.0, 3.0) FROM item_search
+                        WHERE item_search MATCH ? AND rowid = i.id), 0),
+                    i.name"
+                    .to_string()
+            }
+            None => "ORDER BY (i.code = ? COLLATE NOCASE) DESC, i.name".to_string(),
+        }
+    } else {
+        list_order_sql(
+            query.sort_by.as_deref(),
+            query.sort_dir.as_deref(),
+            &[
+                ("name", "i.name COLLATE NOCASE"),
+                ("code", "i.code COLLATE NOCASE"),
+                ("brand", "b.name COLLATE NOCASE"),
+                ("model", "m.name COLLATE NOCASE"),
+                ("category", "cat.name COLLATE NOCASE"),
+                ("is_active", "i.is_active"),
+            ],
+            "i.id",
+        )?
+    };
+
+    let (limit_val, offset) = list_page(&query);
+    params_vec.push(Box::new(limit_val));
+    params_vec.push(Box::new(offset));
+
+    let items = query_items(
+        &conn,
+        "",
+        where_clauses,
+        &format!("{} LIMIT ? OFFSET ?", order),
+        params_vec,
+    )?;
+
+    Ok(PaginatedResponse { items, total_count })
+}
+
+// Items matching the where clauses; joins and the trailing ORDER BY/LIMIT are spliced in as given
+fn query_items(
+    conn: &Connection,
+    joins: &str,
+    where_clauses: Vec<String>,
+    tail: &str,
+    params_vec: Vec<Box<dyn rusqlite::ToSql>>,
+) -> Result<Vec<Item>> {
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let item_attributes = load_attributes(conn, "item")?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT 
+            i.id, i.code, i.name, i.brand_id, i.model_id, i.is_active,
+            b.name as brand_name,
+            m.name as model_name,
+            EXISTS (SELECT 1 FROM item_components c WHERE c.kit_item_id = i.id) as is_kit,
+            i.category_id,
+            cat.name as category_name,
+            i.aliases
+         FROM items i
+         {}
+         LEFT JOIN brands b ON i.brand_id = b.id
+         LEFT JOIN models m ON i.model_id = m.id
+         LEFT JOIN item_categories cat ON i.category_id = cat.id
+         WHERE {}
+         {}",
+        joins,
+        where_clauses.join(" AND "),
+        tail
+    ))?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        let id: i64 = row.get(0)?;
+        Ok(Item {
+            id: Some(id),
+            code: row.get(1)?,
+            name: row.get(2)?,
+            brand_id: row.get(3)?,
+            model_id: row.get(4)?,
+            is_active: row.get(5).unwrap_or(true),
+            brand_name: row.get(6)?,
+            model_name: row.get(7)?,
+            category_id: row.get(9)?,
+            category_name: row.get(10)?,
+            aliases: row.get(11)?,
+            is_kit: Some(row.get(8)?),
+            components: None, // Loaded on demand via get_item_components
+            barcodes: None,   // Loaded on demand via get_item_barcodes
+            attributes: Some(item_attributes.get(&id).cloned().unwrap_or_default()),
+        })
+    })?;
+    rows.collect()
+}
+
+// Items whose words start with every search word, through the FTS index. When the index finds
+// nothing, items whose code or name contains the text as typed, so "ment" still finds Cement and
+// "012" finds CEM-0012 without scanning every row on each search. Returns the FTS query for ranking.
+fn push_item_search(
+    conn: &Connection,
+    text: &str,
+    where_clauses: &mut Vec<String>,
+    params_vec: &mut Vec<Box<dyn rusqlite::ToSql>>,
+) -> Result<Option<String>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    if let Some(search) = item_search_query(text) {
+        let found: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM item_search WHERE item_search MATCH ?1)",
+            params![search],
+            |row| row.get(0),
+        )?;
+        if found {
+            where_clauses.push(
+                "i.id IN (SELECT rowid FROM item_search WHERE item_search MATCH ?)".to_string(),
+            );
+            params_vec.push(Box::new(search.clone()));
+            return Ok(Some(search));
+        }
+    }
+
+    let pattern = format!(
+        "%{}%",
+        text.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    where_clauses.push("(i.code LIKE ? ESCAPE '\\' OR i.name LIKE ? ESCAPE '\\')".to_string());
+    params_vec.push(Box::new(pattern.clone()));
+    params_vec.push(Box::new(pattern));
+    Ok(None)
+}
+
+// Turn free text into an FTS5 query: every word must match the start of a word in any column
+fn item_search_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+// Ranked, paginated item search over code, name, brand, model and aliases.
+// An exact code match always comes first; the rest are ordered by bm25 with code and name weighted highest.
+pub fn search_items(
+    app: &AppHandle,
+    query: String,
+    filters: Option<ItemSearchFilters>,
+    page: i64,
+    limit: i64,
+) -> Result<PaginatedResponse<Item>> {
+    let filters = filters.unwrap_or_default();
+    get_all_items(
+        app,
+        MasterListQuery {
+            search: Some(query),
+            is_active: filters.is_active,
+            brand_id: filters.brand_id,
+            model_id: filters.model_id,
+            category_id: filters.category_id,
+            attributes: filters.attributes,
+            page: Some(page),
+            limit: Some(limit),
+            ..Default::default()
+        },
+    )
+}
+
+// Components are replaced only when the payload carries them
+pub fn update_item(app: &AppHandle, mut item: Item) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    item.brand_id = validate_brand_model(&tx, item.brand_id, item.model_id)?;
+    tx.execute(
+        "UPDATE items SET code = ?1, name = ?2, brand_id = ?3, model_id = ?4, category_id = ?5, aliases = ?6, is_active = ?7 WHERE id = ?8",
         params![
             item.code,
             item.name,
             item.brand_id,
             item.model_id,
+            item.category_id,
+            item.aliases,
             item.is_active,
             item.id
         ],
     )?;
+
+    if let (Some(item_id), Some(components)) = (item.id, &item.components) {
+        save_item_components(&tx, item_id, components)?;
+    }
+    if let (Some(item_id), Some(barcodes)) = (item.id, &item.barcodes) {
+        save_item_barcodes(&tx, item_id, barcodes)?;
+    }
+    if let Some(item_id) = item.id {
+        save_attributes(&tx, "item", item_id, item.attributes.as_ref(), false)?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn delete_item(app: &AppHandle, id: i64) -> Result<DeleteOutcome> {
+    delete_master(app, "item", id)
+}
+
+// Replace the bill of materials of a kit. An empty list makes the item a plain item again.
+fn save_item_components(
+    tx: &rusqlite::Transaction,
+    kit_item_id: i64,
+    components: &[ItemComponent],
+) -> Result<()> {
+    for (index, component) in components.iter().enumerate() {
+        if component.quantity <= 0.0 {
+            return Err(validation_error(
+                "Component quantities must be greater than zero",
+            ));
+        }
+        if component.component_item_id == kit_item_id {
+            return Err(validation_error("A kit cannot contain itself"));
+        }
+        if components[..index]
+            .iter()
+            .any(|c| c.component_item_id == component.component_item_id)
+        {
+            return Err(validation_error("Each component can only be listed once"));
+        }
+
+        let nested: Option<String> = tx
+            .query_row(
+                "SELECT i.code FROM items i
+                 WHERE i.id = ?1 AND EXISTS (SELECT 1 FROM item_components c WHERE c.kit_item_id = i.id)",
+                params![component.component_item_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(code) = nested {
+            return Err(validation_error(format!(
+                "Item {} is itself a kit and cannot be a component",
+                code
+            )));
+        }
+    }
+
+    if !components.is_empty() {
+        let used_as_component: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM item_components WHERE component_item_id = ?1",
+            params![kit_item_id],
+            |row| row.get(0),
+        )?;
+        if used_as_component > 0 {
+            return Err(validation_error(
+                "The item is a component of another kit and cannot have components itself",
+            ));
+        }
+    }
+
+    tx.execute(
+        "DELETE FROM item_components WHERE kit_item_id = ?1",
+        params![kit_item_id],
+    )?;
+    for component in components {
+        tx.execute(
+            "INSERT INTO item_components (kit_item_id, component_item_id, quantity) VALUES (?1, ?2, ?3)",
+            params![kit_item_id, component.component_item_id, component.quantity],
+        )?;
+    }
+
     Ok(())
 }
 
-pub fn delete_item(app: &AppHandle, id: i64) -> Result<()> {
+pub fn get_item_components(app: &AppHandle, kit_item_id: i64) -> Result<Vec<ItemComponent>> {
     let conn = get_db_conn(app)?;
-    conn.execute("DELETE FROM items WHERE id = ?1", params![id])?;
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.kit_item_id, c.component_item_id, i.code, i.name, c.quantity
+         FROM item_components c
+         JOIN items i ON c.component_item_id = i.id
+         WHERE c.kit_item_id = ?1
+         ORDER BY c.id",
+    )?;
+    let rows = stmt.query_map(params![kit_item_id], |row| {
+        Ok(ItemComponent {
+            id: Some(row.get(0)?),
+            kit_item_id: Some(row.get(1)?),
+            component_item_id: row.get(2)?,
+            component_code: row.get(3)?,
+            component_name: row.get(4)?,
+            quantity: row.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// Manufacturer barcodes are GTINs (EAN-8, UPC-A, EAN-13, GTIN-14) with a valid check digit.
+// Our own barcodes can be any printable ASCII that Code128 can encode.
+fn validate_barcode(barcode: &ItemBarcode) -> Result<()> {
+    match barcode.barcode_type.as_str() {
+        "EAN" => {
+            let digits: Vec<u32> = barcode
+                .barcode
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .collect();
+            if digits.len() != barcode.barcode.len() || ![8, 12, 13, 14].contains(&digits.len()) {
+                return Err(validation_error(format!(
+                    "Barcode {} must be 8, 12, 13 or 14 digits",
+                    barcode.barcode
+                )));
+            }
+            let (body, check) = digits.split_at(digits.len() - 1);
+            let sum: u32 = body
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d })
+                .sum();
+            if (10 - sum % 10) % 10 != check[0] {
+                return Err(validation_error(format!(
+                    "Barcode {} has an invalid check digit",
+                    barcode.barcode
+                )));
+            }
+        }
+        "Internal" => {
+            if barcode.barcode.len() > 48
+                || !barcode
+                    .barcode
+                    .chars()
+                    .all(|c| c.is_ascii_graphic() || c == ' ')
+            {
+                return Err(validation_error(format!(
+                    "Barcode {} must be at most 48 printable ASCII characters",
+                    barcode.barcode
+                )));
+            }
+        }
+        other => {
+            return Err(validation_error(format!(
+                "Unknown barcode type '{}'",
+                other
+            )))
+        }
+    }
+    Ok(())
+}
+
+// Replace the barcodes of an item. The first barcode is primary unless one is marked.
+fn save_item_barcodes(
+    tx: &rusqlite::Transaction,
+    item_id: i64,
+    barcodes: &[ItemBarcode],
+) -> Result<()> {
+    let mut cleaned: Vec<ItemBarcode> = vec![];
+    for barcode in barcodes {
+        let value = barcode.barcode.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        let barcode = ItemBarcode {
+            id: None,
+            item_id: Some(item_id),
+            barcode: value,
+            barcode_type: barcode.barcode_type.clone(),
+            is_primary: barcode.is_primary,
+        };
+        validate_barcode(&barcode)?;
+
+        if cleaned.iter().any(|b| b.barcode == barcode.barcode) {
+            return Err(validation_error(format!(
+                "Barcode {} is listed twice",
+                barcode.barcode
+            )));
+        }
+        let owner: Option<String> = tx
+            .query_row(
+                "SELECT i.code FROM item_barcodes b JOIN items i ON b.item_id = i.id
+                 WHERE b.barcode = ?1 AND b.item_id != ?2
+                 UNION ALL
+                 SELECT code FROM items WHERE code = ?1 COLLATE NOCASE AND id != ?2",
+                params![barcode.barcode, item_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(code) = owner {
+            return Err(validation_error(format!(
+                "Barcode {} is already used by item {}",
+                barcode.barcode, code
+            )));
+        }
+        cleaned.push(barcode);
+    }
+
+    match cleaned.iter().filter(|b| b.is_primary).count() {
+        0 => {
+            if let Some(first) = cleaned.first_mut() {
+                first.is_primary = true;
+            }
+        }
+        1 => {}
+        _ => return Err(validation_error("Only one barcode can be primary")),
+    }
+
+    tx.execute(
+        "DELETE FROM item_barcodes WHERE item_id = ?1",
+        params![item_id],
+    )?;
+    for barcode in &cleaned {
+        tx.execute(
+            "INSERT INTO item_barcodes (item_id, barcode, barcode_type, is_primary) VALUES (?1, ?2, ?3, ?4)",
+            params![item_id, barcode.barcode, barcode.barcode_type, barcode.is_primary],
+        )?;
+    }
+
     Ok(())
 }
 
+fn load_item_barcodes(conn: &Connection, item_id: i64) -> Result<Vec<ItemBarcode>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, item_id, barcode, barcode_type, is_primary
+         FROM item_barcodes
+         WHERE item_id = ?1
+         ORDER BY is_primary DESC, id",
+    )?;
+    let rows = stmt.query_map(params![item_id], |row| {
+        Ok(ItemBarcode {
+            id: Some(row.get(0)?),
+            item_id: Some(row.get(1)?),
+            barcode: row.get(2)?,
+            barcode_type: row.get(3)?,
+            is_primary: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn get_item_barcodes(app: &AppHandle, item_id: i64) -> Result<Vec<ItemBarcode>> {
+    let conn = get_db_conn(app)?;
+    load_item_barcodes(&conn, item_id)
+}
+
+// Find the item for a scanned barcode, falling back to the item code
+pub fn lookup_item_by_barcode(app: &AppHandle, barcode: String) -> Result<Option<Item>> {
+    let conn = get_db_conn(app)?;
+    let barcode = barcode.trim().to_string();
+
+    let item_id: Option<i64> = conn
+        .query_row(
+            "SELECT item_id FROM item_barcodes WHERE barcode = ?1
+             UNION ALL
+             SELECT id FROM items WHERE code = ?1 COLLATE NOCASE",
+            params![barcode],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(item_id) = item_id else {
+        return Ok(None);
+    };
+
+    let mut item = query_items(
+        &conn,
+        "",
+        vec!["i.id = ?".to_string()],
+        "",
+        vec![Box::new(item_id)],
+    )?
+    .pop();
+    if let Some(item) = item.as_mut() {
+        item.barcodes = Some(load_item_barcodes(&conn, item_id)?);
+    }
+    Ok(item)
+}
+
 pub fn import_items(app: &AppHandle, items: Vec<ImportItem>) -> Result<()> {
     let mut conn = get_db_conn(app)?;
     let tx = conn.transaction()?;
@@ -501,7 +2273,7 @@ pub fn import_items(app: &AppHandle, items: Vec<ImportItem>) -> Result<()> {
             }
         };
 
-        // 2. Get or Create Model
+        // 2. Get or Create Model under the brand
         let model_id: i64 = match tx.query_row(
             "SELECT id FROM models WHERE name = ?1",
             params![item.model_name],
@@ -510,14 +2282,24 @@ pub fn import_items(app: &AppHandle, items: Vec<ImportItem>) -> Result<()> {
             Ok(id) => id,
             Err(_) => {
                 tx.execute(
-                    "INSERT INTO models (name) VALUES (?1)",
-                    params![item.model_name],
+                    "INSERT INTO models (name, brand_id) VALUES (?1, ?2)",
+                    params![item.model_name, brand_id],
                 )?;
                 tx.last_insert_rowid()
             }
         };
 
+        validate_brand_model(&tx, Some(brand_id), Some(model_id))
+            .map_err(|e| validation_error(format!("Item {}: {}", item.code, e)))?;
+
         // 3. Upsert Item based on code
+        let existing_id: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM items WHERE code = ?1",
+                params![item.code],
+                |row| row.get(0),
+            )
+            .optional()?;
         tx.execute(
             "INSERT INTO items (code, name, brand_id, model_id, is_active) 
              VALUES (?1, ?2, ?3, ?4, 1)
@@ -527,498 +2309,1219 @@ pub fn import_items(app: &AppHandle, items: Vec<ImportItem>) -> Result<()> {
                 model_id = excluded.model_id",
             params![item.code, item.name, brand_id, model_id],
         )?;
+        let item_id = existing_id.unwrap_or_else(|| tx.last_insert_rowid());
+
+        // 4. Custom attribute columns
+        save_attributes(
+            &tx,
+            "item",
+            item_id,
+            item.attributes.as_ref(),
+            existing_id.is_none(),
+        )
+        .map_err(|e| validation_error(format!("Item {}: {}", item.code, e)))?;
     }
 
     tx.commit()?;
     Ok(())
 }
 
+// Items in the import layout, with their attributes, for round-tripping through a spreadsheet
+pub fn export_items(app: &AppHandle) -> Result<Vec<ImportItem>> {
+    let conn = get_db_conn(app)?;
+    let item_attributes = load_attributes(&conn, "item")?;
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.code, i.name, COALESCE(b.name, ''), COALESCE(m.name, '')
+         FROM items i
+         LEFT JOIN brands b ON i.brand_id = b.id
+         LEFT JOIN models m ON i.model_id = m.id
+         ORDER BY i.code",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let id: i64 = row.get(0)?;
+        Ok(ImportItem {
+            code: row.get(1)?,
+            name: row.get(2)?,
+            brand_name: row.get(3)?,
+            model_name: row.get(4)?,
+            attributes: Some(item_attributes.get(&id).cloned().unwrap_or_default()),
+        })
+    })?;
+    rows.collect()
+}
+
 // ============================================================================
-// Site Operations
+// Item Category Operations
 // ============================================================================
 
-pub fn create_site(app: &AppHandle, site: Site) -> Result<i64> {
+// Full "Parent > Child" path and depth for every category node
+const CATEGORY_TREE_CTE: &str = "category_tree(id, name, parent_id, path, depth) AS (
+    SELECT id, name, parent_id, name, 0 FROM item_categories WHERE parent_id IS NULL
+    UNION ALL
+    SELECT c.id, c.name, c.parent_id, ct.path || ' > ' || c.name, ct.depth + 1
+    FROM item_categories c
+    JOIN category_tree ct ON c.parent_id = ct.id
+)";
+
+// Filter on an item id column matching items in a category or any of its subcategories.
+// Binds the category id as a single parameter.
+fn items_in_category_sql(item_column: &str) -> String {
+    format!(
+        "{} IN (
+            WITH RECURSIVE subtree(id) AS (
+                SELECT ?
+                UNION ALL
+                SELECT c.id FROM item_categories c JOIN subtree ON c.parent_id = subtree.id
+            )
+            SELECT it.id FROM items it WHERE it.category_id IN (SELECT id FROM subtree)
+        )",
+        item_column
+    )
+}
+
+fn validate_item_category(conn: &Connection, category: &ItemCategory) -> Result<()> {
+    if category.name.trim().is_empty() {
+        return Err(validation_error("Category name is required"));
+    }
+
+    if let (Some(id), Some(parent_id)) = (category.id, category.parent_id) {
+        let is_descendant: bool = conn.query_row(
+            "WITH RECURSIVE subtree(id) AS (
+                SELECT ?1
+                UNION ALL
+                SELECT c.id FROM item_categories c JOIN subtree ON c.parent_id = subtree.id
+             )
+             SELECT EXISTS (SELECT 1 FROM subtree WHERE id = ?2)",
+            params![id, parent_id],
+            |row| row.get(0),
+        )?;
+        if is_descendant {
+            return Err(validation_error(
+                "A category cannot be moved under itself or one of its subcategories",
+            ));
+        }
+    }
+
+    let duplicate: bool = conn.query_row(
+        "SELECT EXISTS (
+            SELECT 1 FROM item_categories
+            WHERE parent_id IS ?1 AND name = ?2 COLLATE NOCASE AND id IS NOT ?3
+         )",
+        params![category.parent_id, category.name.trim(), category.id],
+        |row| row.get(0),
+    )?;
+    if duplicate {
+        return Err(validation_error(format!(
+            "Category '{}' already exists at this level",
+            category.name.trim()
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn create_item_category(app: &AppHandle, category: ItemCategory) -> Result<i64> {
     let conn = get_db_conn(app)?;
+    validate_item_category(&conn, &category)?;
     conn.execute(
-        "INSERT INTO sites (code, name, address, type, is_active) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![
-            site.code,
-            site.name,
-            site.address,
-            site.r#type,
-            site.is_active
-        ],
+        "INSERT INTO item_categories (name, parent_id) VALUES (?1, ?2)",
+        params![category.name.trim(), category.parent_id],
     )?;
     Ok(conn.last_insert_rowid())
 }
 
-pub fn get_all_sites(app: &AppHandle) -> Result<Vec<Site>> {
+// Categories in tree order with their full path
+pub fn get_item_categories(app: &AppHandle) -> Result<Vec<ItemCategory>> {
     let conn = get_db_conn(app)?;
-    let mut stmt = conn.prepare("SELECT id, code, name, address, type, is_active FROM sites")?;
+    load_item_categories(&conn)
+}
+
+pub fn load_item_categories(conn: &Connection) -> Result<Vec<ItemCategory>> {
+    let mut stmt = conn.prepare(&format!(
+        "WITH RECURSIVE {}
+         SELECT id, name, parent_id, path, depth FROM category_tree ORDER BY path",
+        CATEGORY_TREE_CTE
+    ))?;
     let rows = stmt.query_map([], |row| {
-        Ok(Site {
+        Ok(ItemCategory {
             id: Some(row.get(0)?),
-            code: row.get(1)?,
-            name: row.get(2)?,
-            address: row.get(3)?,
-            r#type: row.get(4)?,
-            is_active: row.get(5)?,
+            name: row.get(1)?,
+            parent_id: row.get(2)?,
+            path: row.get(3)?,
+            depth: row.get(4)?,
         })
     })?;
     rows.collect()
 }
 
-pub fn update_site(app: &AppHandle, site: Site) -> Result<()> {
+pub fn update_item_category(app: &AppHandle, category: ItemCategory) -> Result<()> {
     let conn = get_db_conn(app)?;
+    validate_item_category(&conn, &category)?;
     conn.execute(
-        "UPDATE sites SET code = ?1, name = ?2, address = ?3, type = ?4, is_active = ?5 WHERE id = ?6",
-        params![site.code, site.name, site.address, site.r#type, site.is_active, site.id],
+        "UPDATE item_categories SET name = ?1, parent_id = ?2 WHERE id = ?3",
+        params![category.name.trim(), category.parent_id, category.id],
     )?;
     Ok(())
 }
 
-pub fn delete_site(app: &AppHandle, id: i64) -> Result<()> {
+pub fn delete_item_category(app: &AppHandle, id: i64) -> Result<()> {
     let conn = get_db_conn(app)?;
-    conn.execute("DELETE FROM sites WHERE id = ?1", params![id])?;
-    Ok(())
-}
 
-// ============================================================================
-// Inventory Transaction Type Operations
-// ============================================================================
+    let (children, items): (i64, i64) = conn.query_row(
+        "SELECT
+            (SELECT COUNT(*) FROM item_categories WHERE parent_id = ?1),
+            (SELECT COUNT(*) FROM items WHERE category_id = ?1)",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    if children > 0 {
+        return Err(validation_error(
+            "The category has subcategories. Move or delete them first.",
+        ));
+    }
+    if items > 0 {
+        return Err(validation_error(format!(
+            "{} item(s) are assigned to this category. Reassign them first.",
+            items
+        )));
+    }
 
-pub fn get_all_inventory_transaction_types(
-    app: &AppHandle,
-) -> Result<Vec<InventoryTransactionType>> {
-    let conn = get_db_conn(app)?;
-    let mut stmt = conn.prepare("SELECT id, name FROM inventory_transaction_types")?;
-    let rows = stmt.query_map([], |row| {
-        Ok(InventoryTransactionType {
-            id: Some(row.get(0)?),
-            name: row.get(1)?,
-        })
-    })?;
-    rows.collect()
+    conn.execute("DELETE FROM item_categories WHERE id = ?1", params![id])?;
+    Ok(())
 }
 
 // ============================================================================
-// Inventory Voucher Operations
+// Site Operations
 // ============================================================================
 
-pub fn create_inventory_voucher(app: &AppHandle, mut voucher: InventoryVoucher) -> Result<i64> {
+pub fn create_site(app: &AppHandle, site: Site) -> Result<i64> {
     let mut conn = get_db_conn(app)?;
     let tx = conn.transaction()?;
-
-    // Generate sequential transaction number
-    let next_number: i64 = tx.query_row(
-        "SELECT COALESCE(MAX(CAST(transaction_number AS INTEGER)), 0) + 1 FROM inventory_vouchers",
-        [],
-        |row| row.get(0),
+    tx.execute(
+        "INSERT INTO sites (code, name, address, type, is_active) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            site.code,
+            site.name,
+            site.address,
+            site.r#type,
+            site.is_active
+        ],
     )?;
-    let transaction_number = next_number.to_string();
+    let site_id = tx.last_insert_rowid();
+    save_attributes(&tx, "site", site_id, site.attributes.as_ref(), true)?;
+    tx.commit()?;
+    Ok(site_id)
+}
 
-    // Get transaction type name for movement logic and remarks generation
-    let type_name: String = tx.query_row(
-        "SELECT name FROM inventory_transaction_types WHERE id = ?1",
-        params![voucher.voucher_type_id],
+pub fn get_all_sites(app: &AppHandle, query: MasterListQuery) -> Result<PaginatedResponse<Site>> {
+    let conn = get_db_conn(app)?;
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(search) = list_search_pattern(&query) {
+        where_clauses.push("(code LIKE ? OR name LIKE ? OR address LIKE ?)".to_string());
+        params_vec.push(Box::new(search.clone()));
+        params_vec.push(Box::new(search.clone()));
+        params_vec.push(Box::new(search));
+    }
+    if let Some(active) = query.is_active {
+        where_clauses.push("is_active = ?".to_string());
+        params_vec.push(Box::new(active));
+    }
+    push_attribute_filters(
+        "site",
+        "id",
+        &query.attributes,
+        &mut where_clauses,
+        &mut params_vec,
+    );
+
+    let order = list_order_sql(
+        query.sort_by.as_deref(),
+        query.sort_dir.as_deref(),
+        &[
+            ("name", "name COLLATE NOCASE"),
+            ("code", "code COLLATE NOCASE"),
+            ("type", "type"),
+            ("is_active", "is_active"),
+        ],
+        "id",
+    )?;
+
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let total_count: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM sites WHERE {}",
+            where_clauses.join(" AND ")
+        ),
+        &param_refs[..],
         |row| row.get(0),
     )?;
 
-    // Auto-generate remarks if empty
-    if voucher.remarks.is_none() || voucher.remarks.as_ref().unwrap().trim().is_empty() {
-        let mut generated_remark = type_name.clone();
+    let (limit_val, offset) = list_page(&query);
+    params_vec.push(Box::new(limit_val));
+    params_vec.push(Box::new(offset));
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let site_attributes = load_attributes(&conn, "site")?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, code, name, address, type, is_active FROM sites WHERE {} {} LIMIT ? OFFSET ?",
+        where_clauses.join(" AND "),
+        order
+    ))?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        let id: i64 = row.get(0)?;
+        Ok(Site {
+            id: Some(id),
+            code: row.get(1)?,
+            name: row.get(2)?,
+            address: row.get(3)?,
+            r#type: row.get(4)?,
+            is_active: row.get(5)?,
+            attributes: Some(site_attributes.get(&id).cloned().unwrap_or_default()),
+        })
+    })?;
+    let items = rows.collect::<Result<Vec<_>>>()?;
 
-        let src_name: Option<String> = if let Some(sid) = voucher.source_site_id {
-            tx.query_row("SELECT name FROM sites WHERE id = ?", params![sid], |row| {
-                row.get(0)
-            })
-            .ok()
-        } else {
-            None
-        };
+    Ok(PaginatedResponse { items, total_count })
+}
 
-        let dest_name: Option<String> = if let Some(did) = voucher.destination_site_id {
-            tx.query_row("SELECT name FROM sites WHERE id = ?", params![did], |row| {
-                row.get(0)
-            })
-            .ok()
-        } else {
-            None
-        };
+pub fn update_site(app: &AppHandle, site: Site) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE sites SET code = ?1, name = ?2, address = ?3, type = ?4, is_active = ?5 WHERE id = ?6",
+        params![site.code, site.name, site.address, site.r#type, site.is_active, site.id],
+    )?;
+    if let Some(site_id) = site.id {
+        save_attributes(&tx, "site", site_id, site.attributes.as_ref(), false)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
 
-        match type_name.as_str() {
-            "Godown → Site" | "Site → Godown" | "Site → Site" => {
-                if let (Some(src), Some(dest)) = (src_name, dest_name) {
-                    generated_remark = format!("{} -> {}", src, dest);
-                }
-            }
-            "Purchase Inward" => {
-                // Keep default or maybe add "Recv at " + dest_name
-            }
-            _ => {}
-        }
-        voucher.remarks = Some(generated_remark);
+pub fn delete_site(app: &AppHandle, id: i64) -> Result<DeleteOutcome> {
+    delete_master(app, "site", id)
+}
+
+// ============================================================================
+// Master Usage, Deletion and Merge
+// ============================================================================
+
+// (entity, label, table, column) for every column that points at a master record
+const MASTER_REFERENCES: &[(&str, &str, &str, &str)] = &[
+    ("brand", "Items", "items", "brand_id"),
+    ("brand", "Models", "models", "brand_id"),
+    ("model", "Items", "items", "model_id"),
+    (
+        "item",
+        "Voucher lines",
+        "inventory_voucher_items",
+        "item_id",
+    ),
+    (
+        "item",
+        "Kit voucher lines",
+        "inventory_voucher_items",
+        "kit_item_id",
+    ),
+    ("item", "Stock movements", "stock_movements", "item_id"),
+    (
+        "item",
+        "Purchase order lines",
+        "purchase_order_items",
+        "item_id",
+    ),
+    (
+        "item",
+        "Requisition lines",
+        "material_requisition_items",
+        "item_id",
+    ),
+    (
+        "item",
+        "Kit components",
+        "item_components",
+        "component_item_id",
+    ),
+    (
+        "site",
+        "Vouchers (from)",
+        "inventory_vouchers",
+        "source_site_id",
+    ),
+    (
+        "site",
+        "Vouchers (to)",
+        "inventory_vouchers",
+        "destination_site_id",
+    ),
+    ("site", "Stock movements", "stock_movements", "site_id"),
+    (
+        "site",
+        "Purchase orders",
+        "purchase_orders",
+        "delivery_site_id",
+    ),
+    ("site", "Requisitions", "material_requisitions", "site_id"),
+    ("site", "Projects", "projects", "site_id"),
+    ("supplier", "Vouchers", "inventory_vouchers", "supplier_id"),
+    (
+        "supplier",
+        "Purchase orders",
+        "purchase_orders",
+        "supplier_id",
+    ),
+];
+
+fn master_table(entity: &str) -> Result<&'static str> {
+    match entity {
+        "brand" => Ok("brands"),
+        "model" => Ok("models"),
+        "item" => Ok("items"),
+        "site" => Ok("sites"),
+        "supplier" => Ok("suppliers"),
+        other => Err(validation_error(format!("Unknown master '{}'", other))),
     }
+}
 
-    // Insert Voucher
-    tx.execute(
-        "INSERT INTO inventory_vouchers (transaction_number, voucher_date, source_site_id, destination_site_id, voucher_type_id, remarks, created_by) 
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        params![
-            transaction_number,
-            voucher.voucher_date,
-            voucher.source_site_id,
-            voucher.destination_site_id,
-            voucher.voucher_type_id,
-            voucher.remarks,
-            voucher.created_by
-        ],
-    )?;
-    let voucher_id = tx.last_insert_rowid();
-    voucher.id = Some(voucher_id);
+fn master_usage(conn: &Connection, entity: &str, id: i64) -> Result<MasterUsage> {
+    let table = master_table(entity)?;
+    let name: String = conn
+        .query_row(
+            &format!("SELECT name FROM {} WHERE id = ?1", table),
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| validation_error(format!("The {} does not exist", entity)))?;
+
+    let mut references = vec![];
+    for (_, label, ref_table, column) in MASTER_REFERENCES.iter().filter(|r| r.0 == entity) {
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE {} = ?1", ref_table, column),
+            params![id],
+            |row| row.get(0),
+        )?;
+        references.push(MasterReference {
+            label: label.to_string(),
+            count,
+        });
+    }
 
-    // Insert Items and create Stock Movements
-    for item in &voucher.items {
+    Ok(MasterUsage {
+        entity: entity.to_string(),
+        id,
+        name,
+        in_use: references.iter().any(|r| r.count > 0),
+        references,
+    })
+}
+
+// How many records point at a brand, model, item, site or supplier
+pub fn get_master_usage(app: &AppHandle, entity: String, id: i64) -> Result<MasterUsage> {
+    let conn = get_db_conn(app)?;
+    master_usage(&conn, &entity, id)
+}
+
+// Records still referenced elsewhere are deactivated instead of deleted
+fn delete_master(app: &AppHandle, entity: &str, id: i64) -> Result<DeleteOutcome> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    let table = master_table(entity)?;
+
+    let outcome = if master_usage(&tx, entity, id)?.in_use {
         tx.execute(
-            "INSERT INTO inventory_voucher_items (inventory_voucher_id, item_id, quantity) VALUES (?1, ?2, ?3)",
-            params![voucher_id, item.item_id, item.quantity],
+            &format!("UPDATE {} SET is_active = 0 WHERE id = ?1", table),
+            params![id],
         )?;
-        let voucher_item_id = tx.last_insert_rowid();
-
-        // Create stock movements based on transaction type
-        create_stock_movements(&tx, &type_name, &voucher, voucher_item_id, item)?;
-    }
+        DeleteOutcome::Deactivated
+    } else {
+        if entity == "item" {
+            tx.execute(
+                "DELETE FROM item_components WHERE kit_item_id = ?1",
+                params![id],
+            )?;
+            tx.execute("DELETE FROM item_barcodes WHERE item_id = ?1", params![id])?;
+        }
+        delete_attributes(&tx, entity, id)?;
+        tx.execute(&format!("DELETE FROM {} WHERE id = ?1", table), params![id])?;
+        DeleteOutcome::Deleted
+    };
 
     tx.commit()?;
-    Ok(voucher_id)
+    Ok(outcome)
 }
 
-fn create_stock_movements(
-    tx: &rusqlite::Transaction,
-    type_name: &str,
-    voucher: &InventoryVoucher,
-    voucher_item_id: i64,
-    item: &InventoryVoucherItem,
+// Repoint every reference from the source record to the target and remove the source
+pub fn merge_masters(
+    app: &AppHandle,
+    entity: String,
+    source_id: i64,
+    target_id: i64,
 ) -> Result<()> {
-    let voucher_id = voucher.id.unwrap();
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    let table = master_table(&entity)?;
 
-    match type_name {
-        "Purchase Inward" | "Opening Stock" => {
-            if let Some(dest_id) = voucher.destination_site_id {
-                tx.execute(
-                    "INSERT INTO stock_movements (voucher_id, voucher_item_id, item_id, site_id, stock_in) VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![voucher_id, voucher_item_id, item.item_id, dest_id, item.quantity],
-                )?;
-            }
-        }
-        "Godown → Site" | "Site → Godown" | "Site → Site" => {
-            // Stock out from source
-            if let Some(src_id) = voucher.source_site_id {
-                tx.execute(
-                    "INSERT INTO stock_movements (voucher_id, voucher_item_id, item_id, site_id, stock_out) VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![voucher_id, voucher_item_id, item.item_id, src_id, item.quantity],
-                )?;
+    if source_id == target_id {
+        return Err(validation_error(format!(
+            "Select a different {} to merge into",
+            entity
+        )));
+    }
+    let source = master_usage(&tx, &entity, source_id)?;
+    let target = master_usage(&tx, &entity, target_id)?;
+
+    match entity.as_str() {
+        "model" => {
+            let (source_brand, target_brand): (Option<i64>, Option<i64>) = tx.query_row(
+                "SELECT (SELECT brand_id FROM models WHERE id = ?1), (SELECT brand_id FROM models WHERE id = ?2)",
+                params![source_id, target_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            if let (Some(s), Some(t)) = (source_brand, target_brand) {
+                if s != t {
+                    return Err(validation_error(format!(
+                        "Models '{}' and '{}' belong to different brands",
+                        source.name, target.name
+                    )));
+                }
             }
-            // Stock in to destination
-            if let Some(dest_id) = voucher.destination_site_id {
+            if source_brand.is_some() && target_brand.is_none() {
                 tx.execute(
-                    "INSERT INTO stock_movements (voucher_id, voucher_item_id, item_id, site_id, stock_in) VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![voucher_id, voucher_item_id, item.item_id, dest_id, item.quantity],
+                    "UPDATE models SET brand_id = ?1 WHERE id = ?2",
+                    params![source_brand, target_id],
                 )?;
             }
         }
-        "Material Usage" | "Damaged Stock" => {
-            if let Some(src_id) = voucher.source_site_id {
-                tx.execute(
-                    "INSERT INTO stock_movements (voucher_id, voucher_item_id, item_id, site_id, stock_out) VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![voucher_id, voucher_item_id, item.item_id, src_id, item.quantity],
-                )?;
+        "item" => {
+            let (source_is_kit, shared_kits): (bool, i64) = tx.query_row(
+                "SELECT
+                    EXISTS (SELECT 1 FROM item_components WHERE kit_item_id = ?1),
+                    (SELECT COUNT(*) FROM item_components a
+                     JOIN item_components b ON a.kit_item_id = b.kit_item_id
+                     WHERE a.component_item_id = ?1 AND b.component_item_id = ?2)",
+                params![source_id, target_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            if source_is_kit {
+                return Err(validation_error(format!(
+                    "Item '{}' is a kit. Remove its components before merging it.",
+                    source.name
+                )));
             }
-        }
-        "Stock Adjustment" => {
-            // If destination is set, it's an In adjustment
-            if let Some(dest_id) = voucher.destination_site_id {
-                tx.execute(
-                    "INSERT INTO stock_movements (voucher_id, voucher_item_id, item_id, site_id, stock_in) VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![voucher_id, voucher_item_id, item.item_id, dest_id, item.quantity],
-                )?;
-            } else if let Some(src_id) = voucher.source_site_id {
-                // If only source is set, it's an Out adjustment
-                tx.execute(
-                    "INSERT INTO stock_movements (voucher_id, voucher_item_id, item_id, site_id, stock_out) VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![voucher_id, voucher_item_id, item.item_id, src_id, item.quantity],
-                )?;
+            if shared_kits > 0 {
+                return Err(validation_error(format!(
+                    "'{}' and '{}' are both components of the same kit",
+                    source.name, target.name
+                )));
             }
+            // The target keeps its primary barcode and gains the source's as extras
+            tx.execute(
+                "UPDATE item_barcodes SET item_id = ?1, is_primary = 0 WHERE item_id = ?2",
+                params![target_id, source_id],
+            )?;
         }
-        _ => {} // Unknown type - no movement
+        _ => {}
+    }
+
+    for (_, _, ref_table, column) in MASTER_REFERENCES.iter().filter(|r| r.0 == entity) {
+        tx.execute(
+            &format!(
+                "UPDATE {} SET {} = ?1 WHERE {} = ?2",
+                ref_table, column, column
+            ),
+            params![target_id, source_id],
+        )?;
     }
 
+    // The target keeps its own attribute values and takes the source's for the rest
+    tx.execute(
+        "UPDATE OR IGNORE attribute_values SET entity_id = ?1
+         WHERE entity_id = ?2
+         AND definition_id IN (SELECT id FROM attribute_definitions WHERE entity = ?3)",
+        params![target_id, source_id, entity],
+    )?;
+    delete_attributes(&tx, &entity, source_id)?;
+
+    tx.execute(
+        &format!("DELETE FROM {} WHERE id = ?1", table),
+        params![source_id],
+    )?;
+
+    tx.commit()?;
     Ok(())
 }
 
-pub fn get_inventory_vouchers(
-    app: &AppHandle,
-    page: i64,
-    limit: i64,
-) -> Result<PaginatedResponse<InventoryVoucherDisplay>> {
-    let conn = get_db_conn(app)?;
-
-    // 1. Get Total Count
-    let total_count: i64 =
-        conn.query_row("SELECT COUNT(*) FROM inventory_vouchers", [], |row| {
-            row.get(0)
-        })?;
+// ============================================================================
+// Custom Attribute Operations
+// ============================================================================
 
-    // 2. Get Page Items
-    let (limit_val, offset) = if limit == -1 {
-        (-1, 0)
-    } else {
-        (limit, (page - 1) * limit)
-    };
+const ATTRIBUTE_ENTITIES: [&str; 3] = ["item", "site", "voucher"];
+const ATTRIBUTE_TYPES: [&str; 4] = ["text", "number", "date", "enum"];
 
-    let mut stmt = conn.prepare(
-        "SELECT 
-            v.id, 
-            v.transaction_number, 
-            v.voucher_date, 
-            v.source_site_id, 
-            s.name as source_site_name,
-            v.destination_site_id, 
-            d.name as destination_site_name,
-            v.voucher_type_id,
-            t.name as voucher_type_name,
-            v.remarks,
-            v.created_at
-         FROM inventory_vouchers v
-         LEFT JOIN sites s ON v.source_site_id = s.id
-         LEFT JOIN sites d ON v.destination_site_id = d.id
-         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
-         ORDER BY v.created_at DESC
-         LIMIT ?1 OFFSET ?2",
-    )?;
+fn normalize_attribute_definition(definition: &mut AttributeDefinition) -> Result<()> {
+    definition.name = definition.name.trim().to_string();
+    if definition.name.is_empty() {
+        return Err(validation_error("Attribute name is required"));
+    }
+    if !ATTRIBUTE_ENTITIES.contains(&definition.entity.as_str()) {
+        return Err(validation_error(format!(
+            "Attributes cannot be defined on '{}'",
+            definition.entity
+        )));
+    }
+    if !ATTRIBUTE_TYPES.contains(&definition.data_type.as_str()) {
+        return Err(validation_error(format!(
+            "Unknown attribute type '{}'",
+            definition.data_type
+        )));
+    }
 
-    let rows = stmt.query_map(params![limit_val, offset], |row| {
-        Ok(InventoryVoucherDisplay {
-            id: row.get(0)?,
-            transaction_number: row.get(1)?,
-            voucher_date: row.get(2).unwrap_or_default(),
-            source_site_id: row.get(3)?,
-            source_site_name: row.get(4)?,
-            destination_site_id: row.get(5)?,
-            destination_site_name: row.get(6)?,
-            voucher_type_id: row.get(7)?,
-            voucher_type_name: row.get(8)?,
-            remarks: row.get(9)?,
-            created_at: row.get(10).unwrap_or_default(),
-        })
-    })?;
+    if definition.data_type == "enum" {
+        let mut options: Vec<String> = vec![];
+        for option in definition.options.iter().flatten() {
+            let option = option.trim();
+            if !option.is_empty() && !options.iter().any(|o| o.eq_ignore_ascii_case(option)) {
+                options.push(option.to_string());
+            }
+        }
+        if options.is_empty() {
+            return Err(validation_error(format!(
+                "List attribute '{}' needs at least one option",
+                definition.name
+            )));
+        }
+        definition.options = Some(options);
+    } else {
+        definition.options = None;
+    }
 
-    let items: Vec<InventoryVoucherDisplay> = rows.collect::<Result<Vec<_>>>()?;
+    Ok(())
+}
 
-    Ok(PaginatedResponse { items, total_count })
+fn read_attribute_definition(row: &rusqlite::Row) -> Result<AttributeDefinition> {
+    let options: Option<String> = row.get(5)?;
+    Ok(AttributeDefinition {
+        id: Some(row.get(0)?),
+        entity: row.get(1)?,
+        name: row.get(2)?,
+        data_type: row.get(3)?,
+        is_required: row.get(4)?,
+        options: options.and_then(|o| serde_json::from_str(&o).ok()),
+        sort_order: row.get(6)?,
+        is_active: row.get(7)?,
+    })
 }
 
-pub fn get_inventory_voucher(app: &AppHandle, id: i64) -> Result<InventoryVoucher> {
-    let conn = get_db_conn(app)?;
+pub fn load_attribute_definitions(
+    conn: &Connection,
+    entity: Option<&str>,
+    active_only: bool,
+) -> Result<Vec<AttributeDefinition>> {
     let mut stmt = conn.prepare(
-        "SELECT 
-            id, 
-            transaction_number, 
-            voucher_date, 
-            source_site_id, 
-            destination_site_id, 
-            voucher_type_id, 
-            remarks, 
-            created_by 
-         FROM inventory_vouchers 
-         WHERE id = ?1",
+        "SELECT id, entity, name, data_type, is_required, options, sort_order, is_active
+         FROM attribute_definitions
+         WHERE (?1 IS NULL OR entity = ?1) AND (?2 = 0 OR is_active = 1)
+         ORDER BY entity, sort_order, name",
     )?;
+    let rows = stmt.query_map(params![entity, active_only], read_attribute_definition)?;
+    rows.collect()
+}
 
-    let voucher = stmt.query_row(params![id], |row| {
-        Ok(InventoryVoucher {
-            id: Some(row.get(0)?),
-            transaction_number: row.get(1)?,
-            voucher_date: row.get(2)?,
-            source_site_id: row.get(3)?,
-            destination_site_id: row.get(4)?,
-            voucher_type_id: row.get(5)?,
-            items: vec![], // Will populate below
-            remarks: row.get(6)?,
-            created_at: None, // Not needed for edit
-            created_by: row.get(7)?,
-        })
-    })?;
-
-    // Get Items
-    let mut stmt_items = conn.prepare(
-        "SELECT item_id, quantity FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
+pub fn create_attribute_definition(
+    app: &AppHandle,
+    mut definition: AttributeDefinition,
+) -> Result<i64> {
+    normalize_attribute_definition(&mut definition)?;
+    let conn = get_db_conn(app)?;
+    conn.execute(
+        "INSERT INTO attribute_definitions (entity, name, data_type, is_required, options, sort_order, is_active)
+         VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, 0), ?7)",
+        params![
+            definition.entity,
+            definition.name,
+            definition.data_type,
+            definition.is_required,
+            definition.options.as_ref().map(|o| serde_json::to_string(o).unwrap_or_default()),
+            definition.sort_order,
+            definition.is_active
+        ],
     )?;
+    Ok(conn.last_insert_rowid())
+}
 
-    let items_iter = stmt_items.query_map(params![id], |row| {
-        Ok(InventoryVoucherItem {
-            id: None,
-            inventory_voucher_id: Some(id),
-            item_id: row.get(0)?,
-            quantity: row.get(1)?,
-        })
-    })?;
+pub fn get_attribute_definitions(
+    app: &AppHandle,
+    entity: Option<String>,
+) -> Result<Vec<AttributeDefinition>> {
+    let conn = get_db_conn(app)?;
+    load_attribute_definitions(&conn, entity.as_deref(), false)
+}
 
-    let mut final_voucher = voucher;
-    for item in items_iter {
-        final_voucher.items.push(item?);
+// The entity cannot change, and the type only while no values are stored
+pub fn update_attribute_definition(
+    app: &AppHandle,
+    mut definition: AttributeDefinition,
+) -> Result<()> {
+    normalize_attribute_definition(&mut definition)?;
+    let conn = get_db_conn(app)?;
+
+    let (entity, data_type, value_count): (String, String, i64) = conn.query_row(
+        "SELECT entity, data_type, (SELECT COUNT(*) FROM attribute_values WHERE definition_id = d.id)
+         FROM attribute_definitions d WHERE d.id = ?1",
+        params![definition.id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    if entity != definition.entity {
+        return Err(validation_error(
+            "An attribute cannot be moved to a different record type",
+        ));
+    }
+    if data_type != definition.data_type && value_count > 0 {
+        return Err(validation_error(format!(
+            "Attribute '{}' already has values and its type cannot be changed",
+            definition.name
+        )));
     }
 
-    Ok(final_voucher)
+    conn.execute(
+        "UPDATE attribute_definitions
+         SET name = ?1, data_type = ?2, is_required = ?3, options = ?4, sort_order = COALESCE(?5, sort_order), is_active = ?6
+         WHERE id = ?7",
+        params![
+            definition.name,
+            definition.data_type,
+            definition.is_required,
+            definition.options.as_ref().map(|o| serde_json::to_string(o).unwrap_or_default()),
+            definition.sort_order,
+            definition.is_active,
+            definition.id
+        ],
+    )?;
+    Ok(())
 }
 
-pub fn delete_inventory_voucher(app: &AppHandle, id: i64) -> Result<()> {
+// Removes the attribute together with every stored value
+pub fn delete_attribute_definition(app: &AppHandle, id: i64) -> Result<()> {
     let mut conn = get_db_conn(app)?;
     let tx = conn.transaction()?;
-
-    // 1. Delete Stock Movements
     tx.execute(
-        "DELETE FROM stock_movements WHERE voucher_id = ?1",
+        "DELETE FROM attribute_values WHERE definition_id = ?1",
         params![id],
     )?;
-
-    // 2. Delete Voucher Items
     tx.execute(
-        "DELETE FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
+        "DELETE FROM attribute_definitions WHERE id = ?1",
         params![id],
     )?;
-
-    // 3. Delete Voucher
-    tx.execute("DELETE FROM inventory_vouchers WHERE id = ?1", params![id])?;
-
     tx.commit()?;
     Ok(())
 }
 
-pub fn update_inventory_voucher(app: &AppHandle, mut voucher: InventoryVoucher) -> Result<()> {
-    let mut conn = get_db_conn(app)?;
-    let tx = conn.transaction()?;
-    let voucher_id = voucher.id.ok_or(rusqlite::Error::QueryReturnedNoRows)?; // Check for ID
+// Attribute values of every record of an entity, keyed by record id
+fn load_attributes(conn: &Connection, entity: &str) -> Result<HashMap<i64, AttributeValues>> {
+    let mut stmt = conn.prepare(
+        "SELECT av.entity_id, ad.name, av.value
+         FROM attribute_values av
+         JOIN attribute_definitions ad ON av.definition_id = ad.id
+         WHERE ad.entity = ?1",
+    )?;
+    let mut rows = stmt.query(params![entity])?;
+
+    let mut attributes: HashMap<i64, AttributeValues> = HashMap::new();
+    while let Some(row) = rows.next()? {
+        attributes
+            .entry(row.get(0)?)
+            .or_default()
+            .insert(row.get(1)?, row.get(2)?);
+    }
+    Ok(attributes)
+}
 
-    // 1. Check if exists
-    // (Optional but good practice)
+pub fn load_entity_attributes(conn: &Connection, entity: &str, id: i64) -> Result<AttributeValues> {
+    let mut stmt = conn.prepare(
+        "SELECT ad.name, av.value
+         FROM attribute_values av
+         JOIN attribute_definitions ad ON av.definition_id = ad.id
+         WHERE ad.entity = ?1 AND av.entity_id = ?2",
+    )?;
+    let rows = stmt.query_map(params![entity, id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+// Validate and store the attribute values of a record. Values of the record's active
+// attributes are replaced; None leaves an existing record's values untouched.
+pub fn save_attributes(
+    tx: &rusqlite::Transaction,
+    entity: &str,
+    entity_id: i64,
+    attributes: Option<&AttributeValues>,
+    is_new: bool,
+) -> Result<()> {
+    if attributes.is_none() && !is_new {
+        return Ok(());
+    }
+
+    let definitions = load_attribute_definitions(tx, Some(entity), true)?;
+    let empty = AttributeValues::new();
+    let attributes = attributes.unwrap_or(&empty);
+
+    let mut values: Vec<(i64, String)> = vec![];
+    for (name, value) in attributes {
+        let definition = definitions
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| validation_error(format!("Unknown {} attribute '{}'", entity, name)))?;
+
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        let value = match definition.data_type.as_str() {
+            "number" => value
+                .parse::<f64>()
+                .map(|_| value.to_string())
+                .map_err(|_| validation_error(format!("'{}' must be a number", definition.name)))?,
+            "date" => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .map_err(|_| {
+                    validation_error(format!(
+                        "'{}' must be a date in YYYY-MM-DD format",
+                        definition.name
+                    ))
+                })?,
+            "enum" => definition
+                .options
+                .iter()
+                .flatten()
+                .find(|o| o.eq_ignore_ascii_case(value))
+                .cloned()
+                .ok_or_else(|| {
+                    validation_error(format!(
+                        "'{}' is not an allowed value for '{}'",
+                        value, definition.name
+                    ))
+                })?,
+            _ => value.to_string(),
+        };
+
+        values.push((definition.id.unwrap_or_default(), value));
+    }
+
+    for definition in definitions.iter().filter(|d| d.is_required) {
+        if !values.iter().any(|(id, _)| Some(*id) == definition.id) {
+            return Err(validation_error(format!(
+                "'{}' is required",
+                definition.name
+            )));
+        }
+    }
 
-    // 2. Clear existing items and movements (Simpler than diffing)
     tx.execute(
-        "DELETE FROM stock_movements WHERE voucher_id = ?1",
-        params![voucher_id],
+        "DELETE FROM attribute_values
+         WHERE entity_id = ?1
+         AND definition_id IN (SELECT id FROM attribute_definitions WHERE entity = ?2 AND is_active = 1)",
+        params![entity_id, entity],
     )?;
-    tx.execute(
-        "DELETE FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
-        params![voucher_id],
+    for (definition_id, value) in values {
+        tx.execute(
+            "INSERT INTO attribute_values (definition_id, entity_id, value) VALUES (?1, ?2, ?3)",
+            params![definition_id, entity_id, value],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn delete_attributes(conn: &Connection, entity: &str, entity_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM attribute_values
+         WHERE entity_id = ?1
+         AND definition_id IN (SELECT id FROM attribute_definitions WHERE entity = ?2)",
+        params![entity_id, entity],
     )?;
+    Ok(())
+}
 
-    // 3. Update Voucher Details
-    // Re-generate remarks if needed (logic similar to create, or just keep what user sent)
-    // Get transaction type name for movement logic and remarks generation
-    let type_name: String = tx.query_row(
-        "SELECT name FROM inventory_transaction_types WHERE id = ?1",
-        params![voucher.voucher_type_id],
-        |row| row.get(0),
+// Restrict a query to records whose attributes match every name/value pair (case-insensitive)
+fn push_attribute_filters<'a>(
+    entity: &str,
+    id_column: &str,
+    filters: &Option<HashMap<String, String>>,
+    where_clauses: &mut Vec<String>,
+    params_vec: &mut Vec<Box<dyn rusqlite::ToSql + 'a>>,
+) {
+    for (name, value) in filters.iter().flatten() {
+        where_clauses.push(format!(
+            "EXISTS (
+                SELECT 1 FROM attribute_values av
+                JOIN attribute_definitions ad ON av.definition_id = ad.id
+                WHERE ad.entity = ? AND ad.name = ? COLLATE NOCASE
+                AND av.entity_id = {} AND av.value = ? COLLATE NOCASE
+            )",
+            id_column
+        ));
+        params_vec.push(Box::new(entity.to_string()));
+        params_vec.push(Box::new(name.clone()));
+        params_vec.push(Box::new(value.trim().to_string()));
+    }
+}
+
+// ============================================================================
+// Supplier Operations
+// ============================================================================
+
+fn normalize_supplier(supplier: &mut Supplier) -> Result<()> {
+    supplier.gstin = supplier
+        .gstin
+        .as_ref()
+        .map(|g| g.trim().to_uppercase())
+        .filter(|g| !g.is_empty());
+
+    if let Some(gstin) = &supplier.gstin {
+        if gstin.len() != 15 || !gstin.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(validation_error(format!(
+                "GSTIN '{}' must be 15 letters or digits",
+                gstin
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn create_supplier(app: &AppHandle, mut supplier: Supplier) -> Result<i64> {
+    normalize_supplier(&mut supplier)?;
+    let conn = get_db_conn(app)?;
+    conn.execute(
+        "INSERT INTO suppliers (code, name, gstin, contact_person, phone, email, address, is_active)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            supplier.code,
+            supplier.name,
+            supplier.gstin,
+            supplier.contact_person,
+            supplier.phone,
+            supplier.email,
+            supplier.address,
+            supplier.is_active
+        ],
     )?;
+    Ok(conn.last_insert_rowid())
+}
 
-    // If remarks are empty, regenerate them
-    if voucher.remarks.is_none() || voucher.remarks.as_ref().unwrap().trim().is_empty() {
-        let mut generated_remark = type_name.clone();
+pub fn get_all_suppliers(app: &AppHandle) -> Result<Vec<Supplier>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, code, name, gstin, contact_person, phone, email, address, is_active
+         FROM suppliers
+         ORDER BY name",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Supplier {
+            id: Some(row.get(0)?),
+            code: row.get(1)?,
+            name: row.get(2)?,
+            gstin: row.get(3)?,
+            contact_person: row.get(4)?,
+            phone: row.get(5)?,
+            email: row.get(6)?,
+            address: row.get(7)?,
+            is_active: row.get(8)?,
+        })
+    })?;
+    rows.collect()
+}
 
-        let src_name: Option<String> = if let Some(sid) = voucher.source_site_id {
-            tx.query_row("SELECT name FROM sites WHERE id = ?", params![sid], |row| {
-                row.get(0)
-            })
-            .ok()
-        } else {
-            None
-        };
+pub fn update_supplier(app: &AppHandle, mut supplier: Supplier) -> Result<()> {
+    normalize_supplier(&mut supplier)?;
+    let conn = get_db_conn(app)?;
+    conn.execute(
+        "UPDATE suppliers
+         SET code = ?1, name = ?2, gstin = ?3, contact_person = ?4, phone = ?5, email = ?6, address = ?7, is_active = ?8
+         WHERE id = ?9",
+        params![
+            supplier.code,
+            supplier.name,
+            supplier.gstin,
+            supplier.contact_person,
+            supplier.phone,
+            supplier.email,
+            supplier.address,
+            supplier.is_active,
+            supplier.id
+        ],
+    )?;
+    Ok(())
+}
 
-        let dest_name: Option<String> = if let Some(did) = voucher.destination_site_id {
-            tx.query_row("SELECT name FROM sites WHERE id = ?", params![did], |row| {
-                row.get(0)
-            })
-            .ok()
-        } else {
-            None
-        };
+pub fn delete_supplier(app: &AppHandle, id: i64) -> Result<DeleteOutcome> {
+    delete_master(app, "supplier", id)
+}
 
-        match type_name.as_str() {
-            "Godown → Site" | "Site → Godown" | "Site → Site" => {
-                if let (Some(src), Some(dest)) = (src_name, dest_name) {
-                    generated_remark = format!("{} -> {}", src, dest);
-                }
-            }
-            _ => {}
+// ============================================================================
+// Project Operations
+// ============================================================================
+
+const PROJECT_STATUSES: [&str; 3] = ["Active", "On Hold", "Completed"];
+
+fn normalize_project(project: &mut Project) -> Result<()> {
+    if !PROJECT_STATUSES.contains(&project.status.as_str()) {
+        return Err(validation_error(format!(
+            "Unknown project status '{}'",
+            project.status
+        )));
+    }
+
+    if let (Some(start), Some(end)) = (&project.start_date, &project.end_date) {
+        if end < start {
+            return Err(validation_error(
+                "Project end date cannot be before its start date",
+            ));
         }
-        voucher.remarks = Some(generated_remark);
     }
 
-    tx.execute(
-        "UPDATE inventory_vouchers 
-         SET voucher_date = ?1, source_site_id = ?2, destination_site_id = ?3, voucher_type_id = ?4, remarks = ?5 
-         WHERE id = ?6",
+    Ok(())
+}
+
+pub fn create_project(app: &AppHandle, mut project: Project) -> Result<i64> {
+    normalize_project(&mut project)?;
+    let conn = get_db_conn(app)?;
+    conn.execute(
+        "INSERT INTO projects (code, name, site_id, start_date, end_date, status, description)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         params![
-            voucher.voucher_date,
-            voucher.source_site_id,
-            voucher.destination_site_id,
-            voucher.voucher_type_id,
-            voucher.remarks,
-            voucher_id
+            project.code,
+            project.name,
+            project.site_id,
+            project.start_date,
+            project.end_date,
+            project.status,
+            project.description
         ],
     )?;
+    Ok(conn.last_insert_rowid())
+}
 
-    // 4. Re-insert Items and recreate Stock Movements
-    for item in &voucher.items {
-        tx.execute(
-            "INSERT INTO inventory_voucher_items (inventory_voucher_id, item_id, quantity) VALUES (?1, ?2, ?3)",
-            params![voucher_id, item.item_id, item.quantity],
-        )?;
-        let voucher_item_id = tx.last_insert_rowid();
+pub fn get_all_projects(app: &AppHandle, site_id: Option<i64>) -> Result<Vec<Project>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.code, p.name, p.site_id, s.name, p.start_date, p.end_date, p.status, p.description
+         FROM projects p
+         JOIN sites s ON p.site_id = s.id
+         WHERE ?1 IS NULL OR p.site_id = ?1
+         ORDER BY p.code",
+    )?;
+    let rows = stmt.query_map(params![site_id], |row| {
+        Ok(Project {
+            id: Some(row.get(0)?),
+            code: row.get(1)?,
+            name: row.get(2)?,
+            site_id: row.get(3)?,
+            site_name: row.get(4)?,
+            start_date: row.get(5)?,
+            end_date: row.get(6)?,
+            status: row.get(7)?,
+            description: row.get(8)?,
+        })
+    })?;
+    rows.collect()
+}
 
-        // Create stock movements based on transaction type
-        create_stock_movements(&tx, &type_name, &voucher, voucher_item_id, item)?;
+pub fn update_project(app: &AppHandle, mut project: Project) -> Result<()> {
+    normalize_project(&mut project)?;
+    let conn = get_db_conn(app)?;
+
+    // Vouchers already booked to the project were validated against its site
+    let used_elsewhere: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM inventory_vouchers WHERE project_id = ?1 AND source_site_id != ?2",
+        params![project.id, project.site_id],
+        |row| row.get(0),
+    )?;
+    if used_elsewhere > 0 {
+        return Err(validation_error(
+            "The project has vouchers booked at its current site and cannot be moved to another site",
+        ));
     }
 
-    tx.commit()?;
+    conn.execute(
+        "UPDATE projects
+         SET code = ?1, name = ?2, site_id = ?3, start_date = ?4, end_date = ?5, status = ?6, description = ?7
+         WHERE id = ?8",
+        params![
+            project.code,
+            project.name,
+            project.site_id,
+            project.start_date,
+            project.end_date,
+            project.status,
+            project.description,
+            project.id
+        ],
+    )?;
+    Ok(())
+}
+
+// Usage booked to a project keeps it; a finished project is marked Completed instead
+pub fn delete_project(app: &AppHandle, id: i64) -> Result<()> {
+    let conn = get_db_conn(app)?;
+    let vouchers: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM inventory_vouchers WHERE project_id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if vouchers > 0 {
+        return Err(validation_error(format!(
+            "{} voucher(s) are booked to this project. Mark it Completed instead.",
+            vouchers
+        )));
+    }
+    conn.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
     Ok(())
 }
 
 // ============================================================================
-// Stock Balance Operations
+// Purchase Order Operations
 // ============================================================================
 
-pub fn get_stock_balance(app: &AppHandle, site_id: i64, item_id: i64) -> Result<f64> {
-    let conn = get_db_conn(app)?;
-    let balance: f64 = conn.query_row(
-        "SELECT COALESCE(SUM(stock_in) - SUM(stock_out), 0) FROM stock_movements WHERE site_id = ?1 AND item_id = ?2",
-        params![site_id, item_id],
+// Quantity comparisons tolerate floating point noise from summed receipts
+const QUANTITY_EPSILON: f64 = 1e-9;
+
+fn validate_purchase_order_lines(items: &[PurchaseOrderItem]) -> Result<()> {
+    if items.is_empty() {
+        return Err(validation_error("A purchase order needs at least one item"));
+    }
+    for line in items {
+        if line.ordered_quantity <= 0.0 {
+            return Err(validation_error(
+                "Ordered quantity must be greater than zero",
+            ));
+        }
+        if line.rate.is_some_and(|r| r < 0.0) {
+            return Err(validation_error("Rate cannot be negative"));
+        }
+    }
+    Ok(())
+}
+
+fn get_purchase_order_line_received(conn: &Connection, purchase_order_item_id: i64) -> Result<f64> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(quantity), 0) FROM inventory_voucher_items WHERE purchase_order_item_id = ?1",
+        params![purchase_order_item_id],
         |row| row.get(0),
+    )
+}
+
+// Re-derive Open/Partial/Closed from receipts. Cancelled orders keep their status.
+fn refresh_purchase_order_status(conn: &Connection, purchase_order_id: i64) -> Result<()> {
+    let status: Option<String> = conn
+        .query_row(
+            "SELECT status FROM purchase_orders WHERE id = ?1",
+            params![purchase_order_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match status.as_deref() {
+        None | Some("Cancelled") => return Ok(()),
+        _ => {}
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT 
+            poi.ordered_quantity,
+            COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi WHERE vi.purchase_order_item_id = poi.id), 0)
+         FROM purchase_order_items poi
+         WHERE poi.purchase_order_id = ?1",
     )?;
-    Ok(balance)
+    let lines = stmt
+        .query_map(params![purchase_order_id], |row| {
+            Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let fully_received = lines
+        .iter()
+        .all(|(ordered, received)| received + QUANTITY_EPSILON >= *ordered);
+    let any_received = lines.iter().any(|(_, received)| *received > 0.0);
+
+    let new_status = if !lines.is_empty() && fully_received {
+        "Closed"
+    } else if any_received {
+        "Partial"
+    } else {
+        "Open"
+    };
+
+    conn.execute(
+        "UPDATE purchase_orders SET status = ?1 WHERE id = ?2",
+        params![new_status, purchase_order_id],
+    )?;
+    Ok(())
 }
 
-// Get all stock balances (grouped by item and site)
-pub fn get_stock_balances(
+pub fn create_purchase_order(app: &AppHandle, order: PurchaseOrder) -> Result<i64> {
+    validate_purchase_order_lines(&order.items)?;
+
+    let mut conn = get_db_conn(app)?;
+    let tx = numbering_transaction(&mut conn)?;
+
+    // Generate sequential PO number
+    let next_number: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(CAST(po_number AS INTEGER)), 0) + 1 FROM purchase_orders",
+        [],
+        |row| row.get(0),
+    )?;
+
+    tx.execute(
+        "INSERT INTO purchase_orders (po_number, po_date, supplier_id, delivery_site_id, expected_date, status, remarks, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'Open', ?6, ?7)",
+        params![
+            next_number.to_string(),
+            order.po_date,
+            order.supplier_id,
+            order.delivery_site_id,
+            order.expected_date,
+            order.remarks,
+            order.created_by
+        ],
+    )?;
+    let order_id = tx.last_insert_rowid();
+
+    for line in &order.items {
+        tx.execute(
+            "INSERT INTO purchase_order_items (purchase_order_id, item_id, ordered_quantity, rate) VALUES (?1, ?2, ?3, ?4)",
+            params![order_id, line.item_id, line.ordered_quantity, line.rate],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(order_id)
+}
+
+pub fn get_purchase_orders(
     app: &AppHandle,
-    item_name: Option<String>,
-    site_id: Option<i64>,
+    supplier_id: Option<i64>,
+    status: Option<String>,
     page: i64,
     limit: i64,
-) -> Result<PaginatedResponse<StockBalance>> {
+) -> Result<PaginatedResponse<PurchaseOrderDisplay>> {
     let conn = get_db_conn(app)?;
 
     let mut where_clauses = vec!["1=1".to_string()];
     let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
 
-    if let Some(name) = &item_name {
-        where_clauses.push("i.name LIKE ?".to_string());
-        params_vec.push(Box::new(format!("%{}%", name)));
+    if let Some(sid) = supplier_id {
+        where_clauses.push("po.supplier_id = ?".to_string());
+        params_vec.push(Box::new(sid));
     }
 
-    if let Some(sid) = site_id {
-        where_clauses.push("s.id = ?".to_string());
-        params_vec.push(Box::new(sid));
+    if let Some(st) = &status {
+        where_clauses.push("po.status = ?".to_string());
+        params_vec.push(Box::new(st.clone()));
     }
 
     let where_sql = where_clauses.join(" AND ");
@@ -1026,157 +3529,3360 @@ pub fn get_stock_balances(
 
     // 1. Get Total Count
     let count_query = format!(
-        "SELECT COUNT(*) FROM (
-            SELECT i.id
-            FROM items i
-            CROSS JOIN sites s
-            LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
-            WHERE {}
-            GROUP BY i.id, s.id
-            HAVING COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) != 0
-        )",
+        "SELECT COUNT(*) FROM purchase_orders po WHERE {}",
         where_sql
     );
     let total_count: i64 = conn.query_row(&count_query, &param_refs[..], |row| row.get(0))?;
 
+    // 2. Get Page Items
+    let query = format!(
+        "SELECT 
+            po.id,
+            po.po_number,
+            po.po_date,
+            po.supplier_id,
+            sup.name as supplier_name,
+            po.delivery_site_id,
+            s.name as delivery_site_name,
+            po.expected_date,
+            po.status,
+            COALESCE((SELECT SUM(poi.ordered_quantity) FROM purchase_order_items poi WHERE poi.purchase_order_id = po.id), 0),
+            COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi
+                      JOIN purchase_order_items poi ON vi.purchase_order_item_id = poi.id
+                      WHERE poi.purchase_order_id = po.id), 0),
+            COALESCE((SELECT SUM(poi.ordered_quantity * COALESCE(poi.rate, 0)) FROM purchase_order_items poi WHERE poi.purchase_order_id = po.id), 0),
+            po.remarks,
+            po.created_at
+         FROM purchase_orders po
+         JOIN suppliers sup ON po.supplier_id = sup.id
+         LEFT JOIN sites s ON po.delivery_site_id = s.id
+         WHERE {}
+         ORDER BY po.po_date DESC, po.id DESC
+         LIMIT ? OFFSET ?",
+        where_sql
+    );
+
+    let (limit_val, offset) = if limit == -1 {
+        (-1, 0)
+    } else {
+        (limit, (page - 1) * limit)
+    };
+
+    let mut final_params_refs = param_refs.clone();
+    final_params_refs.push(&limit_val);
+    final_params_refs.push(&offset);
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(&final_params_refs[..], |row| {
+        Ok(PurchaseOrderDisplay {
+            id: row.get(0)?,
+            po_number: row.get(1)?,
+            po_date: row.get(2)?,
+            supplier_id: row.get(3)?,
+            supplier_name: row.get(4)?,
+            delivery_site_id: row.get(5)?,
+            delivery_site_name: row.get(6)?,
+            expected_date: row.get(7)?,
+            status: row.get(8)?,
+            ordered_quantity: row.get(9)?,
+            received_quantity: row.get(10)?,
+            order_value: row.get(11)?,
+            remarks: row.get(12)?,
+            created_at: row.get(13).unwrap_or_default(),
+        })
+    })?;
+
+    let items: Vec<PurchaseOrderDisplay> = rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginatedResponse { items, total_count })
+}
+
+pub fn get_purchase_order(app: &AppHandle, id: i64) -> Result<PurchaseOrder> {
+    let conn = get_db_conn(app)?;
+    let mut order = conn.query_row(
+        "SELECT id, po_number, po_date, supplier_id, delivery_site_id, expected_date, status, remarks, created_at, created_by
+         FROM purchase_orders
+         WHERE id = ?1",
+        params![id],
+        |row| {
+            Ok(PurchaseOrder {
+                id: Some(row.get(0)?),
+                po_number: row.get(1)?,
+                po_date: row.get(2)?,
+                supplier_id: row.get(3)?,
+                delivery_site_id: row.get(4)?,
+                expected_date: row.get(5)?,
+                status: row.get(6)?,
+                items: vec![], // Will populate below
+                remarks: row.get(7)?,
+                created_at: row.get(8)?,
+                created_by: row.get(9)?,
+            })
+        },
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT 
+            poi.id,
+            poi.item_id,
+            poi.ordered_quantity,
+            poi.rate,
+            COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi WHERE vi.purchase_order_item_id = poi.id), 0)
+         FROM purchase_order_items poi
+         WHERE poi.purchase_order_id = ?1
+         ORDER BY poi.id",
+    )?;
+    let lines = stmt.query_map(params![id], |row| {
+        Ok(PurchaseOrderItem {
+            id: Some(row.get(0)?),
+            purchase_order_id: Some(id),
+            item_id: row.get(1)?,
+            ordered_quantity: row.get(2)?,
+            rate: row.get(3)?,
+            received_quantity: Some(row.get(4)?),
+        })
+    })?;
+
+    for line in lines {
+        order.items.push(line?);
+    }
+
+    Ok(order)
+}
+
+pub fn update_purchase_order(app: &AppHandle, order: PurchaseOrder) -> Result<()> {
+    validate_purchase_order_lines(&order.items)?;
+
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    let order_id = order.id.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+
+    let status: String = tx.query_row(
+        "SELECT status FROM purchase_orders WHERE id = ?1",
+        params![order_id],
+        |row| row.get(0),
+    )?;
+    if status == "Cancelled" {
+        return Err(validation_error(
+            "A cancelled purchase order cannot be edited",
+        ));
+    }
+
+    // Supplier cannot change once goods have been received against the order
+    let received_vouchers: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM inventory_vouchers WHERE purchase_order_id = ?1 AND supplier_id != ?2",
+        params![order_id, order.supplier_id],
+        |row| row.get(0),
+    )?;
+    if received_vouchers > 0 {
+        return Err(validation_error(
+            "The supplier cannot be changed after goods are received against this order",
+        ));
+    }
+
+    tx.execute(
+        "UPDATE purchase_orders
+         SET po_date = ?1, supplier_id = ?2, delivery_site_id = ?3, expected_date = ?4, remarks = ?5, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?6",
+        params![
+            order.po_date,
+            order.supplier_id,
+            order.delivery_site_id,
+            order.expected_date,
+            order.remarks,
+            order_id
+        ],
+    )?;
+
+    // Lines are diffed rather than recreated because receipts point at line ids
+    let mut stmt =
+        tx.prepare("SELECT id FROM purchase_order_items WHERE purchase_order_id = ?1")?;
+    let existing_ids = stmt
+        .query_map(params![order_id], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    drop(stmt);
+
+    for line_id in &existing_ids {
+        if order.items.iter().any(|l| l.id == Some(*line_id)) {
+            continue;
+        }
+        if get_purchase_order_line_received(&tx, *line_id)? > 0.0 {
+            return Err(validation_error(
+                "A line that has goods received against it cannot be removed",
+            ));
+        }
+        tx.execute(
+            "DELETE FROM purchase_order_items WHERE id = ?1",
+            params![line_id],
+        )?;
+    }
+
+    for line in &order.items {
+        match line.id.filter(|id| existing_ids.contains(id)) {
+            Some(line_id) => {
+                let received = get_purchase_order_line_received(&tx, line_id)?;
+                if line.ordered_quantity + QUANTITY_EPSILON < received {
+                    return Err(validation_error(format!(
+                        "Ordered quantity cannot be less than the {} already received",
+                        received
+                    )));
+                }
+                let received_item: Option<i64> = tx
+                    .query_row(
+                        "SELECT item_id FROM inventory_voucher_items WHERE purchase_order_item_id = ?1 LIMIT 1",
+                        params![line_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if received_item.is_some_and(|item_id| item_id != line.item_id) {
+                    return Err(validation_error(
+                        "The item on a line with receipts cannot be changed",
+                    ));
+                }
+                tx.execute(
+                    "UPDATE purchase_order_items SET item_id = ?1, ordered_quantity = ?2, rate = ?3 WHERE id = ?4",
+                    params![line.item_id, line.ordered_quantity, line.rate, line_id],
+                )?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO purchase_order_items (purchase_order_id, item_id, ordered_quantity, rate) VALUES (?1, ?2, ?3, ?4)",
+                    params![order_id, line.item_id, line.ordered_quantity, line.rate],
+                )?;
+            }
+        }
+    }
+
+    refresh_purchase_order_status(&tx, order_id)?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn cancel_purchase_order(app: &AppHandle, id: i64) -> Result<()> {
+    let conn = get_db_conn(app)?;
+    let updated = conn.execute(
+        "UPDATE purchase_orders SET status = 'Cancelled', updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1 AND status IN ('Open', 'Partial')",
+        params![id],
+    )?;
+    if updated == 0 {
+        return Err(validation_error(
+            "Only open or partly received purchase orders can be cancelled",
+        ));
+    }
+    Ok(())
+}
+
+pub fn delete_purchase_order(app: &AppHandle, id: i64) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+
+    let receipts: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM inventory_vouchers WHERE purchase_order_id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if receipts > 0 {
+        return Err(validation_error(
+            "Goods have been received against this purchase order. Cancel it instead.",
+        ));
+    }
+
+    tx.execute(
+        "DELETE FROM purchase_order_items WHERE purchase_order_id = ?1",
+        params![id],
+    )?;
+    tx.execute("DELETE FROM purchase_orders WHERE id = ?1", params![id])?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// ============================================================================
+// Material Requisition Operations
+// ============================================================================
+
+fn validate_requisition_lines(items: &[MaterialRequisitionItem]) -> Result<()> {
+    if items.is_empty() {
+        return Err(validation_error("A requisition needs at least one item"));
+    }
+    if items.iter().any(|line| line.requested_quantity <= 0.0) {
+        return Err(validation_error(
+            "Requested quantity must be greater than zero",
+        ));
+    }
+    Ok(())
+}
+
+fn get_requisition_line_fulfilled(conn: &Connection, requisition_item_id: i64) -> Result<f64> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(quantity), 0) FROM inventory_voucher_items WHERE requisition_item_id = ?1",
+        params![requisition_item_id],
+        |row| row.get(0),
+    )
+}
+
+// Re-derive Approved/Partial/Fulfilled from transfers. Other statuses are set explicitly.
+fn refresh_requisition_status(conn: &Connection, requisition_id: i64) -> Result<()> {
+    let status: Option<String> = conn
+        .query_row(
+            "SELECT status FROM material_requisitions WHERE id = ?1",
+            params![requisition_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match status.as_deref() {
+        Some("Approved") | Some("Partial") | Some("Fulfilled") => {}
+        _ => return Ok(()),
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT 
+            COALESCE(ri.approved_quantity, ri.requested_quantity),
+            COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi WHERE vi.requisition_item_id = ri.id), 0)
+         FROM material_requisition_items ri
+         WHERE ri.requisition_id = ?1",
+    )?;
+    let lines = stmt
+        .query_map(params![requisition_id], |row| {
+            Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let fully_fulfilled = lines
+        .iter()
+        .all(|(approved, fulfilled)| fulfilled + QUANTITY_EPSILON >= *approved);
+    let any_fulfilled = lines.iter().any(|(_, fulfilled)| *fulfilled > 0.0);
+
+    let new_status = if !lines.is_empty() && fully_fulfilled {
+        "Fulfilled"
+    } else if any_fulfilled {
+        "Partial"
+    } else {
+        "Approved"
+    };
+
+    conn.execute(
+        "UPDATE material_requisitions SET status = ?1 WHERE id = ?2",
+        params![new_status, requisition_id],
+    )?;
+    Ok(())
+}
+
+pub fn create_material_requisition(
+    app: &AppHandle,
+    requisition: MaterialRequisition,
+) -> Result<i64> {
+    validate_requisition_lines(&requisition.items)?;
+
+    let mut conn = get_db_conn(app)?;
+    let tx = numbering_transaction(&mut conn)?;
+
+    // Generate sequential requisition number
+    let next_number: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(CAST(requisition_number AS INTEGER)), 0) + 1 FROM material_requisitions",
+        [],
+        |row| row.get(0),
+    )?;
+
+    tx.execute(
+        "INSERT INTO material_requisitions (requisition_number, requisition_date, site_id, needed_by, status, requested_by, remarks, created_by)
+         VALUES (?1, ?2, ?3, ?4, 'Pending', ?5, ?6, ?7)",
+        params![
+            next_number.to_string(),
+            requisition.requisition_date,
+            requisition.site_id,
+            requisition.needed_by,
+            requisition.requested_by,
+            requisition.remarks,
+            requisition.created_by
+        ],
+    )?;
+    let requisition_id = tx.last_insert_rowid();
+
+    for line in &requisition.items {
+        tx.execute(
+            "INSERT INTO material_requisition_items (requisition_id, item_id, requested_quantity) VALUES (?1, ?2, ?3)",
+            params![requisition_id, line.item_id, line.requested_quantity],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(requisition_id)
+}
+
+pub fn get_material_requisitions(
+    app: &AppHandle,
+    site_id: Option<i64>,
+    status: Option<String>,
+    page: i64,
+    limit: i64,
+) -> Result<PaginatedResponse<MaterialRequisitionDisplay>> {
+    let conn = get_db_conn(app)?;
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(sid) = site_id {
+        where_clauses.push("r.site_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    if let Some(st) = &status {
+        where_clauses.push("r.status = ?".to_string());
+        params_vec.push(Box::new(st.clone()));
+    }
+
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    // 1. Get Total Count
+    let count_query = format!(
+        "SELECT COUNT(*) FROM material_requisitions r WHERE {}",
+        where_sql
+    );
+    let total_count: i64 = conn.query_row(&count_query, &param_refs[..], |row| row.get(0))?;
+
+    // 2. Get Page Items
+    let query = format!(
+        "SELECT 
+            r.id,
+            r.requisition_number,
+            r.requisition_date,
+            r.site_id,
+            s.name as site_name,
+            r.needed_by,
+            r.status,
+            r.requested_by,
+            COALESCE((SELECT SUM(ri.requested_quantity) FROM material_requisition_items ri WHERE ri.requisition_id = r.id), 0),
+            COALESCE((SELECT SUM(COALESCE(ri.approved_quantity, 0)) FROM material_requisition_items ri WHERE ri.requisition_id = r.id), 0),
+            COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi
+                      JOIN material_requisition_items ri ON vi.requisition_item_id = ri.id
+                      WHERE ri.requisition_id = r.id), 0),
+            r.remarks,
+            r.created_at
+         FROM material_requisitions r
+         JOIN sites s ON r.site_id = s.id
+         WHERE {}
+         ORDER BY r.requisition_date DESC, r.id DESC
+         LIMIT ? OFFSET ?",
+        where_sql
+    );
+
+    let (limit_val, offset) = if limit == -1 {
+        (-1, 0)
+    } else {
+        (limit, (page - 1) * limit)
+    };
+
+    let mut final_params_refs = param_refs.clone();
+    final_params_refs.push(&limit_val);
+    final_params_refs.push(&offset);
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(&final_params_refs[..], |row| {
+        Ok(MaterialRequisitionDisplay {
+            id: row.get(0)?,
+            requisition_number: row.get(1)?,
+            requisition_date: row.get(2)?,
+            site_id: row.get(3)?,
+            site_name: row.get(4)?,
+            needed_by: row.get(5)?,
+            status: row.get(6)?,
+            requested_by: row.get(7)?,
+            requested_quantity: row.get(8)?,
+            approved_quantity: row.get(9)?,
+            fulfilled_quantity: row.get(10)?,
+            remarks: row.get(11)?,
+            created_at: row.get(12).unwrap_or_default(),
+        })
+    })?;
+
+    let items: Vec<MaterialRequisitionDisplay> = rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginatedResponse { items, total_count })
+}
+
+pub fn get_material_requisition(app: &AppHandle, id: i64) -> Result<MaterialRequisition> {
+    let conn = get_db_conn(app)?;
+    let mut requisition = conn.query_row(
+        "SELECT id, requisition_number, requisition_date, site_id, needed_by, status, requested_by, remarks,
+                approved_by, approved_at, rejection_reason, created_at, created_by
+         FROM material_requisitions
+         WHERE id = ?1",
+        params![id],
+        |row| {
+            Ok(MaterialRequisition {
+                id: Some(row.get(0)?),
+                requisition_number: row.get(1)?,
+                requisition_date: row.get(2)?,
+                site_id: row.get(3)?,
+                needed_by: row.get(4)?,
+                status: row.get(5)?,
+                requested_by: row.get(6)?,
+                items: vec![], // Will populate below
+                remarks: row.get(7)?,
+                approved_by: row.get(8)?,
+                approved_at: row.get(9)?,
+                rejection_reason: row.get(10)?,
+                created_at: row.get(11)?,
+                created_by: row.get(12)?,
+            })
+        },
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT 
+            ri.id,
+            ri.item_id,
+            ri.requested_quantity,
+            ri.approved_quantity,
+            COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi WHERE vi.requisition_item_id = ri.id), 0)
+         FROM material_requisition_items ri
+         WHERE ri.requisition_id = ?1
+         ORDER BY ri.id",
+    )?;
+    let lines = stmt.query_map(params![id], |row| {
+        Ok(MaterialRequisitionItem {
+            id: Some(row.get(0)?),
+            requisition_id: Some(id),
+            item_id: row.get(1)?,
+            requested_quantity: row.get(2)?,
+            approved_quantity: row.get(3)?,
+            fulfilled_quantity: Some(row.get(4)?),
+        })
+    })?;
+
+    for line in lines {
+        requisition.items.push(line?);
+    }
+
+    Ok(requisition)
+}
+
+// Requisitions can only be edited while they are waiting for approval
+pub fn update_material_requisition(
+    app: &AppHandle,
+    requisition: MaterialRequisition,
+) -> Result<()> {
+    validate_requisition_lines(&requisition.items)?;
+
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    let requisition_id = requisition.id.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+
+    let status: String = tx.query_row(
+        "SELECT status FROM material_requisitions WHERE id = ?1",
+        params![requisition_id],
+        |row| row.get(0),
+    )?;
+    if status != "Pending" {
+        return Err(validation_error(format!(
+            "A requisition that is {} cannot be edited",
+            status.to_lowercase()
+        )));
+    }
+
+    tx.execute(
+        "UPDATE material_requisitions
+         SET requisition_date = ?1, site_id = ?2, needed_by = ?3, requested_by = ?4, remarks = ?5, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?6",
+        params![
+            requisition.requisition_date,
+            requisition.site_id,
+            requisition.needed_by,
+            requisition.requested_by,
+            requisition.remarks,
+            requisition_id
+        ],
+    )?;
+
+    tx.execute(
+        "DELETE FROM material_requisition_items WHERE requisition_id = ?1",
+        params![requisition_id],
+    )?;
+    for line in &requisition.items {
+        tx.execute(
+            "INSERT INTO material_requisition_items (requisition_id, item_id, requested_quantity) VALUES (?1, ?2, ?3)",
+            params![requisition_id, line.item_id, line.requested_quantity],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+// Approve a pending requisition. Lines without an explicit approval get the requested quantity.
+pub fn approve_material_requisition(
+    app: &AppHandle,
+    id: i64,
+    approvals: Vec<RequisitionApproval>,
+    approved_by: Option<i64>,
+) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+
+    let status: String = tx.query_row(
+        "SELECT status FROM material_requisitions WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if status != "Pending" {
+        return Err(validation_error(format!(
+            "Only pending requisitions can be approved (this one is {})",
+            status.to_lowercase()
+        )));
+    }
+
+    tx.execute(
+        "UPDATE material_requisition_items SET approved_quantity = requested_quantity WHERE requisition_id = ?1",
+        params![id],
+    )?;
+
+    for approval in &approvals {
+        if approval.approved_quantity < 0.0 {
+            return Err(validation_error("Approved quantity cannot be negative"));
+        }
+        let updated = tx.execute(
+            "UPDATE material_requisition_items SET approved_quantity = ?1 WHERE id = ?2 AND requisition_id = ?3",
+            params![approval.approved_quantity, approval.requisition_item_id, id],
+        )?;
+        if updated == 0 {
+            return Err(validation_error(
+                "An approved line does not belong to this requisition",
+            ));
+        }
+    }
+
+    tx.execute(
+        "UPDATE material_requisitions
+         SET status = 'Approved', approved_by = ?1, approved_at = CURRENT_TIMESTAMP, rejection_reason = NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?2",
+        params![approved_by, id],
+    )?;
+
+    // Lines approved with zero quantity may already complete the requisition
+    refresh_requisition_status(&tx, id)?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn reject_material_requisition(
+    app: &AppHandle,
+    id: i64,
+    reason: Option<String>,
+    rejected_by: Option<i64>,
+) -> Result<()> {
+    let conn = get_db_conn(app)?;
+    let updated = conn.execute(
+        "UPDATE material_requisitions
+         SET status = 'Rejected', rejection_reason = ?1, approved_by = ?2, approved_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?3 AND status = 'Pending'",
+        params![reason, rejected_by, id],
+    )?;
+    if updated == 0 {
+        return Err(validation_error(
+            "Only pending requisitions can be rejected",
+        ));
+    }
+    Ok(())
+}
+
+pub fn cancel_material_requisition(app: &AppHandle, id: i64) -> Result<()> {
+    let conn = get_db_conn(app)?;
+    let updated = conn.execute(
+        "UPDATE material_requisitions SET status = 'Cancelled', updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1 AND status IN ('Pending', 'Approved', 'Partial')",
+        params![id],
+    )?;
+    if updated == 0 {
+        return Err(validation_error(
+            "Only pending, approved or partly fulfilled requisitions can be cancelled",
+        ));
+    }
+    Ok(())
+}
+
+pub fn delete_material_requisition(app: &AppHandle, id: i64) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+
+    let transfers: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM inventory_vouchers WHERE requisition_id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if transfers > 0 {
+        return Err(validation_error(
+            "Material has been issued against this requisition. Cancel it instead.",
+        ));
+    }
+
+    tx.execute(
+        "DELETE FROM material_requisition_items WHERE requisition_id = ?1",
+        params![id],
+    )?;
+    tx.execute(
+        "DELETE FROM material_requisitions WHERE id = ?1",
+        params![id],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// ============================================================================
+// Inventory Transaction Type Operations
+// ============================================================================
+
+pub fn get_all_inventory_transaction_types(
+    app: &AppHandle,
+) -> Result<Vec<InventoryTransactionType>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare("SELECT id, name FROM inventory_transaction_types")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(InventoryTransactionType {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// ============================================================================
+// Inventory Voucher Operations
+// ============================================================================
+
+// Documents that take the next number hold the write lock from the start, so two desktops
+// sharing a host database cannot both read the same highest number
+fn numbering_transaction(conn: &mut Connection) -> Result<Transaction<'_>> {
+    conn.transaction_with_behavior(TransactionBehavior::Immediate)
+}
+
+// Next sequential voucher number, continuing the series of the configured prefix
+fn next_transaction_number(conn: &Connection) -> Result<String> {
+    let prefix = load_settings(conn)?
+        .voucher_number_prefix
+        .unwrap_or_default();
+    let next_number: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(CAST(SUBSTR(transaction_number, ?1 + 1) AS INTEGER)), 0) + 1
+         FROM inventory_vouchers
+         WHERE SUBSTR(transaction_number, 1, ?1) = ?2
+           AND SUBSTR(transaction_number, ?1 + 1) NOT GLOB '*[^0-9]*'",
+        params![prefix.len() as i64, prefix],
+        |row| row.get(0),
+    )?;
+    Ok(format!("{}{}", prefix, next_number))
+}
+
+pub fn create_inventory_voucher(app: &AppHandle, mut voucher: InventoryVoucher) -> Result<i64> {
+    // A new voucher always gets a fresh uuid; only sync inserts keep the one they were sent with
+    voucher.uuid = None;
+    let mut conn = get_db_conn(app)?;
+    let tx = numbering_transaction(&mut conn)?;
+    let voucher_id = insert_inventory_voucher(&tx, voucher)?;
+    tx.commit()?;
+    Ok(voucher_id)
+}
+
+// Creates a voucher within the caller's transaction, which should hold the write lock for numbering
+pub fn insert_inventory_voucher(
+    tx: &rusqlite::Transaction,
+    mut voucher: InventoryVoucher,
+) -> Result<i64> {
+    let transaction_number = next_transaction_number(tx)?;
+
+    // Get transaction type name for movement logic and remarks generation
+    let type_name: String = tx.query_row(
+        "SELECT name FROM inventory_transaction_types WHERE id = ?1",
+        params![voucher.voucher_type_id],
+        |row| row.get(0),
+    )?;
+
+    expand_kit_lines(tx, &type_name, &mut voucher)?;
+    validate_return_reference(tx, &type_name, &mut voucher)?;
+    validate_project_reference(tx, &type_name, &mut voucher)?;
+    validate_purchase_order_receipt(tx, &type_name, &mut voucher)?;
+    validate_purchase_reference(tx, &type_name, &mut voucher, None)?;
+    validate_requisition_fulfilment(tx, &type_name, &mut voucher)?;
+    normalize_voucher_references(&mut voucher);
+
+    // Auto-generate remarks if empty
+    if voucher.remarks.is_none() || voucher.remarks.as_ref().unwrap().trim().is_empty() {
+        voucher.remarks = Some(generate_remarks(tx, &type_name, &voucher));
+    }
+
+    // Insert Voucher
+    tx.execute(
+        "INSERT INTO inventory_vouchers (transaction_number, voucher_date, source_site_id, destination_site_id, voucher_type_id, supplier_id, bill_number, bill_date, purchase_order_id, requisition_id, original_voucher_id, project_id, activity_code, challan_number, vehicle_number, driver_name, receiver_name, remarks, created_by, uuid) 
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+        params![
+            transaction_number,
+            voucher.voucher_date,
+            voucher.source_site_id,
+            voucher.destination_site_id,
+            voucher.voucher_type_id,
+            voucher.supplier_id,
+            voucher.bill_number,
+            voucher.bill_date,
+            voucher.purchase_order_id,
+            voucher.requisition_id,
+            voucher.original_voucher_id,
+            voucher.project_id,
+            voucher.activity_code,
+            voucher.challan_number,
+            voucher.vehicle_number,
+            voucher.driver_name,
+            voucher.receiver_name,
+            voucher.remarks,
+            voucher.created_by,
+            voucher.uuid
+        ],
+    )?;
+    let voucher_id = tx.last_insert_rowid();
+    voucher.id = Some(voucher_id);
+    save_attributes(tx, "voucher", voucher_id, voucher.attributes.as_ref(), true)?;
+
+    // Insert Items and create Stock Movements
+    for item in &voucher.items {
+        let voucher_item_id = insert_voucher_item(tx, voucher_id, item, None)?;
+
+        // Create stock movements based on transaction type
+        create_stock_movements(tx, &type_name, &voucher, voucher_item_id, item)?;
+    }
+
+    if let Some(order_id) = voucher.purchase_order_id {
+        refresh_purchase_order_status(tx, order_id)?;
+    }
+    if let Some(requisition_id) = voucher.requisition_id {
+        refresh_requisition_status(tx, requisition_id)?;
+    }
+
+    Ok(voucher_id)
+}
+
+// Kit lines on transfers and usage are exploded into their components. Lines loaded from a
+// saved voucher come back with expand_kit = false so assembled kit stock can still be moved.
+// Assembly vouchers list the kits produced; component consumption lines are re-derived on every save.
+fn expand_kit_lines(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &mut InventoryVoucher,
+) -> Result<()> {
+    let assembly = type_name == "Kit Assembly";
+    if !assembly
+        && !matches!(
+            type_name,
+            "Godown → Site" | "Site → Godown" | "Site → Site" | "Material Usage" | "Damaged Stock"
+        )
+    {
+        return Ok(());
+    }
+
+    if assembly {
+        if voucher.source_site_id.is_none() {
+            return Err(validation_error(
+                "Select the site where the kits are assembled",
+            ));
+        }
+        voucher.destination_site_id = None;
+        voucher.items.retain(|item| item.kit_item_id.is_none());
+    }
+
+    let mut stmt = tx.prepare(
+        "SELECT component_item_id, quantity FROM item_components WHERE kit_item_id = ?1 ORDER BY id",
+    )?;
+    let mut lines = Vec::with_capacity(voucher.items.len());
+
+    for item in voucher.items.drain(..) {
+        let components = stmt
+            .query_map(params![item.item_id], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        if assembly && components.is_empty() {
+            return Err(validation_error("Only kit items can be assembled"));
+        }
+
+        if components.is_empty() || (!assembly && item.expand_kit == Some(false)) {
+            lines.push(item);
+            continue;
+        }
+
+        let kit_item_id = item.item_id;
+        let kit_quantity = item.quantity;
+        let kit_remarks = item.remarks.clone();
+        if assembly {
+            lines.push(item);
+        }
+
+        for (component_item_id, quantity) in components {
+            lines.push(InventoryVoucherItem {
+                id: None,
+                uuid: None,
+                inventory_voucher_id: None,
+                item_id: component_item_id,
+                quantity: quantity * kit_quantity,
+                purchase_order_item_id: None,
+                requisition_item_id: None,
+                original_voucher_item_id: None,
+                kit_item_id: Some(kit_item_id),
+                expand_kit: None,
+                remarks: kit_remarks.clone(),
+                returned_quantity: None,
+                net_quantity: None,
+            });
+        }
+    }
+
+    voucher.items = lines;
+    Ok(())
+}
+
+fn insert_voucher_item(
+    tx: &rusqlite::Transaction,
+    voucher_id: i64,
+    item: &InventoryVoucherItem,
+    keep_id: Option<i64>,
+) -> Result<i64> {
+    // A uuid already taken by another line is replaced by a fresh one
+    tx.execute(
+        "INSERT INTO inventory_voucher_items (id, uuid, inventory_voucher_id, item_id, quantity, purchase_order_item_id, requisition_item_id, original_voucher_item_id, kit_item_id, remarks)
+         VALUES (?1, CASE WHEN EXISTS (SELECT 1 FROM inventory_voucher_items WHERE uuid = ?2) THEN NULL ELSE ?2 END,
+                 ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            keep_id,
+            item.uuid,
+            voucher_id,
+            item.item_id,
+            item.quantity,
+            item.purchase_order_item_id,
+            item.requisition_item_id,
+            item.original_voucher_item_id,
+            item.kit_item_id,
+            item.remarks
+        ],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+// Blank reference fields and line notes are stored as NULL
+fn normalize_voucher_references(voucher: &mut InventoryVoucher) {
+    fn clean(value: &mut Option<String>) {
+        *value = value
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+    }
+
+    clean(&mut voucher.challan_number);
+    clean(&mut voucher.vehicle_number);
+    clean(&mut voucher.driver_name);
+    clean(&mut voucher.receiver_name);
+    if let Some(vehicle) = voucher.vehicle_number.as_mut() {
+        *vehicle = vehicle.to_uppercase();
+    }
+    for item in &mut voucher.items {
+        clean(&mut item.remarks);
+    }
+}
+
+// Purchase-type vouchers carry the supplier and the supplier's bill reference.
+fn is_purchase_type(type_name: &str) -> bool {
+    matches!(type_name, "Purchase Inward" | "Purchase Return")
+}
+
+// Validate supplier/bill fields and drop them for non-purchase vouchers
+fn validate_purchase_reference(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &mut InventoryVoucher,
+    exclude_voucher_id: Option<i64>,
+) -> Result<()> {
+    if !is_purchase_type(type_name) {
+        voucher.supplier_id = None;
+        voucher.bill_number = None;
+        voucher.bill_date = None;
+        return Ok(());
+    }
+
+    voucher.bill_number = voucher
+        .bill_number
+        .as_ref()
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+
+    if let Some(bill_number) = &voucher.bill_number {
+        let supplier_id = voucher
+            .supplier_id
+            .ok_or_else(|| validation_error("Select a supplier for the bill number"))?;
+
+        let duplicate: Option<String> = tx
+            .query_row(
+                "SELECT transaction_number FROM inventory_vouchers
+                 WHERE supplier_id = ?1 AND voucher_type_id = ?2
+                   AND UPPER(TRIM(bill_number)) = UPPER(?3) AND id != ?4",
+                params![
+                    supplier_id,
+                    voucher.voucher_type_id,
+                    bill_number,
+                    exclude_voucher_id.unwrap_or(0)
+                ],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if let Some(transaction_number) = duplicate {
+            return Err(validation_error(format!(
+                "Bill {} from this supplier is already entered in voucher #{}",
+                bill_number, transaction_number
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_return_type(type_name: &str) -> bool {
+    matches!(type_name, "Purchase Return" | "Return from Site")
+}
+
+// Link return lines to the original voucher and reject returns beyond the quantity moved
+fn validate_return_reference(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &mut InventoryVoucher,
+) -> Result<()> {
+    if !is_return_type(type_name) {
+        voucher.original_voucher_id = None;
+        for item in &mut voucher.items {
+            item.original_voucher_item_id = None;
+        }
+        return Ok(());
+    }
+
+    let original_id = voucher
+        .original_voucher_id
+        .ok_or_else(|| validation_error("Select the voucher the goods are returned against"))?;
+
+    let (transaction_number, original_type, original_source, original_destination, original_supplier): (
+        String,
+        String,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+    ) = tx
+        .query_row(
+            "SELECT v.transaction_number, t.name, v.source_site_id, v.destination_site_id, v.supplier_id
+             FROM inventory_vouchers v
+             JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+             WHERE v.id = ?1",
+            params![original_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .optional()?
+        .ok_or_else(|| validation_error("The original voucher does not exist"))?;
+
+    let compatible = match type_name {
+        "Purchase Return" => original_type == "Purchase Inward",
+        _ => matches!(original_type.as_str(), "Godown → Site" | "Site → Site"),
+    };
+    if !compatible {
+        return Err(validation_error(format!(
+            "A {} cannot be recorded against a {} voucher",
+            type_name, original_type
+        )));
+    }
+
+    // Goods go back out of the site that received them
+    match voucher.source_site_id {
+        None => voucher.source_site_id = original_destination,
+        Some(sid) if Some(sid) != original_destination => {
+            return Err(validation_error(format!(
+                "Goods can only be returned from the site that received them on voucher #{}",
+                transaction_number
+            )))
+        }
+        _ => {}
+    }
+
+    if type_name == "Purchase Return" {
+        voucher.destination_site_id = None;
+        match (voucher.supplier_id, original_supplier) {
+            (None, _) => voucher.supplier_id = original_supplier,
+            (Some(sid), Some(original)) if sid != original => {
+                return Err(validation_error(format!(
+                    "Voucher #{} was purchased from a different supplier",
+                    transaction_number
+                )))
+            }
+            _ => {}
+        }
+    } else if voucher.destination_site_id.is_none() {
+        voucher.destination_site_id = original_source;
+    }
+
+    let mut stmt = tx.prepare(
+        "SELECT id, item_id, quantity FROM inventory_voucher_items WHERE inventory_voucher_id = ?1 ORDER BY id",
+    )?;
+    let lines = stmt
+        .query_map(params![original_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    drop(stmt);
+
+    // Quantity returned by this voucher per original line
+    let mut returns: Vec<(i64, f64)> = vec![];
+
+    for item in &mut voucher.items {
+        let line = match item.original_voucher_item_id {
+            Some(line_id) => lines
+                .iter()
+                .find(|(id, item_id, _)| *id == line_id && *item_id == item.item_id),
+            None => lines
+                .iter()
+                .find(|(_, item_id, _)| *item_id == item.item_id),
+        }
+        .ok_or_else(|| {
+            validation_error(format!(
+                "A returned item is not on voucher #{}",
+                transaction_number
+            ))
+        })?;
+        item.original_voucher_item_id = Some(line.0);
+
+        match returns.iter_mut().find(|(id, _)| *id == line.0) {
+            Some(entry) => entry.1 += item.quantity,
+            None => returns.push((line.0, item.quantity)),
+        }
+    }
+
+    for (line_id, quantity) in returns {
+        let (_, item_id, moved) = lines.iter().find(|(id, _, _)| *id == line_id).unwrap();
+        let already_returned: f64 = tx.query_row(
+            "SELECT COALESCE(SUM(quantity), 0) FROM inventory_voucher_items WHERE original_voucher_item_id = ?1",
+            params![line_id],
+            |row| row.get(0),
+        )?;
+        let returnable = moved - already_returned;
+
+        if quantity > returnable + QUANTITY_EPSILON {
+            let item_code: String = tx.query_row(
+                "SELECT code FROM items WHERE id = ?1",
+                params![item_id],
+                |row| row.get(0),
+            )?;
+            return Err(validation_error(format!(
+                "Return of {} for item {} exceeds the {} returnable on voucher #{}",
+                quantity,
+                item_code,
+                returnable.max(0.0),
+                transaction_number
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Returns recorded against a voucher must still fit its lines after an edit
+fn validate_returns_against(tx: &rusqlite::Transaction, voucher_id: i64) -> Result<()> {
+    let mut stmt = tx.prepare(
+        "SELECT 
+            r.original_voucher_item_id,
+            r.item_id,
+            SUM(r.quantity),
+            o.item_id,
+            o.quantity
+         FROM inventory_voucher_items r
+         JOIN inventory_vouchers rv ON r.inventory_voucher_id = rv.id
+         LEFT JOIN inventory_voucher_items o ON r.original_voucher_item_id = o.id
+         WHERE rv.original_voucher_id = ?1
+         GROUP BY r.original_voucher_item_id",
+    )?;
+    let returned = stmt
+        .query_map(params![voucher_id], |row| {
+            Ok((
+                row.get::<_, i64>(1)?,
+                row.get::<_, f64>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, Option<f64>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    for (item_id, returned_quantity, original_item_id, original_quantity) in returned {
+        match (original_item_id, original_quantity) {
+            (Some(original_item_id), Some(quantity))
+                if original_item_id == item_id
+                    && quantity + QUANTITY_EPSILON >= returned_quantity => {}
+            _ => {
+                return Err(validation_error(
+                    "Lines with returns recorded against them cannot be removed, changed or reduced below the returned quantity",
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn is_consumption_type(type_name: &str) -> bool {
+    matches!(type_name, "Material Usage" | "Damaged Stock")
+}
+
+// Book usage and damage to a project at the issuing site
+fn validate_project_reference(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &mut InventoryVoucher,
+) -> Result<()> {
+    if !is_consumption_type(type_name) {
+        voucher.project_id = None;
+        voucher.activity_code = None;
+        return Ok(());
+    }
+
+    voucher.activity_code = voucher
+        .activity_code
+        .as_ref()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty());
+
+    let project_id = match voucher.project_id {
+        Some(pid) => pid,
+        None if voucher.activity_code.is_some() => {
+            return Err(validation_error("Select a project for the activity code"))
+        }
+        None => return Ok(()),
+    };
+
+    let (code, site_id, status): (String, i64, String) = tx
+        .query_row(
+            "SELECT code, site_id, status FROM projects WHERE id = ?1",
+            params![project_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or_else(|| validation_error("The selected project does not exist"))?;
+
+    if status == "Completed" {
+        return Err(validation_error(format!(
+            "Project {} is completed and cannot take new consumption",
+            code
+        )));
+    }
+
+    if voucher.source_site_id != Some(site_id) {
+        return Err(validation_error(format!(
+            "Project {} belongs to a different site",
+            code
+        )));
+    }
+
+    Ok(())
+}
+
+// Link receipt lines to the purchase order and reject receipts beyond the pending quantity
+fn validate_purchase_order_receipt(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &mut InventoryVoucher,
+) -> Result<()> {
+    let order_id = match voucher.purchase_order_id {
+        Some(id) if type_name == "Purchase Inward" => id,
+        _ => {
+            voucher.purchase_order_id = None;
+            for item in &mut voucher.items {
+                item.purchase_order_item_id = None;
+            }
+            return Ok(());
+        }
+    };
+
+    let (po_number, supplier_id, delivery_site_id, status): (String, i64, Option<i64>, String) = tx
+        .query_row(
+            "SELECT po_number, supplier_id, delivery_site_id, status FROM purchase_orders WHERE id = ?1",
+            params![order_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?
+        .ok_or_else(|| validation_error("The selected purchase order does not exist"))?;
+
+    if status == "Cancelled" {
+        return Err(validation_error(format!(
+            "Purchase order #{} is cancelled",
+            po_number
+        )));
+    }
+
+    match voucher.supplier_id {
+        None => voucher.supplier_id = Some(supplier_id),
+        Some(sid) if sid != supplier_id => {
+            return Err(validation_error(format!(
+                "Purchase order #{} belongs to a different supplier",
+                po_number
+            )))
+        }
+        _ => {}
+    }
+
+    if voucher.destination_site_id.is_none() {
+        voucher.destination_site_id = delivery_site_id;
+    }
+
+    let mut stmt = tx.prepare(
+        "SELECT id, item_id, ordered_quantity FROM purchase_order_items WHERE purchase_order_id = ?1 ORDER BY id",
+    )?;
+    let lines = stmt
+        .query_map(params![order_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    drop(stmt);
+
+    // Quantity received by this voucher per PO line
+    let mut receipts: Vec<(i64, f64)> = vec![];
+
+    for item in &mut voucher.items {
+        let line = match item.purchase_order_item_id {
+            Some(line_id) => lines
+                .iter()
+                .find(|(id, item_id, _)| *id == line_id && *item_id == item.item_id)
+                .ok_or_else(|| {
+                    validation_error(format!(
+                        "A receipt line does not match any item on purchase order #{}",
+                        po_number
+                    ))
+                })?,
+            // Unlinked lines are matched to the PO line for the same item, if any
+            None => match lines
+                .iter()
+                .find(|(_, item_id, _)| *item_id == item.item_id)
+            {
+                Some(line) => line,
+                None => continue,
+            },
+        };
+        item.purchase_order_item_id = Some(line.0);
+
+        match receipts.iter_mut().find(|(id, _)| *id == line.0) {
+            Some(entry) => entry.1 += item.quantity,
+            None => receipts.push((line.0, item.quantity)),
+        }
+    }
+
+    for (line_id, quantity) in receipts {
+        let (_, item_id, ordered) = lines.iter().find(|(id, _, _)| *id == line_id).unwrap();
+        let already_received = get_purchase_order_line_received(tx, line_id)?;
+        let pending = ordered - already_received;
+
+        if quantity > pending + QUANTITY_EPSILON {
+            let item_code: String = tx.query_row(
+                "SELECT code FROM items WHERE id = ?1",
+                params![item_id],
+                |row| row.get(0),
+            )?;
+            return Err(validation_error(format!(
+                "Receipt of {} for item {} exceeds the {} pending on purchase order #{}",
+                quantity,
+                item_code,
+                pending.max(0.0),
+                po_number
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Link transfer lines to the requisition and reject issues beyond the approved quantity
+fn validate_requisition_fulfilment(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &mut InventoryVoucher,
+) -> Result<()> {
+    let requisition_id = match voucher.requisition_id {
+        Some(id) if type_name == "Godown → Site" => id,
+        _ => {
+            voucher.requisition_id = None;
+            for item in &mut voucher.items {
+                item.requisition_item_id = None;
+            }
+            return Ok(());
+        }
+    };
+
+    let (requisition_number, site_id, status): (String, i64, String) = tx
+        .query_row(
+            "SELECT requisition_number, site_id, status FROM material_requisitions WHERE id = ?1",
+            params![requisition_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or_else(|| validation_error("The selected requisition does not exist"))?;
+
+    if !matches!(status.as_str(), "Approved" | "Partial" | "Fulfilled") {
+        return Err(validation_error(format!(
+            "Requisition #{} is {} and cannot be issued against",
+            requisition_number,
+            status.to_lowercase()
+        )));
+    }
+
+    match voucher.destination_site_id {
+        None => voucher.destination_site_id = Some(site_id),
+        Some(did) if did != site_id => {
+            return Err(validation_error(format!(
+                "Requisition #{} was raised by a different site",
+                requisition_number
+            )))
+        }
+        _ => {}
+    }
+
+    let mut stmt = tx.prepare(
+        "SELECT id, item_id, COALESCE(approved_quantity, requested_quantity)
+         FROM material_requisition_items WHERE requisition_id = ?1 ORDER BY id",
+    )?;
+    let lines = stmt
+        .query_map(params![requisition_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    drop(stmt);
+
+    // Quantity issued by this voucher per requisition line
+    let mut issues: Vec<(i64, f64)> = vec![];
+
+    for item in &mut voucher.items {
+        let line = match item.requisition_item_id {
+            Some(line_id) => lines
+                .iter()
+                .find(|(id, item_id, _)| *id == line_id && *item_id == item.item_id)
+                .ok_or_else(|| {
+                    validation_error(format!(
+                        "A transfer line does not match any item on requisition #{}",
+                        requisition_number
+                    ))
+                })?,
+            // Unlinked lines are matched to the requisition line for the same item, if any
+            None => match lines
+                .iter()
+                .find(|(_, item_id, _)| *item_id == item.item_id)
+            {
+                Some(line) => line,
+                None => continue,
+            },
+        };
+        item.requisition_item_id = Some(line.0);
+
+        match issues.iter_mut().find(|(id, _)| *id == line.0) {
+            Some(entry) => entry.1 += item.quantity,
+            None => issues.push((line.0, item.quantity)),
+        }
+    }
+
+    for (line_id, quantity) in issues {
+        let (_, item_id, approved) = lines.iter().find(|(id, _, _)| *id == line_id).unwrap();
+        let already_issued = get_requisition_line_fulfilled(tx, line_id)?;
+        let pending = approved - already_issued;
+
+        if quantity > pending + QUANTITY_EPSILON {
+            let item_code: String = tx.query_row(
+                "SELECT code FROM items WHERE id = ?1",
+                params![item_id],
+                |row| row.get(0),
+            )?;
+            return Err(validation_error(format!(
+                "Issue of {} for item {} exceeds the {} pending on requisition #{}",
+                quantity,
+                item_code,
+                pending.max(0.0),
+                requisition_number
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn generate_remarks(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &InventoryVoucher,
+) -> String {
+    let site_name = |site_id: Option<i64>| -> Option<String> {
+        site_id.and_then(|sid| {
+            tx.query_row("SELECT name FROM sites WHERE id = ?", params![sid], |row| {
+                row.get(0)
+            })
+            .ok()
+        })
+    };
+
+    let original_number = |voucher: &InventoryVoucher| -> Option<String> {
+        voucher.original_voucher_id.and_then(|vid| {
+            tx.query_row(
+                "SELECT transaction_number FROM inventory_vouchers WHERE id = ?",
+                params![vid],
+                |row| row.get(0),
+            )
+            .ok()
+        })
+    };
+
+    match type_name {
+        "Godown → Site" | "Site → Godown" | "Site → Site" | "Return from Site" => {
+            let route = match (
+                site_name(voucher.source_site_id),
+                site_name(voucher.destination_site_id),
+            ) {
+                (Some(src), Some(dest)) => format!("{} -> {}", src, dest),
+                _ => type_name.to_string(),
+            };
+
+            let requisition_number: Option<String> = voucher.requisition_id.and_then(|rid| {
+                tx.query_row(
+                    "SELECT requisition_number FROM material_requisitions WHERE id = ?",
+                    params![rid],
+                    |row| row.get(0),
+                )
+                .ok()
+            });
+
+            match (requisition_number, original_number(voucher)) {
+                (Some(number), _) => format!("{} (Req #{})", route, number),
+                (None, Some(number)) => format!("{} (Return of #{})", route, number),
+                _ => route,
+            }
+        }
+        "Purchase Inward" | "Purchase Return" => {
+            let supplier_name: Option<String> = voucher.supplier_id.and_then(|sid| {
+                tx.query_row(
+                    "SELECT name FROM suppliers WHERE id = ?",
+                    params![sid],
+                    |row| row.get(0),
+                )
+                .ok()
+            });
+
+            let remark = match (supplier_name, &voucher.bill_number) {
+                (Some(name), Some(bill)) => format!("{} - {} (Bill {})", type_name, name, bill),
+                (Some(name), None) => format!("{} - {}", type_name, name),
+                _ => type_name.to_string(),
+            };
+
+            match original_number(voucher) {
+                Some(number) => format!("{} against #{}", remark, number),
+                None => remark,
+            }
+        }
+        "Material Usage" | "Damaged Stock" => {
+            let project_code: Option<String> = voucher.project_id.and_then(|pid| {
+                tx.query_row(
+                    "SELECT code FROM projects WHERE id = ?",
+                    params![pid],
+                    |row| row.get(0),
+                )
+                .ok()
+            });
+
+            match (project_code, &voucher.activity_code) {
+                (Some(code), Some(activity)) => format!("{} - {} ({})", type_name, code, activity),
+                (Some(code), None) => format!("{} - {}", type_name, code),
+                _ => type_name.to_string(),
+            }
+        }
+        "Kit Assembly" => match site_name(voucher.source_site_id) {
+            Some(site) => format!("{} at {}", type_name, site),
+            None => type_name.to_string(),
+        },
+        _ => type_name.to_string(),
+    }
+}
+
+// Sites a voucher line posts stock at, in order, with true for stock in and false for stock out
+fn line_movements(
+    type_name: &str,
+    source_site_id: Option<i64>,
+    destination_site_id: Option<i64>,
+    kit_item_id: Option<i64>,
+) -> Vec<(i64, bool)> {
+    match type_name {
+        "Purchase Inward" | "Opening Stock" => destination_site_id
+            .map(|id| (id, true))
+            .into_iter()
+            .collect(),
+        "Godown → Site" | "Site → Godown" | "Site → Site" | "Return from Site" => {
+            // Stock out from source, stock in to destination
+            let out = source_site_id.map(|id| (id, false));
+            let into = destination_site_id.map(|id| (id, true));
+            out.into_iter().chain(into).collect()
+        }
+        "Material Usage" | "Damaged Stock" | "Purchase Return" => {
+            source_site_id.map(|id| (id, false)).into_iter().collect()
+        }
+        // Components are consumed and the finished kit is stocked at the same site
+        "Kit Assembly" => source_site_id
+            .map(|id| (id, kit_item_id.is_none()))
+            .into_iter()
+            .collect(),
+        // A destination makes it an In adjustment; only a source makes it an Out adjustment
+        "Stock Adjustment" => match (destination_site_id, source_site_id) {
+            (Some(dest_id), _) => vec![(dest_id, true)],
+            (None, Some(src_id)) => vec![(src_id, false)],
+            (None, None) => vec![],
+        },
+        _ => vec![], // Unknown type - no movement
+    }
+}
+
+// Without a timestamp the movement is stamped now
+fn insert_stock_movement(
+    tx: &rusqlite::Transaction,
+    voucher_id: i64,
+    voucher_item_id: i64,
+    item_id: i64,
+    (site_id, is_in): (i64, bool),
+    quantity: f64,
+    created_at: Option<&str>,
+) -> Result<()> {
+    let (stock_in, stock_out) = if is_in {
+        (quantity, 0.0)
+    } else {
+        (0.0, quantity)
+    };
+    tx.execute(
+        "INSERT INTO stock_movements (voucher_id, voucher_item_id, item_id, site_id, stock_in, stock_out, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, CURRENT_TIMESTAMP))",
+        params![voucher_id, voucher_item_id, item_id, site_id, stock_in, stock_out, created_at],
+    )?;
+    Ok(())
+}
+
+fn create_stock_movements(
+    tx: &rusqlite::Transaction,
+    type_name: &str,
+    voucher: &InventoryVoucher,
+    voucher_item_id: i64,
+    item: &InventoryVoucherItem,
+) -> Result<()> {
+    let voucher_id = voucher.id.unwrap();
+    let movements = line_movements(
+        type_name,
+        voucher.source_site_id,
+        voucher.destination_site_id,
+        item.kit_item_id,
+    );
+    for movement in movements {
+        insert_stock_movement(
+            tx,
+            voucher_id,
+            voucher_item_id,
+            item.item_id,
+            movement,
+            item.quantity,
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+const VOUCHER_STATUSES: [&str; 3] = ["Posted", "Partially Returned", "Returned"];
+
+// Posted until something is returned against the voucher; Returned once every line is fully returned
+const VOUCHER_STATUS_SQL: &str = "CASE
+    WHEN NOT EXISTS (SELECT 1 FROM inventory_vouchers rv WHERE rv.original_voucher_id = v.id) THEN 'Posted'
+    WHEN EXISTS (
+        SELECT 1 FROM inventory_voucher_items oi
+        WHERE oi.inventory_voucher_id = v.id
+          AND oi.quantity > COALESCE((SELECT SUM(ri.quantity) FROM inventory_voucher_items ri WHERE ri.original_voucher_item_id = oi.id), 0) + 1e-9
+    ) THEN 'Partially Returned'
+    ELSE 'Returned'
+END";
+
+pub fn get_inventory_vouchers(
+    app: &AppHandle,
+    filters: InventoryVoucherFilters,
+    page: i64,
+    limit: i64,
+) -> Result<PaginatedResponse<InventoryVoucherDisplay>> {
+    let conn = get_db_conn(app)?;
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(from) = &filters.from_date {
+        where_clauses.push("v.voucher_date >= ?".to_string());
+        params_vec.push(Box::new(from.clone()));
+    }
+    if let Some(to) = &filters.to_date {
+        where_clauses.push("v.voucher_date <= ?".to_string());
+        params_vec.push(Box::new(to.clone()));
+    }
+    if let Some(tid) = filters.voucher_type_id {
+        where_clauses.push("v.voucher_type_id = ?".to_string());
+        params_vec.push(Box::new(tid));
+    }
+    if let Some(sid) = filters.source_site_id {
+        where_clauses.push("v.source_site_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+    if let Some(did) = filters.destination_site_id {
+        where_clauses.push("v.destination_site_id = ?".to_string());
+        params_vec.push(Box::new(did));
+    }
+    if let Some(iid) = filters.item_id {
+        where_clauses.push(
+            "EXISTS (SELECT 1 FROM inventory_voucher_items vi WHERE vi.inventory_voucher_id = v.id AND (vi.item_id = ? OR vi.kit_item_id = ?))"
+                .to_string(),
+        );
+        params_vec.push(Box::new(iid));
+        params_vec.push(Box::new(iid));
+    }
+    if let Some(number) = filters
+        .transaction_number
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        where_clauses.push("v.transaction_number LIKE ?".to_string());
+        params_vec.push(Box::new(format!("%{}%", number)));
+    }
+    if let Some(remarks) = filters
+        .remarks
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        where_clauses.push("v.remarks LIKE ?".to_string());
+        params_vec.push(Box::new(format!("%{}%", remarks)));
+    }
+    if let Some(user) = filters.created_by {
+        where_clauses.push("v.created_by = ?".to_string());
+        params_vec.push(Box::new(user));
+    }
+    if let Some(status) = &filters.status {
+        if !VOUCHER_STATUSES.contains(&status.as_str()) {
+            return Err(validation_error(format!(
+                "Unknown voucher status '{}'",
+                status
+            )));
+        }
+        where_clauses.push(format!("({}) = ?", VOUCHER_STATUS_SQL));
+        params_vec.push(Box::new(status.clone()));
+    }
+    push_attribute_filters(
+        "voucher",
+        "v.id",
+        &filters.attributes,
+        &mut where_clauses,
+        &mut params_vec,
+    );
+
+    let order = match filters.sort_by.as_deref().unwrap_or("") {
+        "" => "ORDER BY v.created_at DESC, v.id DESC".to_string(),
+        // Numbers are grouped by prefix (which never ends in a digit), then sorted numerically
+        "transaction_number" => {
+            let direction = sort_direction(filters.sort_dir.as_deref())?;
+            let prefix = "RTRIM(v.transaction_number, '0123456789')";
+            format!(
+                "ORDER BY {prefix} {direction},
+                    CAST(SUBSTR(v.transaction_number, LENGTH({prefix}) + 1) AS INTEGER) {direction},
+                    v.id {direction}"
+            )
+        }
+        sort_by => list_order_sql(
+            Some(sort_by),
+            filters.sort_dir.as_deref(),
+            &[
+                ("voucher_date", "v.voucher_date"),
+                ("created_at", "v.created_at"),
+            ],
+            "v.id",
+        )?,
+    };
+
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    // 1. Get Total Count
+    let total_count: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM inventory_vouchers v WHERE {}",
+            where_sql
+        ),
+        &param_refs[..],
+        |row| row.get(0),
+    )?;
+
+    // 2. Get Page Items
+    let (limit_val, offset) = if limit == -1 {
+        (-1, 0)
+    } else {
+        (limit, (page - 1) * limit)
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT 
+            v.id, 
+            v.transaction_number, 
+            v.voucher_date, 
+            v.source_site_id, 
+            s.name as source_site_name,
+            v.destination_site_id, 
+            d.name as destination_site_name,
+            v.voucher_type_id,
+            t.name as voucher_type_name,
+            v.supplier_id,
+            sup.name as supplier_name,
+            v.bill_number,
+            v.bill_date,
+            v.purchase_order_id,
+            po.po_number,
+            v.requisition_id,
+            mr.requisition_number,
+            v.original_voucher_id,
+            ov.transaction_number,
+            v.project_id,
+            p.code,
+            p.name,
+            v.activity_code,
+            v.remarks,
+            v.created_at,
+            v.created_by,
+            {},
+            v.challan_number,
+            v.vehicle_number
+         FROM inventory_vouchers v
+         LEFT JOIN sites s ON v.source_site_id = s.id
+         LEFT JOIN sites d ON v.destination_site_id = d.id
+         LEFT JOIN suppliers sup ON v.supplier_id = sup.id
+         LEFT JOIN purchase_orders po ON v.purchase_order_id = po.id
+         LEFT JOIN material_requisitions mr ON v.requisition_id = mr.id
+         LEFT JOIN inventory_vouchers ov ON v.original_voucher_id = ov.id
+         LEFT JOIN projects p ON v.project_id = p.id
+         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+         WHERE {}
+         {}
+         LIMIT ? OFFSET ?",
+        VOUCHER_STATUS_SQL, where_sql, order
+    ))?;
+
+    let mut final_params_refs = param_refs.clone();
+    final_params_refs.push(&limit_val);
+    final_params_refs.push(&offset);
+    let voucher_attributes = load_attributes(&conn, "voucher")?;
+
+    let rows = stmt.query_map(&final_params_refs[..], |row| {
+        let id: i64 = row.get(0)?;
+        Ok(InventoryVoucherDisplay {
+            id,
+            transaction_number: row.get(1)?,
+            voucher_date: row.get(2).unwrap_or_default(),
+            source_site_id: row.get(3)?,
+            source_site_name: row.get(4)?,
+            destination_site_id: row.get(5)?,
+            destination_site_name: row.get(6)?,
+            voucher_type_id: row.get(7)?,
+            voucher_type_name: row.get(8)?,
+            supplier_id: row.get(9)?,
+            supplier_name: row.get(10)?,
+            bill_number: row.get(11)?,
+            bill_date: row.get(12)?,
+            purchase_order_id: row.get(13)?,
+            purchase_order_number: row.get(14)?,
+            requisition_id: row.get(15)?,
+            requisition_number: row.get(16)?,
+            original_voucher_id: row.get(17)?,
+            original_transaction_number: row.get(18)?,
+            project_id: row.get(19)?,
+            project_code: row.get(20)?,
+            project_name: row.get(21)?,
+            activity_code: row.get(22)?,
+            attributes: Some(voucher_attributes.get(&id).cloned().unwrap_or_default()),
+            remarks: row.get(23)?,
+            created_at: row.get(24).unwrap_or_default(),
+            created_by: row.get(25)?,
+            status: row.get(26)?,
+            challan_number: row.get(27)?,
+            vehicle_number: row.get(28)?,
+        })
+    })?;
+
+    let items: Vec<InventoryVoucherDisplay> = rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginatedResponse { items, total_count })
+}
+
+pub fn get_inventory_voucher(app: &AppHandle, id: i64) -> Result<InventoryVoucher> {
+    let conn = get_db_conn(app)?;
+    load_inventory_voucher(&conn, id)
+}
+
+pub fn load_inventory_voucher(conn: &Connection, id: i64) -> Result<InventoryVoucher> {
+    let mut stmt = conn.prepare(
+        "SELECT 
+            id, 
+            transaction_number, 
+            voucher_date, 
+            source_site_id, 
+            destination_site_id, 
+            voucher_type_id, 
+            supplier_id, 
+            bill_number, 
+            bill_date, 
+            purchase_order_id, 
+            requisition_id, 
+            original_voucher_id, 
+            project_id, 
+            activity_code, 
+            remarks, 
+            created_by,
+            challan_number,
+            vehicle_number,
+            driver_name,
+            receiver_name,
+            uuid
+         FROM inventory_vouchers 
+         WHERE id = ?1",
+    )?;
+
+    let voucher = stmt.query_row(params![id], |row| {
+        Ok(InventoryVoucher {
+            id: Some(row.get(0)?),
+            uuid: row.get(20)?,
+            transaction_number: row.get(1)?,
+            voucher_date: row.get(2)?,
+            source_site_id: row.get(3)?,
+            destination_site_id: row.get(4)?,
+            voucher_type_id: row.get(5)?,
+            supplier_id: row.get(6)?,
+            bill_number: row.get(7)?,
+            bill_date: row.get(8)?,
+            purchase_order_id: row.get(9)?,
+            requisition_id: row.get(10)?,
+            original_voucher_id: row.get(11)?,
+            project_id: row.get(12)?,
+            activity_code: row.get(13)?,
+            challan_number: row.get(16)?,
+            vehicle_number: row.get(17)?,
+            driver_name: row.get(18)?,
+            receiver_name: row.get(19)?,
+            attributes: None, // Will populate below
+            items: vec![],    // Will populate below
+            remarks: row.get(14)?,
+            created_at: None, // Not needed for edit
+            created_by: row.get(15)?,
+        })
+    })?;
+
+    // Get Items
+    let mut stmt_items = conn.prepare(
+        "SELECT 
+            vi.id,
+            vi.item_id,
+            vi.quantity,
+            vi.purchase_order_item_id,
+            vi.requisition_item_id,
+            vi.original_voucher_item_id,
+            vi.kit_item_id,
+            COALESCE((SELECT SUM(r.quantity) FROM inventory_voucher_items r WHERE r.original_voucher_item_id = vi.id), 0),
+            vi.remarks,
+            vi.uuid
+         FROM inventory_voucher_items vi
+         WHERE vi.inventory_voucher_id = ?1
+         ORDER BY vi.id",
+    )?;
+
+    let items_iter = stmt_items.query_map(params![id], |row| {
+        let quantity: f64 = row.get(2)?;
+        let returned_quantity: f64 = row.get(7)?;
+        Ok(InventoryVoucherItem {
+            id: Some(row.get(0)?),
+            uuid: row.get(9)?,
+            inventory_voucher_id: Some(id),
+            item_id: row.get(1)?,
+            quantity,
+            purchase_order_item_id: row.get(3)?,
+            requisition_item_id: row.get(4)?,
+            original_voucher_item_id: row.get(5)?,
+            kit_item_id: row.get(6)?,
+            expand_kit: Some(false), // Saved lines are already expanded
+            remarks: row.get(8)?,
+            returned_quantity: Some(returned_quantity),
+            net_quantity: Some(quantity - returned_quantity),
+        })
+    })?;
+
+    let mut final_voucher = voucher;
+    for item in items_iter {
+        final_voucher.items.push(item?);
+    }
+    final_voucher.attributes = Some(load_entity_attributes(conn, "voucher", id)?);
+
+    Ok(final_voucher)
+}
+
+pub fn delete_inventory_voucher(app: &AppHandle, id: i64) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    remove_inventory_voucher(&tx, id)?;
+    tx.commit()?;
+
+    // The rows are gone; a folder that cannot be removed only leaves orphaned files behind
+    let _ = std::fs::remove_dir_all(attachments_dir(app, id));
+    Ok(())
+}
+
+// Deletes a voucher within the caller's transaction; its attachment files are left to the caller
+pub fn remove_inventory_voucher(tx: &rusqlite::Transaction, id: i64) -> Result<()> {
+    let (order_id, requisition_id): (Option<i64>, Option<i64>) = tx
+        .query_row(
+            "SELECT purchase_order_id, requisition_id FROM inventory_vouchers WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .unwrap_or_default();
+
+    let returns: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM inventory_vouchers WHERE original_voucher_id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if returns > 0 {
+        return Err(validation_error(
+            "Returns are recorded against this voucher. Delete the returns first.",
+        ));
+    }
+
+    // 1. Delete Stock Movements
+    tx.execute(
+        "DELETE FROM stock_movements WHERE voucher_id = ?1",
+        params![id],
+    )?;
+
+    // 2. Delete Voucher Items
+    tx.execute(
+        "DELETE FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
+        params![id],
+    )?;
+
+    // 3. Delete Voucher
+    delete_attributes(tx, "voucher", id)?;
+    tx.execute(
+        "DELETE FROM voucher_attachments WHERE voucher_id = ?1",
+        params![id],
+    )?;
+    tx.execute("DELETE FROM inventory_vouchers WHERE id = ?1", params![id])?;
+
+    // 4. Reopen the purchase order or requisition the voucher was recorded against
+    if let Some(order_id) = order_id {
+        refresh_purchase_order_status(tx, order_id)?;
+    }
+    if let Some(requisition_id) = requisition_id {
+        refresh_requisition_status(tx, requisition_id)?;
+    }
+    Ok(())
+}
+
+pub fn update_inventory_voucher(app: &AppHandle, voucher: InventoryVoucher) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    replace_inventory_voucher(&tx, voucher)?;
+    tx.commit()?;
+    Ok(())
+}
+
+// Rewrites a voucher and its stock movements within the caller's transaction
+pub fn replace_inventory_voucher(
+    tx: &rusqlite::Transaction,
+    mut voucher: InventoryVoucher,
+) -> Result<()> {
+    let voucher_id = voucher.id.ok_or(rusqlite::Error::QueryReturnedNoRows)?; // Check for ID
+
+    // 1. Check if exists, remembering the purchase order or requisition it was linked to
+    let (previous_order_id, previous_requisition_id): (Option<i64>, Option<i64>) = tx.query_row(
+        "SELECT purchase_order_id, requisition_id FROM inventory_vouchers WHERE id = ?1",
+        params![voucher_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let mut stmt =
+        tx.prepare("SELECT id, uuid FROM inventory_voucher_items WHERE inventory_voucher_id = ?1")?;
+    let previous_items = stmt
+        .query_map(params![voucher_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?))
+        })?
+        .collect::<Result<HashMap<_, _>>>()?;
+    drop(stmt);
+
+    // 2. Clear existing items and movements (Simpler than diffing)
+    tx.execute(
+        "DELETE FROM stock_movements WHERE voucher_id = ?1",
+        params![voucher_id],
+    )?;
+    tx.execute(
+        "DELETE FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
+        params![voucher_id],
+    )?;
+
+    // 3. Update Voucher Details
+    // Re-generate remarks if needed (logic similar to create, or just keep what user sent)
+    // Get transaction type name for movement logic and remarks generation
+    let type_name: String = tx.query_row(
+        "SELECT name FROM inventory_transaction_types WHERE id = ?1",
+        params![voucher.voucher_type_id],
+        |row| row.get(0),
+    )?;
+
+    expand_kit_lines(tx, &type_name, &mut voucher)?;
+    validate_return_reference(tx, &type_name, &mut voucher)?;
+    validate_project_reference(tx, &type_name, &mut voucher)?;
+    validate_purchase_order_receipt(tx, &type_name, &mut voucher)?;
+    validate_purchase_reference(tx, &type_name, &mut voucher, Some(voucher_id))?;
+    validate_requisition_fulfilment(tx, &type_name, &mut voucher)?;
+    normalize_voucher_references(&mut voucher);
+
+    // If remarks are empty, regenerate them
+    if voucher.remarks.is_none() || voucher.remarks.as_ref().unwrap().trim().is_empty() {
+        voucher.remarks = Some(generate_remarks(tx, &type_name, &voucher));
+    }
+
+    tx.execute(
+        "UPDATE inventory_vouchers 
+         SET voucher_date = ?1, source_site_id = ?2, destination_site_id = ?3, voucher_type_id = ?4,
+             supplier_id = ?5, bill_number = ?6, bill_date = ?7, purchase_order_id = ?8, requisition_id = ?9,
+             original_voucher_id = ?10, project_id = ?11, activity_code = ?12, remarks = ?13,
+             challan_number = ?14, vehicle_number = ?15, driver_name = ?16, receiver_name = ?17
+         WHERE id = ?18",
+        params![
+            voucher.voucher_date,
+            voucher.source_site_id,
+            voucher.destination_site_id,
+            voucher.voucher_type_id,
+            voucher.supplier_id,
+            voucher.bill_number,
+            voucher.bill_date,
+            voucher.purchase_order_id,
+            voucher.requisition_id,
+            voucher.original_voucher_id,
+            voucher.project_id,
+            voucher.activity_code,
+            voucher.remarks,
+            voucher.challan_number,
+            voucher.vehicle_number,
+            voucher.driver_name,
+            voucher.receiver_name,
+            voucher_id
+        ],
+    )?;
+    save_attributes(
+        tx,
+        "voucher",
+        voucher_id,
+        voucher.attributes.as_ref(),
+        false,
+    )?;
+
+    // 4. Re-insert Items and recreate Stock Movements
+    // Existing lines keep their ids and uuids so returns recorded against them stay linked
+    for item in &mut voucher.items {
+        if let Some(uuid) = item.id.and_then(|id| previous_items.get(&id)) {
+            item.uuid = uuid.clone();
+        }
+    }
+    for item in &voucher.items {
+        let keep_id = item.id.filter(|id| previous_items.contains_key(id));
+        let voucher_item_id = insert_voucher_item(tx, voucher_id, item, keep_id)?;
+
+        // Create stock movements based on transaction type
+        create_stock_movements(tx, &type_name, &voucher, voucher_item_id, item)?;
+    }
+
+    validate_returns_against(tx, voucher_id)?;
+
+    // 5. Refresh status of purchase orders and requisitions affected by the change
+    if let Some(order_id) = previous_order_id {
+        refresh_purchase_order_status(tx, order_id)?;
+    }
+    if let Some(order_id) = voucher
+        .purchase_order_id
+        .filter(|id| Some(*id) != previous_order_id)
+    {
+        refresh_purchase_order_status(tx, order_id)?;
+    }
+    if let Some(requisition_id) = previous_requisition_id {
+        refresh_requisition_status(tx, requisition_id)?;
+    }
+    if let Some(requisition_id) = voucher
+        .requisition_id
+        .filter(|id| Some(*id) != previous_requisition_id)
+    {
+        refresh_requisition_status(tx, requisition_id)?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Voucher Attachment Operations
+// ============================================================================
+
+pub const MAX_ATTACHMENT_BYTES: usize = 25 * 1024 * 1024;
+
+// Attachment files are kept under <company folder>/attachments/<voucher id>/
+pub fn attachments_dir(app: &AppHandle, voucher_id: i64) -> PathBuf {
+    company_data_dir(app)
+        .join("attachments")
+        .join(voucher_id.to_string())
+}
+
+fn attachment_file_error(e: std::io::Error) -> rusqlite::Error {
+    validation_error(format!("Could not access the attachment file: {}", e))
+}
+
+fn attachment_mime_type(file_name: &str) -> Option<String> {
+    let extension = Path::new(file_name)
+        .extension()?
+        .to_str()?
+        .to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        _ => return None,
+    };
+    Some(mime.to_string())
+}
+
+// Keep the original name recognisable on disk while staying valid on every platform
+fn stored_attachment_name(id: i64, file_name: &str) -> String {
+    let safe: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(100)
+        .collect();
+    format!("{}-{}", id, safe.trim_start_matches('.'))
+}
+
+fn attachment_from_row(app: &AppHandle, row: &rusqlite::Row) -> Result<VoucherAttachment> {
+    let voucher_id: i64 = row.get(1)?;
+    let stored_name: String = row.get(3)?;
+    Ok(VoucherAttachment {
+        id: row.get(0)?,
+        voucher_id,
+        file_name: row.get(2)?,
+        mime_type: row.get(4)?,
+        size_bytes: row.get(5)?,
+        description: row.get(6)?,
+        path: attachments_dir(app, voucher_id)
+            .join(stored_name)
+            .to_string_lossy()
+            .into_owned(),
+        created_at: row.get(7)?,
+    })
+}
+
+// Stores a copy of the file (sent base64-encoded) next to the database and records it against the voucher
+pub fn add_voucher_attachment(
+    app: &AppHandle,
+    voucher_id: i64,
+    file_name: String,
+    data: String,
+    description: Option<String>,
+) -> Result<VoucherAttachment> {
+    let file_name = Path::new(file_name.trim())
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| validation_error("Attachment file name is required"))?;
+    let bytes = general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|_| validation_error("Attachment data is not valid base64"))?;
+    if bytes.is_empty() {
+        return Err(validation_error("Attachment file is empty"));
+    }
+    if bytes.len() > MAX_ATTACHMENT_BYTES {
+        return Err(validation_error(format!(
+            "Attachments can be at most {} MB",
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        )));
+    }
+    let description = description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    let exists: bool = tx.query_row(
+        "SELECT EXISTS (SELECT 1 FROM inventory_vouchers WHERE id = ?1)",
+        params![voucher_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(validation_error("The voucher does not exist"));
+    }
+
+    tx.execute(
+        "INSERT INTO voucher_attachments (voucher_id, file_name, stored_name, mime_type, size_bytes, description)
+         VALUES (?1, ?2, '', ?3, ?4, ?5)",
+        params![
+            voucher_id,
+            file_name,
+            attachment_mime_type(&file_name),
+            bytes.len() as i64,
+            description
+        ],
+    )?;
+    let id = tx.last_insert_rowid();
+    let stored_name = stored_attachment_name(id, &file_name);
+    tx.execute(
+        "UPDATE voucher_attachments SET stored_name = ?1 WHERE id = ?2",
+        params![stored_name, id],
+    )?;
+
+    // Write the file before committing so a failed write leaves no row behind
+    let dir = attachments_dir(app, voucher_id);
+    std::fs::create_dir_all(&dir).map_err(attachment_file_error)?;
+    std::fs::write(dir.join(&stored_name), &bytes).map_err(attachment_file_error)?;
+
+    let attachment = tx.query_row(
+        "SELECT id, voucher_id, file_name, stored_name, mime_type, size_bytes, description, created_at
+         FROM voucher_attachments WHERE id = ?1",
+        params![id],
+        |row| attachment_from_row(app, row),
+    )?;
+    tx.commit()?;
+    Ok(attachment)
+}
+
+pub fn get_voucher_attachments(app: &AppHandle, voucher_id: i64) -> Result<Vec<VoucherAttachment>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare(
+        "SELECT id, voucher_id, file_name, stored_name, mime_type, size_bytes, description, created_at
+         FROM voucher_attachments
+         WHERE voucher_id = ?1
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![voucher_id], |row| attachment_from_row(app, row))?;
+    rows.collect()
+}
+
+// Path of an attachment's stored copy, for opening it
+pub fn voucher_attachment_path(app: &AppHandle, id: i64) -> Result<PathBuf> {
+    let conn = get_db_conn(app)?;
+    let (voucher_id, stored_name): (i64, String) = conn
+        .query_row(
+            "SELECT voucher_id, stored_name FROM voucher_attachments WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| validation_error("The attachment does not exist"))?;
+    let path = attachments_dir(app, voucher_id).join(stored_name);
+    if !path.exists() {
+        return Err(validation_error("The attachment file is missing"));
+    }
+    Ok(path)
+}
+
+pub fn delete_voucher_attachment(app: &AppHandle, id: i64) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    let (voucher_id, stored_name): (i64, String) = tx.query_row(
+        "SELECT voucher_id, stored_name FROM voucher_attachments WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    tx.execute("DELETE FROM voucher_attachments WHERE id = ?1", params![id])?;
+    tx.commit()?;
+
+    // The row is gone; a file that is missing or cannot be removed is only left orphaned
+    let _ = std::fs::remove_file(attachments_dir(app, voucher_id).join(stored_name));
+    Ok(())
+}
+
+// ============================================================================
+// Stock Balance Operations
+// ============================================================================
+
+pub fn get_stock_balance(app: &AppHandle, site_id: i64, item_id: i64) -> Result<f64> {
+    let conn = get_db_conn(app)?;
+    let balance: f64 = conn.query_row(
+        "SELECT COALESCE(SUM(stock_in) - SUM(stock_out), 0) FROM stock_movements WHERE site_id = ?1 AND item_id = ?2",
+        params![site_id, item_id],
+        |row| row.get(0),
+    )?;
+    Ok(balance)
+}
+
+// Get all stock balances (grouped by item and site)
+pub fn get_stock_balances(
+    app: &AppHandle,
+    item_name: Option<String>,
+    site_id: Option<i64>,
+    category_id: Option<i64>,
+    item_attributes: Option<HashMap<String, String>>,
+    page: i64,
+    limit: i64,
+) -> Result<PaginatedResponse<StockBalance>> {
+    let conn = get_db_conn(app)?;
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    push_item_search(
+        &conn,
+        item_name.as_deref().unwrap_or(""),
+        &mut where_clauses,
+        &mut params_vec,
+    )?;
+
+    if let Some(sid) = site_id {
+        where_clauses.push("s.id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    if let Some(cid) = category_id {
+        where_clauses.push(items_in_category_sql("i.id"));
+        params_vec.push(Box::new(cid));
+    }
+
+    push_attribute_filters(
+        "item",
+        "i.id",
+        &item_attributes,
+        &mut where_clauses,
+        &mut params_vec,
+    );
+
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    // 1. Get Total Count
+    let count_query = format!(
+        "SELECT COUNT(*) FROM (
+            SELECT i.id
+            FROM items i
+            CROSS JOIN sites s
+            LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
+            WHERE {}
+            GROUP BY i.id, s.id
+            HAVING COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) != 0
+        )",
+        where_sql
+    );
+    let total_count: i64 = conn.query_row(&count_query, &param_refs[..], |row| row.get(0))?;
+
+    let query = format!(
+        "SELECT 
+            i.id as item_id,
+            i.code as item_code,
+            i.name as item_name,
+            b.name as brand_name,
+            m.name as model_name,
+            s.id as site_id,
+            s.code as site_code,
+            s.name as site_name,
+            s.type as site_type,
+            COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) as balance
+         FROM items i
+         CROSS JOIN sites s
+         LEFT JOIN brands b ON i.brand_id = b.id
+         LEFT JOIN models m ON i.model_id = m.id
+         LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
+         WHERE {}
+         GROUP BY i.id, s.id
+         HAVING balance != 0
+         ORDER BY s.name, i.name
+         LIMIT ? OFFSET ?",
+        where_sql
+    );
+
+    let (limit_val, offset) = if limit == -1 {
+        (-1, 0)
+    } else {
+        (limit, (page - 1) * limit)
+    };
+
+    let mut final_params_refs = param_refs.clone();
+    final_params_refs.push(&limit_val);
+    final_params_refs.push(&offset);
+
+    let mut stmt = conn.prepare(&query)?;
+
+    let rows = stmt.query_map(&final_params_refs[..], |row| {
+        Ok(StockBalance {
+            item_id: row.get(0)?,
+            item_code: row.get(1)?,
+            item_name: row.get(2)?,
+            brand_name: row.get(3)?,
+            model_name: row.get(4)?,
+            site_id: row.get(5)?,
+            site_code: row.get(6)?,
+            site_name: row.get(7)?,
+            site_type: row.get(8)?,
+            balance: row.get(9)?,
+        })
+    })?;
+
+    let items: Vec<StockBalance> = rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginatedResponse { items, total_count })
+}
+
+// Get stock balance for specific item across all sites
+pub fn get_item_stock_by_sites(app: &AppHandle, item_id: i64) -> Result<Vec<StockBalance>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare(
+        "SELECT 
+            i.id as item_id,
+            i.code as item_code,
+            i.name as item_name,
+            b.name as brand_name,
+            m.name as model_name,
+            s.id as site_id,
+            s.code as site_code,
+            s.name as site_name,
+            s.type as site_type,
+            COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) as balance
+         FROM items i
+         CROSS JOIN sites s
+         LEFT JOIN brands b ON i.brand_id = b.id
+         LEFT JOIN models m ON i.model_id = m.id
+         LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
+         WHERE i.id = ?1
+         GROUP BY s.id
+         ORDER BY s.name",
+    )?;
+
+    let rows = stmt.query_map(params![item_id], |row| {
+        Ok(StockBalance {
+            item_id: row.get(0)?,
+            item_code: row.get(1)?,
+            item_name: row.get(2)?,
+            brand_name: row.get(3)?,
+            model_name: row.get(4)?,
+            site_id: row.get(5)?,
+            site_code: row.get(6)?,
+            site_name: row.get(7)?,
+            site_type: row.get(8)?,
+            balance: row.get(9)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+// Get stock balance for specific site
+pub fn get_site_stock_balances(app: &AppHandle, site_id: i64) -> Result<Vec<StockBalance>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare(
+        "SELECT 
+            i.id as item_id,
+            i.code as item_code,
+            i.name as item_name,
+            b.name as brand_name,
+            m.name as model_name,
+            s.id as site_id,
+            s.code as site_code,
+            s.name as site_name,
+            s.type as site_type,
+            COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) as balance
+         FROM items i
+         CROSS JOIN sites s
+         LEFT JOIN brands b ON i.brand_id = b.id
+         LEFT JOIN models m ON i.model_id = m.id
+         LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
+         WHERE s.id = ?1
+         GROUP BY i.id
+         HAVING balance != 0
+         ORDER BY i.name",
+    )?;
+
+    let rows = stmt.query_map(params![site_id], |row| {
+        Ok(StockBalance {
+            item_id: row.get(0)?,
+            item_code: row.get(1)?,
+            item_name: row.get(2)?,
+            brand_name: row.get(3)?,
+            model_name: row.get(4)?,
+            site_id: row.get(5)?,
+            site_code: row.get(6)?,
+            site_name: row.get(7)?,
+            site_type: row.get(8)?,
+            balance: row.get(9)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+// ============================================================================
+// Stock Movement History Operations
+// ============================================================================
+
+pub fn get_stock_movement_history(
+    app: &AppHandle,
+    item_id: Option<i64>,
+    site_id: Option<i64>,
+    voucher_type_id: Option<i64>,
+    category_id: Option<i64>,
+    item_attributes: Option<HashMap<String, String>>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    page: i64,
+    limit: i64,
+) -> Result<PaginatedResponse<StockMovementHistory>> {
+    let conn = get_db_conn(app)?;
+
+    // Base WHERE clause construction
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(iid) = item_id {
+        where_clauses.push("sm.item_id = ?".to_string());
+        params_vec.push(Box::new(iid));
+    }
+
+    if let Some(sid) = site_id {
+        where_clauses.push("sm.site_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    if let Some(vtid) = voucher_type_id {
+        where_clauses.push("v.voucher_type_id = ?".to_string());
+        params_vec.push(Box::new(vtid));
+    }
+
+    if let Some(cid) = category_id {
+        where_clauses.push(items_in_category_sql("sm.item_id"));
+        params_vec.push(Box::new(cid));
+    }
+
+    push_attribute_filters(
+        "item",
+        "sm.item_id",
+        &item_attributes,
+        &mut where_clauses,
+        &mut params_vec,
+    );
+
+    if let Some(fd) = &from_date {
+        where_clauses.push("v.voucher_date >= ?".to_string());
+        params_vec.push(Box::new(fd));
+    }
+
+    if let Some(td) = &to_date {
+        where_clauses.push("v.voucher_date <= ?".to_string());
+        params_vec.push(Box::new(td));
+    }
+
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    // 1. Get Total Count
+    let count_query = format!(
+        "SELECT COUNT(*) 
+         FROM stock_movements sm
+         JOIN inventory_vouchers v ON sm.voucher_id = v.id
+         WHERE {}",
+        where_sql
+    );
+    let total_count: i64 = conn.query_row(&count_query, &param_refs[..], |row| row.get(0))?;
+
+    // 2. Calculate Opening Balance (if item is selected)
+    let mut current_balance = 0.0;
+
+    // 2a. Initial Opening Balance (before from_date)
+    if let (Some(iid), Some(fd)) = (item_id, &from_date) {
+        let mut balance_query = String::from(
+            "SELECT COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0)
+             FROM stock_movements sm
+             JOIN inventory_vouchers v ON sm.voucher_id = v.id
+             WHERE sm.item_id = ? AND v.voucher_date < ?",
+        );
+        let mut balance_params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(iid), Box::new(fd)];
+
+        if let Some(sid) = site_id {
+            balance_query.push_str(" AND sm.site_id = ?");
+            balance_params.push(Box::new(sid));
+        }
+
+        if let Some(vtid) = voucher_type_id {
+            balance_query.push_str(" AND v.voucher_type_id = ?");
+            balance_params.push(Box::new(vtid));
+        }
+
+        let b_param_refs: Vec<&dyn rusqlite::ToSql> =
+            balance_params.iter().map(|p| p.as_ref()).collect();
+        let initial_opening: f64 = conn
+            .query_row(&balance_query, &b_param_refs[..], |row| row.get(0))
+            .unwrap_or(0.0);
+        current_balance += initial_opening;
+    }
+
+    // 2b. Add skipped rows balance (for pagination > page 1)
+    let (limit_val, offset) = if limit == -1 {
+        (-1, 0)
+    } else {
+        (limit, (page - 1) * limit)
+    };
+
+    if offset > 0 && item_id.is_some() {
+        // We need to sum stock_in - stock_out for the rows that are skipped by OFFSET
+        // To do this reliably, we must use the exact same ORDER BY as the main query
+        // This is complex in SQLite without window functions or a subquery with LIMIT
+
+        let skipped_balance_query = format!(
+            "SELECT COALESCE(SUM(sub.stock_in) - SUM(sub.stock_out), 0) FROM (
+                 SELECT sm.stock_in, sm.stock_out
+                 FROM stock_movements sm
+                 JOIN inventory_vouchers v ON sm.voucher_id = v.id
+                 WHERE {}
+                 ORDER BY v.voucher_date ASC, sm.created_at ASC
+                 LIMIT ?
+            ) sub",
+            where_sql
+        );
+
+        // Add limit to params
+        let mut skipped_params_refs = param_refs.clone();
+        let offset_val = offset; // Move to own var to borrow
+        skipped_params_refs.push(&offset_val);
+
+        let skipped_balance: f64 = conn
+            .query_row(&skipped_balance_query, &skipped_params_refs[..], |row| {
+                row.get(0)
+            })
+            .unwrap_or(0.0);
+
+        current_balance += skipped_balance;
+    }
+
+    // 3. Fetch Page Items
+    let query = format!(
+        "SELECT 
+            sm.id,
+            sm.voucher_id,
+            v.transaction_number,
+            v.voucher_date,
+            t.name as voucher_type_name,
+            sm.item_id,
+            i.code as item_code,
+            i.name as item_name,
+            b.name as brand_name,
+            m.name as model_name,
+            sm.site_id,
+            s.code as site_code,
+            s.name as site_name,
+            sm.stock_in,
+            sm.stock_out,
+            v.remarks,
+            sm.created_at
+         FROM stock_movements sm
+         JOIN inventory_vouchers v ON sm.voucher_id = v.id
+         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+         JOIN items i ON sm.item_id = i.id
+         LEFT JOIN brands b ON i.brand_id = b.id
+         LEFT JOIN models m ON i.model_id = m.id
+         JOIN sites s ON sm.site_id = s.id
+         WHERE {}
+         ORDER BY v.voucher_date ASC, sm.created_at ASC
+         LIMIT ? OFFSET ?",
+        where_sql
+    );
+
+    let mut final_params_refs = param_refs.clone();
+    final_params_refs.push(&limit_val);
+    final_params_refs.push(&offset);
+
+    let mut stmt = conn.prepare(&query)?;
+
+    let mut movements: Vec<StockMovementHistory> = stmt
+        .query_map(&final_params_refs[..], |row| {
+            Ok(StockMovementHistory {
+                id: row.get(0)?,
+                voucher_id: row.get(1)?,
+                transaction_number: row.get(2)?,
+                voucher_date: row.get(3)?,
+                voucher_type_name: row.get(4)?,
+                item_id: row.get(5)?,
+                item_code: row.get(6)?,
+                item_name: row.get(7)?,
+                brand_name: row.get(8)?,
+                model_name: row.get(9)?,
+                site_id: row.get(10)?,
+                site_code: row.get(11)?,
+                site_name: row.get(12)?,
+                stock_in: row.get(13)?,
+                stock_out: row.get(14)?,
+                remarks: row.get(15)?,
+                running_balance: 0.0, // Will calculate below
+                created_at: row.get(16)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    // 4. Calculate Running Balance for the page
+    if item_id.is_some() {
+        for movement in &mut movements {
+            current_balance += movement.stock_in - movement.stock_out;
+            movement.running_balance = current_balance;
+        }
+    } else {
+        // If multiple items, running balance is meaningless
+        for movement in &mut movements {
+            movement.running_balance = 0.0;
+        }
+    }
+
+    Ok(PaginatedResponse {
+        items: movements,
+        total_count,
+    })
+}
+
+// ============================================================================
+// Purchase Reports
+// ============================================================================
+
+fn purchase_filters(
+    supplier_id: Option<i64>,
+    item_id: Option<i64>,
+    from_date: &Option<String>,
+    to_date: &Option<String>,
+) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut where_clauses = vec!["t.name IN ('Purchase Inward', 'Purchase Return')".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(sid) = supplier_id {
+        where_clauses.push("v.supplier_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    if let Some(iid) = item_id {
+        where_clauses.push("vi.item_id = ?".to_string());
+        params_vec.push(Box::new(iid));
+    }
+
+    if let Some(fd) = from_date {
+        where_clauses.push("v.voucher_date >= ?".to_string());
+        params_vec.push(Box::new(fd.clone()));
+    }
+
+    if let Some(td) = to_date {
+        where_clauses.push("v.voucher_date <= ?".to_string());
+        params_vec.push(Box::new(td.clone()));
+    }
+
+    (where_clauses.join(" AND "), params_vec)
+}
+
+// Line-level list of purchased items with supplier and bill reference.
+// Purchase returns are listed with negative quantities.
+pub fn get_purchase_register(
+    app: &AppHandle,
+    supplier_id: Option<i64>,
+    item_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    page: i64,
+    limit: i64,
+) -> Result<PaginatedResponse<PurchaseRegisterEntry>> {
+    let conn = get_db_conn(app)?;
+
+    let (where_sql, params_vec) = purchase_filters(supplier_id, item_id, &from_date, &to_date);
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    // 1. Get Total Count
+    let count_query = format!(
+        "SELECT COUNT(*)
+         FROM inventory_voucher_items vi
+         JOIN inventory_vouchers v ON vi.inventory_voucher_id = v.id
+         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+         WHERE {}",
+        where_sql
+    );
+    let total_count: i64 = conn.query_row(&count_query, &param_refs[..], |row| row.get(0))?;
+
+    // 2. Fetch Page Items
+    let query = format!(
+        "SELECT 
+            v.id,
+            v.transaction_number,
+            v.voucher_date,
+            v.supplier_id,
+            sup.name as supplier_name,
+            v.bill_number,
+            v.bill_date,
+            vi.item_id,
+            i.code as item_code,
+            i.name as item_name,
+            b.name as brand_name,
+            m.name as model_name,
+            COALESCE(v.destination_site_id, v.source_site_id),
+            s.name as site_name,
+            CASE WHEN t.name = 'Purchase Return' THEN -vi.quantity ELSE vi.quantity END
+         FROM inventory_voucher_items vi
+         JOIN inventory_vouchers v ON vi.inventory_voucher_id = v.id
+         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+         JOIN items i ON vi.item_id = i.id
+         LEFT JOIN brands b ON i.brand_id = b.id
+         LEFT JOIN models m ON i.model_id = m.id
+         LEFT JOIN suppliers sup ON v.supplier_id = sup.id
+         LEFT JOIN sites s ON COALESCE(v.destination_site_id, v.source_site_id) = s.id
+         WHERE {}
+         ORDER BY v.voucher_date DESC, v.id DESC
+         LIMIT ? OFFSET ?",
+        where_sql
+    );
+
+    let (limit_val, offset) = if limit == -1 {
+        (-1, 0)
+    } else {
+        (limit, (page - 1) * limit)
+    };
+
+    let mut final_params_refs = param_refs.clone();
+    final_params_refs.push(&limit_val);
+    final_params_refs.push(&offset);
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(&final_params_refs[..], |row| {
+        Ok(PurchaseRegisterEntry {
+            voucher_id: row.get(0)?,
+            transaction_number: row.get(1)?,
+            voucher_date: row.get(2).unwrap_or_default(),
+            supplier_id: row.get(3)?,
+            supplier_name: row.get(4)?,
+            bill_number: row.get(5)?,
+            bill_date: row.get(6)?,
+            item_id: row.get(7)?,
+            item_code: row.get(8)?,
+            item_name: row.get(9)?,
+            brand_name: row.get(10)?,
+            model_name: row.get(11)?,
+            site_id: row.get(12)?,
+            site_name: row.get(13)?,
+            quantity: row.get(14)?,
+        })
+    })?;
+
+    let items: Vec<PurchaseRegisterEntry> = rows.collect::<Result<Vec<_>>>()?;
+
+    Ok(PaginatedResponse { items, total_count })
+}
+
+// Net purchased quantities grouped by "supplier", "item" or "month"
+pub fn get_purchase_summary(
+    app: &AppHandle,
+    group_by: String,
+    supplier_id: Option<i64>,
+    item_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<Vec<PurchaseSummary>> {
+    let conn = get_db_conn(app)?;
+
+    let (key_sql, name_sql) = match group_by.as_str() {
+        "supplier" => (
+            "COALESCE(CAST(v.supplier_id AS TEXT), '')",
+            "COALESCE(sup.name, '(No supplier)')",
+        ),
+        "item" => ("CAST(vi.item_id AS TEXT)", "i.code || ' - ' || i.name"),
+        "month" => (
+            "strftime('%Y-%m', v.voucher_date)",
+            "strftime('%Y-%m', v.voucher_date)",
+        ),
+        other => {
+            return Err(validation_error(format!(
+                "Unknown purchase report grouping '{}'",
+                other
+            )))
+        }
+    };
+
+    let (where_sql, params_vec) = purchase_filters(supplier_id, item_id, &from_date, &to_date);
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    let query = format!(
+        "SELECT 
+            {key} as group_key,
+            {name} as group_name,
+            COUNT(DISTINCT v.id) as voucher_count,
+            COALESCE(SUM(CASE WHEN t.name = 'Purchase Return' THEN -vi.quantity ELSE vi.quantity END), 0) as total_quantity
+         FROM inventory_voucher_items vi
+         JOIN inventory_vouchers v ON vi.inventory_voucher_id = v.id
+         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+         JOIN items i ON vi.item_id = i.id
+         LEFT JOIN suppliers sup ON v.supplier_id = sup.id
+         WHERE {where_sql}
+         GROUP BY group_key
+         ORDER BY group_name",
+        key = key_sql,
+        name = name_sql,
+        where_sql = where_sql
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        Ok(PurchaseSummary {
+            group_key: row.get(0)?,
+            group_name: row.get(1)?,
+            voucher_count: row.get(2)?,
+            total_quantity: row.get(3)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+// Open and partially received purchase order lines with pending quantities
+pub fn get_outstanding_purchase_orders(
+    app: &AppHandle,
+    supplier_id: Option<i64>,
+    item_id: Option<i64>,
+    site_id: Option<i64>,
+) -> Result<Vec<OutstandingPurchaseOrderLine>> {
+    let conn = get_db_conn(app)?;
+
+    let mut where_clauses = vec!["po.status IN ('Open', 'Partial')".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(sid) = supplier_id {
+        where_clauses.push("po.supplier_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    if let Some(iid) = item_id {
+        where_clauses.push("poi.item_id = ?".to_string());
+        params_vec.push(Box::new(iid));
+    }
+
+    if let Some(sid) = site_id {
+        where_clauses.push("po.delivery_site_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    let query = format!(
+        "SELECT * FROM (
+            SELECT 
+                po.id,
+                po.po_number,
+                po.po_date,
+                po.expected_date,
+                po.status,
+                po.supplier_id,
+                sup.name as supplier_name,
+                po.delivery_site_id,
+                s.name as delivery_site_name,
+                poi.id as purchase_order_item_id,
+                poi.item_id,
+                i.code as item_code,
+                i.name as item_name,
+                poi.ordered_quantity,
+                COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi WHERE vi.purchase_order_item_id = poi.id), 0) as received_quantity,
+                poi.rate
+             FROM purchase_order_items poi
+             JOIN purchase_orders po ON poi.purchase_order_id = po.id
+             JOIN suppliers sup ON po.supplier_id = sup.id
+             JOIN items i ON poi.item_id = i.id
+             LEFT JOIN sites s ON po.delivery_site_id = s.id
+             WHERE {}
+         )
+         WHERE ordered_quantity - received_quantity > 0
+         ORDER BY COALESCE(expected_date, po_date), po_number, purchase_order_item_id",
+        where_sql
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        let ordered_quantity: f64 = row.get(13)?;
+        let received_quantity: f64 = row.get(14)?;
+        let rate: Option<f64> = row.get(15)?;
+        let pending_quantity = ordered_quantity - received_quantity;
+
+        Ok(OutstandingPurchaseOrderLine {
+            purchase_order_id: row.get(0)?,
+            po_number: row.get(1)?,
+            po_date: row.get(2)?,
+            expected_date: row.get(3)?,
+            status: row.get(4)?,
+            supplier_id: row.get(5)?,
+            supplier_name: row.get(6)?,
+            delivery_site_id: row.get(7)?,
+            delivery_site_name: row.get(8)?,
+            purchase_order_item_id: row.get(9)?,
+            item_id: row.get(10)?,
+            item_code: row.get(11)?,
+            item_name: row.get(12)?,
+            ordered_quantity,
+            received_quantity,
+            pending_quantity,
+            rate,
+            pending_value: rate.map(|r| r * pending_quantity),
+        })
+    })?;
+
+    rows.collect()
+}
+
+// ============================================================================
+// Requisition Reports
+// ============================================================================
+
+// Requisition lines per site with approved, issued and pending quantities
+pub fn get_requisition_status_report(
+    app: &AppHandle,
+    site_id: Option<i64>,
+    status: Option<String>,
+    pending_only: bool,
+) -> Result<Vec<RequisitionStatusLine>> {
+    let conn = get_db_conn(app)?;
+
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(sid) = site_id {
+        where_clauses.push("r.site_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    if let Some(st) = &status {
+        where_clauses.push("r.status = ?".to_string());
+        params_vec.push(Box::new(st.clone()));
+    }
+
+    if pending_only {
+        where_clauses.push("r.status IN ('Pending', 'Approved', 'Partial')".to_string());
+    }
+
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
     let query = format!(
         "SELECT 
-            i.id as item_id,
-            i.code as item_code,
-            i.name as item_name,
-            b.name as brand_name,
-            m.name as model_name,
-            s.id as site_id,
+            r.id,
+            r.requisition_number,
+            r.requisition_date,
+            r.needed_by,
+            r.status,
+            r.site_id,
             s.code as site_code,
             s.name as site_name,
-            s.type as site_type,
-            COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) as balance
-         FROM items i
-         CROSS JOIN sites s
-         LEFT JOIN brands b ON i.brand_id = b.id
-         LEFT JOIN models m ON i.model_id = m.id
-         LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
+            ri.id,
+            ri.item_id,
+            i.code as item_code,
+            i.name as item_name,
+            ri.requested_quantity,
+            ri.approved_quantity,
+            COALESCE((SELECT SUM(vi.quantity) FROM inventory_voucher_items vi WHERE vi.requisition_item_id = ri.id), 0)
+         FROM material_requisition_items ri
+         JOIN material_requisitions r ON ri.requisition_id = r.id
+         JOIN sites s ON r.site_id = s.id
+         JOIN items i ON ri.item_id = i.id
          WHERE {}
-         GROUP BY i.id, s.id
-         HAVING balance != 0
-         ORDER BY s.name, i.name
-         LIMIT ? OFFSET ?",
+         ORDER BY s.name, COALESCE(r.needed_by, r.requisition_date), r.id, ri.id",
         where_sql
     );
 
-    let (limit_val, offset) = if limit == -1 {
-        (-1, 0)
-    } else {
-        (limit, (page - 1) * limit)
-    };
-
-    let mut final_params_refs = param_refs.clone();
-    final_params_refs.push(&limit_val);
-    final_params_refs.push(&offset);
-
     let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        let status: String = row.get(4)?;
+        let requested_quantity: f64 = row.get(12)?;
+        let approved_quantity: Option<f64> = row.get(13)?;
+        let fulfilled_quantity: f64 = row.get(14)?;
+
+        // Nothing is pending on requisitions that will not be issued further
+        let pending_quantity = match status.as_str() {
+            "Rejected" | "Cancelled" => 0.0,
+            _ => (approved_quantity.unwrap_or(requested_quantity) - fulfilled_quantity).max(0.0),
+        };
 
-    let rows = stmt.query_map(&final_params_refs[..], |row| {
-        Ok(StockBalance {
-            item_id: row.get(0)?,
-            item_code: row.get(1)?,
-            item_name: row.get(2)?,
-            brand_name: row.get(3)?,
-            model_name: row.get(4)?,
+        Ok(RequisitionStatusLine {
+            requisition_id: row.get(0)?,
+            requisition_number: row.get(1)?,
+            requisition_date: row.get(2)?,
+            needed_by: row.get(3)?,
+            status,
             site_id: row.get(5)?,
             site_code: row.get(6)?,
             site_name: row.get(7)?,
-            site_type: row.get(8)?,
-            balance: row.get(9)?,
+            requisition_item_id: row.get(8)?,
+            item_id: row.get(9)?,
+            item_code: row.get(10)?,
+            item_name: row.get(11)?,
+            requested_quantity,
+            approved_quantity,
+            fulfilled_quantity,
+            pending_quantity,
         })
     })?;
 
-    let items: Vec<StockBalance> = rows.collect::<Result<Vec<_>>>()?;
+    let lines = rows.collect::<Result<Vec<_>>>()?;
 
-    Ok(PaginatedResponse { items, total_count })
+    if pending_only {
+        return Ok(lines
+            .into_iter()
+            .filter(|line| line.pending_quantity > 0.0)
+            .collect());
+    }
+
+    Ok(lines)
 }
 
-// Get stock balance for specific item across all sites
-pub fn get_item_stock_by_sites(app: &AppHandle, item_id: i64) -> Result<Vec<StockBalance>> {
+// ============================================================================
+// Project Consumption Reports
+// ============================================================================
+
+// Weighted average purchase order rate of received stock per item
+const ITEM_COSTS_CTE: &str = "item_costs AS (
+    SELECT vi.item_id, SUM(vi.quantity * poi.rate) / SUM(vi.quantity) as unit_cost
+    FROM inventory_voucher_items vi
+    JOIN purchase_order_items poi ON vi.purchase_order_item_id = poi.id
+    WHERE poi.rate IS NOT NULL
+    GROUP BY vi.item_id
+    HAVING SUM(vi.quantity) > 0
+)";
+
+// Usage and damage booked to projects, grouped by "project", "activity", "item" or "month".
+// Quantities are valued at the weighted average purchase order rate of received stock;
+// items never received against a priced order are left unvalued.
+pub fn get_project_consumption(
+    app: &AppHandle,
+    group_by: String,
+    project_id: Option<i64>,
+    site_id: Option<i64>,
+    item_id: Option<i64>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+) -> Result<Vec<ProjectConsumptionSummary>> {
     let conn = get_db_conn(app)?;
-    let mut stmt = conn.prepare(
-        "SELECT 
-            i.id as item_id,
-            i.code as item_code,
-            i.name as item_name,
-            b.name as brand_name,
-            m.name as model_name,
-            s.id as site_id,
-            s.code as site_code,
-            s.name as site_name,
-            s.type as site_type,
-            COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) as balance
-         FROM items i
-         CROSS JOIN sites s
-         LEFT JOIN brands b ON i.brand_id = b.id
-         LEFT JOIN models m ON i.model_id = m.id
-         LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
-         WHERE i.id = ?1
-         GROUP BY s.id
-         ORDER BY s.name",
-    )?;
 
-    let rows = stmt.query_map(params![item_id], |row| {
-        Ok(StockBalance {
-            item_id: row.get(0)?,
-            item_code: row.get(1)?,
-            item_name: row.get(2)?,
-            brand_name: row.get(3)?,
-            model_name: row.get(4)?,
-            site_id: row.get(5)?,
-            site_code: row.get(6)?,
-            site_name: row.get(7)?,
-            site_type: row.get(8)?,
-            balance: row.get(9)?,
-        })
-    })?;
+    let (key_sql, name_sql) = match group_by.as_str() {
+        "project" => ("CAST(p.id AS TEXT)", "p.code || ' - ' || p.name"),
+        "activity" => (
+            "CAST(p.id AS TEXT) || '/' || COALESCE(v.activity_code, '')",
+            "p.code || ' / ' || COALESCE(v.activity_code, '(No activity)')",
+        ),
+        "item" => ("CAST(vi.item_id AS TEXT)", "i.code || ' - ' || i.name"),
+        "month" => (
+            "strftime('%Y-%m', v.voucher_date)",
+            "strftime('%Y-%m', v.voucher_date)",
+        ),
+        other => {
+            return Err(validation_error(format!(
+                "Unknown consumption report grouping '{}'",
+                other
+            )))
+        }
+    };
 
-    rows.collect()
-}
+    let mut where_clauses = vec![
+        "t.name IN ('Material Usage', 'Damaged Stock')".to_string(),
+        "v.project_id IS NOT NULL".to_string(),
+    ];
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
 
-// Get stock balance for specific site
-pub fn get_site_stock_balances(app: &AppHandle, site_id: i64) -> Result<Vec<StockBalance>> {
-    let conn = get_db_conn(app)?;
-    let mut stmt = conn.prepare(
-        "SELECT 
-            i.id as item_id,
-            i.code as item_code,
-            i.name as item_name,
-            b.name as brand_name,
-            m.name as model_name,
-            s.id as site_id,
-            s.code as site_code,
-            s.name as site_name,
-            s.type as site_type,
-            COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0) as balance
-         FROM items i
-         CROSS JOIN sites s
-         LEFT JOIN brands b ON i.brand_id = b.id
-         LEFT JOIN models m ON i.model_id = m.id
-         LEFT JOIN stock_movements sm ON sm.item_id = i.id AND sm.site_id = s.id
-         WHERE s.id = ?1
-         GROUP BY i.id
-         HAVING balance != 0
-         ORDER BY i.name",
-    )?;
+    if let Some(pid) = project_id {
+        where_clauses.push("v.project_id = ?".to_string());
+        params_vec.push(Box::new(pid));
+    }
 
-    let rows = stmt.query_map(params![site_id], |row| {
-        Ok(StockBalance {
-            item_id: row.get(0)?,
-            item_code: row.get(1)?,
-            item_name: row.get(2)?,
-            brand_name: row.get(3)?,
-            model_name: row.get(4)?,
-            site_id: row.get(5)?,
-            site_code: row.get(6)?,
-            site_name: row.get(7)?,
-            site_type: row.get(8)?,
-            balance: row.get(9)?,
+    if let Some(sid) = site_id {
+        where_clauses.push("p.site_id = ?".to_string());
+        params_vec.push(Box::new(sid));
+    }
+
+    if let Some(iid) = item_id {
+        where_clauses.push("vi.item_id = ?".to_string());
+        params_vec.push(Box::new(iid));
+    }
+
+    if let Some(fd) = &from_date {
+        where_clauses.push("v.voucher_date >= ?".to_string());
+        params_vec.push(Box::new(fd.clone()));
+    }
+
+    if let Some(td) = &to_date {
+        where_clauses.push("v.voucher_date <= ?".to_string());
+        params_vec.push(Box::new(td.clone()));
+    }
+
+    let where_sql = where_clauses.join(" AND ");
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    let query = format!(
+        "WITH {costs}
+         SELECT 
+            {key} as group_key,
+            {name} as group_name,
+            COUNT(DISTINCT v.id) as voucher_count,
+            COALESCE(SUM(CASE WHEN t.name = 'Material Usage' THEN vi.quantity ELSE 0 END), 0) as used_quantity,
+            COALESCE(SUM(CASE WHEN t.name = 'Damaged Stock' THEN vi.quantity ELSE 0 END), 0) as damaged_quantity,
+            COALESCE(SUM(vi.quantity), 0) as total_quantity,
+            SUM(vi.quantity * c.unit_cost) as total_value,
+            COALESCE(SUM(CASE WHEN c.unit_cost IS NULL THEN vi.quantity ELSE 0 END), 0) as unvalued_quantity
+         FROM inventory_voucher_items vi
+         JOIN inventory_vouchers v ON vi.inventory_voucher_id = v.id
+         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+         JOIN projects p ON v.project_id = p.id
+         JOIN items i ON vi.item_id = i.id
+         LEFT JOIN item_costs c ON vi.item_id = c.item_id
+         WHERE {where_sql}
+         GROUP BY group_key
+         ORDER BY group_name",
+        costs = ITEM_COSTS_CTE,
+        key = key_sql,
+        name = name_sql,
+        where_sql = where_sql
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map(&param_refs[..], |row| {
+        Ok(ProjectConsumptionSummary {
+            group_key: row.get(0)?,
+            group_name: row.get(1)?,
+            voucher_count: row.get(2)?,
+            used_quantity: row.get(3)?,
+            damaged_quantity: row.get(4)?,
+            total_quantity: row.get(5)?,
+            total_value: row.get(6)?,
+            unvalued_quantity: row.get(7)?,
         })
     })?;
 
@@ -1184,213 +6890,723 @@ pub fn get_site_stock_balances(app: &AppHandle, site_id: i64) -> Result<Vec<Stoc
 }
 
 // ============================================================================
-// Stock Movement History Operations
+// Category Reports
 // ============================================================================
 
-pub fn get_stock_movement_history(
+// Add per-category totals (category id, item count, quantity, value) up the tree so every
+// node carries the totals of its whole subtree. Uncategorised items get their own row.
+fn roll_up_categories(
+    conn: &Connection,
+    direct: Vec<(Option<i64>, i64, f64, Option<f64>)>,
+) -> Result<Vec<CategoryTotal>> {
+    let mut totals: Vec<CategoryTotal> = load_item_categories(conn)?
+        .into_iter()
+        .map(|c| CategoryTotal {
+            category_id: c.id,
+            category_name: c.name,
+            parent_id: c.parent_id,
+            path: c.path.unwrap_or_default(),
+            depth: c.depth.unwrap_or_default(),
+            item_count: 0,
+            total_quantity: 0.0,
+            total_value: None,
+        })
+        .collect();
+
+    let add = |total: &mut CategoryTotal, count: i64, quantity: f64, value: Option<f64>| {
+        total.item_count += count;
+        total.total_quantity += quantity;
+        total.total_value = match (total.total_value, value) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    };
+
+    for (category_id, count, quantity, value) in direct {
+        match category_id {
+            Some(cid) => {
+                let mut current = Some(cid);
+                while let Some(node) = current {
+                    match totals.iter_mut().find(|t| t.category_id == Some(node)) {
+                        Some(total) => {
+                            add(total, count, quantity, value);
+                            current = total.parent_id;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            None => {
+                let mut total = CategoryTotal {
+                    category_id: None,
+                    category_name: "(Uncategorised)".to_string(),
+                    parent_id: None,
+                    path: "(Uncategorised)".to_string(),
+                    depth: 0,
+                    item_count: 0,
+                    total_quantity: 0.0,
+                    total_value: None,
+                };
+                add(&mut total, count, quantity, value);
+                totals.push(total);
+            }
+        }
+    }
+
+    Ok(totals)
+}
+
+// Current stock rolled up the category tree, valued where item costs are known
+pub fn get_category_stock_summary(
     app: &AppHandle,
-    item_id: Option<i64>,
     site_id: Option<i64>,
-    voucher_type_id: Option<i64>,
+) -> Result<Vec<CategoryTotal>> {
+    let conn = get_db_conn(app)?;
+    let mut stmt = conn.prepare(&format!(
+        "WITH {costs},
+         balances AS (
+            SELECT sm.item_id, SUM(sm.stock_in) - SUM(sm.stock_out) as quantity
+            FROM stock_movements sm
+            WHERE ?1 IS NULL OR sm.site_id = ?1
+            GROUP BY sm.item_id
+            HAVING quantity != 0
+         )
+         SELECT i.category_id, COUNT(*), SUM(b.quantity), SUM(b.quantity * c.unit_cost)
+         FROM balances b
+         JOIN items i ON b.item_id = i.id
+         LEFT JOIN item_costs c ON b.item_id = c.item_id
+         GROUP BY i.category_id",
+        costs = ITEM_COSTS_CTE
+    ))?;
+    let direct = stmt
+        .query_map(params![site_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    roll_up_categories(&conn, direct)
+}
+
+// Usage and damage rolled up the category tree, valued where item costs are known
+pub fn get_category_consumption(
+    app: &AppHandle,
+    project_id: Option<i64>,
+    site_id: Option<i64>,
     from_date: Option<String>,
     to_date: Option<String>,
-    page: i64,
-    limit: i64,
-) -> Result<PaginatedResponse<StockMovementHistory>> {
+) -> Result<Vec<CategoryTotal>> {
     let conn = get_db_conn(app)?;
 
-    // Base WHERE clause construction
-    let mut where_clauses = vec!["1=1".to_string()];
+    let mut where_clauses = vec!["t.name IN ('Material Usage', 'Damaged Stock')".to_string()];
     let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
 
-    if let Some(iid) = item_id {
-        where_clauses.push("sm.item_id = ?".to_string());
-        params_vec.push(Box::new(iid));
+    if let Some(pid) = project_id {
+        where_clauses.push("v.project_id = ?".to_string());
+        params_vec.push(Box::new(pid));
     }
 
     if let Some(sid) = site_id {
-        where_clauses.push("sm.site_id = ?".to_string());
+        where_clauses.push("v.source_site_id = ?".to_string());
         params_vec.push(Box::new(sid));
     }
 
-    if let Some(vtid) = voucher_type_id {
-        where_clauses.push("v.voucher_type_id = ?".to_string());
-        params_vec.push(Box::new(vtid));
-    }
-
     if let Some(fd) = &from_date {
         where_clauses.push("v.voucher_date >= ?".to_string());
-        params_vec.push(Box::new(fd));
+        params_vec.push(Box::new(fd.clone()));
     }
 
     if let Some(td) = &to_date {
         where_clauses.push("v.voucher_date <= ?".to_string());
-        params_vec.push(Box::new(td));
+        params_vec.push(Box::new(td.clone()));
     }
 
     let where_sql = where_clauses.join(" AND ");
     let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
 
-    // 1. Get Total Count
-    let count_query = format!(
-        "SELECT COUNT(*) 
-         FROM stock_movements sm
-         JOIN inventory_vouchers v ON sm.voucher_id = v.id
-         WHERE {}",
-        where_sql
-    );
-    let total_count: i64 = conn.query_row(&count_query, &param_refs[..], |row| row.get(0))?;
+    let mut stmt = conn.prepare(&format!(
+        "WITH {costs}
+         SELECT i.category_id, COUNT(DISTINCT vi.item_id), SUM(vi.quantity), SUM(vi.quantity * c.unit_cost)
+         FROM inventory_voucher_items vi
+         JOIN inventory_vouchers v ON vi.inventory_voucher_id = v.id
+         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
+         JOIN items i ON vi.item_id = i.id
+         LEFT JOIN item_costs c ON vi.item_id = c.item_id
+         WHERE {where_sql}
+         GROUP BY i.category_id",
+        costs = ITEM_COSTS_CTE,
+        where_sql = where_sql
+    ))?;
+    let direct = stmt
+        .query_map(&param_refs[..], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<_>>>()?;
 
-    // 2. Calculate Opening Balance (if item is selected)
-    let mut current_balance = 0.0;
+    roll_up_categories(&conn, direct)
+}
 
-    // 2a. Initial Opening Balance (before from_date)
-    if let (Some(iid), Some(fd)) = (item_id, &from_date) {
-        let mut balance_query = String::from(
-            "SELECT COALESCE(SUM(sm.stock_in) - SUM(sm.stock_out), 0)
-             FROM stock_movements sm
-             JOIN inventory_vouchers v ON sm.voucher_id = v.id
-             WHERE sm.item_id = ? AND v.voucher_date < ?",
-        );
-        let mut balance_params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(iid), Box::new(fd)];
+// ============================================================================
+// Integrity Check and Repair
+// ============================================================================
 
-        if let Some(sid) = site_id {
-            balance_query.push_str(" AND sm.site_id = ?");
-            balance_params.push(Box::new(sid));
-        }
+fn integrity_issue(
+    check: &str,
+    message: String,
+    voucher_id: Option<i64>,
+    repairable: bool,
+) -> IntegrityIssue {
+    IntegrityIssue {
+        check: check.to_string(),
+        message,
+        voucher_id,
+        repairable,
+    }
+}
 
-        if let Some(vtid) = voucher_type_id {
-            balance_query.push_str(" AND v.voucher_type_id = ?");
-            balance_params.push(Box::new(vtid));
-        }
+// A voucher line with what it should post; the type is None when the voucher's type is missing
+struct LedgerLine {
+    voucher_id: i64,
+    transaction_number: String,
+    voucher_created_at: Option<String>,
+    type_name: Option<String>,
+    source_site_id: Option<i64>,
+    destination_site_id: Option<i64>,
+    voucher_item_id: i64,
+    item_id: i64,
+    item_code: Option<String>,
+    kit_item_id: Option<i64>,
+    quantity: f64,
+}
+
+impl LedgerLine {
+    fn expected_movements(&self) -> Vec<(i64, bool)> {
+        line_movements(
+            self.type_name.as_deref().unwrap_or_default(),
+            self.source_site_id,
+            self.destination_site_id,
+            self.kit_item_id,
+        )
+    }
+}
+
+fn load_ledger_lines(conn: &Connection) -> Result<Vec<LedgerLine>> {
+    let mut stmt = conn.prepare(
+        "SELECT v.id, v.transaction_number, v.created_at, t.name, v.source_site_id, v.destination_site_id,
+                vi.id, vi.item_id, i.code, vi.kit_item_id, vi.quantity
+         FROM inventory_voucher_items vi
+         JOIN inventory_vouchers v ON v.id = vi.inventory_voucher_id
+         LEFT JOIN inventory_transaction_types t ON t.id = v.voucher_type_id
+         LEFT JOIN items i ON i.id = vi.item_id
+         ORDER BY v.id, vi.id",
+    )?;
+    let lines = stmt
+        .query_map([], |row| {
+            Ok(LedgerLine {
+                voucher_id: row.get(0)?,
+                transaction_number: row.get(1)?,
+                voucher_created_at: row.get(2)?,
+                type_name: row.get(3)?,
+                source_site_id: row.get(4)?,
+                destination_site_id: row.get(5)?,
+                voucher_item_id: row.get(6)?,
+                item_id: row.get(7)?,
+                item_code: row.get(8)?,
+                kit_item_id: row.get(9)?,
+                quantity: row.get(10)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(lines)
+}
 
-        let b_param_refs: Vec<&dyn rusqlite::ToSql> =
-            balance_params.iter().map(|p| p.as_ref()).collect();
-        let initial_opening: f64 = conn
-            .query_row(&balance_query, &b_param_refs[..], |row| row.get(0))
-            .unwrap_or(0.0);
-        current_balance += initial_opening;
+// SQLite's own checks, then the stock ledger against the vouchers it was posted from:
+// every line has exactly the movements its type calls for, movements only point at existing
+// lines, items and sites, and no item has a negative balance at a site.
+pub fn check_integrity(app: &AppHandle) -> Result<IntegrityReport> {
+    let conn = get_db_conn(app)?;
+    let mut issues = Vec::new();
+
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let results = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    for result in results.into_iter().filter(|r| r != "ok") {
+        issues.push(integrity_issue("database", result, None, false));
     }
 
-    // 2b. Add skipped rows balance (for pagination > page 1)
-    let (limit_val, offset) = if limit == -1 {
-        (-1, 0)
-    } else {
-        (limit, (page - 1) * limit)
-    };
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let violations = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<i64>>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    for (table, row_id, parent) in violations {
+        let voucher_id = match table.as_str() {
+            "inventory_vouchers" => row_id,
+            _ => None,
+        };
+        issues.push(integrity_issue(
+            "foreign_keys",
+            format!(
+                "{} row {} refers to a missing {} record",
+                table,
+                row_id.map_or("?".to_string(), |id| id.to_string()),
+                parent
+            ),
+            voucher_id,
+            table == "stock_movements",
+        ));
+    }
 
-    if offset > 0 && item_id.is_some() {
-        // We need to sum stock_in - stock_out for the rows that are skipped by OFFSET
-        // To do this reliably, we must use the exact same ORDER BY as the main query
-        // This is complex in SQLite without window functions or a subquery with LIMIT
+    // Movements grouped by the voucher line they claim to belong to
+    let mut stmt = conn.prepare(
+        "SELECT id, voucher_id, voucher_item_id, item_id, site_id, stock_in, stock_out, created_at
+         FROM stock_movements ORDER BY id",
+    )?;
+    let movements = stmt
+        .query_map([], |row| {
+            Ok(StockMovement {
+                id: row.get(0)?,
+                voucher_id: row.get(1)?,
+                voucher_item_id: row.get(2)?,
+                item_id: row.get(3)?,
+                site_id: row.get(4)?,
+                stock_in: row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+                stock_out: row.get::<_, Option<f64>>(6)?.unwrap_or(0.0),
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    let movement_count = movements.len() as i64;
+    let mut movements_by_line: HashMap<i64, Vec<StockMovement>> = HashMap::new();
+    for movement in movements {
+        movements_by_line
+            .entry(movement.voucher_item_id)
+            .or_default()
+            .push(movement);
+    }
 
-        let skipped_balance_query = format!(
-            "SELECT COALESCE(SUM(sub.stock_in) - SUM(sub.stock_out), 0) FROM (
-                 SELECT sm.stock_in, sm.stock_out
-                 FROM stock_movements sm
-                 JOIN inventory_vouchers v ON sm.voucher_id = v.id
-                 WHERE {}
-                 ORDER BY v.voucher_date ASC, sm.created_at ASC
-                 LIMIT ?
-            ) sub",
-            where_sql
+    let lines = load_ledger_lines(&conn)?;
+    let mut flagged_vouchers = HashSet::new();
+    for line in &lines {
+        let label = format!(
+            "Voucher {} ({})",
+            line.transaction_number,
+            line.item_code.as_deref().unwrap_or("deleted item")
         );
+        let expected = line.expected_movements();
+        if expected.is_empty() && flagged_vouchers.insert(line.voucher_id) {
+            let message = match &line.type_name {
+                None => format!(
+                    "Voucher {} has no transaction type, so it moves no stock",
+                    line.transaction_number
+                ),
+                Some(type_name) if line_movements(type_name, Some(0), Some(0), None).is_empty() => {
+                    format!(
+                        "Voucher {} has type '{}', which does not move stock",
+                        line.transaction_number, type_name
+                    )
+                }
+                Some(type_name) => format!(
+                    "Voucher {} ({}) has no site to post its stock movements to",
+                    line.transaction_number, type_name
+                ),
+            };
+            issues.push(integrity_issue(
+                "movements",
+                message,
+                Some(line.voucher_id),
+                false,
+            ));
+        }
 
-        // Add limit to params
-        let mut skipped_params_refs = param_refs.clone();
-        let offset_val = offset; // Move to own var to borrow
-        skipped_params_refs.push(&offset_val);
-
-        let skipped_balance: f64 = conn
-            .query_row(&skipped_balance_query, &skipped_params_refs[..], |row| {
-                row.get(0)
+        let actual = movements_by_line
+            .remove(&line.voucher_item_id)
+            .unwrap_or_default();
+        let mut expected: Vec<(i64, f64, f64)> = expected
+            .into_iter()
+            .map(|(site_id, is_in)| {
+                if is_in {
+                    (site_id, line.quantity, 0.0)
+                } else {
+                    (site_id, 0.0, line.quantity)
+                }
             })
-            .unwrap_or(0.0);
+            .collect();
+        let mut found: Vec<(i64, f64, f64)> = actual
+            .iter()
+            .filter(|m| m.voucher_id == line.voucher_id && m.item_id == line.item_id)
+            .map(|m| (m.site_id, m.stock_in, m.stock_out))
+            .collect();
+        if found.len() != actual.len() {
+            issues.push(integrity_issue(
+                "movements",
+                format!(
+                    "{}: stock movements are linked to the line but record another voucher or item",
+                    label
+                ),
+                Some(line.voucher_id),
+                true,
+            ));
+        }
+        let by_site = |a: &(i64, f64, f64), b: &(i64, f64, f64)| {
+            a.0.cmp(&b.0)
+                .then(a.1.total_cmp(&b.1))
+                .then(a.2.total_cmp(&b.2))
+        };
+        expected.sort_by(by_site);
+        found.sort_by(by_site);
+        let matches = expected.len() == found.len()
+            && expected.iter().zip(&found).all(|(e, f)| {
+                e.0 == f.0
+                    && (e.1 - f.1).abs() < QUANTITY_EPSILON
+                    && (e.2 - f.2).abs() < QUANTITY_EPSILON
+            });
+        if !matches {
+            issues.push(integrity_issue(
+                "movements",
+                format!(
+                    "{}: stock movements do not match a quantity of {} (expected {}, found {})",
+                    label,
+                    line.quantity,
+                    expected.len(),
+                    found.len()
+                ),
+                Some(line.voucher_id),
+                true,
+            ));
+        }
+    }
 
-        current_balance += skipped_balance;
+    // Whatever is left belongs to no voucher line
+    let mut orphans: Vec<StockMovement> = movements_by_line.into_values().flatten().collect();
+    orphans.sort_by_key(|m| m.id);
+    for movement in orphans {
+        issues.push(integrity_issue(
+            "movements",
+            format!(
+                "Stock movement {} belongs to voucher line {}, which does not exist",
+                movement.id.unwrap_or_default(),
+                movement.voucher_item_id
+            ),
+            None,
+            true,
+        ));
     }
 
-    // 3. Fetch Page Items
-    let query = format!(
-        "SELECT 
-            sm.id,
-            sm.voucher_id,
-            v.transaction_number,
-            v.voucher_date,
-            t.name as voucher_type_name,
-            sm.item_id,
-            i.code as item_code,
-            i.name as item_name,
-            b.name as brand_name,
-            m.name as model_name,
-            sm.site_id,
-            s.code as site_code,
-            s.name as site_name,
-            sm.stock_in,
-            sm.stock_out,
-            v.remarks,
-            sm.created_at
+    let mut stmt = conn.prepare(
+        "SELECT sm.id, sm.voucher_id, v.transaction_number, 'item', sm.item_id
          FROM stock_movements sm
-         JOIN inventory_vouchers v ON sm.voucher_id = v.id
-         JOIN inventory_transaction_types t ON v.voucher_type_id = t.id
-         JOIN items i ON sm.item_id = i.id
-         LEFT JOIN brands b ON i.brand_id = b.id
-         LEFT JOIN models m ON i.model_id = m.id
-         JOIN sites s ON sm.site_id = s.id
-         WHERE {}
-         ORDER BY v.voucher_date ASC, sm.created_at ASC
-         LIMIT ? OFFSET ?",
-        where_sql
-    );
+         LEFT JOIN inventory_vouchers v ON v.id = sm.voucher_id
+         WHERE NOT EXISTS (SELECT 1 FROM items i WHERE i.id = sm.item_id)
+         UNION ALL
+         SELECT sm.id, sm.voucher_id, v.transaction_number, 'site', sm.site_id
+         FROM stock_movements sm
+         LEFT JOIN inventory_vouchers v ON v.id = sm.voucher_id
+         WHERE NOT EXISTS (SELECT 1 FROM sites s WHERE s.id = sm.site_id)
+         ORDER BY 1",
+    )?;
+    let dangling = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    for (movement_id, voucher_id, number, entity, entity_id) in dangling {
+        issues.push(integrity_issue(
+            "masters",
+            format!(
+                "Stock movement {} of voucher {} refers to deleted {} {}",
+                movement_id,
+                number.unwrap_or_else(|| voucher_id.to_string()),
+                entity,
+                entity_id
+            ),
+            Some(voucher_id),
+            false,
+        ));
+    }
 
-    let mut final_params_refs = param_refs.clone();
-    final_params_refs.push(&limit_val);
-    final_params_refs.push(&offset);
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(i.code, 'item ' || sm.item_id), COALESCE(s.name, 'site ' || sm.site_id),
+                SUM(COALESCE(sm.stock_in, 0)) - SUM(COALESCE(sm.stock_out, 0)) AS balance
+         FROM stock_movements sm
+         LEFT JOIN items i ON i.id = sm.item_id
+         LEFT JOIN sites s ON s.id = sm.site_id
+         GROUP BY sm.item_id, sm.site_id
+         HAVING balance < -?1
+         ORDER BY 1, 2",
+    )?;
+    let negatives = stmt
+        .query_map(params![QUANTITY_EPSILON], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    for (item, site, balance) in negatives {
+        issues.push(integrity_issue(
+            "balances",
+            format!("{} has a negative balance of {} at {}", item, balance, site),
+            None,
+            false,
+        ));
+    }
 
-    let mut stmt = conn.prepare(&query)?;
+    let voucher_count: i64 =
+        conn.query_row("SELECT COUNT(*) FROM inventory_vouchers", [], |row| {
+            row.get(0)
+        })?;
+    Ok(IntegrityReport {
+        is_ok: issues.is_empty(),
+        voucher_count,
+        movement_count,
+        issues,
+    })
+}
 
-    let mut movements: Vec<StockMovementHistory> = stmt
-        .query_map(&final_params_refs[..], |row| {
-            Ok(StockMovementHistory {
-                id: row.get(0)?,
-                voucher_id: row.get(1)?,
-                transaction_number: row.get(2)?,
-                voucher_date: row.get(3)?,
-                voucher_type_name: row.get(4)?,
-                item_id: row.get(5)?,
-                item_code: row.get(6)?,
-                item_name: row.get(7)?,
-                brand_name: row.get(8)?,
-                model_name: row.get(9)?,
-                site_id: row.get(10)?,
-                site_code: row.get(11)?,
-                site_name: row.get(12)?,
-                stock_in: row.get(13)?,
-                stock_out: row.get(14)?,
-                remarks: row.get(15)?,
-                running_balance: 0.0, // Will calculate below
-                created_at: row.get(16)?,
-            })
+// Regenerate the whole stock ledger from the voucher lines, keeping each voucher's timestamp.
+// Returns the number of movements written.
+pub fn rebuild_stock_movements(app: &AppHandle) -> Result<i64> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction()?;
+    let lines = load_ledger_lines(&tx)?;
+    tx.execute("DELETE FROM stock_movements", [])?;
+    let mut count = 0;
+    for line in &lines {
+        for movement in line.expected_movements() {
+            insert_stock_movement(
+                &tx,
+                line.voucher_id,
+                line.voucher_item_id,
+                line.item_id,
+                movement,
+                line.quantity,
+                line.voucher_created_at.as_deref(),
+            )?;
+            count += 1;
+        }
+    }
+    tx.commit()?;
+    Ok(count)
+}
+
+// ============================================================================
+// Settings Operations
+// ============================================================================
+
+const DATE_FORMATS: [&str; 4] = ["DD-MM-YYYY", "DD/MM/YYYY", "YYYY-MM-DD", "MM/DD/YYYY"];
+const MAX_LOGO_BYTES: usize = 512 * 1024;
+
+// Stored settings laid over the defaults. Values that no longer parse keep the default, and a
+// default godown that was since deactivated is dropped.
+pub fn load_settings(conn: &Connection) -> Result<Settings> {
+    let mut stmt = conn.prepare("SELECT key, value FROM app_settings")?;
+    let stored = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
         })?
         .collect::<Result<Vec<_>>>()?;
 
-    // 4. Calculate Running Balance for the page
-    if item_id.is_some() {
-        for movement in &mut movements {
-            current_balance += movement.stock_in - movement.stock_out;
-            movement.running_balance = current_balance;
+    let mut settings = Settings::default();
+    for (key, value) in stored {
+        if let Ok(value) = serde_json::from_str(&value) {
+            if let Ok(merged) = merge_setting(&settings, &key, value) {
+                settings = merged;
+            }
         }
-    } else {
-        // If multiple items, running balance is meaningless
-        for movement in &mut movements {
-            movement.running_balance = 0.0;
+    }
+    if let Some(site_id) = settings.default_godown_id {
+        let is_godown: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sites WHERE id = ?1 AND type = 'Warehouse' AND is_active = 1)",
+            params![site_id],
+            |row| row.get(0),
+        )?;
+        if !is_godown {
+            settings.default_godown_id = None;
         }
     }
+    Ok(settings)
+}
 
-    Ok(PaginatedResponse {
-        items: movements,
-        total_count,
-    })
+// Apply one stored key on top of the settings, rejecting values of the wrong type
+fn merge_setting(
+    settings: &Settings,
+    key: &str,
+    value: serde_json::Value,
+) -> std::result::Result<Settings, serde_json::Error> {
+    let mut merged = serde_json::to_value(settings)?;
+    if let Some(fields) = merged.as_object_mut() {
+        fields.insert(key.to_string(), value);
+    }
+    serde_json::from_value(merged)
+}
+
+pub fn get_settings(app: &AppHandle) -> Result<Settings> {
+    let conn = get_db_conn(app)?;
+    load_settings(&conn)
+}
+
+// Trims text fields and checks every value; the default godown must be an active godown
+fn normalize_settings(conn: &Connection, settings: &mut Settings) -> Result<()> {
+    let trim = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    settings.company_name = settings.company_name.trim().to_string();
+    settings.company_address = trim(&settings.company_address);
+    settings.company_phone = trim(&settings.company_phone);
+    settings.company_email = trim(&settings.company_email);
+    settings.gstin = trim(&settings.gstin).map(|g| g.to_uppercase());
+    settings.logo = trim(&settings.logo);
+    settings.voucher_number_prefix = trim(&settings.voucher_number_prefix);
+    settings.api_token = trim(&settings.api_token);
+    if settings.api_enabled && settings.api_token.is_none() {
+        settings.api_token = Some(format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        ));
+    }
+
+    if settings.company_name.is_empty() {
+        return Err(validation_error("Company name is required"));
+    }
+    if let Some(email) = &settings.company_email {
+        if !email.contains('@') || email.contains(char::is_whitespace) {
+            return Err(validation_error(format!(
+                "'{}' is not a valid email",
+                email
+            )));
+        }
+    }
+    if let Some(gstin) = &settings.gstin {
+        if gstin.len() != 15 || !gstin.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(validation_error(format!(
+                "GSTIN '{}' must be 15 letters or digits",
+                gstin
+            )));
+        }
+    }
+    if let Some(logo) = &settings.logo {
+        let data = ["data:image/png;base64,", "data:image/jpeg;base64,"]
+            .iter()
+            .find_map(|prefix| logo.strip_prefix(prefix))
+            .ok_or_else(|| validation_error("Logo must be a PNG or JPEG image"))?;
+        let bytes = general_purpose::STANDARD
+            .decode(data)
+            .map_err(|_| validation_error("Logo image data is not valid base64"))?;
+        if bytes.len() > MAX_LOGO_BYTES {
+            return Err(validation_error(format!(
+                "Logo is larger than the {} KB limit",
+                MAX_LOGO_BYTES / 1024
+            )));
+        }
+    }
+    if !DATE_FORMATS.contains(&settings.date_format.as_str()) {
+        return Err(validation_error(format!(
+            "Date format must be one of: {}",
+            DATE_FORMATS.join(", ")
+        )));
+    }
+    if !(1..=12).contains(&settings.fiscal_year_start_month) {
+        return Err(validation_error(
+            "Fiscal year start month must be between 1 and 12",
+        ));
+    }
+    if settings.quantity_decimals > 4 {
+        return Err(validation_error(
+            "Quantity decimals must be between 0 and 4",
+        ));
+    }
+    if !(1..=365).contains(&settings.backup_retention_count) {
+        return Err(validation_error(
+            "Backups to keep must be between 1 and 365",
+        ));
+    }
+    if settings.api_port < 1024 {
+        return Err(validation_error("API port must be between 1024 and 65535"));
+    }
+    if let Some(token) = &settings.api_token {
+        if token.len() < 16 || token.contains(char::is_whitespace) {
+            return Err(validation_error(
+                "API token must be at least 16 characters without spaces",
+            ));
+        }
+    }
+    if let Some(prefix) = &settings.voucher_number_prefix {
+        if prefix.len() > 10
+            || !prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/'))
+            || prefix.ends_with(|c: char| c.is_ascii_digit())
+        {
+            return Err(validation_error(
+                "Voucher number prefix must be up to 10 letters, digits, '-' or '/' and not end in a digit",
+            ));
+        }
+    }
+    if let Some(site_id) = settings.default_godown_id {
+        let site_type: Option<String> = conn
+            .query_row(
+                "SELECT type FROM sites WHERE id = ?1 AND is_active = 1",
+                params![site_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if site_type.as_deref() != Some("Warehouse") {
+            return Err(validation_error(
+                "Default godown must be an active warehouse",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Validates and stores the complete settings, returning them as saved
+pub fn update_settings(app: &AppHandle, mut settings: Settings) -> Result<Settings> {
+    let mut conn = get_db_conn(app)?;
+    normalize_settings(&conn, &mut settings)?;
+    let values = match serde_json::to_value(&settings) {
+        Ok(serde_json::Value::Object(values)) => values,
+        _ => return Err(validation_error("Settings could not be serialized")),
+    };
+
+    let tx = conn.transaction()?;
+    for (key, value) in values {
+        tx.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            params![key, value.to_string()],
+        )?;
+    }
+    tx.commit()?;
+    Ok(settings)
+}
+
+// First day of the fiscal year that contains the date
+pub fn fiscal_year_start(settings: &Settings, date: chrono::NaiveDate) -> chrono::NaiveDate {
+    use chrono::Datelike;
+    let month = settings.fiscal_year_start_month.clamp(1, 12);
+    let year = if date.month() >= month {
+        date.year()
+    } else {
+        date.year() - 1
+    };
+    chrono::NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(date)
 }
 
 // ============================================================================
@@ -1421,9 +7637,307 @@ pub fn get_dashboard_stats(app: &AppHandle) -> Result<DashboardStats> {
         |row| row.get(0),
     )?;
 
+    // Count transactions since the start of the current fiscal year
+    let settings = load_settings(&conn)?;
+    let fiscal_year_start =
+        fiscal_year_start(&settings, chrono::Local::now().date_naive()).to_string();
+    let fiscal_year_transactions_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM inventory_vouchers WHERE voucher_date >= ?1",
+        params![fiscal_year_start],
+        |row| row.get(0),
+    )?;
+
     Ok(DashboardStats {
         active_items_count,
         active_sites_count,
         recent_transactions_count,
+        fiscal_year_start,
+        fiscal_year_transactions_count,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        prepare_database(&conn).unwrap();
+        conn
+    }
+
+    fn add_site(conn: &Connection, code: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO sites (code, name, type) VALUES (?1, ?1, 'Warehouse')",
+            params![code],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn add_item(conn: &Connection, code: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO items (code, name) VALUES (?1, ?1)",
+            params![code],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn type_id(conn: &Connection, name: &str) -> i64 {
+        conn.query_row(
+            "SELECT id FROM inventory_transaction_types WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn add_voucher(conn: &Connection, voucher: Value) -> Result<i64> {
+        let voucher: InventoryVoucher = serde_json::from_value(voucher).unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let id = insert_inventory_voucher(&tx, voucher)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    fn edit_voucher(
+        conn: &Connection,
+        id: i64,
+        edit: impl FnOnce(&mut InventoryVoucher),
+    ) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        let mut voucher = load_inventory_voucher(&tx, id)?;
+        edit(&mut voucher);
+        replace_inventory_voucher(&tx, voucher)?;
+        tx.commit()
+    }
+
+    fn purchase(conn: &Connection, site_id: i64, item_id: i64, quantity: f64) -> i64 {
+        let voucher_type_id = type_id(conn, "Purchase Inward");
+        add_voucher(
+            conn,
+            json!({
+                "voucher_date": "2026-10-01",
+                "destination_site_id": site_id,
+                "voucher_type_id": voucher_type_id,
+                "items": [{ "item_id": item_id, "quantity": quantity }],
+            }),
+        )
+        .unwrap()
+    }
+
+    fn purchase_return(
+        conn: &Connection,
+        original_id: i64,
+        item_id: i64,
+        quantity: f64,
+    ) -> Result<i64> {
+        let voucher_type_id = type_id(conn, "Purchase Return");
+        add_voucher(
+            conn,
+            json!({
+                "voucher_date": "2026-10-02",
+                "voucher_type_id": voucher_type_id,
+                "original_voucher_id": original_id,
+                "items": [{ "item_id": item_id, "quantity": quantity }],
+            }),
+        )
+    }
+
+    #[test]
+    fn return_cannot_exceed_the_quantity_received() {
+        let conn = open_db();
+        let site_id = add_site(&conn, "G1");
+        let item_id = add_item(&conn, "CEM");
+        let voucher_id = purchase(&conn, site_id, item_id, 10.0);
+
+        let err = purchase_return(&conn, voucher_id, item_id, 12.0).unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds the 10 returnable"),
+            "{}",
+            err
+        );
+
+        purchase_return(&conn, voucher_id, item_id, 6.0).unwrap();
+        let err = purchase_return(&conn, voucher_id, item_id, 5.0).unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds the 4 returnable"),
+            "{}",
+            err
+        );
+        purchase_return(&conn, voucher_id, item_id, 4.0).unwrap();
+    }
+
+    #[test]
+    fn returns_are_checked_against_the_edited_original() {
+        let conn = open_db();
+        let site_id = add_site(&conn, "G1");
+        let item_id = add_item(&conn, "CEM");
+        let voucher_id = purchase(&conn, site_id, item_id, 10.0);
+        purchase_return(&conn, voucher_id, item_id, 6.0).unwrap();
+
+        edit_voucher(&conn, voucher_id, |v| v.items[0].quantity = 8.0).unwrap();
+        let err = edit_voucher(&conn, voucher_id, |v| v.items[0].quantity = 4.0).unwrap_err();
+        assert!(
+            err.to_string().contains("below the returned quantity"),
+            "{}",
+            err
+        );
+
+        let err = purchase_return(&conn, voucher_id, item_id, 3.0).unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds the 2 returnable"),
+            "{}",
+            err
+        );
+        purchase_return(&conn, voucher_id, item_id, 2.0).unwrap();
+    }
+
+    fn order_status(conn: &Connection, order_id: i64) -> String {
+        conn.query_row(
+            "SELECT status FROM purchase_orders WHERE id = ?1",
+            params![order_id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn purchase_order_moves_from_open_to_partial_to_closed() {
+        let conn = open_db();
+        let site_id = add_site(&conn, "G1");
+        let item_id = add_item(&conn, "CEM");
+        conn.execute(
+            "INSERT INTO suppliers (code, name) VALUES ('ACC', 'ACC Cements')",
+            [],
+        )
+        .unwrap();
+        let supplier_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO purchase_orders (po_number, po_date, supplier_id, delivery_site_id)
+             VALUES ('PO-1', '2026-10-01', ?1, ?2)",
+            params![supplier_id, site_id],
+        )
+        .unwrap();
+        let order_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO purchase_order_items (purchase_order_id, item_id, ordered_quantity)
+             VALUES (?1, ?2, 10)",
+            params![order_id, item_id],
+        )
+        .unwrap();
+        let order_line_id = conn.last_insert_rowid();
+        assert_eq!(order_status(&conn, order_id), "Open");
+
+        let receipt = |quantity: f64| {
+            add_voucher(
+                &conn,
+                json!({
+                    "voucher_date": "2026-10-05",
+                    "voucher_type_id": type_id(&conn, "Purchase Inward"),
+                    "purchase_order_id": order_id,
+                    "items": [{
+                        "item_id": item_id,
+                        "quantity": quantity,
+                        "purchase_order_item_id": order_line_id,
+                    }],
+                }),
+            )
+        };
+        let first = receipt(4.0).unwrap();
+        assert_eq!(order_status(&conn, order_id), "Partial");
+        receipt(6.0).unwrap();
+        assert_eq!(order_status(&conn, order_id), "Closed");
+
+        let tx = conn.unchecked_transaction().unwrap();
+        remove_inventory_voucher(&tx, first).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(order_status(&conn, order_id), "Partial");
+    }
+
+    // (item code, site code, in, out) for every movement of a voucher
+    fn movements(conn: &Connection, voucher_id: i64) -> Vec<(String, String, f64, f64)> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT i.code, s.code, m.stock_in, m.stock_out FROM stock_movements m
+                 JOIN items i ON i.id = m.item_id
+                 JOIN sites s ON s.id = m.site_id
+                 WHERE m.voucher_id = ?1
+                 ORDER BY i.code, s.code",
+            )
+            .unwrap();
+        let rows = stmt
+            .query_map(params![voucher_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap();
+        rows.collect::<Result<Vec<_>>>().unwrap()
+    }
+
+    #[test]
+    fn kit_is_issued_as_its_components() {
+        let conn = open_db();
+        let godown_id = add_site(&conn, "G1");
+        let site_id = add_site(&conn, "S1");
+        let kit_id = add_item(&conn, "KIT");
+        let bolt_id = add_item(&conn, "BOLT");
+        let plate_id = add_item(&conn, "PLATE");
+        conn.execute(
+            "INSERT INTO item_components (kit_item_id, component_item_id, quantity)
+             VALUES (?1, ?2, 4), (?1, ?3, 1)",
+            params![kit_id, bolt_id, plate_id],
+        )
+        .unwrap();
+
+        let voucher_id = add_voucher(
+            &conn,
+            json!({
+                "voucher_date": "2026-10-05",
+                "voucher_type_id": type_id(&conn, "Godown → Site"),
+                "source_site_id": godown_id,
+                "destination_site_id": site_id,
+                "items": [{ "item_id": kit_id, "quantity": 2.0, "expand_kit": true }],
+            }),
+        )
+        .unwrap();
+
+        let voucher = load_inventory_voucher(&conn, voucher_id).unwrap();
+        assert_eq!(voucher.items.len(), 2);
+        assert!(voucher
+            .items
+            .iter()
+            .all(|line| line.kit_item_id == Some(kit_id)));
+        let text = |s: &str| s.to_string();
+        assert_eq!(
+            movements(&conn, voucher_id),
+            vec![
+                (text("BOLT"), text("G1"), 0.0, 8.0),
+                (text("BOLT"), text("S1"), 8.0, 0.0),
+                (text("PLATE"), text("G1"), 0.0, 2.0),
+                (text("PLATE"), text("S1"), 2.0, 0.0),
+            ]
+        );
+
+        // Assembly stocks the kit and consumes its components at the same site
+        let assembly_id = add_voucher(
+            &conn,
+            json!({
+                "voucher_date": "2026-10-06",
+                "voucher_type_id": type_id(&conn, "Kit Assembly"),
+                "source_site_id": site_id,
+                "items": [{ "item_id": kit_id, "quantity": 1.0 }],
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            movements(&conn, assembly_id),
+            vec![
+                (text("BOLT"), text("S1"), 0.0, 4.0),
+                (text("KIT"), text("S1"), 1.0, 0.0),
+                (text("PLATE"), text("S1"), 0.0, 1.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,897 @@
+use crate::db::{self, company_data_dir, get_db_conn, load_settings, InventoryVoucher, Settings};
+use crate::fonts::{PdfFonts, BOLD_FONT_NAME, FONT_NAME};
+use base64::{engine::general_purpose, Engine as _};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::hash_map::{Entry, HashMap};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+// A4 portrait with 15 mm margins
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const FOOTER_Y: f32 = PAGE_HEIGHT - 10.0;
+const BODY_BOTTOM: f32 = PAGE_HEIGHT - 20.0;
+const ROW_HEIGHT: f32 = 6.5;
+const SIGNATURE_HEIGHT: f32 = 28.0;
+const PT_PER_MM: f32 = 72.0 / 25.4;
+const LOGO_HEIGHT: f32 = 16.0;
+const LOGO_MAX_WIDTH: f32 = 40.0;
+
+// Title, reference fields shown in the header and the signature blocks for a transaction type
+struct Template {
+    title: &'static str,
+    show_supplier: bool,
+    show_transport: bool,
+    signatures: &'static [&'static str],
+}
+
+fn template_for(type_name: &str) -> Template {
+    match type_name {
+        "Purchase Inward" => Template {
+            title: "Goods Receipt Note",
+            show_supplier: true,
+            show_transport: true,
+            signatures: &["Received By", "Checked By", "Store In-charge"],
+        },
+        "Purchase Return" => Template {
+            title: "Purchase Return Note",
+            show_supplier: true,
+            show_transport: true,
+            signatures: &["Returned By", "Driver", "Supplier's Signature"],
+        },
+        "Godown → Site" | "Site → Godown" | "Site → Site" => Template {
+            title: "Delivery Challan",
+            show_supplier: false,
+            show_transport: true,
+            signatures: &["Issued By", "Driver", "Received By"],
+        },
+        "Return from Site" => Template {
+            title: "Site Return Note",
+            show_supplier: false,
+            show_transport: true,
+            signatures: &["Returned By", "Driver", "Received By"],
+        },
+        "Material Usage" => Template {
+            title: "Material Issue Slip",
+            show_supplier: false,
+            show_transport: false,
+            signatures: &["Issued By", "Received By", "Site Engineer"],
+        },
+        "Damaged Stock" => Template {
+            title: "Damaged Stock Report",
+            show_supplier: false,
+            show_transport: false,
+            signatures: &["Reported By", "Verified By", "Approved By"],
+        },
+        _ => Template {
+            title: "Stock Voucher",
+            show_supplier: false,
+            show_transport: false,
+            signatures: &["Prepared By", "Authorized Signatory"],
+        },
+    }
+}
+
+// Shapes in millimetres with the origin at the top left of the page
+enum Mark {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        bold: bool,
+        text: String,
+    },
+    Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
+    Shade {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
+    Logo {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+// Width in mm
+fn text_width(fonts: &PdfFonts, text: &str, size: f32, bold: bool) -> f32 {
+    fonts.width(text, size, bold) / PT_PER_MM
+}
+
+// Shorten text with a trailing ellipsis until it fits the width
+fn fit_text(fonts: &PdfFonts, text: &str, size: f32, bold: bool, max_width: f32) -> String {
+    if text_width(fonts, text, size, bold) <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate: String = chars.iter().collect::<String>() + "...";
+        if text_width(fonts, &candidate, size, bold) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+fn format_quantity(quantity: f64, settings: &Settings) -> String {
+    format!("{:.*}", settings.quantity_decimals as usize, quantity)
+}
+
+// Company logo in a form PDF readers decode natively: JPEG data as is, or the zlib stream of a
+// non-interlaced 8-bit grey or RGB PNG. Other images are left off the document.
+struct Logo {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    colors: u8,
+    png: bool,
+}
+
+fn decode_logo(data_url: &str) -> Option<Logo> {
+    let (kind, data) = data_url
+        .strip_prefix("data:image/")?
+        .split_once(";base64,")?;
+    let bytes = general_purpose::STANDARD.decode(data).ok()?;
+    match kind {
+        "jpeg" => jpeg_logo(bytes),
+        "png" => png_logo(&bytes),
+        _ => None,
+    }
+}
+
+fn be_u16(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32)
+}
+
+fn be_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+// Size and components come from the first start-of-frame marker
+fn jpeg_logo(bytes: Vec<u8>) -> Option<Logo> {
+    let mut at = 2;
+    loop {
+        if *bytes.get(at)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(at + 1)?;
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let height = be_u16(&bytes, at + 5)?;
+            let width = be_u16(&bytes, at + 7)?;
+            let colors = *bytes.get(at + 9)?;
+            if !matches!(colors, 1 | 3) {
+                return None;
+            }
+            return Some(Logo {
+                data: bytes,
+                width,
+                height,
+                colors,
+                png: false,
+            });
+        }
+        at += 2 + be_u16(&bytes, at + 2)? as usize;
+    }
+}
+
+fn png_logo(bytes: &[u8]) -> Option<Logo> {
+    if bytes.get(..8)? != b"\x89PNG\r\n\x1a\n" {
+        return None;
+    }
+    let mut header = None;
+    let mut data = vec![];
+    let mut at = 8;
+    while at + 8 <= bytes.len() {
+        let length = be_u32(bytes, at)? as usize;
+        let chunk = bytes.get(at + 8..at + 8 + length)?;
+        match bytes.get(at + 4..at + 8)? {
+            b"IHDR" => header = Some(chunk.to_vec()),
+            b"IDAT" => data.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+        at += length + 12;
+    }
+    let header = header?;
+    let colors = match (header.get(8)?, header.get(9)?, header.get(12)?) {
+        (8, 0, 0) => 1,
+        (8, 2, 0) => 3,
+        _ => return None,
+    };
+    Some(Logo {
+        width: be_u32(&header, 0)?,
+        height: be_u32(&header, 4)?,
+        data,
+        colors,
+        png: true,
+    })
+}
+
+struct Column {
+    header: &'static str,
+    width: f32,
+    align: Align,
+}
+
+const COLUMNS: [Column; 6] = [
+    Column {
+        header: "S.No",
+        width: 11.0,
+        align: Align::Center,
+    },
+    Column {
+        header: "Item",
+        width: 66.0,
+        align: Align::Left,
+    },
+    Column {
+        header: "Brand",
+        width: 27.0,
+        align: Align::Left,
+    },
+    Column {
+        header: "Model",
+        width: 27.0,
+        align: Align::Left,
+    },
+    Column {
+        header: "Notes",
+        width: 31.0,
+        align: Align::Left,
+    },
+    Column {
+        header: "Quantity",
+        width: 18.0,
+        align: Align::Right,
+    },
+];
+
+struct DocumentLine {
+    code: String,
+    name: String,
+    brand_name: Option<String>,
+    model_name: Option<String>,
+    kit_name: Option<String>,
+    remarks: Option<String>,
+    quantity: f64,
+}
+
+// Everything printed on a voucher document, resolved to display text
+struct VoucherDocument {
+    settings: Settings,
+    logo: Option<Logo>,
+    type_name: String,
+    transaction_number: String,
+    details: Vec<(&'static str, String)>,
+    lines: Vec<DocumentLine>,
+    remarks: Option<String>,
+}
+
+fn lookup_name(conn: &Connection, sql: &str, id: Option<i64>) -> Result<Option<String>, String> {
+    match id {
+        Some(id) => conn
+            .query_row(sql, params![id], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+fn load_document(app: &AppHandle, voucher: &InventoryVoucher) -> Result<VoucherDocument, String> {
+    let conn = get_db_conn(app).map_err(|e| e.to_string())?;
+    let settings = load_settings(&conn).map_err(|e| e.to_string())?;
+    let type_name = lookup_name(
+        &conn,
+        "SELECT name FROM inventory_transaction_types WHERE id = ?1",
+        Some(voucher.voucher_type_id),
+    )?
+    .unwrap_or_else(|| "Voucher".to_string());
+    let template = template_for(&type_name);
+
+    let site_sql = "SELECT name || ' (' || code || ')' FROM sites WHERE id = ?1";
+    let mut details = vec![
+        (
+            "Voucher No",
+            voucher.transaction_number.clone().unwrap_or_default(),
+        ),
+        ("Date", format_date(&voucher.voucher_date, &settings)),
+        ("Type", type_name.clone()),
+    ];
+    if let Some(source) = lookup_name(&conn, site_sql, voucher.source_site_id)? {
+        details.push(("From", source));
+    }
+    if let Some(destination) = lookup_name(&conn, site_sql, voucher.destination_site_id)? {
+        details.push(("To", destination));
+    }
+    if template.show_supplier {
+        if let Some(supplier) = lookup_name(
+            &conn,
+            "SELECT name FROM suppliers WHERE id = ?1",
+            voucher.supplier_id,
+        )? {
+            details.push(("Supplier", supplier));
+        }
+        if let Some(bill) = &voucher.bill_number {
+            let bill_date = voucher
+                .bill_date
+                .as_deref()
+                .map(|d| format!(" dated {}", format_date(d, &settings)))
+                .unwrap_or_default();
+            details.push(("Bill No", format!("{}{}", bill, bill_date)));
+        }
+        if let Some(po) = lookup_name(
+            &conn,
+            "SELECT po_number FROM purchase_orders WHERE id = ?1",
+            voucher.purchase_order_id,
+        )? {
+            details.push(("PO No", po));
+        }
+    }
+    if let Some(requisition) = lookup_name(
+        &conn,
+        "SELECT requisition_number FROM material_requisitions WHERE id = ?1",
+        voucher.requisition_id,
+    )? {
+        details.push(("Requisition No", requisition));
+    }
+    if let Some(original) = lookup_name(
+        &conn,
+        "SELECT transaction_number FROM inventory_vouchers WHERE id = ?1",
+        voucher.original_voucher_id,
+    )? {
+        details.push(("Against Voucher", original));
+    }
+    if let Some(project) = lookup_name(
+        &conn,
+        "SELECT code || ' - ' || name FROM projects WHERE id = ?1",
+        voucher.project_id,
+    )? {
+        let activity = voucher
+            .activity_code
+            .as_deref()
+            .map(|a| format!(" / {}", a))
+            .unwrap_or_default();
+        details.push(("Project", format!("{}{}", project, activity)));
+    }
+    if template.show_transport {
+        for (label, value) in [
+            ("Challan No", &voucher.challan_number),
+            ("Vehicle No", &voucher.vehicle_number),
+            ("Driver", &voucher.driver_name),
+        ] {
+            if let Some(value) = value {
+                details.push((label, value.clone()));
+            }
+        }
+    }
+    if let Some(receiver) = &voucher.receiver_name {
+        details.push(("Received By", receiver.clone()));
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.code, i.name, b.name, m.name
+             FROM items i
+             LEFT JOIN brands b ON i.brand_id = b.id
+             LEFT JOIN models m ON i.model_id = m.id
+             WHERE i.id = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut items: HashMap<i64, (String, String, Option<String>, Option<String>)> = HashMap::new();
+    let mut lines = vec![];
+    for line in &voucher.items {
+        for item_id in [Some(line.item_id), line.kit_item_id].into_iter().flatten() {
+            if let Entry::Vacant(entry) = items.entry(item_id) {
+                let item = stmt
+                    .query_row(params![item_id], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                    })
+                    .map_err(|e| e.to_string())?;
+                entry.insert(item);
+            }
+        }
+        let (code, name, brand_name, model_name) = items[&line.item_id].clone();
+        lines.push(DocumentLine {
+            code,
+            name,
+            brand_name,
+            model_name,
+            kit_name: line.kit_item_id.map(|kit| items[&kit].1.clone()),
+            remarks: line.remarks.clone(),
+            quantity: line.quantity,
+        });
+    }
+
+    Ok(VoucherDocument {
+        logo: settings.logo.as_deref().and_then(decode_logo),
+        settings,
+        type_name,
+        transaction_number: voucher.transaction_number.clone().unwrap_or_default(),
+        details,
+        lines,
+        remarks: voucher.remarks.clone(),
+    })
+}
+
+// Vouchers store ISO dates; documents use the configured date format
+fn format_date(date: &str, settings: &Settings) -> String {
+    let format = match settings.date_format.as_str() {
+        "DD/MM/YYYY" => "%d/%m/%Y",
+        "YYYY-MM-DD" => "%Y-%m-%d",
+        "MM/DD/YYYY" => "%m/%d/%Y",
+        _ => "%d-%m-%Y",
+    };
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.format(format).to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+fn text_mark(
+    fonts: &PdfFonts,
+    x: f32,
+    y: f32,
+    size: f32,
+    bold: bool,
+    align: Align,
+    text: &str,
+) -> Mark {
+    let width = text_width(fonts, text, size, bold);
+    let x = match align {
+        Align::Left => x,
+        Align::Center => x - width / 2.0,
+        Align::Right => x - width,
+    };
+    Mark::Text {
+        x,
+        y,
+        size,
+        bold,
+        text: text.to_string(),
+    }
+}
+
+struct Layout<'a> {
+    fonts: &'a PdfFonts,
+    pages: Vec<Vec<Mark>>,
+    y: f32,
+}
+
+impl Layout<'_> {
+    fn page(&mut self) -> &mut Vec<Mark> {
+        self.pages.last_mut().expect("layout has a page")
+    }
+
+    fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, align: Align, text: &str) {
+        let mark = text_mark(self.fonts, x, y, size, bold, align, text);
+        self.page().push(mark);
+    }
+
+    fn rule(&mut self, y: f32) {
+        self.page().push(Mark::Line {
+            x1: MARGIN,
+            y1: y,
+            x2: PAGE_WIDTH - MARGIN,
+            y2: y,
+        });
+    }
+
+    // Company profile and document title; later pages carry a short continuation header
+    fn new_page(&mut self, document: &VoucherDocument, template: &Template) {
+        self.pages.push(vec![]);
+        let center = PAGE_WIDTH / 2.0;
+        let settings = &document.settings;
+        if self.pages.len() == 1 {
+            if let Some(logo) = &document.logo {
+                let width = (LOGO_HEIGHT * logo.width as f32 / logo.height.max(1) as f32)
+                    .min(LOGO_MAX_WIDTH);
+                self.page().push(Mark::Logo {
+                    x: MARGIN,
+                    y: 12.0,
+                    w: width,
+                    h: LOGO_HEIGHT,
+                });
+            }
+            self.text(
+                center,
+                20.0,
+                16.0,
+                true,
+                Align::Center,
+                &settings.company_name,
+            );
+            let mut y = 20.0;
+            let contact = [
+                settings
+                    .company_phone
+                    .as_ref()
+                    .map(|p| format!("Phone: {}", p)),
+                settings.company_email.clone(),
+                settings.gstin.as_ref().map(|g| format!("GSTIN: {}", g)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("  |  ");
+            for line in [settings.company_address.clone(), Some(contact)]
+                .into_iter()
+                .flatten()
+                .filter(|l| !l.is_empty())
+            {
+                y += 4.5;
+                let line = fit_text(
+                    self.fonts,
+                    &line.replace('\n', ", "),
+                    8.5,
+                    false,
+                    CONTENT_WIDTH - 90.0,
+                );
+                self.text(center, y, 8.5, false, Align::Center, &line);
+            }
+            y = y.max(24.0) + 7.0;
+            self.text(
+                center,
+                y,
+                12.0,
+                true,
+                Align::Center,
+                &template.title.to_uppercase(),
+            );
+            self.rule(y + 4.0);
+            self.y = y + 11.0;
+        } else {
+            let heading = format!(
+                "{} - {} No. {} (continued)",
+                settings.company_name, template.title, document.transaction_number
+            );
+            self.text(MARGIN, 20.0, 10.0, true, Align::Left, &heading);
+            self.rule(23.0);
+            self.y = 30.0;
+        }
+    }
+
+    fn table_header(&mut self) {
+        let y = self.y - 4.8;
+        self.page().push(Mark::Shade {
+            x: MARGIN,
+            y,
+            w: CONTENT_WIDTH,
+            h: ROW_HEIGHT + 0.5,
+        });
+        self.row(
+            &COLUMNS
+                .iter()
+                .map(|c| c.header.to_string())
+                .collect::<Vec<_>>(),
+            true,
+        );
+    }
+
+    fn row(&mut self, cells: &[String], bold: bool) {
+        let mut x = MARGIN;
+        let y = self.y;
+        for (column, cell) in COLUMNS.iter().zip(cells) {
+            let text = fit_text(self.fonts, cell, 8.5, bold, column.width - 2.0);
+            let anchor = match column.align {
+                Align::Left => x + 1.0,
+                Align::Center => x + column.width / 2.0,
+                Align::Right => x + column.width - 1.0,
+            };
+            self.text(anchor, y, 8.5, bold, column.align, &text);
+            x += column.width;
+        }
+        self.page().push(Mark::Line {
+            x1: MARGIN,
+            y1: y + 1.7,
+            x2: PAGE_WIDTH - MARGIN,
+            y2: y + 1.7,
+        });
+        self.y += ROW_HEIGHT;
+    }
+}
+
+fn layout_document(document: &VoucherDocument, fonts: &PdfFonts) -> Vec<Vec<Mark>> {
+    let template = template_for(&document.type_name);
+    let mut layout = Layout {
+        fonts,
+        pages: vec![],
+        y: 0.0,
+    };
+    layout.new_page(document, &template);
+
+    // Reference details in two columns
+    for pair in document.details.chunks(2) {
+        for (i, (label, value)) in pair.iter().enumerate() {
+            let x = MARGIN + i as f32 * CONTENT_WIDTH / 2.0;
+            let y = layout.y;
+            layout.text(x, y, 9.0, true, Align::Left, &format!("{}:", label));
+            let value = fit_text(fonts, value, 9.0, false, CONTENT_WIDTH / 2.0 - 32.0);
+            layout.text(x + 30.0, y, 9.0, false, Align::Left, &value);
+        }
+        layout.y += 5.5;
+    }
+    layout.y += 5.0;
+
+    layout.table_header();
+    for (index, line) in document.lines.iter().enumerate() {
+        if layout.y > BODY_BOTTOM - ROW_HEIGHT {
+            layout.new_page(document, &template);
+            layout.table_header();
+        }
+        let notes = match (&line.kit_name, &line.remarks) {
+            (Some(kit), Some(remarks)) => format!("{} (kit: {})", remarks, kit),
+            (Some(kit), None) => format!("Kit: {}", kit),
+            (None, Some(remarks)) => remarks.clone(),
+            (None, None) => String::new(),
+        };
+        layout.row(
+            &[
+                (index + 1).to_string(),
+                format!("{} - {}", line.code, line.name),
+                line.brand_name.clone().unwrap_or_default(),
+                line.model_name.clone().unwrap_or_default(),
+                notes,
+                format_quantity(line.quantity, &document.settings),
+            ],
+            false,
+        );
+    }
+
+    let total: f64 = document.lines.iter().map(|l| l.quantity).sum();
+    if layout.y > BODY_BOTTOM - ROW_HEIGHT {
+        layout.new_page(document, &template);
+    }
+    layout.row(
+        &[
+            String::new(),
+            format!("Total ({} lines)", document.lines.len()),
+            String::new(),
+            String::new(),
+            String::new(),
+            format_quantity(total, &document.settings),
+        ],
+        true,
+    );
+
+    if let Some(remarks) = &document.remarks {
+        layout.y += 3.0;
+        let y = layout.y;
+        layout.text(MARGIN, y, 9.0, true, Align::Left, "Remarks:");
+        let remarks = fit_text(fonts, remarks, 9.0, false, CONTENT_WIDTH - 20.0);
+        layout.text(MARGIN + 18.0, y, 9.0, false, Align::Left, &remarks);
+        layout.y += 5.5;
+    }
+
+    // Signature blocks share the width evenly at the foot of the last page
+    if layout.y + SIGNATURE_HEIGHT > BODY_BOTTOM {
+        layout.new_page(document, &template);
+    }
+    let slot = CONTENT_WIDTH / template.signatures.len() as f32;
+    let line_y = BODY_BOTTOM - 6.0;
+    for (i, label) in template.signatures.iter().enumerate() {
+        let center = MARGIN + slot * (i as f32 + 0.5);
+        layout.page().push(Mark::Line {
+            x1: center - 22.0,
+            y1: line_y,
+            x2: center + 22.0,
+            y2: line_y,
+        });
+        layout.text(center, line_y + 4.5, 9.0, false, Align::Center, label);
+    }
+
+    // Page footers
+    let page_count = layout.pages.len();
+    let generated = format!(
+        "Generated {}",
+        chrono::Local::now().format("%d-%m-%Y %H:%M")
+    );
+    for (index, page) in layout.pages.iter_mut().enumerate() {
+        page.push(text_mark(
+            fonts,
+            MARGIN,
+            FOOTER_Y,
+            7.5,
+            false,
+            Align::Left,
+            &generated,
+        ));
+        page.push(text_mark(
+            fonts,
+            PAGE_WIDTH - MARGIN,
+            FOOTER_Y,
+            7.5,
+            false,
+            Align::Right,
+            &format!("Page {} of {}", index + 1, page_count),
+        ));
+    }
+    layout.pages
+}
+
+fn render_pdf(pages: &[Vec<Mark>], logo: Option<&Logo>, fonts: &PdfFonts) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let font_id = Ref::new(3);
+    let bold_font_id = Ref::new(4);
+    let page_ids: Vec<Ref> = (0..pages.len())
+        .map(|i| Ref::new(5 + 2 * i as i32))
+        .collect();
+    let logo_id = Ref::new(5 + 2 * pages.len() as i32);
+    let logo_name = Name(b"Im1");
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(pages.len() as i32);
+    if let Some(logo) = logo {
+        let mut image = pdf.image_xobject(logo_id, &logo.data);
+        image.width(logo.width as i32).height(logo.height as i32);
+        if logo.colors == 1 {
+            image.color_space().device_gray();
+        } else {
+            image.color_space().device_rgb();
+        }
+        image.bits_per_component(8);
+        if logo.png {
+            image.filter(Filter::FlateDecode);
+            image
+                .insert(Name(b"DecodeParms"))
+                .dict()
+                .pair(Name(b"Predictor"), 15)
+                .pair(Name(b"Colors"), logo.colors as i32)
+                .pair(Name(b"BitsPerComponent"), 8)
+                .pair(Name(b"Columns"), logo.width as i32);
+        } else {
+            image.filter(Filter::DctDecode);
+        }
+    }
+
+    for (page_id, marks) in page_ids.iter().zip(pages) {
+        let content_id = Ref::new(page_id.get() + 1);
+        let mut page = pdf.page(*page_id);
+        page.media_box(Rect::new(
+            0.0,
+            0.0,
+            PAGE_WIDTH * PT_PER_MM,
+            PAGE_HEIGHT * PT_PER_MM,
+        ))
+        .parent(page_tree_id)
+        .contents(content_id);
+        let mut resources = page.resources();
+        resources
+            .fonts()
+            .pair(FONT_NAME, font_id)
+            .pair(BOLD_FONT_NAME, bold_font_id);
+        if logo.is_some() {
+            resources.x_objects().pair(logo_name, logo_id);
+        }
+        resources.finish();
+        page.finish();
+
+        let mut content = Content::new();
+        content.set_line_width(0.5);
+        for mark in marks {
+            match mark {
+                Mark::Text {
+                    x,
+                    y,
+                    size,
+                    bold,
+                    text,
+                } => {
+                    fonts.show(
+                        &mut content,
+                        x * PT_PER_MM,
+                        (PAGE_HEIGHT - y) * PT_PER_MM,
+                        *size,
+                        *bold,
+                        text,
+                    );
+                }
+                Mark::Line { x1, y1, x2, y2 } => {
+                    content
+                        .move_to(x1 * PT_PER_MM, (PAGE_HEIGHT - y1) * PT_PER_MM)
+                        .line_to(x2 * PT_PER_MM, (PAGE_HEIGHT - y2) * PT_PER_MM)
+                        .stroke();
+                }
+                Mark::Shade { x, y, w, h } => {
+                    content
+                        .save_state()
+                        .set_fill_gray(0.92)
+                        .rect(
+                            x * PT_PER_MM,
+                            (PAGE_HEIGHT - y - h) * PT_PER_MM,
+                            w * PT_PER_MM,
+                            h * PT_PER_MM,
+                        )
+                        .fill_nonzero()
+                        .restore_state();
+                }
+                Mark::Logo { x, y, w, h } => {
+                    content
+                        .save_state()
+                        .transform([
+                            w * PT_PER_MM,
+                            0.0,
+                            0.0,
+                            h * PT_PER_MM,
+                            x * PT_PER_MM,
+                            (PAGE_HEIGHT - y - h) * PT_PER_MM,
+                        ])
+                        .x_object(logo_name)
+                        .restore_state();
+                }
+            }
+        }
+        pdf.stream(content_id, &content.finish());
+    }
+    fonts.write(&mut pdf, font_id, Some(bold_font_id), logo_id.get() + 1);
+
+    pdf.finish()
+}
+
+// File names keep letters and digits only, e.g. delivery-challan-42.pdf
+fn document_file_name(title: &str, transaction_number: &str) -> String {
+    let slug = |text: &str| {
+        text.to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    };
+    format!("{}-{}.pdf", slug(title), slug(transaction_number))
+}
+
+// Renders the voucher and keeps the PDF under the company folder, replacing any earlier copy
+pub fn render_voucher_document(app: &AppHandle, voucher_id: i64) -> Result<PathBuf, String> {
+    let voucher = db::get_inventory_voucher(app, voucher_id)
+        .map_err(|_| format!("Voucher {} does not exist", voucher_id))?;
+    let document = load_document(app, &voucher)?;
+    // Laid out once with the standard fonts to see which characters the document needs
+    let fonts = PdfFonts::for_texts(
+        layout_document(&document, &PdfFonts::standard())
+            .iter()
+            .flatten()
+            .filter_map(|mark| match mark {
+                Mark::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            }),
+    )?;
+    let pages = layout_document(&document, &fonts);
+    let bytes = render_pdf(&pages, document.logo.as_ref(), &fonts);
+
+    let documents_dir = company_data_dir(app).join("documents");
+    std::fs::create_dir_all(&documents_dir).map_err(|e| e.to_string())?;
+    let path = documents_dir.join(document_file_name(
+        template_for(&document.type_name).title,
+        &document.transaction_number,
+    ));
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+// Writes the voucher's printable document and returns the path of the PDF
+#[tauri::command]
+pub fn generate_voucher_document(app: AppHandle, voucher_id: i64) -> Result<String, String> {
+    let path = render_voucher_document(&app, voucher_id)?;
+    Ok(path.to_string_lossy().to_string())
+}
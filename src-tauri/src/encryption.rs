@@ -0,0 +1,166 @@
+use crate::api_server;
+use crate::db::{self, company_data_dir, validation_error, DB_FILE_NAME};
+use rusqlite::{params, Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+// Encrypted databases are SQLCipher files. The passphrase is only ever held in memory, for the
+// open company, from unlock until the app closes or another company is opened.
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+// How long a connection waits for another writer, such as the LAN server, before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+const PLAIN_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+#[derive(Default)]
+pub struct DatabaseKey(Mutex<Option<String>>);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptionStatus {
+    pub is_encrypted: bool,
+    pub is_unlocked: bool,
+}
+
+// Passphrase of the open company, if it has been unlocked
+pub fn passphrase(app: &AppHandle) -> Option<String> {
+    let state = app.try_state::<DatabaseKey>()?;
+    let key = state.0.lock().unwrap_or_else(PoisonError::into_inner);
+    key.clone()
+}
+
+pub fn set_passphrase(app: &AppHandle, passphrase: Option<String>) {
+    if let Some(state) = app.try_state::<DatabaseKey>() {
+        *state.0.lock().unwrap_or_else(PoisonError::into_inner) = passphrase;
+    }
+}
+
+// A plain SQLite file starts with a fixed header; an encrypted one is random from the first byte.
+// Missing and empty files are not encrypted.
+pub fn is_encrypted(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    match std::fs::File::open(path).and_then(|mut f| f.read_exact(&mut header)) {
+        Ok(()) => &header != PLAIN_HEADER,
+        Err(_) => false,
+    }
+}
+
+pub fn is_locked(app: &AppHandle) -> bool {
+    passphrase(app).is_none() && is_encrypted(&company_data_dir(app).join(DB_FILE_NAME))
+}
+
+// Open a database with its passphrase, failing early when the passphrase is wrong
+pub fn open_database(path: &Path, passphrase: Option<&str>) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    if let Some(passphrase) = passphrase {
+        conn.pragma_update(None, "key", passphrase)?;
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map_err(|_| validation_error("The passphrase is incorrect"))?;
+    }
+    Ok(conn)
+}
+
+// Write a full copy of the database to a new file, encrypted with the passphrase or plain
+// without one. The schema version is carried over, which sqlcipher_export leaves out.
+pub fn export_database(
+    conn: &Connection,
+    target: &Path,
+    passphrase: Option<&str>,
+) -> rusqlite::Result<()> {
+    let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS export KEY ?2",
+        params![target.to_string_lossy(), passphrase.unwrap_or_default()],
+    )?;
+    let result = conn
+        .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+        .and_then(|_| {
+            conn.pragma_update(
+                Some(DatabaseName::Attached("export")),
+                "user_version",
+                version,
+            )
+        });
+    conn.execute("DETACH DATABASE export", [])?;
+    result
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(format!(
+            "The passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_encryption_status(app: AppHandle) -> EncryptionStatus {
+    let is_encrypted = is_encrypted(&company_data_dir(&app).join(DB_FILE_NAME));
+    EncryptionStatus {
+        is_encrypted,
+        is_unlocked: !is_encrypted || passphrase(&app).is_some(),
+    }
+}
+
+// Unlocks the open company for this session and upgrades its database, which is skipped while
+// it is locked
+#[tauri::command]
+pub fn unlock_database(app: AppHandle, passphrase: String) -> Result<EncryptionStatus, String> {
+    let data_dir = company_data_dir(&app);
+    open_database(&data_dir.join(DB_FILE_NAME), Some(&passphrase)).map_err(|e| e.to_string())?;
+    db::init_company_db(&data_dir, Some(&passphrase)).map_err(|e| e.to_string())?;
+    set_passphrase(&app, Some(passphrase));
+    api_server::restart(&app);
+    Ok(get_encryption_status(app))
+}
+
+// Encrypts the open company's database, changes its passphrase or, without a new passphrase,
+// decrypts it. An encrypted database needs its current passphrase.
+#[tauri::command]
+pub fn set_database_passphrase(
+    app: AppHandle,
+    current_passphrase: Option<String>,
+    new_passphrase: Option<String>,
+) -> Result<EncryptionStatus, String> {
+    let path = company_data_dir(&app).join(DB_FILE_NAME);
+    let encrypted = is_encrypted(&path);
+    let current = current_passphrase.filter(|p| !p.is_empty());
+    if encrypted && current.is_none() {
+        return Err("Enter the current passphrase".to_string());
+    }
+    if let Some(new_passphrase) = &new_passphrase {
+        validate_passphrase(new_passphrase)?;
+    } else if !encrypted {
+        return Err("The database is not encrypted".to_string());
+    }
+
+    let conn = open_database(&path, current.as_deref().filter(|_| encrypted))
+        .map_err(|e| e.to_string())?;
+    if encrypted && new_passphrase.is_some() {
+        conn.pragma_update(None, "rekey", new_passphrase.as_deref())
+            .map_err(|e| e.to_string())?;
+    } else {
+        // Switching between plain and encrypted needs a new file, swapped in once it is complete
+        let staged = path.with_extension("db.staged");
+        let _ = std::fs::remove_file(&staged);
+        let result = export_database(&conn, &staged, new_passphrase.as_deref())
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                drop(conn);
+                std::fs::rename(&staged, &path).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&staged);
+            return Err(format!("Could not change the database encryption: {}", e));
+        }
+    }
+    set_passphrase(&app, new_passphrase);
+    Ok(get_encryption_status(app))
+}
@@ -0,0 +1,469 @@
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
+use rustybuzz::{ttf_parser, Face, UnicodeBuffer};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::PathBuf;
+
+// Text in generated PDFs. Text that WinAnsi can encode is set in the standard Helvetica fonts,
+// which every PDF reader has. Anything else, such as Hindi names or the rupee sign, is shaped
+// with a Unicode font installed on this computer and a subset of that font is embedded. When no
+// installed font covers the text the document is refused rather than printed with '?'.
+
+pub(crate) const FONT_NAME: Name<'static> = Name(b"F1");
+pub(crate) const BOLD_FONT_NAME: Name<'static> = Name(b"F2");
+
+const IDENTITY: SystemInfo<'static> = SystemInfo {
+    registry: Str(b"Adobe"),
+    ordering: Str(b"Identity"),
+    supplement: 0,
+};
+
+// Helvetica advance widths (per 1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+// Font families tried in order, each as the files holding its faces. Nirmala UI ships with
+// Windows and covers every Indic script; the others are common on macOS and Linux.
+fn unicode_font_families() -> Vec<Vec<PathBuf>> {
+    let windows = PathBuf::from(std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".into()))
+        .join("Fonts");
+    let family =
+        |dir: &str, files: &[&str]| files.iter().map(|f| PathBuf::from(dir).join(f)).collect();
+    vec![
+        vec![
+            windows.join("Nirmala.ttf"),
+            windows.join("NirmalaB.ttf"),
+            windows.join("Nirmala.ttc"),
+        ],
+        family(
+            "/usr/share/fonts/truetype/noto",
+            &[
+                "NotoSansDevanagari-Regular.ttf",
+                "NotoSansDevanagari-Bold.ttf",
+            ],
+        ),
+        family(
+            "/usr/share/fonts/truetype/lohit-devanagari",
+            &["Lohit-Devanagari.ttf"],
+        ),
+        family("/System/Library/Fonts/Supplemental", &["Arial Unicode.ttf"]),
+        family(
+            "/usr/share/fonts/truetype/dejavu",
+            &["DejaVuSans.ttf", "DejaVuSans-Bold.ttf"],
+        ),
+        family(
+            "/usr/share/fonts/TTF",
+            &["DejaVuSans.ttf", "DejaVuSans-Bold.ttf"],
+        ),
+    ]
+}
+
+// Code of the character in WinAnsiEncoding, the encoding of the standard fonts
+fn win_ansi(c: char) -> Option<u8> {
+    let code = match c {
+        '\0'..='\x7F' | '\u{A0}'..='\u{FF}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        _ => return None,
+    };
+    Some(code)
+}
+
+// Joiners steer shaping in Indic scripts and need no glyph of their own
+fn needs_glyph(c: char) -> bool {
+    !c.is_control() && !matches!(c, '\u{200B}'..='\u{200D}')
+}
+
+struct ShapedGlyph {
+    id: u16,
+    text: String,
+    x_advance: i32,
+    x_offset: i32,
+    y_offset: i32,
+}
+
+struct UnicodeFont {
+    data: Vec<u8>,
+    index: u32,
+    // Glyphs used so far and the text each stands for, for the subset and the ToUnicode map
+    used: RefCell<BTreeMap<u16, String>>,
+}
+
+impl UnicodeFont {
+    // The face closest to the weight among the family's files, skipping italics and CFF outlines
+    fn load(files: &[PathBuf], weight: u16) -> Option<UnicodeFont> {
+        let mut best: Option<(u16, Vec<u8>, u32)> = None;
+        for path in files {
+            let Ok(data) = std::fs::read(path) else {
+                continue;
+            };
+            let mut closest: Option<(u16, u32)> = None;
+            for index in 0..ttf_parser::fonts_in_collection(&data).unwrap_or(1) {
+                let Ok(face) = ttf_parser::Face::parse(&data, index) else {
+                    continue;
+                };
+                if face.is_italic() || face.tables().cff.is_some() {
+                    continue;
+                }
+                let distance = face.weight().to_number().abs_diff(weight);
+                if closest.is_none_or(|(d, _)| distance < d) {
+                    closest = Some((distance, index));
+                }
+            }
+            if let Some((distance, index)) = closest {
+                if best.as_ref().is_none_or(|(d, _, _)| distance < *d) {
+                    best = Some((distance, data, index));
+                }
+            }
+        }
+        best.map(|(_, data, index)| UnicodeFont {
+            data,
+            index,
+            used: RefCell::new(BTreeMap::new()),
+        })
+    }
+
+    fn face(&self) -> Face<'_> {
+        Face::from_slice(&self.data, self.index).expect("font face was parsed when it was loaded")
+    }
+
+    fn covers(&self, chars: &BTreeSet<char>) -> bool {
+        let face = self.face();
+        chars.iter().all(|c| face.glyph_index(*c).is_some())
+    }
+
+    // Glyphs in visual order; the first glyph of each cluster carries the cluster's text
+    fn shape(&self, face: &Face, text: &str) -> Vec<ShapedGlyph> {
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        let output = rustybuzz::shape(face, &[], buffer);
+
+        let mut starts: Vec<usize> = output
+            .glyph_infos()
+            .iter()
+            .map(|info| info.cluster as usize)
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+        let mut seen = BTreeSet::new();
+        output
+            .glyph_infos()
+            .iter()
+            .zip(output.glyph_positions())
+            .map(|(info, position)| {
+                let start = info.cluster as usize;
+                let text = if seen.insert(start) {
+                    let end = starts
+                        .iter()
+                        .find(|s| **s > start)
+                        .copied()
+                        .unwrap_or(text.len());
+                    text.get(start..end).unwrap_or_default().to_string()
+                } else {
+                    String::new()
+                };
+                ShapedGlyph {
+                    id: info.glyph_id as u16,
+                    text,
+                    x_advance: position.x_advance,
+                    x_offset: position.x_offset,
+                    y_offset: position.y_offset,
+                }
+            })
+            .collect()
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let face = self.face();
+        let units: i32 = self.shape(&face, text).iter().map(|g| g.x_advance).sum();
+        units as f32 / face.units_per_em() as f32 * size
+    }
+
+    // Glyphs go out in runs; one that the shaper moved off the pen position starts a new run
+    fn show(&self, content: &mut Content, x: f32, y: f32, size: f32, text: &str) {
+        let face = self.face();
+        let scale = size / face.units_per_em() as f32;
+        let mut used = self.used.borrow_mut();
+        let mut run: Vec<u8> = vec![];
+        let mut cursor: Option<i32> = None;
+        let mut pen = 0;
+        for glyph in self.shape(&face, text) {
+            let glyph_x = pen + glyph.x_offset;
+            if glyph.y_offset != 0 || cursor != Some(glyph_x) {
+                if !run.is_empty() {
+                    content.show(Str(&run));
+                    run.clear();
+                }
+                content.set_text_matrix([
+                    1.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                    x + glyph_x as f32 * scale,
+                    y + glyph.y_offset as f32 * scale,
+                ]);
+            }
+            run.extend(glyph.id.to_be_bytes());
+            let advance = face
+                .glyph_hor_advance(ttf_parser::GlyphId(glyph.id))
+                .unwrap_or(0) as i32;
+            cursor = (glyph.y_offset == 0).then_some(glyph_x + advance);
+            pen += glyph.x_advance;
+            let entry = used.entry(glyph.id).or_default();
+            if entry.is_empty() {
+                *entry = glyph.text;
+            }
+        }
+        if !run.is_empty() {
+            content.show(Str(&run));
+        }
+    }
+
+    // Type0 font over an Identity-H CID font with the subset embedded as TrueType
+    fn write(&self, pdf: &mut Pdf, font_id: Ref, next_id: &mut i32, bold: bool) {
+        let [cid_font_id, descriptor_id, file_id, cmap_id] =
+            std::array::from_fn(|i| Ref::new(*next_id + i as i32));
+        *next_id += 4;
+
+        let face = self.face();
+        let used = self.used.borrow();
+        let glyphs: Vec<u16> = used.keys().copied().collect();
+        let font_file = subsetter::subset(&self.data, self.index, subsetter::Profile::pdf(&glyphs))
+            .unwrap_or_else(|_| self.data.clone());
+
+        // Subset fonts are named with a tag of six capitals derived from the glyphs they hold
+        let postscript_name = face
+            .names()
+            .into_iter()
+            .filter(|name| name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+            .find_map(|name| name.to_string())
+            .unwrap_or_else(|| "UnicodeFont".to_string());
+        let hash = glyphs.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, glyph| {
+            (hash ^ *glyph as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+        let tag: String = (0..6)
+            .map(|i| (b'A' + (hash >> (i * 5) & 31) as u8 % 26) as char)
+            .collect();
+        let base_font = format!(
+            "{}+{}",
+            tag,
+            postscript_name.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "")
+        );
+        let base_font = Name(base_font.as_bytes());
+
+        pdf.type0_font(font_id)
+            .base_font(base_font)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_id)
+            .to_unicode(cmap_id);
+
+        let per_em = |units: f32| units * 1000.0 / face.units_per_em() as f32;
+        let mut cid_font = pdf.cid_font(cid_font_id);
+        cid_font
+            .subtype(CidFontType::Type2)
+            .base_font(base_font)
+            .system_info(IDENTITY)
+            .font_descriptor(descriptor_id)
+            .cid_to_gid_map_predefined(Name(b"Identity"));
+        let mut widths = cid_font.widths();
+        for glyph in &glyphs {
+            let advance = face
+                .glyph_hor_advance(ttf_parser::GlyphId(*glyph))
+                .unwrap_or(0);
+            widths.consecutive(*glyph, [per_em(advance as f32)]);
+        }
+        widths.finish();
+        cid_font.finish();
+
+        let bbox = face.global_bounding_box();
+        pdf.font_descriptor(descriptor_id)
+            .name(base_font)
+            .flags(FontFlags::SYMBOLIC)
+            .bbox(Rect::new(
+                per_em(bbox.x_min as f32),
+                per_em(bbox.y_min as f32),
+                per_em(bbox.x_max as f32),
+                per_em(bbox.y_max as f32),
+            ))
+            .italic_angle(face.italic_angle())
+            .ascent(per_em(face.ascender() as f32))
+            .descent(per_em(face.descender() as f32))
+            .cap_height(per_em(
+                face.capital_height().unwrap_or(face.ascender()) as f32
+            ))
+            .stem_v(if bold { 120.0 } else { 80.0 })
+            .font_file2(file_id);
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        let compressed = encoder
+            .write_all(&font_file)
+            .and_then(|_| encoder.finish())
+            .expect("compressing into memory cannot fail");
+        pdf.stream(file_id, &compressed)
+            .filter(Filter::FlateDecode)
+            .pair(Name(b"Length1"), font_file.len() as i32);
+
+        let mut cmap = UnicodeCmap::new(Name(b"Custom"), IDENTITY);
+        for (glyph, text) in used.iter().filter(|(_, text)| !text.is_empty()) {
+            cmap.pair_with_multiple(*glyph, text.chars());
+        }
+        pdf.cmap(cmap_id, &cmap.finish());
+    }
+}
+
+pub(crate) struct PdfFonts {
+    // Regular and bold faces; None when the standard fonts cover all the text
+    unicode: Option<[UnicodeFont; 2]>,
+}
+
+impl PdfFonts {
+    pub(crate) fn standard() -> PdfFonts {
+        PdfFonts { unicode: None }
+    }
+
+    // Picks the fonts for a document from all the text it shows
+    pub(crate) fn for_texts<'a>(
+        texts: impl IntoIterator<Item = &'a str>,
+    ) -> Result<PdfFonts, String> {
+        let texts: Vec<&str> = texts.into_iter().collect();
+        let chars: BTreeSet<char> = texts
+            .iter()
+            .flat_map(|text| text.chars())
+            .filter(|c| needs_glyph(*c))
+            .collect();
+        let unencodable = texts
+            .iter()
+            .flat_map(|text| text.chars())
+            .find(|c| needs_glyph(*c) && win_ansi(*c).is_none());
+        let Some(unencodable) = unencodable else {
+            return Ok(PdfFonts::standard());
+        };
+
+        for family in unicode_font_families() {
+            let Some(regular) = UnicodeFont::load(&family, 400).filter(|f| f.covers(&chars)) else {
+                continue;
+            };
+            let bold = UnicodeFont::load(&family, 700)
+                .filter(|f| f.covers(&chars))
+                .unwrap_or_else(|| UnicodeFont {
+                    data: regular.data.clone(),
+                    index: regular.index,
+                    used: RefCell::new(BTreeMap::new()),
+                });
+            return Ok(PdfFonts {
+                unicode: Some([regular, bold]),
+            });
+        }
+        Err(format!(
+            "No font on this computer can print '{}'. Install Nirmala UI or Noto Sans Devanagari and try again.",
+            unencodable
+        ))
+    }
+
+    // Advance width in points
+    pub(crate) fn width(&self, text: &str, size: f32, bold: bool) -> f32 {
+        if let Some(fonts) = &self.unicode {
+            return fonts[bold as usize].width(text, size);
+        }
+        // Bold Helvetica runs about 8% wider than regular
+        let units: u32 = text
+            .chars()
+            .map(|c| match c as u32 {
+                code @ 32..=126 => HELVETICA_WIDTHS[(code - 32) as usize] as u32,
+                _ => 556,
+            })
+            .sum();
+        let width = units as f32 / 1000.0 * size;
+        if bold {
+            width * 1.08
+        } else {
+            width
+        }
+    }
+
+    // Text at (x, y) in points from the bottom left of the page
+    pub(crate) fn show(
+        &self,
+        content: &mut Content,
+        x: f32,
+        y: f32,
+        size: f32,
+        bold: bool,
+        text: &str,
+    ) {
+        content
+            .begin_text()
+            .set_font(if bold { BOLD_FONT_NAME } else { FONT_NAME }, size);
+        match &self.unicode {
+            Some(fonts) => fonts[bold as usize].show(content, x, y, size, text),
+            None => {
+                let bytes: Vec<u8> = text.chars().map(|c| win_ansi(c).unwrap_or(b'?')).collect();
+                content.next_line(x, y).show(Str(&bytes));
+            }
+        }
+        content.end_text();
+    }
+
+    // Writes the font objects once all text has been shown; embedded fonts take objects from next_id
+    pub(crate) fn write(
+        &self,
+        pdf: &mut Pdf,
+        font_id: Ref,
+        bold_font_id: Option<Ref>,
+        mut next_id: i32,
+    ) {
+        match &self.unicode {
+            Some([regular, bold]) => {
+                regular.write(pdf, font_id, &mut next_id, false);
+                if let Some(bold_font_id) = bold_font_id {
+                    bold.write(pdf, bold_font_id, &mut next_id, true);
+                }
+            }
+            None => {
+                pdf.type1_font(font_id)
+                    .base_font(Name(b"Helvetica"))
+                    .encoding_predefined(Name(b"WinAnsiEncoding"));
+                if let Some(bold_font_id) = bold_font_id {
+                    pdf.type1_font(bold_font_id)
+                        .base_font(Name(b"Helvetica-Bold"))
+                        .encoding_predefined(Name(b"WinAnsiEncoding"));
+                }
+            }
+        }
+    }
+}
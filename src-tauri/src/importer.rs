@@ -0,0 +1,881 @@
+use crate::db::{self, get_db_conn, validation_error, with_savepoint, AttributeValues};
+use base64::{engine::general_purpose, Engine as _};
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use chrono::{Duration, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension, Result, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Cursor;
+use tauri::AppHandle;
+
+// Item fields a column can be mapped to, with the headers they are recognised by.
+// Attribute columns map to "attr.<name>", as in the HTTP API filters.
+const FIELDS: [(&str, &[&str]); 7] = [
+    (
+        "code",
+        &["code", "item code", "sku", "part no", "part number"],
+    ),
+    ("name", &["name", "item name", "item", "description"]),
+    ("brand", &["brand", "brand name", "make", "manufacturer"]),
+    ("model", &["model", "model name"]),
+    ("category", &["category", "item category", "group"]),
+    ("aliases", &["aliases", "alias", "other names"]),
+    ("is_active", &["active", "is active", "status"]),
+];
+const ATTRIBUTE_PREFIX: &str = "attr.";
+
+// The header is looked for among the first rows; a title or notes may come before it
+const HEADER_SEARCH_ROWS: usize = 20;
+const SAMPLE_ROWS: usize = 5;
+// Similarity a misspelt brand or model name needs to be matched to an existing one
+const FUZZY_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemImportFile {
+    pub file_name: String, // The extension picks the format: .csv, .xlsx, .xls or .ods
+    pub data: String,      // base64
+    pub sheet: Option<String>,
+    pub header_row: Option<usize>, // 1-based; detected when missing
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemImportPreview {
+    pub sheets: Vec<String>,
+    pub sheet: Option<String>,
+    pub header_row: usize,
+    pub headers: Vec<String>,
+    pub fields: Vec<String>,
+    pub mapping: BTreeMap<String, usize>, // Field to column index, as detected from the headers
+    pub sample_rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemImportRequest {
+    pub file: ItemImportFile,
+    pub mapping: Option<BTreeMap<String, usize>>,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub skip_existing: bool, // Otherwise items with a known code are updated
+    #[serde(default)]
+    pub fuzzy_match: bool,
+    #[serde(default)]
+    pub create_missing: bool, // Brands and models that match nothing are created
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ImportRowStatus {
+    Created,
+    Updated,
+    Skipped,
+    Error,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportRowResult {
+    pub row: usize, // Row number in the sheet
+    pub code: String,
+    pub status: ImportRowStatus,
+    pub messages: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemImportReport {
+    pub dry_run: bool,
+    pub sheet: Option<String>,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub errors: usize,
+    pub rows: Vec<ImportRowResult>,
+}
+
+// ==========================================
+// Reading files
+// ==========================================
+
+struct SheetData {
+    sheets: Vec<String>,
+    sheet: Option<String>,
+    first_row: usize, // Sheet row number of rows[0]
+    rows: Vec<Vec<String>>,
+}
+
+fn read_file(file: &ItemImportFile) -> Result<SheetData, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(file.data.trim())
+        .map_err(|_| "File data is not valid base64".to_string())?;
+    let extension = file
+        .file_name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "csv" | "txt" => Ok(SheetData {
+            sheets: vec![],
+            sheet: None,
+            first_row: 1,
+            rows: read_csv(&bytes)?,
+        }),
+        "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" => read_workbook(bytes, file.sheet.as_deref()),
+        _ => Err("Unsupported file type. Use a CSV, XLSX, XLS or ODS file.".to_string()),
+    }
+}
+
+// Comma, semicolon or tab separated, whichever the first line uses most
+fn read_csv(bytes: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or_default();
+    let delimiter = [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|d| first_line.bytes().filter(|b| b == d).count())
+        .unwrap_or(b',');
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
+    reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(|cell| cell.trim().to_string()).collect())
+                .map_err(|e| format!("Could not read the CSV file: {}", e))
+        })
+        .collect()
+}
+
+fn read_workbook(bytes: Vec<u8>, sheet: Option<&str>) -> Result<SheetData, String> {
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(bytes))
+        .map_err(|e| format!("Could not read the spreadsheet: {}", e))?;
+    let sheets = workbook.sheet_names();
+    let name = match sheet {
+        Some(name) if sheets.iter().any(|s| s == name) => name.to_string(),
+        Some(name) => return Err(format!("The file has no sheet named '{}'", name)),
+        None => sheets
+            .first()
+            .cloned()
+            .ok_or("The spreadsheet has no sheets")?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .map_err(|e| format!("Could not read sheet '{}': {}", name, e))?;
+
+    // The range starts at the first used cell; leading empty columns are kept so column
+    // positions stay the same on every row
+    let (first_row, first_column) = range.start().unwrap_or((0, 0));
+    let rows = range
+        .rows()
+        .map(|row| {
+            std::iter::repeat_n(String::new(), first_column as usize)
+                .chain(row.iter().map(cell_text))
+                .collect()
+        })
+        .collect();
+    Ok(SheetData {
+        sheets,
+        sheet: Some(name),
+        first_row: first_row as usize + 1,
+        rows,
+    })
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.trim().to_string(),
+        // Codes typed as numbers come back as floats
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", *f as i64),
+        Data::DateTime(date) => NaiveDate::from_ymd_opt(1899, 12, 30)
+            .map(|base| base + Duration::days(date.as_f64() as i64))
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+// ==========================================
+// Headers and column mapping
+// ==========================================
+
+// Lowercase words only, so "Item-Code" and "item code" compare equal
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn field_for_header(header: &str, attribute_names: &[String]) -> Option<String> {
+    let header = normalize(header);
+    if header.is_empty() {
+        return None;
+    }
+    FIELDS
+        .iter()
+        .find(|(_, names)| names.contains(&header.as_str()))
+        .map(|(field, _)| field.to_string())
+        .or_else(|| {
+            attribute_names
+                .iter()
+                .find(|name| normalize(name) == header)
+                .map(|name| format!("{}{}", ATTRIBUTE_PREFIX, name))
+        })
+}
+
+// The row among the first few that names the most known fields, else the first non-empty row
+fn detect_header_row(rows: &[Vec<String>], attribute_names: &[String]) -> usize {
+    let scored = rows
+        .iter()
+        .take(HEADER_SEARCH_ROWS)
+        .enumerate()
+        .map(|(i, row)| {
+            let score = row
+                .iter()
+                .filter(|cell| field_for_header(cell, attribute_names).is_some())
+                .count();
+            (i, score)
+        });
+    let (best, score) = scored.fold((0, 0), |best, row| if row.1 > best.1 { row } else { best });
+    if score > 0 {
+        return best;
+    }
+    rows.iter()
+        .position(|row| row.iter().any(|cell| !cell.is_empty()))
+        .unwrap_or(0)
+}
+
+fn detect_mapping(headers: &[String], attribute_names: &[String]) -> BTreeMap<String, usize> {
+    let mut mapping = BTreeMap::new();
+    for (column, header) in headers.iter().enumerate() {
+        if let Some(field) = field_for_header(header, attribute_names) {
+            mapping.entry(field).or_insert(column);
+        }
+    }
+    mapping
+}
+
+fn item_attribute_names(conn: &Connection) -> Result<Vec<String>> {
+    Ok(db::load_attribute_definitions(conn, Some("item"), true)?
+        .into_iter()
+        .map(|definition| definition.name)
+        .collect())
+}
+
+// Index into SheetData::rows of the header, from the requested sheet row or detection
+fn header_index(data: &SheetData, header_row: Option<usize>, attribute_names: &[String]) -> usize {
+    match header_row {
+        Some(row) => row.saturating_sub(data.first_row),
+        None => detect_header_row(&data.rows, attribute_names),
+    }
+}
+
+fn preview(app: &AppHandle, file: &ItemImportFile) -> Result<ItemImportPreview, String> {
+    let data = read_file(file)?;
+    let conn = get_db_conn(app).map_err(|e| e.to_string())?;
+    let attribute_names = item_attribute_names(&conn).map_err(|e| e.to_string())?;
+
+    let header = header_index(&data, file.header_row, &attribute_names);
+    let headers = data.rows.get(header).cloned().unwrap_or_default();
+    let body = data.rows.iter().skip(header + 1);
+    let fields = FIELDS
+        .iter()
+        .map(|(field, _)| field.to_string())
+        .chain(
+            attribute_names
+                .iter()
+                .map(|name| format!("{}{}", ATTRIBUTE_PREFIX, name)),
+        )
+        .collect();
+
+    Ok(ItemImportPreview {
+        mapping: detect_mapping(&headers, &attribute_names),
+        sample_rows: body.clone().take(SAMPLE_ROWS).cloned().collect(),
+        total_rows: body
+            .filter(|row| row.iter().any(|cell| !cell.is_empty()))
+            .count(),
+        header_row: data.first_row + header,
+        sheets: data.sheets,
+        sheet: data.sheet,
+        headers,
+        fields,
+    })
+}
+
+// ==========================================
+// Matching brands, models and categories
+// ==========================================
+
+struct Master {
+    id: i64,
+    name: String,
+    brand_id: Option<i64>, // Models only
+}
+
+enum Match<'a> {
+    Exact(&'a Master),
+    Close(&'a Master),
+    None,
+}
+
+// Case, spacing and punctuation never matter; with fuzzy matching a single close name does too
+fn find_master<'a>(masters: impl Iterator<Item = &'a Master>, name: &str) -> Match<'a> {
+    let wanted = normalize(name);
+    let mut best: Option<(&Master, f64)> = None;
+    let mut tied = false;
+    for master in masters {
+        let candidate = normalize(&master.name);
+        if candidate == wanted {
+            return Match::Exact(master);
+        }
+        let score = strsim::normalized_levenshtein(&candidate, &wanted);
+        match best {
+            Some((_, best_score)) if score < best_score => {}
+            Some((_, best_score)) if score == best_score => tied = true,
+            _ => {
+                best = Some((master, score));
+                tied = false;
+            }
+        }
+    }
+    match best {
+        Some((master, score)) if score >= FUZZY_THRESHOLD && !tied => Match::Close(master),
+        _ => Match::None,
+    }
+}
+
+struct Masters {
+    brands: Vec<Master>,
+    models: Vec<Master>,
+    categories: Vec<(Master, String)>, // With the full "Parent > Child" path
+}
+
+impl Masters {
+    fn load(conn: &Connection) -> Result<Self> {
+        let load = |sql: &str| -> Result<Vec<Master>> {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map([], |row| {
+                Ok(Master {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    brand_id: row.get(2)?,
+                })
+            })?;
+            rows.collect()
+        };
+        let categories = db::load_item_categories(conn)?
+            .into_iter()
+            .map(|category| {
+                let path = category.path.unwrap_or_else(|| category.name.clone());
+                let master = Master {
+                    id: category.id.unwrap_or_default(),
+                    name: category.name,
+                    brand_id: None,
+                };
+                (master, path)
+            })
+            .collect();
+        Ok(Masters {
+            brands: load("SELECT id, name, NULL FROM brands")?,
+            models: load("SELECT id, name, brand_id FROM models")?,
+            categories,
+        })
+    }
+}
+
+// How a row's brand or model name was resolved, noted in the row's messages
+fn resolve_master(
+    found: Match,
+    kind: &str,
+    name: &str,
+    fuzzy_match: bool,
+    messages: &mut Vec<String>,
+) -> Result<Option<i64>> {
+    match found {
+        Match::Exact(master) => Ok(Some(master.id)),
+        Match::Close(master) if fuzzy_match => {
+            messages.push(format!("{} '{}' matched to '{}'", kind, name, master.name));
+            Ok(Some(master.id))
+        }
+        Match::Close(master) => Err(validation_error(format!(
+            "{} '{}' not found. Did you mean '{}'?",
+            kind, name, master.name
+        ))),
+        Match::None => Ok(None),
+    }
+}
+
+// ==========================================
+// Importing rows
+// ==========================================
+
+struct ExistingItem {
+    id: i64,
+    name: String,
+    brand_id: Option<i64>,
+    model_id: Option<i64>,
+    category_id: Option<i64>,
+    aliases: Option<String>,
+    is_active: bool,
+}
+
+struct RowContext<'a> {
+    request: &'a ItemImportRequest,
+    mapping: &'a BTreeMap<String, usize>,
+    masters: Masters,
+}
+
+fn parse_active(value: &str) -> Result<bool> {
+    match normalize(value).as_str() {
+        "1" | "yes" | "y" | "true" | "active" => Ok(true),
+        "0" | "no" | "n" | "false" | "inactive" => Ok(false),
+        _ => Err(validation_error(format!(
+            "Active must be yes or no, not '{}'",
+            value
+        ))),
+    }
+}
+
+fn import_row(
+    tx: &Transaction,
+    context: &mut RowContext,
+    row: &[String],
+    code: &str,
+    messages: &mut Vec<String>,
+) -> Result<ImportRowStatus> {
+    let request = context.request;
+    // None when the column is not mapped; Some("") clears the field
+    let cell = |field: &str| -> Option<&str> {
+        context
+            .mapping
+            .get(field)
+            .map(|column| row.get(*column).map(String::as_str).unwrap_or_default())
+    };
+
+    let existing = tx
+        .query_row(
+            "SELECT id, name, brand_id, model_id, category_id, aliases, is_active
+             FROM items WHERE code = ?1",
+            params![code],
+            |row| {
+                Ok(ExistingItem {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    brand_id: row.get(2)?,
+                    model_id: row.get(3)?,
+                    category_id: row.get(4)?,
+                    aliases: row.get(5)?,
+                    is_active: row.get(6)?,
+                })
+            },
+        )
+        .optional()?;
+    if existing.is_some() && request.skip_existing {
+        messages.push("An item with this code already exists".to_string());
+        return Ok(ImportRowStatus::Skipped);
+    }
+
+    let name = match (cell("name").filter(|n| !n.is_empty()), &existing) {
+        (Some(name), _) => name.to_string(),
+        (None, Some(existing)) => existing.name.clone(),
+        (None, None) => return Err(validation_error("Name is required")),
+    };
+
+    let mut brand_id = existing.as_ref().and_then(|e| e.brand_id);
+    if let Some(brand) = cell("brand") {
+        brand_id = match brand {
+            "" => None,
+            brand => {
+                let found = find_master(context.masters.brands.iter(), brand);
+                match resolve_master(found, "Brand", brand, request.fuzzy_match, messages)? {
+                    Some(id) => Some(id),
+                    None if request.create_missing => {
+                        tx.execute("INSERT INTO brands (name) VALUES (?1)", params![brand])?;
+                        let id = tx.last_insert_rowid();
+                        context.masters.brands.push(Master {
+                            id,
+                            name: brand.to_string(),
+                            brand_id: None,
+                        });
+                        messages.push(format!("Brand '{}' created", brand));
+                        Some(id)
+                    }
+                    None => return Err(validation_error(format!("Brand '{}' not found", brand))),
+                }
+            }
+        };
+    }
+
+    let mut model_id = existing.as_ref().and_then(|e| e.model_id);
+    if let Some(model) = cell("model") {
+        model_id = match model {
+            "" => None,
+            model => {
+                // Close names are only looked for among the brand's models; the exact name of
+                // another brand's model is left for validate_brand_model to report
+                let candidates = context.masters.models.iter().filter(|m| {
+                    brand_id.is_none() || m.brand_id.is_none() || m.brand_id == brand_id
+                });
+                let found = match find_master(candidates, model) {
+                    Match::None => match find_master(context.masters.models.iter(), model) {
+                        Match::Exact(master) => Match::Exact(master),
+                        _ => Match::None,
+                    },
+                    found => found,
+                };
+                match resolve_master(found, "Model", model, request.fuzzy_match, messages)? {
+                    Some(id) => Some(id),
+                    None if request.create_missing => {
+                        tx.execute(
+                            "INSERT INTO models (name, brand_id) VALUES (?1, ?2)",
+                            params![model, brand_id],
+                        )?;
+                        let id = tx.last_insert_rowid();
+                        context.masters.models.push(Master {
+                            id,
+                            name: model.to_string(),
+                            brand_id,
+                        });
+                        messages.push(format!("Model '{}' created", model));
+                        Some(id)
+                    }
+                    None => return Err(validation_error(format!("Model '{}' not found", model))),
+                }
+            }
+        };
+    }
+    let brand_id = db::validate_brand_model(tx, brand_id, model_id)?;
+
+    let mut category_id = existing.as_ref().and_then(|e| e.category_id);
+    if let Some(category) = cell("category") {
+        category_id = match category {
+            "" => None,
+            category => {
+                // A full "Parent > Child" path, or a name that only one category has
+                let wanted = normalize(category);
+                let by_path = context
+                    .masters
+                    .categories
+                    .iter()
+                    .find(|(_, path)| normalize(path) == wanted);
+                let mut by_name = context
+                    .masters
+                    .categories
+                    .iter()
+                    .filter(|(master, _)| normalize(&master.name) == wanted);
+                match (by_path, by_name.next(), by_name.next()) {
+                    (Some((master, _)), _, _) | (None, Some((master, _)), None) => Some(master.id),
+                    (None, Some(_), Some(_)) => {
+                        return Err(validation_error(format!(
+                        "Several categories are named '{}'; use the full path, e.g. 'Parent > {}'",
+                        category, category
+                    )))
+                    }
+                    (None, None, _) => {
+                        return Err(validation_error(format!(
+                            "Category '{}' not found",
+                            category
+                        )))
+                    }
+                }
+            }
+        };
+    }
+
+    let aliases = match cell("aliases") {
+        Some(aliases) => Some(aliases.to_string()).filter(|a| !a.is_empty()),
+        None => existing.as_ref().and_then(|e| e.aliases.clone()),
+    };
+    let is_active = match cell("is_active").filter(|a| !a.is_empty()) {
+        Some(value) => parse_active(value)?,
+        None => existing.as_ref().is_none_or(|e| e.is_active),
+    };
+
+    // Only the attribute columns in the file change; an item's other attributes are kept
+    let mut attributes: AttributeValues = match &existing {
+        Some(existing) => db::load_entity_attributes(tx, "item", existing.id)?,
+        None => AttributeValues::new(),
+    };
+    let before = attributes.clone();
+    for field in context.mapping.keys() {
+        if let Some(name) = field.strip_prefix(ATTRIBUTE_PREFIX) {
+            attributes.insert(
+                name.to_string(),
+                cell(field).unwrap_or_default().to_string(),
+            );
+        }
+    }
+    attributes.retain(|_, value| !value.is_empty());
+
+    let Some(existing) = existing else {
+        tx.execute(
+            "INSERT INTO items (code, name, brand_id, model_id, category_id, aliases, is_active)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                code,
+                name,
+                brand_id,
+                model_id,
+                category_id,
+                aliases,
+                is_active
+            ],
+        )?;
+        let item_id = tx.last_insert_rowid();
+        db::save_attributes(tx, "item", item_id, Some(&attributes), true)?;
+        return Ok(ImportRowStatus::Created);
+    };
+
+    let unchanged = existing.name == name
+        && existing.brand_id == brand_id
+        && existing.model_id == model_id
+        && existing.category_id == category_id
+        && existing.aliases == aliases
+        && existing.is_active == is_active
+        && before == attributes;
+    if unchanged {
+        messages.push("No changes".to_string());
+        return Ok(ImportRowStatus::Skipped);
+    }
+
+    tx.execute(
+        "UPDATE items SET name = ?1, brand_id = ?2, model_id = ?3, category_id = ?4,
+            aliases = ?5, is_active = ?6
+         WHERE id = ?7",
+        params![
+            name,
+            brand_id,
+            model_id,
+            category_id,
+            aliases,
+            is_active,
+            existing.id
+        ],
+    )?;
+    db::save_attributes(tx, "item", existing.id, Some(&attributes), false)?;
+    Ok(ImportRowStatus::Updated)
+}
+
+// Every row is tried on its own, so one bad row is reported instead of stopping the import.
+// A dry run does the same work and then rolls it all back.
+fn import(app: &AppHandle, request: &ItemImportRequest) -> Result<ItemImportReport, String> {
+    let data = read_file(&request.file)?;
+    let mut conn = get_db_conn(app).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let attribute_names = item_attribute_names(&tx).map_err(|e| e.to_string())?;
+
+    let header = header_index(&data, request.file.header_row, &attribute_names);
+    let mapping = match &request.mapping {
+        Some(mapping) => mapping.clone(),
+        None => detect_mapping(
+            data.rows.get(header).map_or(&[][..], Vec::as_slice),
+            &attribute_names,
+        ),
+    };
+    if !mapping.contains_key("code") {
+        return Err("Choose the column that holds the item code".to_string());
+    }
+    let unknown = mapping.keys().find(|field| {
+        !FIELDS.iter().any(|(known, _)| known == field)
+            && !field
+                .strip_prefix(ATTRIBUTE_PREFIX)
+                .is_some_and(|name| attribute_names.iter().any(|a| a == name))
+    });
+    if let Some(field) = unknown {
+        return Err(format!("Unknown import field '{}'", field));
+    }
+
+    let mut context = RowContext {
+        request,
+        mapping: &mapping,
+        masters: Masters::load(&tx).map_err(|e| e.to_string())?,
+    };
+    let mut report = ItemImportReport {
+        dry_run: request.dry_run,
+        sheet: data.sheet.clone(),
+        created: 0,
+        updated: 0,
+        skipped: 0,
+        errors: 0,
+        rows: vec![],
+    };
+    let mut seen_codes: HashMap<String, usize> = HashMap::new();
+
+    for (index, row) in data.rows.iter().enumerate().skip(header + 1) {
+        if row.iter().all(|cell| cell.is_empty()) {
+            continue;
+        }
+        let row_number = data.first_row + index;
+        let code = row
+            .get(mapping["code"])
+            .map(|c| c.trim().to_string())
+            .unwrap_or_default();
+        let mut messages = vec![];
+
+        let status = if code.is_empty() {
+            messages.push("Code is required".to_string());
+            ImportRowStatus::Error
+        } else if let Some(first) = seen_codes.get(&code) {
+            messages.push(format!("Code already appears on row {}", first));
+            ImportRowStatus::Error
+        } else {
+            seen_codes.insert(code.clone(), row_number);
+            match with_savepoint(&tx, || {
+                import_row(&tx, &mut context, row, &code, &mut messages)
+            }) {
+                Ok(status) => status,
+                Err(e) => {
+                    // Brands and models created for the failed row were rolled back with it
+                    context.masters = Masters::load(&tx).map_err(|e| e.to_string())?;
+                    messages.retain(|m| !m.ends_with(" created"));
+                    messages.push(e.to_string());
+                    ImportRowStatus::Error
+                }
+            }
+        };
+
+        match status {
+            ImportRowStatus::Created => report.created += 1,
+            ImportRowStatus::Updated => report.updated += 1,
+            ImportRowStatus::Skipped => report.skipped += 1,
+            ImportRowStatus::Error => report.errors += 1,
+        }
+        report.rows.push(ImportRowResult {
+            row: row_number,
+            code,
+            status,
+            messages,
+        });
+    }
+
+    if !request.dry_run {
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn preview_item_import(
+    app: AppHandle,
+    file: ItemImportFile,
+) -> Result<ItemImportPreview, String> {
+    preview(&app, &file)
+}
+
+#[tauri::command]
+pub fn import_items_file(
+    app: AppHandle,
+    request: ItemImportRequest,
+) -> Result<ItemImportReport, String> {
+    import(&app, &request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    fn master(id: i64, name: &str) -> Master {
+        Master {
+            id,
+            name: name.to_string(),
+            brand_id: None,
+        }
+    }
+
+    fn matched(masters: &[Master], name: &str) -> Option<(bool, i64)> {
+        match find_master(masters.iter(), name) {
+            Match::Exact(master) => Some((true, master.id)),
+            Match::Close(master) => Some((false, master.id)),
+            Match::None => None,
+        }
+    }
+
+    #[test]
+    fn csv_delimiter_follows_the_first_line() {
+        let comma = read_csv(b"code,name\nA1,\"Cement, grey\"\n").unwrap();
+        assert_eq!(
+            comma,
+            vec![row(&["code", "name"]), row(&["A1", "Cement, grey"])]
+        );
+
+        let semicolon = read_csv(b"code;name;brand\nA1;Cement, grey;ACC\n").unwrap();
+        assert_eq!(semicolon[1], row(&["A1", "Cement, grey", "ACC"]));
+
+        let tab = read_csv(b"code\tname\nA1\t Cement \n").unwrap();
+        assert_eq!(tab[1], row(&["A1", "Cement"]));
+    }
+
+    #[test]
+    fn csv_byte_order_mark_is_dropped() {
+        let rows = read_csv("\u{feff}code,name\nA1,Cement\n".as_bytes()).unwrap();
+        assert_eq!(rows[0], row(&["code", "name"]));
+    }
+
+    #[test]
+    fn header_row_is_found_below_a_title() {
+        let rows = vec![
+            row(&["Item master as of March", "", ""]),
+            row(&["", "", ""]),
+            row(&["Item Code", "Item-Name", "Colour"]),
+            row(&["A1", "Cement", "Grey"]),
+        ];
+        assert_eq!(detect_header_row(&rows, &[]), 2);
+
+        let mapping = detect_mapping(&rows[2], &["colour".to_string()]);
+        assert_eq!(mapping.get("code"), Some(&0));
+        assert_eq!(mapping.get("name"), Some(&1));
+        assert_eq!(mapping.get("attr.colour"), Some(&2));
+    }
+
+    #[test]
+    fn header_row_defaults_to_the_first_non_empty_row() {
+        let rows = vec![row(&["", ""]), row(&["A1", "Cement"]), row(&["A2", "Sand"])];
+        assert_eq!(detect_header_row(&rows, &[]), 1);
+    }
+
+    #[test]
+    fn first_column_wins_when_two_name_the_same_field() {
+        let mapping = detect_mapping(&row(&["SKU", "Name", "Code"]), &[]);
+        assert_eq!(mapping.get("code"), Some(&0));
+    }
+
+    #[test]
+    fn master_names_match_ignoring_case_and_punctuation() {
+        let masters = [master(1, "Asian Paints"), master(2, "Philips")];
+        assert_eq!(matched(&masters, "asian-paints"), Some((true, 1)));
+    }
+
+    #[test]
+    fn single_close_master_name_is_matched() {
+        let masters = [master(1, "Asian Paints"), master(2, "Philips")];
+        assert_eq!(matched(&masters, "Philps"), Some((false, 2)));
+        assert_eq!(matched(&masters, "Bosch"), None);
+    }
+
+    #[test]
+    fn tied_close_master_names_are_not_matched() {
+        let masters = [master(1, "Philipz"), master(2, "Philipx")];
+        assert_eq!(matched(&masters, "Philips"), None);
+    }
+
+    #[test]
+    fn numeric_cells_read_as_codes() {
+        assert_eq!(cell_text(&Data::Float(1234.0)), "1234");
+        assert_eq!(cell_text(&Data::Float(12.5)), "12.5");
+        assert_eq!(cell_text(&Data::Int(42)), "42");
+        assert_eq!(
+            cell_text(&Data::String("  CEM-0012 ".to_string())),
+            "CEM-0012"
+        );
+        assert_eq!(cell_text(&Data::Empty), "");
+    }
+}
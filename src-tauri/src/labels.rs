@@ -0,0 +1,433 @@
+use crate::db::{company_data_dir, get_db_conn};
+use crate::fonts::{PdfFonts, FONT_NAME};
+use pdf_writer::{Content, Finish, Pdf, Rect, Ref};
+use qrcode::{Color, QrCode};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+// A4 sheet with 3 x 8 labels of 70 x 37 mm (the common adhesive label layout)
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const COLUMNS: usize = 3;
+const ROWS: usize = 8;
+const LABEL_WIDTH: f32 = 70.0;
+const LABEL_HEIGHT: f32 = 37.0;
+const TOP_MARGIN: f32 = (PAGE_HEIGHT - LABEL_HEIGHT * ROWS as f32) / 2.0;
+const PT_PER_MM: f32 = 72.0 / 25.4;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LabelRequest {
+    pub item_ids: Vec<i64>,
+    pub symbology: String, // "code128" or "qr"
+    pub format: String,    // "svg" or "pdf"
+    pub copies: Option<i64>,
+}
+
+#[derive(Clone)]
+struct LabelItem {
+    code: String,
+    name: String,
+    brand_name: Option<String>,
+    model_name: Option<String>,
+    barcode: String,
+}
+
+// Shapes in millimetres with the origin at the top left of the page
+enum Mark {
+    Bar {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        text: String,
+    },
+}
+
+// Code 128 bar/space widths for symbol values 0..=105, followed by the stop pattern
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+const CODE128_START_B: usize = 104;
+const CODE128_START_C: usize = 105;
+const CODE128_STOP: usize = 106;
+
+// Symbol values for a barcode: code set C for even-length digit strings, code set B otherwise
+fn code128_symbols(value: &str) -> Result<Vec<usize>, String> {
+    let mut symbols = vec![];
+    if value.len() >= 4
+        && value.len().is_multiple_of(2)
+        && value.bytes().all(|b| b.is_ascii_digit())
+    {
+        symbols.push(CODE128_START_C);
+        for pair in value.as_bytes().chunks(2) {
+            symbols.push(((pair[0] - b'0') * 10 + (pair[1] - b'0')) as usize);
+        }
+    } else {
+        symbols.push(CODE128_START_B);
+        for c in value.chars() {
+            if !(' '..='~').contains(&c) {
+                return Err(format!("'{}' cannot be encoded as Code 128", value));
+            }
+            symbols.push(c as usize - 32);
+        }
+    }
+
+    let checksum = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, s)| i.max(1) * s)
+        .sum::<usize>()
+        % 103;
+    symbols.push(checksum);
+    symbols.push(CODE128_STOP);
+    Ok(symbols)
+}
+
+fn code128_marks(
+    value: &str,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+) -> Result<Vec<Mark>, String> {
+    let widths: Vec<u32> = code128_symbols(value)?
+        .into_iter()
+        .flat_map(|s| CODE128_PATTERNS[s].chars().filter_map(|c| c.to_digit(10)))
+        .collect();
+
+    // 10 module quiet zone on each side
+    let modules: u32 = widths.iter().sum::<u32>() + 20;
+    let module = width / modules as f32;
+
+    let mut marks = vec![];
+    let mut cursor = x + module * 10.0;
+    for (i, w) in widths.iter().enumerate() {
+        let w = *w as f32 * module;
+        if i % 2 == 0 {
+            marks.push(Mark::Bar {
+                x: cursor,
+                y,
+                w,
+                h: height,
+            });
+        }
+        cursor += w;
+    }
+    Ok(marks)
+}
+
+fn qr_marks(value: &str, x: f32, y: f32, size: f32) -> Result<Vec<Mark>, String> {
+    let code = QrCode::new(value.as_bytes()).map_err(|e| e.to_string())?;
+    let width = code.width();
+
+    // 4 module quiet zone on each side
+    let module = size / (width + 8) as f32;
+
+    let mut marks = vec![];
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            marks.push(Mark::Bar {
+                x: x + (i % width + 4) as f32 * module,
+                y: y + (i / width + 4) as f32 * module,
+                w: module,
+                h: module,
+            });
+        }
+    }
+    Ok(marks)
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(max_chars - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+fn label_marks(label: &LabelItem, symbology: &str, x: f32, y: f32) -> Result<Vec<Mark>, String> {
+    let mut marks = vec![];
+    let brand_model = [label.brand_name.as_deref(), label.model_name.as_deref()]
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" / ");
+
+    if symbology == "qr" {
+        let size = LABEL_HEIGHT - 4.0;
+        marks.extend(qr_marks(&label.barcode, x + 2.0, y + 2.0, size)?);
+        let text_x = x + size + 3.0;
+        marks.push(Mark::Text {
+            x: text_x,
+            y: y + 10.0,
+            size: 9.0,
+            text: truncate(&label.code, 18),
+        });
+        marks.push(Mark::Text {
+            x: text_x,
+            y: y + 16.0,
+            size: 7.0,
+            text: truncate(&label.name, 22),
+        });
+        marks.push(Mark::Text {
+            x: text_x,
+            y: y + 21.0,
+            size: 6.0,
+            text: truncate(&brand_model, 26),
+        });
+    } else {
+        marks.push(Mark::Text {
+            x: x + 3.0,
+            y: y + 6.0,
+            size: 9.0,
+            text: truncate(&label.code, 30),
+        });
+        marks.push(Mark::Text {
+            x: x + 3.0,
+            y: y + 10.5,
+            size: 7.0,
+            text: truncate(&label.name, 38),
+        });
+        marks.push(Mark::Text {
+            x: x + 3.0,
+            y: y + 14.0,
+            size: 6.0,
+            text: truncate(&brand_model, 44),
+        });
+        marks.extend(code128_marks(
+            &label.barcode,
+            x + 1.0,
+            y + 16.0,
+            LABEL_WIDTH - 2.0,
+            15.0,
+        )?);
+        marks.push(Mark::Text {
+            x: x + 3.0,
+            y: y + 34.5,
+            size: 7.0,
+            text: label.barcode.clone(),
+        });
+    }
+    marks.retain(|mark| !matches!(mark, Mark::Text { text, .. } if text.is_empty()));
+    Ok(marks)
+}
+
+// Lay out the labels row by row, one page per 24 labels
+fn layout_pages(labels: &[LabelItem], symbology: &str) -> Result<Vec<Vec<Mark>>, String> {
+    let left_margin = (PAGE_WIDTH - LABEL_WIDTH * COLUMNS as f32) / 2.0;
+    let mut pages = vec![];
+    for chunk in labels.chunks(COLUMNS * ROWS) {
+        let mut page = vec![];
+        for (i, label) in chunk.iter().enumerate() {
+            let x = left_margin + (i % COLUMNS) as f32 * LABEL_WIDTH;
+            let y = TOP_MARGIN + (i / COLUMNS) as f32 * LABEL_HEIGHT;
+            page.extend(label_marks(label, symbology, x, y)?);
+        }
+        pages.push(page);
+    }
+    Ok(pages)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Pages are stacked vertically in a single SVG document
+fn render_svg(pages: &[Vec<Mark>]) -> Vec<u8> {
+    let height = PAGE_HEIGHT * pages.len() as f32;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n",
+        w = PAGE_WIDTH,
+        h = height
+    );
+    for (index, page) in pages.iter().enumerate() {
+        let offset = PAGE_HEIGHT * index as f32;
+        svg.push_str(&format!(
+            "<g transform=\"translate(0 {})\">\n<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+            offset, PAGE_WIDTH, PAGE_HEIGHT
+        ));
+        for mark in page {
+            match mark {
+                Mark::Bar { x, y, w, h } => svg.push_str(&format!(
+                    "<rect x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\"/>\n",
+                    x, y, w, h
+                )),
+                Mark::Text { x, y, size, text } => svg.push_str(&format!(
+                    "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"Helvetica, Arial, sans-serif\" font-size=\"{:.2}\">{}</text>\n",
+                    x,
+                    y,
+                    size / PT_PER_MM,
+                    escape_xml(text)
+                )),
+            }
+        }
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg.into_bytes()
+}
+
+fn render_pdf(pages: &[Vec<Mark>], fonts: &PdfFonts) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let font_id = Ref::new(3);
+    let page_ids: Vec<Ref> = (0..pages.len())
+        .map(|i| Ref::new(4 + 2 * i as i32))
+        .collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(pages.len() as i32);
+    for (page_id, marks) in page_ids.iter().zip(pages) {
+        let content_id = Ref::new(page_id.get() + 1);
+        let mut page = pdf.page(*page_id);
+        page.media_box(Rect::new(
+            0.0,
+            0.0,
+            PAGE_WIDTH * PT_PER_MM,
+            PAGE_HEIGHT * PT_PER_MM,
+        ))
+        .parent(page_tree_id)
+        .contents(content_id);
+        page.resources().fonts().pair(FONT_NAME, font_id);
+        page.finish();
+
+        let mut content = Content::new();
+        for mark in marks {
+            match mark {
+                Mark::Bar { x, y, w, h } => {
+                    content
+                        .rect(
+                            x * PT_PER_MM,
+                            (PAGE_HEIGHT - y - h) * PT_PER_MM,
+                            w * PT_PER_MM,
+                            h * PT_PER_MM,
+                        )
+                        .fill_nonzero();
+                }
+                Mark::Text { x, y, size, text } => {
+                    fonts.show(
+                        &mut content,
+                        x * PT_PER_MM,
+                        (PAGE_HEIGHT - y) * PT_PER_MM,
+                        *size,
+                        false,
+                        text,
+                    );
+                }
+            }
+        }
+        pdf.stream(content_id, &content.finish());
+    }
+    fonts.write(&mut pdf, font_id, None, 4 + 2 * pages.len() as i32);
+
+    pdf.finish()
+}
+
+// Code, name, brand, model and the barcode to print (primary barcode, else the item code)
+fn load_label_items(app: &AppHandle, item_ids: &[i64]) -> Result<Vec<LabelItem>, String> {
+    let conn = get_db_conn(app).map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.code, i.name, b.name, m.name,
+                COALESCE(
+                    (SELECT bc.barcode FROM item_barcodes bc WHERE bc.item_id = i.id ORDER BY bc.is_primary DESC, bc.id LIMIT 1),
+                    i.code
+                )
+             FROM items i
+             LEFT JOIN brands b ON i.brand_id = b.id
+             LEFT JOIN models m ON i.model_id = m.id
+             WHERE i.id = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut labels = vec![];
+    for item_id in item_ids {
+        let label = stmt
+            .query_row([item_id], |row| {
+                Ok(LabelItem {
+                    code: row.get(0)?,
+                    name: row.get(1)?,
+                    brand_name: row.get(2)?,
+                    model_name: row.get(3)?,
+                    barcode: row.get(4)?,
+                })
+            })
+            .map_err(|_| format!("Item {} does not exist", item_id))?;
+        labels.push(label);
+    }
+    Ok(labels)
+}
+
+pub fn render_labels(app: &AppHandle, request: &LabelRequest) -> Result<PathBuf, String> {
+    if request.item_ids.is_empty() {
+        return Err("Select at least one item to print labels for".to_string());
+    }
+    if !["code128", "qr"].contains(&request.symbology.as_str()) {
+        return Err(format!("Unknown barcode symbology '{}'", request.symbology));
+    }
+    let copies = request.copies.unwrap_or(1);
+    if !(1..=500).contains(&copies) {
+        return Err("Copies must be between 1 and 500".to_string());
+    }
+
+    let mut labels = vec![];
+    for label in load_label_items(app, &request.item_ids)? {
+        labels.extend(vec![label; copies as usize]);
+    }
+
+    let pages = layout_pages(&labels, &request.symbology)?;
+    let bytes = match request.format.as_str() {
+        "svg" => render_svg(&pages),
+        "pdf" => {
+            let fonts =
+                PdfFonts::for_texts(pages.iter().flatten().filter_map(|mark| match mark {
+                    Mark::Text { text, .. } => Some(text.as_str()),
+                    _ => None,
+                }))?;
+            render_pdf(&pages, &fonts)
+        }
+        other => return Err(format!("Unknown label format '{}'", other)),
+    };
+
+    let labels_dir = company_data_dir(app).join("labels");
+    std::fs::create_dir_all(&labels_dir).map_err(|e| e.to_string())?;
+    let path = labels_dir.join(format!(
+        "item-labels-{}.{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        request.format
+    ));
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+// Writes a printable label sheet to the app data folder and returns its path
+#[tauri::command]
+pub fn generate_item_labels(app: AppHandle, request: LabelRequest) -> Result<String, String> {
+    render_labels(&app, &request).map(|path| path.to_string_lossy().to_string())
+}
@@ -0,0 +1,575 @@
+use crate::db::company_data_dir;
+use base64::{engine::general_purpose, Engine as _};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{AppHandle, Manager};
+
+// One installation hosts the company database through the API server with network access on;
+// the others run as clients and send every data command to it. The client settings belong to
+// the installation, not to a company, so they live beside the company registry.
+const CONFIG_FILE_NAME: &str = "lan.json";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+
+// Commands that write a file into a folder of the company data dir. The host sends the file
+// back and the client saves it in the same folder of its own data dir.
+const FILE_COMMANDS: &[(&str, &str)] = &[
+    ("generate_item_labels", "labels"),
+    ("generate_voucher_document", "documents"),
+    ("get_voucher_attachment_file", "attachments"),
+];
+
+// Commands for the installation itself, which run locally in client mode. Everything else is
+// sent to the host, so a data command the host does not know fails instead of running locally.
+const LOCAL_COMMANDS: &[&str] = &[
+    "greet",
+    "list_companies",
+    "get_active_company",
+    "create_company",
+    "open_company",
+    "rename_company",
+    "archive_company",
+    "get_encryption_status",
+    "unlock_database",
+    "set_database_passphrase",
+    "backup_database",
+    "list_backups",
+    "get_backup_status",
+    "restore_backup",
+    "get_api_server_status",
+    "get_license_status",
+    "activate_license",
+    "get_system_id",
+    "get_lan_status",
+    "connect_to_host",
+    "disconnect_from_host",
+    "get_sync_status",
+    "set_sync_node_name",
+    "export_sync_package",
+    "import_sync_package",
+    "resolve_sync_conflict",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanConfig {
+    pub client_enabled: bool,
+    pub host_address: Option<String>,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanStatus {
+    pub client_enabled: bool,
+    pub host_address: Option<String>,
+    pub is_connected: bool,
+    pub host_company: Option<String>,
+    pub error: Option<String>,
+    pub last_contact: Option<String>,
+}
+
+#[derive(Default)]
+pub struct LanClient(Mutex<ClientState>);
+
+#[derive(Default)]
+struct ClientState {
+    config: LanConfig,
+    connected: bool,
+    host_company: Option<String>,
+    error: Option<String>,
+    last_contact: Option<String>,
+}
+
+enum HostError {
+    // The host could not be reached; the client keeps retrying
+    Unreachable(String),
+    // The host answered with an error, which is passed on as the command's error
+    Rejected(String),
+}
+
+impl HostError {
+    fn message(self) -> String {
+        match self {
+            HostError::Unreachable(message) | HostError::Rejected(message) => message,
+        }
+    }
+}
+
+fn config_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .expect("failed to get app data dir")
+        .join(CONFIG_FILE_NAME)
+}
+
+// A missing or unreadable file leaves the installation standalone
+fn load_config(app: &AppHandle) -> LanConfig {
+    std::fs::read(config_path(app))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_config(app: &AppHandle, config: &LanConfig) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(config_path(app), data)
+        .map_err(|e| format!("Could not save the network settings: {}", e))
+}
+
+pub fn init_lan_client(app: &AppHandle) {
+    let state = ClientState {
+        config: load_config(app),
+        ..Default::default()
+    };
+    app.manage(LanClient(Mutex::new(state)));
+}
+
+// The host to send commands to, while this installation is a client
+fn client_config(app: &AppHandle) -> Option<LanConfig> {
+    let state = app.try_state::<LanClient>()?;
+    let state = state.0.lock().unwrap_or_else(PoisonError::into_inner);
+    Some(state.config.clone()).filter(|c| c.client_enabled)
+}
+
+fn record_contact(app: &AppHandle, result: Result<(), &HostError>) {
+    let Some(state) = app.try_state::<LanClient>() else {
+        return;
+    };
+    let mut state = state.0.lock().unwrap_or_else(PoisonError::into_inner);
+    match result {
+        Ok(()) => {
+            state.connected = true;
+            state.error = None;
+            state.last_contact = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+        Err(HostError::Unreachable(message)) => {
+            state.connected = false;
+            state.error = Some(message.clone());
+        }
+        Err(HostError::Rejected(_)) => {}
+    }
+}
+
+fn status(app: &AppHandle) -> LanStatus {
+    let state = app.state::<LanClient>();
+    let state = state.0.lock().unwrap_or_else(PoisonError::into_inner);
+    LanStatus {
+        client_enabled: state.config.client_enabled,
+        host_address: state.config.host_address.clone(),
+        is_connected: state.config.client_enabled && state.connected,
+        host_company: state.host_company.clone(),
+        error: state.error.clone(),
+        last_contact: state.last_contact.clone(),
+    }
+}
+
+// Accepts "192.168.1.20:8787" as well as a pasted "http://192.168.1.20:8787/api"
+fn normalize_address(address: &str) -> Result<String, String> {
+    let address = address.trim();
+    let address = address
+        .strip_prefix("http://")
+        .unwrap_or(address)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    if address.is_empty() {
+        return Err("Enter the host address".to_string());
+    }
+    if address
+        .rsplit_once(':')
+        .and_then(|(_, port)| port.parse::<u16>().ok())
+        .is_none()
+    {
+        return Err("The host address needs a port, e.g. 192.168.1.20:8787".to_string());
+    }
+    Ok(address.to_string())
+}
+
+// ============================================================================
+// HTTP Client
+// ============================================================================
+
+// Plain HTTP/1.0 so the server closes the connection after a response that is never chunked
+fn http_request(
+    address: &str,
+    token: &str,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> std::io::Result<(u16, Vec<u8>)> {
+    let socket_address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "address not found"))?;
+    let mut stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+    stream.set_write_timeout(Some(RESPONSE_TIMEOUT))?;
+    write!(
+        stream,
+        "{} {} HTTP/1.0\r\nHost: {}\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+        method,
+        path,
+        address,
+        token,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid response");
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(invalid)?;
+    let status = std::str::from_utf8(&response[..header_end])
+        .ok()
+        .and_then(|head| head.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(invalid)?;
+    Ok((status, response[header_end + 4..].to_vec()))
+}
+
+fn call_host(
+    config: &LanConfig,
+    method: &str,
+    path: &str,
+    body: Option<&Value>,
+) -> Result<Value, HostError> {
+    let address = config.host_address.as_deref().unwrap_or_default();
+    let body = body.map(Value::to_string).unwrap_or_default();
+    let (status, response) = http_request(
+        address,
+        config.token.as_deref().unwrap_or_default(),
+        method,
+        path,
+        body.as_bytes(),
+    )
+    .map_err(|e| HostError::Unreachable(format!("Cannot reach the host at {}: {}", address, e)))?;
+
+    let value: Value = if response.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&response)
+            .map_err(|_| HostError::Unreachable(format!("{} is not a Stock Star host", address)))?
+    };
+    match status {
+        200..=299 => Ok(value),
+        401 => Err(HostError::Unreachable(
+            "The host did not accept the access token".to_string(),
+        )),
+        _ => Err(HostError::Rejected(
+            value["error"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("The host answered with status {}", status)),
+        )),
+    }
+}
+
+fn ping(app: &AppHandle, config: &LanConfig) -> Result<(), String> {
+    let result = call_host(config, "GET", "/api/ping", None);
+    record_contact(app, result.as_ref().map(|_| ()));
+    let info = result.map_err(HostError::message)?;
+    if let Some(state) = app.try_state::<LanClient>() {
+        state
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .host_company = info["company_name"].as_str().map(str::to_string);
+    }
+    Ok(())
+}
+
+// Checks the host every few seconds so the connection indicator recovers on its own
+pub fn start_lan_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        if let Some(config) = client_config(&app) {
+            let _ = ping(&app, &config);
+        }
+        std::thread::sleep(PING_INTERVAL);
+    });
+}
+
+// ============================================================================
+// Client: Forwarding Commands
+// ============================================================================
+
+// Wraps the command handler so that, in client mode, every data command runs on the host.
+// Commands for the installation itself (see LOCAL_COMMANDS) stay local.
+pub fn route_commands<F>(handler: F) -> impl Fn(Invoke) -> bool + Send + Sync + 'static
+where
+    F: Fn(Invoke) -> bool + Send + Sync + 'static,
+{
+    move |invoke| match forward(invoke) {
+        Some(invoke) => handler(invoke),
+        None => true,
+    }
+}
+
+// Returns the invoke when it should run locally
+fn forward(invoke: Invoke) -> Option<Invoke> {
+    let command = invoke.message.command().to_string();
+    if LOCAL_COMMANDS.contains(&command.as_str()) {
+        return Some(invoke);
+    }
+    let app = invoke.message.webview().app_handle().clone();
+    let Some(config) = client_config(&app) else {
+        return Some(invoke);
+    };
+    let args = match invoke.message.payload() {
+        InvokeBody::Json(args) => args.clone(),
+        _ => return Some(invoke),
+    };
+
+    std::thread::spawn(move || {
+        let result = call_host(
+            &config,
+            "POST",
+            &format!("/api/rpc/{}", command),
+            Some(&args),
+        );
+        record_contact(&app, result.as_ref().map(|_| ()));
+        match result.map_err(HostError::message).and_then(|value| {
+            match FILE_COMMANDS.iter().find(|(name, _)| *name == command) {
+                Some((_, folder)) => save_host_file(&app, folder, value),
+                None => Ok(value),
+            }
+        }) {
+            Ok(value) => invoke.resolver.resolve(value),
+            Err(message) => invoke.resolver.reject(message),
+        }
+    });
+    None
+}
+
+fn save_host_file(app: &AppHandle, folder: &str, value: Value) -> Result<Value, String> {
+    let file_name = value["file_name"]
+        .as_str()
+        .and_then(|name| Path::new(name).file_name())
+        .ok_or("The host sent a file without a name")?;
+    let content = general_purpose::STANDARD
+        .decode(value["content"].as_str().unwrap_or_default())
+        .map_err(|_| "The host sent a damaged file".to_string())?;
+    let dir = company_data_dir(app).join(folder);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(file_name);
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Could not save {}: {}", path.display(), e))?;
+    Ok(Value::String(path.to_string_lossy().to_string()))
+}
+
+// ============================================================================
+// Host: Running Forwarded Commands
+// ============================================================================
+
+// Arguments arrive as the frontend sent them, with camelCase names. A missing argument is
+// null, which an Option argument accepts.
+fn arg<T: DeserializeOwned>(args: &Value, name: &str) -> Result<T, String> {
+    let mut key = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '_' => upper = true,
+            c if upper => {
+                key.extend(c.to_uppercase());
+                upper = false;
+            }
+            c => key.push(c),
+        }
+    }
+    serde_json::from_value(args.get(&key).cloned().unwrap_or(Value::Null))
+        .map_err(|e| format!("Invalid argument '{}': {}", key, e))
+}
+
+fn to_json<T: Serialize>(result: Result<T, String>) -> Result<Value, String> {
+    serde_json::to_value(result?).map_err(|e| e.to_string())
+}
+
+// Commands the host runs for its clients, with the names of their arguments
+macro_rules! routed_commands {
+    ($($module:ident::$name:ident($($arg:ident),*)),* $(,)?) => {
+        fn run_command(app: &AppHandle, command: &str, args: &Value) -> Result<Value, String> {
+            match command {
+                $(stringify!($name) => to_json(crate::$module::$name(
+                    app.clone(),
+                    $(arg(args, stringify!($arg))?),*
+                )),)*
+                _ => Err(format!("Unknown command '{}'", command)),
+            }
+        }
+    };
+}
+
+routed_commands! {
+    commands::create_item(item),
+    commands::get_items(query),
+    commands::search_items(query, filters, page, limit),
+    commands::update_item(item),
+    commands::delete_item(id),
+    commands::import_items(items),
+    commands::export_items(),
+    commands::get_item_components(kit_item_id),
+    commands::get_item_barcodes(item_id),
+    commands::lookup_item_by_barcode(barcode),
+    commands::create_item_category(category),
+    commands::get_item_categories(),
+    commands::update_item_category(category),
+    commands::delete_item_category(id),
+    commands::create_brand(brand),
+    commands::get_brands(query),
+    commands::update_brand(brand),
+    commands::delete_brand(id),
+    commands::create_model(model),
+    commands::get_models(query),
+    commands::get_models_by_brand(brand_id),
+    commands::update_model(model),
+    commands::delete_model(id),
+    commands::create_site(site),
+    commands::get_sites(query),
+    commands::update_site(site),
+    commands::delete_site(id),
+    commands::get_master_usage(entity, id),
+    commands::merge_masters(entity, source_id, target_id),
+    commands::create_attribute_definition(definition),
+    commands::get_attribute_definitions(entity),
+    commands::update_attribute_definition(definition),
+    commands::delete_attribute_definition(id),
+    commands::create_supplier(supplier),
+    commands::get_suppliers(),
+    commands::update_supplier(supplier),
+    commands::delete_supplier(id),
+    commands::create_project(project),
+    commands::get_projects(site_id),
+    commands::update_project(project),
+    commands::delete_project(id),
+    commands::create_purchase_order(order),
+    commands::get_purchase_orders(supplier_id, status, page, limit),
+    commands::get_purchase_order(id),
+    commands::update_purchase_order(order),
+    commands::cancel_purchase_order(id),
+    commands::delete_purchase_order(id),
+    commands::create_material_requisition(requisition),
+    commands::get_material_requisitions(site_id, status, page, limit),
+    commands::get_material_requisition(id),
+    commands::update_material_requisition(requisition),
+    commands::approve_material_requisition(id, approvals, approved_by),
+    commands::reject_material_requisition(id, reason, rejected_by),
+    commands::cancel_material_requisition(id),
+    commands::delete_material_requisition(id),
+    commands::get_inventory_transaction_types(),
+    commands::create_inventory_voucher(voucher),
+    commands::get_inventory_vouchers(page, limit, filters),
+    commands::get_inventory_voucher(id),
+    commands::update_inventory_voucher(voucher),
+    commands::delete_inventory_voucher(id),
+    commands::add_voucher_attachment(voucher_id, file_name, data, description),
+    commands::get_voucher_attachments(voucher_id),
+    commands::get_voucher_attachment_file(id),
+    commands::delete_voucher_attachment(id),
+    commands::get_stock_balance(site_id, item_id),
+    commands::get_stock_balances(item_name, site_id, category_id, item_attributes, page, limit),
+    commands::get_item_stock_by_sites(item_id),
+    commands::get_site_stock_balances(site_id),
+    commands::get_stock_movement_history(
+        item_id,
+        site_id,
+        voucher_type_id,
+        category_id,
+        item_attributes,
+        from_date,
+        to_date,
+        page,
+        limit
+    ),
+    commands::get_purchase_register(supplier_id, item_id, from_date, to_date, page, limit),
+    commands::get_purchase_summary(group_by, supplier_id, item_id, from_date, to_date),
+    commands::get_outstanding_purchase_orders(supplier_id, item_id, site_id),
+    commands::get_requisition_status_report(site_id, status, pending_only),
+    commands::get_project_consumption(group_by, project_id, site_id, item_id, from_date, to_date),
+    commands::get_category_stock_summary(site_id),
+    commands::get_category_consumption(project_id, site_id, from_date, to_date),
+    commands::get_dashboard_stats(),
+    commands::get_settings(),
+    commands::update_settings(settings),
+    commands::check_integrity(),
+    commands::rebuild_stock_movements(),
+    labels::generate_item_labels(request),
+    documents::generate_voucher_document(voucher_id),
+    importer::preview_item_import(file),
+    importer::import_items_file(request),
+}
+
+// Runs a command sent by a client against the open company
+pub fn dispatch(app: &AppHandle, command: &str, args: &Value) -> Result<Value, String> {
+    let value = run_command(app, command, args)?;
+    if !FILE_COMMANDS.iter().any(|(name, _)| *name == command) {
+        return Ok(value);
+    }
+    let path = PathBuf::from(value.as_str().unwrap_or_default());
+    let content = std::fs::read(&path).map_err(|e| e.to_string())?;
+    Ok(json!({
+        "file_name": path.file_name().map(|name| name.to_string_lossy()),
+        "content": general_purpose::STANDARD.encode(content),
+    }))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_lan_status(app: AppHandle) -> LanStatus {
+    status(&app)
+}
+
+// Checks the host and its token before switching this installation to client mode
+#[tauri::command]
+pub fn connect_to_host(
+    app: AppHandle,
+    host_address: String,
+    token: String,
+) -> Result<LanStatus, String> {
+    let config = LanConfig {
+        client_enabled: true,
+        host_address: Some(normalize_address(&host_address)?),
+        token: Some(token.trim().to_string()).filter(|t| !t.is_empty()),
+    };
+    if config.token.is_none() {
+        return Err("Enter the host's access token".to_string());
+    }
+    ping(&app, &config)?;
+    save_config(&app, &config)?;
+    app.state::<LanClient>()
+        .0
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .config = config;
+    Ok(status(&app))
+}
+
+// Back to this installation's own company databases
+#[tauri::command]
+pub fn disconnect_from_host(app: AppHandle) -> Result<LanStatus, String> {
+    let config = LanConfig {
+        client_enabled: false,
+        ..load_config(&app)
+    };
+    save_config(&app, &config)?;
+    *app.state::<LanClient>()
+        .0
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = ClientState {
+        config,
+        ..Default::default()
+    };
+    Ok(status(&app))
+}
@@ -1,3 +1,5 @@
+use tauri::Manager;
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -5,21 +7,38 @@ fn greet(name: &str) -> String {
 }
 
 mod activation;
+mod api_server;
+mod backup;
 mod commands;
+mod companies;
 mod db;
+mod documents;
+mod encryption;
+mod fonts;
+mod importer;
+mod labels;
+mod lan;
+mod sync;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
-            db::init_db(app.handle()).expect("failed to initialize database");
+            companies::init_companies(app.handle()).expect("failed to initialize database");
+            app.manage(backup::BackupScheduler::default());
+            backup::start_backup_scheduler(app.handle().clone());
+            app.manage(api_server::ApiServer::default());
+            api_server::restart(app.handle());
+            lan::init_lan_client(app.handle());
+            lan::start_lan_monitor(app.handle().clone());
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(lan::route_commands(tauri::generate_handler![
             greet,
             commands::create_item,
             commands::get_items,
+            commands::search_items,
             commands::update_item,
             commands::delete_item,
             commands::create_brand,
@@ -28,29 +47,107 @@ pub fn run() {
             commands::delete_brand,
             commands::create_model,
             commands::get_models,
+            commands::get_models_by_brand,
             commands::update_model,
             commands::delete_model,
             commands::create_site,
             commands::get_sites,
             commands::update_site,
             commands::delete_site,
+            commands::get_master_usage,
+            commands::merge_masters,
+            commands::create_attribute_definition,
+            commands::get_attribute_definitions,
+            commands::update_attribute_definition,
+            commands::delete_attribute_definition,
+            commands::create_supplier,
+            commands::get_suppliers,
+            commands::update_supplier,
+            commands::delete_supplier,
+            commands::create_project,
+            commands::get_projects,
+            commands::update_project,
+            commands::delete_project,
+            commands::create_purchase_order,
+            commands::get_purchase_orders,
+            commands::get_purchase_order,
+            commands::update_purchase_order,
+            commands::cancel_purchase_order,
+            commands::delete_purchase_order,
+            commands::create_material_requisition,
+            commands::get_material_requisitions,
+            commands::get_material_requisition,
+            commands::update_material_requisition,
+            commands::approve_material_requisition,
+            commands::reject_material_requisition,
+            commands::cancel_material_requisition,
+            commands::delete_material_requisition,
             commands::get_inventory_transaction_types,
             commands::create_inventory_voucher,
             commands::get_inventory_vouchers,
             commands::get_inventory_voucher,
             commands::update_inventory_voucher,
             commands::delete_inventory_voucher,
+            commands::add_voucher_attachment,
+            commands::get_voucher_attachments,
+            commands::get_voucher_attachment_file,
+            commands::delete_voucher_attachment,
             commands::get_stock_balance,
             commands::get_stock_balances,
             commands::get_item_stock_by_sites,
             commands::get_site_stock_balances,
             commands::get_stock_movement_history,
+            commands::get_purchase_register,
+            commands::get_purchase_summary,
+            commands::get_outstanding_purchase_orders,
+            commands::get_requisition_status_report,
+            commands::get_project_consumption,
+            commands::get_category_stock_summary,
+            commands::get_category_consumption,
             commands::get_dashboard_stats,
+            commands::get_settings,
+            commands::update_settings,
+            commands::check_integrity,
+            commands::rebuild_stock_movements,
             commands::import_items,
+            commands::export_items,
+            commands::get_item_components,
+            commands::get_item_barcodes,
+            commands::lookup_item_by_barcode,
+            commands::create_item_category,
+            commands::get_item_categories,
+            commands::update_item_category,
+            commands::delete_item_category,
+            companies::list_companies,
+            companies::get_active_company,
+            companies::create_company,
+            companies::open_company,
+            companies::rename_company,
+            companies::archive_company,
+            encryption::get_encryption_status,
+            encryption::unlock_database,
+            encryption::set_database_passphrase,
+            backup::backup_database,
+            backup::list_backups,
+            backup::get_backup_status,
+            backup::restore_backup,
+            api_server::get_api_server_status,
             activation::get_license_status,
             activation::activate_license,
-            activation::get_system_id
-        ])
+            activation::get_system_id,
+            labels::generate_item_labels,
+            documents::generate_voucher_document,
+            lan::get_lan_status,
+            lan::connect_to_host,
+            lan::disconnect_from_host,
+            sync::get_sync_status,
+            sync::set_sync_node_name,
+            sync::export_sync_package,
+            sync::import_sync_package,
+            sync::resolve_sync_conflict,
+            importer::preview_item_import,
+            importer::import_items_file
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,1548 @@
+use crate::db::{
+    self, company_data_dir, get_db_conn, validation_error, AttributeValues, InventoryVoucher,
+    InventoryVoucherItem,
+};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Local;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OptionalExtension, Result, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+// Packages are written to the company folder and carried to the other node by hand
+const SYNC_DIR: &str = "sync";
+const PACKAGE_FORMAT: u32 = 1;
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const VOUCHERS: &str = "inventory_vouchers";
+const VOUCHER_LINES: &str = "inventory_voucher_items";
+
+// A master table as it travels between nodes. Reference columns carry the uuid of the
+// referenced row instead of its id, which differs from node to node.
+struct MasterTable {
+    table: &'static str,
+    // Identifies the same record on a node that has not seen its uuid yet
+    natural_key: &'static [&'static str],
+    columns: &'static [&'static str],
+    references: &'static [(&'static str, &'static str)],
+}
+
+// In dependency order; categories may also refer to each other. Left out on purpose: kit
+// components and barcodes, which each node sets up for its own scanning and kit building (kit
+// lines travel already expanded), attribute definitions and values, which each node defines for
+// itself, and purchase orders and requisitions, which are raised and received at one node.
+static MASTER_TABLES: [MasterTable; 7] = [
+    MasterTable {
+        table: "item_categories",
+        natural_key: &["name", "parent_id"],
+        columns: &["name", "parent_id"],
+        references: &[("parent_id", "item_categories")],
+    },
+    MasterTable {
+        table: "brands",
+        natural_key: &["name"],
+        columns: &["name", "is_active"],
+        references: &[],
+    },
+    MasterTable {
+        table: "models",
+        natural_key: &["name"],
+        columns: &["name", "brand_id", "is_active"],
+        references: &[("brand_id", "brands")],
+    },
+    MasterTable {
+        table: "sites",
+        natural_key: &["code"],
+        columns: &["code", "name", "address", "type", "is_active"],
+        references: &[],
+    },
+    MasterTable {
+        table: "suppliers",
+        natural_key: &["code"],
+        columns: &[
+            "code",
+            "name",
+            "gstin",
+            "contact_person",
+            "phone",
+            "email",
+            "address",
+            "is_active",
+        ],
+        references: &[],
+    },
+    MasterTable {
+        table: "projects",
+        natural_key: &["code"],
+        columns: &[
+            "code",
+            "name",
+            "site_id",
+            "start_date",
+            "end_date",
+            "status",
+            "description",
+        ],
+        references: &[("site_id", "sites")],
+    },
+    MasterTable {
+        table: "items",
+        natural_key: &["code"],
+        columns: &[
+            "code",
+            "name",
+            "brand_id",
+            "model_id",
+            "category_id",
+            "aliases",
+            "is_active",
+        ],
+        references: &[
+            ("brand_id", "brands"),
+            ("model_id", "models"),
+            ("category_id", "item_categories"),
+        ],
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SyncRecord {
+    uuid: String,
+    values: Map<String, Value>,
+}
+
+// Purchase orders and requisitions are not synced, so vouchers travel without those links. A return refers to its original voucher and the lines it returns by uuid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SyncVoucher {
+    uuid: String,
+    transaction_number: Option<String>,
+    voucher_type: String,
+    voucher_date: String,
+    source_site: Option<String>,
+    destination_site: Option<String>,
+    supplier: Option<String>,
+    project: Option<String>,
+    activity_code: Option<String>,
+    original_voucher: Option<String>,
+    bill_number: Option<String>,
+    bill_date: Option<String>,
+    challan_number: Option<String>,
+    vehicle_number: Option<String>,
+    driver_name: Option<String>,
+    receiver_name: Option<String>,
+    remarks: Option<String>,
+    attributes: AttributeValues,
+    items: Vec<SyncVoucherLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SyncVoucherLine {
+    uuid: String,
+    item: String,
+    quantity: f64,
+    kit_item: Option<String>,
+    original_line: Option<String>,
+    remarks: Option<String>,
+}
+
+// Changes from since_seq (exclusive) to up_to_seq of the sending node's change log.
+// acknowledged_seq is how far the sender has imported the target's own changes.
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncPackage {
+    format: u32,
+    node_id: String,
+    node_name: String,
+    target_node_id: Option<String>,
+    created_at: String,
+    since_seq: i64,
+    up_to_seq: i64,
+    acknowledged_seq: i64,
+    masters: BTreeMap<String, Vec<SyncRecord>>,
+    vouchers: Vec<SyncVoucher>,
+    deleted_vouchers: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncPeer {
+    pub node_id: String,
+    pub node_name: String,
+    pub last_exported_at: Option<String>,
+    pub last_imported_at: Option<String>,
+    pub pending_changes: i64, // Records changed since the last package sent to this node
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub id: i64,
+    pub entity: String,
+    pub entity_uuid: String,
+    pub node_id: String,
+    pub node_name: Option<String>,
+    pub message: String,
+    pub local_values: Option<Value>,
+    pub remote_values: Option<Value>,
+    pub detected_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub node_id: String,
+    pub node_name: String,
+    pub packages_dir: String,
+    pub peers: Vec<SyncPeer>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncExportInfo {
+    pub file_name: String,
+    pub path: String,
+    pub masters: usize,
+    pub vouchers: usize,
+    pub deleted_vouchers: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncImportReport {
+    pub node_name: String,
+    pub masters_added: usize,
+    pub masters_updated: usize,
+    pub vouchers_added: usize,
+    pub vouchers_updated: usize,
+    pub vouchers_deleted: usize,
+    pub conflicts: usize,
+    pub warnings: Vec<String>,
+}
+
+// What happened to one incoming record
+enum Applied {
+    Added,
+    Updated,
+    Unchanged,
+    Conflict,
+    // Refers to a record that has not been applied yet
+    Pending(String),
+}
+
+fn sync_dir(app: &AppHandle) -> PathBuf {
+    company_data_dir(app).join(SYNC_DIR)
+}
+
+fn node_id(conn: &Connection) -> Result<String> {
+    conn.query_row(
+        "SELECT value FROM system_metadata WHERE key = 'node_id'",
+        [],
+        |row| row.get(0),
+    )
+}
+
+fn node_name(conn: &Connection, node_id: &str) -> Result<String> {
+    let name: Option<String> = conn
+        .query_row(
+            "SELECT value FROM system_metadata WHERE key = 'node_name'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(name.unwrap_or_else(|| format!("Node {}", &node_id[..6])))
+}
+
+fn latest_seq(conn: &Connection) -> Result<i64> {
+    conn.query_row("SELECT COALESCE(MAX(seq), 0) FROM change_log", [], |row| {
+        row.get(0)
+    })
+}
+
+fn master_table(table: &str) -> Option<&'static MasterTable> {
+    MASTER_TABLES.iter().find(|spec| spec.table == table)
+}
+
+fn uuid_of(conn: &Connection, table: &str, id: Option<i64>) -> Result<Option<String>> {
+    let Some(id) = id else {
+        return Ok(None);
+    };
+    conn.query_row(
+        &format!("SELECT uuid FROM {} WHERE id = ?1", table),
+        params![id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn id_of(conn: &Connection, table: &str, uuid: &str) -> Result<Option<i64>> {
+    conn.query_row(
+        &format!("SELECT id FROM {} WHERE uuid = ?1", table),
+        params![uuid],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+// Like id_of, but a missing row means the record has to wait for it
+fn required_id(conn: &Connection, table: &str, uuid: Option<&str>) -> Result<Option<i64>, Applied> {
+    let Some(uuid) = uuid else {
+        return Ok(None);
+    };
+    match id_of(conn, table, uuid) {
+        Ok(Some(id)) => Ok(Some(id)),
+        Ok(None) => Err(Applied::Pending(format!(
+            "Refers to a record in {} that this database does not have",
+            table
+        ))),
+        Err(e) => Err(Applied::Pending(e.to_string())),
+    }
+}
+
+fn to_json(value: SqlValue) -> Value {
+    match value {
+        SqlValue::Null | SqlValue::Blob(_) => Value::Null,
+        SqlValue::Integer(i) => i.into(),
+        SqlValue::Real(f) => f.into(),
+        SqlValue::Text(s) => s.into(),
+    }
+}
+
+fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+// ==========================================
+// Export
+// ==========================================
+
+fn export_master(conn: &Connection, spec: &MasterTable, id: i64) -> Result<SyncRecord> {
+    let (uuid, raw): (String, Vec<SqlValue>) = conn.query_row(
+        &format!(
+            "SELECT uuid, {} FROM {} WHERE id = ?1",
+            spec.columns.join(", "),
+            spec.table
+        ),
+        params![id],
+        |row| {
+            let values = (0..spec.columns.len())
+                .map(|i| row.get(i + 1))
+                .collect::<Result<_>>()?;
+            Ok((row.get(0)?, values))
+        },
+    )?;
+
+    let mut values = Map::new();
+    for (column, value) in spec.columns.iter().zip(raw) {
+        let value = match (spec.references.iter().find(|r| r.0 == *column), value) {
+            (Some((_, table)), SqlValue::Integer(id)) => {
+                uuid_of(conn, table, Some(id))?.map_or(Value::Null, Value::from)
+            }
+            (_, value) => to_json(value),
+        };
+        values.insert(column.to_string(), value);
+    }
+    Ok(SyncRecord { uuid, values })
+}
+
+fn export_voucher(conn: &Connection, id: i64) -> Result<SyncVoucher> {
+    let voucher = db::load_inventory_voucher(conn, id)?;
+    let voucher_type: String = conn.query_row(
+        "SELECT name FROM inventory_transaction_types WHERE id = ?1",
+        params![voucher.voucher_type_id],
+        |row| row.get(0),
+    )?;
+    let mut items = Vec::with_capacity(voucher.items.len());
+    for line in &voucher.items {
+        items.push(SyncVoucherLine {
+            uuid: line.uuid.clone().unwrap_or_default(),
+            item: uuid_of(conn, "items", Some(line.item_id))?.unwrap_or_default(),
+            quantity: line.quantity,
+            kit_item: uuid_of(conn, "items", line.kit_item_id)?,
+            original_line: uuid_of(conn, VOUCHER_LINES, line.original_voucher_item_id)?,
+            remarks: line.remarks.clone(),
+        });
+    }
+
+    Ok(SyncVoucher {
+        uuid: voucher.uuid.unwrap_or_default(),
+        transaction_number: voucher.transaction_number,
+        voucher_type,
+        voucher_date: voucher.voucher_date,
+        source_site: uuid_of(conn, "sites", voucher.source_site_id)?,
+        destination_site: uuid_of(conn, "sites", voucher.destination_site_id)?,
+        supplier: uuid_of(conn, "suppliers", voucher.supplier_id)?,
+        project: uuid_of(conn, "projects", voucher.project_id)?,
+        activity_code: voucher.activity_code,
+        original_voucher: uuid_of(conn, VOUCHERS, voucher.original_voucher_id)?,
+        bill_number: voucher.bill_number,
+        bill_date: voucher.bill_date,
+        challan_number: voucher.challan_number,
+        vehicle_number: voucher.vehicle_number,
+        driver_name: voucher.driver_name,
+        receiver_name: voucher.receiver_name,
+        remarks: voucher.remarks,
+        attributes: voucher.attributes.unwrap_or_default(),
+        items,
+    })
+}
+
+// A known node a package is exported for
+struct Target {
+    node_id: String,
+    last_exported_seq: i64,
+    last_imported_seq: i64,
+}
+
+// Everything for a node seen for the first time, otherwise what changed since the last package
+// sent to it. Changes that came from the target node itself are left out. Deleted masters are
+// not sent; merging and deleting masters is done on each node.
+fn build_package(conn: &Connection, target: Option<&Target>) -> Result<SyncPackage> {
+    let own_node_id = node_id(conn)?;
+    let since_seq = target.map_or(0, |t| t.last_exported_seq);
+    let up_to_seq = latest_seq(conn)?;
+
+    // Entity table and uuid of every record to send, in the order the changes were made
+    let mut changed: Vec<(String, String)> = vec![];
+    if since_seq == 0 {
+        for table in db::SYNCED_TABLES {
+            let mut stmt = conn.prepare(&format!("SELECT uuid FROM {} ORDER BY id", table))?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            for uuid in rows {
+                changed.push((table.to_string(), uuid?));
+            }
+        }
+    } else {
+        let mut stmt = conn.prepare(
+            "SELECT entity, entity_uuid FROM change_log
+             WHERE seq > ?1 AND seq <= ?2 AND node_id != ?3
+             GROUP BY entity, entity_uuid
+             ORDER BY MAX(seq)",
+        )?;
+        let target_node_id = target.map(|t| t.node_id.as_str()).unwrap_or_default();
+        let rows = stmt.query_map(params![since_seq, up_to_seq, target_node_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        for row in rows {
+            changed.push(row?);
+        }
+    }
+
+    let mut masters: BTreeMap<String, Vec<SyncRecord>> = BTreeMap::new();
+    let mut vouchers = vec![];
+    let mut deleted_vouchers = vec![];
+    for (table, uuid) in changed {
+        let id = id_of(conn, &table, &uuid)?;
+        if table == VOUCHERS {
+            match id {
+                Some(id) => vouchers.push(export_voucher(conn, id)?),
+                None => deleted_vouchers.push(uuid),
+            }
+        } else if let (Some(spec), Some(id)) = (master_table(&table), id) {
+            masters
+                .entry(table)
+                .or_default()
+                .push(export_master(conn, spec, id)?);
+        }
+    }
+
+    // Vouchers go in the order they were created so returns follow their originals
+    vouchers.sort_by_key(|v| id_of(conn, VOUCHERS, &v.uuid).ok().flatten());
+
+    Ok(SyncPackage {
+        format: PACKAGE_FORMAT,
+        node_name: node_name(conn, &own_node_id)?,
+        node_id: own_node_id,
+        target_node_id: target.map(|t| t.node_id.clone()),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        since_seq,
+        up_to_seq,
+        acknowledged_seq: target.map_or(0, |t| t.last_imported_seq),
+        masters,
+        vouchers,
+        deleted_vouchers,
+    })
+}
+
+fn load_target(conn: &Connection, node_id: &str) -> Result<Option<Target>> {
+    conn.query_row(
+        "SELECT node_id, last_exported_seq, last_imported_seq FROM sync_peers WHERE node_id = ?1",
+        params![node_id],
+        |row| {
+            Ok(Target {
+                node_id: row.get(0)?,
+                last_exported_seq: row.get(1)?,
+                last_imported_seq: row.get(2)?,
+            })
+        },
+    )
+    .optional()
+}
+
+fn mark_exported(conn: &Connection, node_id: &str, up_to_seq: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE sync_peers SET last_exported_seq = ?1, last_exported_at = datetime('now', 'localtime')
+         WHERE node_id = ?2",
+        params![up_to_seq, node_id],
+    )?;
+    Ok(())
+}
+
+fn export_package(
+    app: &AppHandle,
+    target_node_id: Option<String>,
+) -> Result<SyncExportInfo, String> {
+    let conn = get_db_conn(app).map_err(|e| e.to_string())?;
+    let target = match target_node_id {
+        Some(node_id) => Some(
+            load_target(&conn, &node_id)
+                .map_err(|e| e.to_string())?
+                .ok_or("Unknown node. Import a package from it first.")?,
+        ),
+        None => None,
+    };
+    let package = build_package(&conn, target.as_ref()).map_err(|e| e.to_string())?;
+
+    let dir = sync_dir(app);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let file_name = format!(
+        "sync-{}-{}.json.gz",
+        &package.node_id[..6],
+        Local::now().format(TIMESTAMP_FORMAT)
+    );
+    let path = dir.join(&file_name);
+    let json = serde_json::to_vec(&package).map_err(|e| e.to_string())?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&json)
+        .and_then(|_| encoder.finish())
+        .and_then(|bytes| std::fs::write(&path, bytes))
+        .map_err(|e| format!("Failed to write sync package: {}", e))?;
+
+    // Only advance once the file exists; a lost package is resent with the next one
+    if let Some(target) = &target {
+        mark_exported(&conn, &target.node_id, package.up_to_seq).map_err(|e| e.to_string())?;
+    }
+
+    Ok(SyncExportInfo {
+        file_name,
+        path: path.to_string_lossy().to_string(),
+        masters: package.masters.values().map(Vec::len).sum(),
+        vouchers: package.vouchers.len(),
+        deleted_vouchers: package.deleted_vouchers.len(),
+    })
+}
+
+// ==========================================
+// Import
+// ==========================================
+
+// The peer importing from, as far as conflict detection is concerned
+struct Peer {
+    node_id: String,
+    last_exported_seq: i64,
+}
+
+// A local record conflicts when it changed here since the last package sent to the peer
+fn changed_locally(tx: &Transaction, entity: &str, uuid: &str, peer: &Peer) -> Result<bool> {
+    tx.query_row(
+        "SELECT EXISTS (SELECT 1 FROM change_log
+         WHERE entity = ?1 AND entity_uuid = ?2 AND seq > ?3 AND node_id != ?4)",
+        params![entity, uuid, peer.last_exported_seq, peer.node_id],
+        |row| row.get(0),
+    )
+}
+
+// Only the latest open conflict of a record is kept
+fn record_conflict(
+    tx: &Transaction,
+    entity: &str,
+    uuid: &str,
+    peer: &Peer,
+    message: &str,
+    local_values: Option<String>,
+    remote_values: Option<String>,
+) -> Result<()> {
+    tx.execute(
+        "DELETE FROM sync_conflicts WHERE entity = ?1 AND entity_uuid = ?2 AND resolved_at IS NULL",
+        params![entity, uuid],
+    )?;
+    tx.execute(
+        "INSERT INTO sync_conflicts (entity, entity_uuid, node_id, message, local_values, remote_values)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![entity, uuid, peer.node_id, message, local_values, remote_values],
+    )?;
+    Ok(())
+}
+
+// Both nodes hold the same version again, so earlier conflicts of the record are over
+fn settle_conflicts(tx: &Transaction, entity: &str, uuid: &str) -> Result<()> {
+    tx.execute(
+        "UPDATE sync_conflicts SET resolved_at = datetime('now', 'localtime')
+         WHERE entity = ?1 AND entity_uuid = ?2 AND resolved_at IS NULL",
+        params![entity, uuid],
+    )?;
+    Ok(())
+}
+
+// Runs one record's changes so a failure only undoes that record
+fn in_savepoint<T>(tx: &Transaction, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    tx.execute_batch("SAVEPOINT sync_record")?;
+    match f() {
+        Ok(value) => {
+            tx.execute_batch("RELEASE sync_record")?;
+            Ok(value)
+        }
+        Err(e) => {
+            tx.execute_batch("ROLLBACK TO sync_record; RELEASE sync_record")?;
+            Err(e)
+        }
+    }
+}
+
+fn apply_master(
+    tx: &Transaction,
+    spec: &MasterTable,
+    record: &SyncRecord,
+    peer: &Peer,
+    force: bool,
+) -> Result<Applied> {
+    let mut values = Vec::with_capacity(spec.columns.len());
+    for column in spec.columns {
+        let value = record.values.get(*column).unwrap_or(&Value::Null);
+        let value = match spec.references.iter().find(|r| r.0 == *column) {
+            Some((_, table)) => match required_id(tx, table, value.as_str()) {
+                Ok(id) => id.map_or(SqlValue::Null, SqlValue::Integer),
+                Err(pending) => return Ok(pending),
+            },
+            None => to_sql(value),
+        };
+        values.push(value);
+    }
+
+    let mut local_id = id_of(tx, spec.table, &record.uuid)?;
+    if local_id.is_none() {
+        let key_filter = (1..=spec.natural_key.len())
+            .map(|i| format!("{} IS ?{}", spec.natural_key[i - 1], i))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let key_values = spec.natural_key.iter().map(|column| {
+            let index = spec.columns.iter().position(|c| c == column).unwrap_or(0);
+            &values[index]
+        });
+        local_id = tx
+            .query_row(
+                &format!("SELECT id FROM {} WHERE {}", spec.table, key_filter),
+                rusqlite::params_from_iter(key_values),
+                |row| row.get(0),
+            )
+            .optional()?;
+    }
+
+    let assignments = spec
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("{} = ?{}", column, i + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let uuid_param = spec.columns.len() + 1;
+
+    let Some(local_id) = local_id else {
+        let placeholders = (1..=uuid_param)
+            .map(|i| format!("?{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        values.push(SqlValue::Text(record.uuid.clone()));
+        tx.execute(
+            &format!(
+                "INSERT INTO {} ({}, uuid) VALUES ({})",
+                spec.table,
+                spec.columns.join(", "),
+                placeholders
+            ),
+            rusqlite::params_from_iter(&values),
+        )?;
+        return Ok(Applied::Added);
+    };
+
+    let local = export_master(tx, spec, local_id)?;
+    // A record created on both nodes is known by the incoming uuid from now on
+    if local.uuid != record.uuid {
+        tx.execute(
+            &format!("UPDATE {} SET uuid = ?1 WHERE id = ?2", spec.table),
+            params![record.uuid, local_id],
+        )?;
+    }
+    if local.values == record.values {
+        return Ok(Applied::Unchanged);
+    }
+
+    if !force && changed_locally(tx, spec.table, &local.uuid, peer)? {
+        record_conflict(
+            tx,
+            spec.table,
+            &record.uuid,
+            peer,
+            "Changed on both nodes; this node's version was kept",
+            serde_json::to_string(&local.values).ok(),
+            serde_json::to_string(&record.values).ok(),
+        )?;
+        return Ok(Applied::Conflict);
+    }
+
+    values.push(SqlValue::Integer(local_id));
+    tx.execute(
+        &format!(
+            "UPDATE {} SET {} WHERE id = ?{}",
+            spec.table, assignments, uuid_param
+        ),
+        rusqlite::params_from_iter(&values),
+    )?;
+    Ok(Applied::Updated)
+}
+
+fn voucher_attribute_names(conn: &Connection) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT lower(name) FROM attribute_definitions WHERE entity = 'voucher' AND is_active = 1",
+    )?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+fn voucher_from_sync(
+    tx: &Transaction,
+    incoming: &SyncVoucher,
+) -> Result<InventoryVoucher, Applied> {
+    let voucher_type_id: i64 = tx
+        .query_row(
+            "SELECT id FROM inventory_transaction_types WHERE name = ?1",
+            params![incoming.voucher_type],
+            |row| row.get(0),
+        )
+        .map_err(|_| {
+            Applied::Pending(format!("Unknown voucher type '{}'", incoming.voucher_type))
+        })?;
+    let original_voucher_id = required_id(tx, VOUCHERS, incoming.original_voucher.as_deref())?;
+
+    // Attributes that are not defined on this node are dropped
+    let known_attributes =
+        voucher_attribute_names(tx).map_err(|e| Applied::Pending(e.to_string()))?;
+    let attributes = incoming
+        .attributes
+        .iter()
+        .filter(|(name, _)| known_attributes.contains(&name.to_lowercase()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    let mut items = Vec::with_capacity(incoming.items.len());
+    for line in &incoming.items {
+        items.push(InventoryVoucherItem {
+            id: None,
+            uuid: Some(line.uuid.clone()),
+            inventory_voucher_id: None,
+            item_id: required_id(tx, "items", Some(&line.item))?.unwrap_or_default(),
+            quantity: line.quantity,
+            purchase_order_item_id: None,
+            requisition_item_id: None,
+            original_voucher_item_id: required_id(
+                tx,
+                VOUCHER_LINES,
+                line.original_line.as_deref(),
+            )?,
+            kit_item_id: required_id(tx, "items", line.kit_item.as_deref())?,
+            expand_kit: Some(false),
+            remarks: line.remarks.clone(),
+            returned_quantity: None,
+            net_quantity: None,
+        });
+    }
+
+    Ok(InventoryVoucher {
+        id: None,
+        uuid: Some(incoming.uuid.clone()),
+        transaction_number: incoming.transaction_number.clone(),
+        voucher_date: incoming.voucher_date.clone(),
+        source_site_id: required_id(tx, "sites", incoming.source_site.as_deref())?,
+        destination_site_id: required_id(tx, "sites", incoming.destination_site.as_deref())?,
+        voucher_type_id,
+        supplier_id: required_id(tx, "suppliers", incoming.supplier.as_deref())?,
+        bill_number: incoming.bill_number.clone(),
+        bill_date: incoming.bill_date.clone(),
+        purchase_order_id: None,
+        requisition_id: None,
+        original_voucher_id,
+        project_id: required_id(tx, "projects", incoming.project.as_deref())?,
+        activity_code: incoming.activity_code.clone(),
+        challan_number: incoming.challan_number.clone(),
+        vehicle_number: incoming.vehicle_number.clone(),
+        driver_name: incoming.driver_name.clone(),
+        receiver_name: incoming.receiver_name.clone(),
+        attributes: Some(attributes),
+        items,
+        remarks: incoming.remarks.clone(),
+        created_at: None,
+        created_by: None,
+    })
+}
+
+fn apply_voucher(
+    tx: &Transaction,
+    incoming: &SyncVoucher,
+    peer: &Peer,
+    force: bool,
+) -> Result<Applied> {
+    let mut voucher = match voucher_from_sync(tx, incoming) {
+        Ok(voucher) => voucher,
+        Err(not_ready) => return Ok(not_ready),
+    };
+
+    let Some(local_id) = id_of(tx, VOUCHERS, &incoming.uuid)? else {
+        let voucher_id = in_savepoint(tx, || db::insert_inventory_voucher(tx, voucher))?;
+        // Keep the sending node's number unless this node already used it
+        tx.execute(
+            "UPDATE inventory_vouchers SET transaction_number = ?1
+             WHERE id = ?2 AND ?1 IS NOT NULL
+               AND NOT EXISTS (SELECT 1 FROM inventory_vouchers WHERE transaction_number = ?1)",
+            params![incoming.transaction_number, voucher_id],
+        )?;
+        return Ok(Applied::Added);
+    };
+
+    // The number may differ when the sending node's number was already taken here
+    let local = export_voucher(tx, local_id)?;
+    let renumbered = SyncVoucher {
+        transaction_number: incoming.transaction_number.clone(),
+        ..local.clone()
+    };
+    if renumbered == *incoming {
+        return Ok(Applied::Unchanged);
+    }
+    if !force && changed_locally(tx, VOUCHERS, &incoming.uuid, peer)? {
+        record_conflict(
+            tx,
+            VOUCHERS,
+            &incoming.uuid,
+            peer,
+            "Changed on both nodes; this node's version was kept",
+            serde_json::to_string(&local).ok(),
+            serde_json::to_string(incoming).ok(),
+        )?;
+        return Ok(Applied::Conflict);
+    }
+
+    // Links that only exist on this node stay with the voucher
+    let existing = db::load_inventory_voucher(tx, local_id)?;
+    voucher.id = Some(local_id);
+    voucher.purchase_order_id = existing.purchase_order_id;
+    voucher.requisition_id = existing.requisition_id;
+    for line in &mut voucher.items {
+        let Some(existing_line) = existing.items.iter().find(|e| e.uuid == line.uuid) else {
+            continue;
+        };
+        if line.item_id == existing_line.item_id {
+            line.id = existing_line.id;
+            line.purchase_order_item_id = existing_line.purchase_order_item_id;
+            line.requisition_item_id = existing_line.requisition_item_id;
+        }
+    }
+    in_savepoint(tx, || db::replace_inventory_voucher(tx, voucher))?;
+    Ok(Applied::Updated)
+}
+
+fn remove_voucher(tx: &Transaction, uuid: &str, peer: &Peer) -> Result<Option<i64>> {
+    let Some(local_id) = id_of(tx, VOUCHERS, uuid)? else {
+        return Ok(None);
+    };
+    if changed_locally(tx, VOUCHERS, uuid, peer)? {
+        record_conflict(
+            tx,
+            VOUCHERS,
+            uuid,
+            peer,
+            "Deleted on the other node but changed here; this node's version was kept",
+            export_voucher(tx, local_id)
+                .ok()
+                .and_then(|v| serde_json::to_string(&v).ok()),
+            None,
+        )?;
+        return Ok(None);
+    }
+    in_savepoint(tx, || db::remove_inventory_voucher(tx, local_id))?;
+    Ok(Some(local_id))
+}
+
+fn read_package(data: &str) -> Result<SyncPackage, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|_| "Sync package data is not valid base64".to_string())?;
+    let mut json = vec![];
+    GzDecoder::new(bytes.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|_| "This file is not a sync package".to_string())?;
+    let package: SyncPackage =
+        serde_json::from_slice(&json).map_err(|_| "This file is not a sync package".to_string())?;
+    if package.format > PACKAGE_FORMAT {
+        return Err("This sync package was made by a newer version of Stock Star".to_string());
+    }
+    Ok(package)
+}
+
+fn import_package(app: &AppHandle, data: &str) -> Result<SyncImportReport, String> {
+    let package = read_package(data)?;
+    let mut conn = get_db_conn(app).map_err(|e| e.to_string())?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let mut removed = vec![];
+    let report = apply_package(&tx, &package, &mut removed).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    for voucher_id in removed {
+        let _ = std::fs::remove_dir_all(db::attachments_dir(app, voucher_id));
+    }
+    Ok(report)
+}
+
+fn apply_package(
+    tx: &Transaction,
+    package: &SyncPackage,
+    removed: &mut Vec<i64>,
+) -> Result<SyncImportReport> {
+    let own_node_id = node_id(tx)?;
+    if package.node_id == own_node_id {
+        return Err(validation_error(
+            "This package was exported from this database or a copy of it",
+        ));
+    }
+    if package
+        .target_node_id
+        .as_ref()
+        .is_some_and(|target| *target != own_node_id)
+    {
+        return Err(validation_error(
+            "This package was exported for another node",
+        ));
+    }
+
+    tx.execute(
+        "INSERT INTO sync_peers (node_id, node_name) VALUES (?1, ?2)
+         ON CONFLICT(node_id) DO UPDATE SET node_name = excluded.node_name",
+        params![package.node_id, package.node_name],
+    )?;
+    let (last_exported_seq, last_imported_seq): (i64, i64) = tx.query_row(
+        "SELECT last_exported_seq, last_imported_seq FROM sync_peers WHERE node_id = ?1",
+        params![package.node_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    if package.up_to_seq <= last_imported_seq {
+        return Err(validation_error("This package has already been imported"));
+    }
+
+    // Whatever the sender has already imported from here is no longer in question
+    let last_exported_seq = last_exported_seq.max(package.acknowledged_seq);
+    let peer = Peer {
+        node_id: package.node_id.clone(),
+        last_exported_seq,
+    };
+    let mut report = SyncImportReport {
+        node_name: package.node_name.clone(),
+        ..Default::default()
+    };
+    if package.since_seq > last_imported_seq {
+        report.warnings.push(format!(
+            "An earlier package from {} was not imported; some records may be missing",
+            package.node_name
+        ));
+    }
+    let seq_before = latest_seq(tx)?;
+
+    // Records whose references arrive later in the package are retried until nothing moves
+    for spec in &MASTER_TABLES {
+        let mut pending: Vec<&SyncRecord> = package
+            .masters
+            .get(spec.table)
+            .map(|records| records.iter().collect())
+            .unwrap_or_default();
+        loop {
+            let mut waiting = vec![];
+            let mut reasons = vec![];
+            for record in pending.iter().copied() {
+                let result = in_savepoint(tx, || apply_master(tx, spec, record, &peer, false));
+                if let Ok(Applied::Added | Applied::Updated | Applied::Unchanged) = result {
+                    settle_conflicts(tx, spec.table, &record.uuid)?;
+                }
+                match result {
+                    Ok(Applied::Added) => report.masters_added += 1,
+                    Ok(Applied::Updated) => report.masters_updated += 1,
+                    Ok(Applied::Unchanged) => {}
+                    Ok(Applied::Conflict) => report.conflicts += 1,
+                    Ok(Applied::Pending(reason)) => {
+                        waiting.push(record);
+                        reasons.push(reason);
+                    }
+                    Err(e) => {
+                        let remote = serde_json::to_string(&record.values).ok();
+                        record_conflict(
+                            tx,
+                            spec.table,
+                            &record.uuid,
+                            &peer,
+                            &e.to_string(),
+                            None,
+                            remote,
+                        )?;
+                        report.conflicts += 1;
+                    }
+                }
+            }
+            if waiting.is_empty() || waiting.len() == pending.len() {
+                for (record, reason) in waiting.into_iter().zip(reasons) {
+                    let remote = serde_json::to_string(&record.values).ok();
+                    record_conflict(tx, spec.table, &record.uuid, &peer, &reason, None, remote)?;
+                    report.conflicts += 1;
+                }
+                break;
+            }
+            pending = waiting;
+        }
+    }
+
+    for voucher in &package.vouchers {
+        let result = apply_voucher(tx, voucher, &peer, false);
+        if let Ok(Applied::Added | Applied::Updated | Applied::Unchanged) = result {
+            settle_conflicts(tx, VOUCHERS, &voucher.uuid)?;
+        }
+        match result {
+            Ok(Applied::Added) => report.vouchers_added += 1,
+            Ok(Applied::Updated) => report.vouchers_updated += 1,
+            Ok(Applied::Unchanged) => {}
+            Ok(Applied::Conflict) => report.conflicts += 1,
+            Ok(Applied::Pending(reason)) => {
+                let remote = serde_json::to_string(voucher).ok();
+                record_conflict(tx, VOUCHERS, &voucher.uuid, &peer, &reason, None, remote)?;
+                report.conflicts += 1;
+            }
+            Err(e) => {
+                let remote = serde_json::to_string(voucher).ok();
+                record_conflict(
+                    tx,
+                    VOUCHERS,
+                    &voucher.uuid,
+                    &peer,
+                    &e.to_string(),
+                    None,
+                    remote,
+                )?;
+                report.conflicts += 1;
+            }
+        }
+    }
+
+    for uuid in &package.deleted_vouchers {
+        match remove_voucher(tx, uuid, &peer) {
+            Ok(Some(voucher_id)) => {
+                removed.push(voucher_id);
+                report.vouchers_deleted += 1;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                record_conflict(tx, VOUCHERS, uuid, &peer, &e.to_string(), None, None)?;
+                report.conflicts += 1;
+            }
+        }
+    }
+
+    // What was applied came from the sending node and is not sent back to it
+    tx.execute(
+        "UPDATE change_log SET node_id = ?1 WHERE seq > ?2",
+        params![package.node_id, seq_before],
+    )?;
+    tx.execute(
+        "UPDATE sync_peers SET last_imported_seq = ?1, last_exported_seq = ?2,
+            last_imported_at = datetime('now', 'localtime')
+         WHERE node_id = ?3",
+        params![package.up_to_seq, last_exported_seq, package.node_id],
+    )?;
+    Ok(report)
+}
+
+// ==========================================
+// Status and conflicts
+// ==========================================
+
+fn load_status(app: &AppHandle) -> Result<SyncStatus> {
+    let conn = get_db_conn(app)?;
+    let node_id = node_id(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.node_id, p.node_name, p.last_exported_at, p.last_imported_at,
+            (SELECT COUNT(DISTINCT c.entity || c.entity_uuid) FROM change_log c
+             WHERE c.seq > p.last_exported_seq AND c.node_id != p.node_id)
+         FROM sync_peers p
+         ORDER BY p.node_name",
+    )?;
+    let peers = stmt
+        .query_map([], |row| {
+            Ok(SyncPeer {
+                node_id: row.get(0)?,
+                node_name: row.get(1)?,
+                last_exported_at: row.get(2)?,
+                last_imported_at: row.get(3)?,
+                pending_changes: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.entity, c.entity_uuid, c.node_id, p.node_name, c.message,
+            c.local_values, c.remote_values, c.detected_at
+         FROM sync_conflicts c
+         LEFT JOIN sync_peers p ON p.node_id = c.node_id
+         WHERE c.resolved_at IS NULL
+         ORDER BY c.id",
+    )?;
+    let conflicts = stmt
+        .query_map([], |row| {
+            let parse = |text: Option<String>| text.and_then(|t| serde_json::from_str(&t).ok());
+            Ok(SyncConflict {
+                id: row.get(0)?,
+                entity: row.get(1)?,
+                entity_uuid: row.get(2)?,
+                node_id: row.get(3)?,
+                node_name: row.get(4)?,
+                message: row.get(5)?,
+                local_values: parse(row.get(6)?),
+                remote_values: parse(row.get(7)?),
+                detected_at: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SyncStatus {
+        node_name: node_name(&conn, &node_id)?,
+        node_id,
+        packages_dir: sync_dir(app).to_string_lossy().to_string(),
+        peers,
+        conflicts,
+    })
+}
+
+// Taking the other node's version applies it as a change made here, so it is sent on to other nodes
+fn resolve_conflict(app: &AppHandle, id: i64, use_remote: bool) -> Result<()> {
+    let mut conn = get_db_conn(app)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let (entity, uuid, node_id, remote_values): (String, String, String, Option<String>) = tx
+        .query_row(
+            "SELECT entity, entity_uuid, node_id, remote_values FROM sync_conflicts
+             WHERE id = ?1 AND resolved_at IS NULL",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+    if use_remote {
+        let remote_values = remote_values
+            .ok_or_else(|| validation_error("The other node sent no version of this record"))?;
+        let peer = Peer {
+            node_id,
+            last_exported_seq: 0,
+        };
+        let invalid = |_| validation_error("The other node's version could not be read");
+        let applied = if entity == VOUCHERS {
+            let voucher: SyncVoucher = serde_json::from_str(&remote_values).map_err(invalid)?;
+            apply_voucher(&tx, &voucher, &peer, true)?
+        } else {
+            let spec = master_table(&entity)
+                .ok_or_else(|| validation_error(format!("Unknown record type '{}'", entity)))?;
+            let values = serde_json::from_str(&remote_values).map_err(invalid)?;
+            apply_master(&tx, spec, &SyncRecord { uuid, values }, &peer, true)?
+        };
+        if let Applied::Pending(reason) = applied {
+            return Err(validation_error(reason));
+        }
+    }
+
+    tx.execute(
+        "UPDATE sync_conflicts SET resolved_at = datetime('now', 'localtime') WHERE id = ?1",
+        params![id],
+    )?;
+    tx.commit()
+}
+
+fn rename_node(app: &AppHandle, name: &str) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(validation_error("Node name is required"));
+    }
+    let conn = get_db_conn(app)?;
+    conn.execute(
+        "INSERT INTO system_metadata (key, value) VALUES ('node_name', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![name],
+    )?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_sync_status(app: AppHandle) -> Result<SyncStatus, String> {
+    load_status(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_sync_node_name(app: AppHandle, name: String) -> Result<(), String> {
+    rename_node(&app, &name).map_err(|e| e.to_string())
+}
+
+// Without a target node the package holds everything, for a node that has never synced
+#[tauri::command]
+pub fn export_sync_package(
+    app: AppHandle,
+    target_node_id: Option<String>,
+) -> Result<SyncExportInfo, String> {
+    export_package(&app, target_node_id)
+}
+
+// The package file's content, base64 encoded
+#[tauri::command]
+pub fn import_sync_package(app: AppHandle, data: String) -> Result<SyncImportReport, String> {
+    import_package(&app, &data)
+}
+
+#[tauri::command]
+pub fn resolve_sync_conflict(app: AppHandle, id: i64, use_remote: bool) -> Result<(), String> {
+    resolve_conflict(&app, id, use_remote).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn open_node(name: &str) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        db::prepare_database(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO system_metadata (key, value) VALUES ('node_name', ?1)",
+            params![name],
+        )
+        .unwrap();
+        conn
+    }
+
+    // Build a package the way export_package does, passing it through JSON like the file would
+    fn send(conn: &Connection, to: Option<&Connection>) -> SyncPackage {
+        let target = to.map(|to| load_target(conn, &node_id(to).unwrap()).unwrap().unwrap());
+        let package = build_package(conn, target.as_ref()).unwrap();
+        if let Some(target) = &target {
+            mark_exported(conn, &target.node_id, package.up_to_seq).unwrap();
+        }
+        serde_json::from_slice(&serde_json::to_vec(&package).unwrap()).unwrap()
+    }
+
+    fn receive(conn: &Connection, package: &SyncPackage) -> Result<SyncImportReport> {
+        let tx = conn.unchecked_transaction().unwrap();
+        let report = apply_package(&tx, package, &mut vec![])?;
+        tx.commit().unwrap();
+        Ok(report)
+    }
+
+    fn add_site(conn: &Connection, code: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO sites (code, name, type) VALUES (?1, ?1, 'Warehouse')",
+            params![code],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn add_item(conn: &Connection, code: &str, name: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO items (code, name) VALUES (?1, ?2)",
+            params![code, name],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn add_voucher(conn: &Connection, voucher: Value) -> i64 {
+        let voucher: InventoryVoucher = serde_json::from_value(voucher).unwrap();
+        let tx = conn.unchecked_transaction().unwrap();
+        let id = db::insert_inventory_voucher(&tx, voucher).unwrap();
+        tx.commit().unwrap();
+        id
+    }
+
+    fn type_id(conn: &Connection, name: &str) -> i64 {
+        conn.query_row(
+            "SELECT id FROM inventory_transaction_types WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn purchase(conn: &Connection, site_id: i64, item_id: i64, quantity: f64) -> i64 {
+        let voucher_type_id = type_id(conn, "Purchase Inward");
+        add_voucher(
+            conn,
+            json!({
+                "voucher_date": "2026-10-01",
+                "destination_site_id": site_id,
+                "voucher_type_id": voucher_type_id,
+                "items": [{ "item_id": item_id, "quantity": quantity }],
+            }),
+        )
+    }
+
+    fn item_name(conn: &Connection, code: &str) -> String {
+        conn.query_row(
+            "SELECT name FROM items WHERE code = ?1",
+            params![code],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn open_conflicts(conn: &Connection) -> Vec<(String, String)> {
+        let mut stmt = conn
+            .prepare("SELECT entity, message FROM sync_conflicts WHERE resolved_at IS NULL")
+            .unwrap();
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        rows.collect::<Result<Vec<_>>>().unwrap()
+    }
+
+    #[test]
+    fn full_package_copies_masters_and_vouchers() {
+        let a = open_node("Head Office");
+        let b = open_node("Branch");
+        let site_id = add_site(&a, "G1");
+        let item_id = add_item(&a, "CEM", "Cement");
+        let voucher_id = purchase(&a, site_id, item_id, 10.0);
+
+        let report = receive(&b, &send(&a, None)).unwrap();
+        assert_eq!(report.masters_added, 2);
+        assert_eq!(report.vouchers_added, 1);
+        assert_eq!(report.conflicts, 0);
+
+        let voucher_uuid = uuid_of(&a, VOUCHERS, Some(voucher_id)).unwrap().unwrap();
+        let copy = id_of(&b, VOUCHERS, &voucher_uuid).unwrap().unwrap();
+        let (number, quantity): (String, f64) = b
+            .query_row(
+                "SELECT v.transaction_number, i.quantity FROM inventory_vouchers v
+                 JOIN inventory_voucher_items i ON i.inventory_voucher_id = v.id
+                 WHERE v.id = ?1",
+                params![copy],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            Some(number),
+            db::load_inventory_voucher(&a, voucher_id)
+                .unwrap()
+                .transaction_number
+        );
+        assert_eq!(quantity, 10.0);
+    }
+
+    #[test]
+    fn package_is_imported_only_once() {
+        let a = open_node("Head Office");
+        let b = open_node("Branch");
+        add_item(&a, "CEM", "Cement");
+        let package = send(&a, None);
+
+        receive(&b, &package).unwrap();
+        let err = receive(&b, &package).unwrap_err();
+        assert!(err.to_string().contains("already been imported"));
+    }
+
+    #[test]
+    fn own_package_is_rejected() {
+        let a = open_node("Head Office");
+        add_item(&a, "CEM", "Cement");
+        let package = send(&a, None);
+
+        let err = receive(&a, &package).unwrap_err();
+        assert!(err.to_string().contains("exported from this database"));
+    }
+
+    #[test]
+    fn record_with_same_code_adopts_incoming_uuid() {
+        let a = open_node("Head Office");
+        let b = open_node("Branch");
+        let item_id = add_item(&a, "CEM", "Cement");
+        add_item(&b, "CEM", "Cement");
+
+        receive(&b, &send(&a, None)).unwrap();
+        let uuid = uuid_of(&a, "items", Some(item_id)).unwrap().unwrap();
+        let count: i64 = b
+            .query_row("SELECT COUNT(*) FROM items WHERE code = 'CEM'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(id_of(&b, "items", &uuid).unwrap().is_some());
+    }
+
+    #[test]
+    fn edits_on_both_nodes_are_recorded_as_conflicts() {
+        let a = open_node("Head Office");
+        let b = open_node("Branch");
+        add_item(&a, "CEM", "Cement");
+        add_item(&a, "SAND", "Sand");
+        receive(&b, &send(&a, None)).unwrap();
+        receive(&a, &send(&b, Some(&a))).unwrap();
+
+        a.execute(
+            "UPDATE items SET name = 'Cement OPC' WHERE code = 'CEM'",
+            [],
+        )
+        .unwrap();
+        a.execute(
+            "UPDATE items SET name = 'River sand' WHERE code = 'SAND'",
+            [],
+        )
+        .unwrap();
+        b.execute(
+            "UPDATE items SET name = 'Cement PPC' WHERE code = 'CEM'",
+            [],
+        )
+        .unwrap();
+
+        let report = receive(&b, &send(&a, Some(&b))).unwrap();
+        assert_eq!(report.masters_updated, 1);
+        assert_eq!(report.conflicts, 1);
+        assert_eq!(item_name(&b, "CEM"), "Cement PPC");
+        assert_eq!(item_name(&b, "SAND"), "River sand");
+        assert_eq!(open_conflicts(&b).len(), 1);
+        assert_eq!(open_conflicts(&b)[0].0, "items");
+    }
+
+    #[test]
+    fn changes_imported_from_a_node_are_not_sent_back() {
+        let a = open_node("Head Office");
+        let b = open_node("Branch");
+        add_item(&a, "CEM", "Cement");
+        receive(&b, &send(&a, None)).unwrap();
+        let a = a;
+        receive(&a, &send(&b, Some(&a))).unwrap();
+
+        let package = send(&b, Some(&a));
+        assert!(package.masters.is_empty());
+        assert!(package.vouchers.is_empty());
+    }
+
+    #[test]
+    fn voucher_edit_applies_when_a_return_is_recorded_against_it() {
+        let a = open_node("Head Office");
+        let b = open_node("Branch");
+        let site_id = add_site(&a, "G1");
+        let item_id = add_item(&a, "CEM", "Cement");
+        let voucher_id = purchase(&a, site_id, item_id, 10.0);
+        let line_id: i64 = a
+            .query_row(
+                "SELECT id FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
+                params![voucher_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let return_type_id = type_id(&a, "Purchase Return");
+        add_voucher(
+            &a,
+            json!({
+                "voucher_date": "2026-10-02",
+                "voucher_type_id": return_type_id,
+                "original_voucher_id": voucher_id,
+                "items": [{ "item_id": item_id, "quantity": 2.0, "original_voucher_item_id": line_id }],
+            }),
+        );
+        receive(&b, &send(&a, None)).unwrap();
+        receive(&a, &send(&b, Some(&a))).unwrap();
+
+        let tx = a.unchecked_transaction().unwrap();
+        let mut voucher = db::load_inventory_voucher(&tx, voucher_id).unwrap();
+        voucher.items[0].quantity = 12.0;
+        db::replace_inventory_voucher(&tx, voucher).unwrap();
+        tx.commit().unwrap();
+
+        let report = receive(&b, &send(&a, Some(&b))).unwrap();
+        assert_eq!(report.conflicts, 0, "{:?}", open_conflicts(&b));
+        assert_eq!(report.vouchers_updated, 1);
+        let uuid = uuid_of(&a, VOUCHERS, Some(voucher_id)).unwrap().unwrap();
+        let copy = id_of(&b, VOUCHERS, &uuid).unwrap().unwrap();
+        let quantity: f64 = b
+            .query_row(
+                "SELECT quantity FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
+                params![copy],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(quantity, 12.0);
+    }
+
+    #[test]
+    fn line_added_above_keeps_the_return_on_its_line() {
+        let a = open_node("Head Office");
+        let b = open_node("Branch");
+        let site_id = add_site(&a, "G1");
+        let cement_id = add_item(&a, "CEM", "Cement");
+        let sand_id = add_item(&a, "SAND", "Sand");
+        let voucher_id = purchase(&a, site_id, cement_id, 10.0);
+        let line_id: i64 = a
+            .query_row(
+                "SELECT id FROM inventory_voucher_items WHERE inventory_voucher_id = ?1",
+                params![voucher_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let return_type_id = type_id(&a, "Purchase Return");
+        let return_id = add_voucher(
+            &a,
+            json!({
+                "voucher_date": "2026-10-02",
+                "voucher_type_id": return_type_id,
+                "original_voucher_id": voucher_id,
+                "items": [{ "item_id": cement_id, "quantity": 2.0, "original_voucher_item_id": line_id }],
+            }),
+        );
+        receive(&b, &send(&a, None)).unwrap();
+        receive(&a, &send(&b, Some(&a))).unwrap();
+
+        let tx = a.unchecked_transaction().unwrap();
+        let mut voucher = db::load_inventory_voucher(&tx, voucher_id).unwrap();
+        let sand: InventoryVoucherItem =
+            serde_json::from_value(json!({ "item_id": sand_id, "quantity": 5.0 })).unwrap();
+        voucher.items.insert(0, sand);
+        db::replace_inventory_voucher(&tx, voucher).unwrap();
+        tx.commit().unwrap();
+
+        let report = receive(&b, &send(&a, Some(&b))).unwrap();
+        assert_eq!(report.conflicts, 0, "{:?}", open_conflicts(&b));
+        assert_eq!(report.vouchers_updated, 1);
+        let return_uuid = uuid_of(&a, VOUCHERS, Some(return_id)).unwrap().unwrap();
+        let return_copy = id_of(&b, VOUCHERS, &return_uuid).unwrap().unwrap();
+        let (item_code, quantity): (String, f64) = b
+            .query_row(
+                "SELECT it.code, o.quantity FROM inventory_voucher_items r
+                 JOIN inventory_voucher_items o ON o.id = r.original_voucher_item_id
+                 JOIN items it ON it.id = o.item_id
+                 WHERE r.inventory_voucher_id = ?1",
+                params![return_copy],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(item_code, "CEM");
+        assert_eq!(quantity, 10.0);
+    }
+}
@@ -10,30 +10,49 @@ import StockBalanceReport from "./pages/StockBalanceReport";
 import DashboardPage from "./pages/DashboardPage";
 import StockMovementReport from "./pages/StockMovementReport";
 import ActivationPage from "./pages/ActivationPage";
+import SettingsPage from "./pages/SettingsPage";
+import CompaniesPage from "./pages/CompaniesPage";
+import SyncPage from "./pages/SyncPage";
+import SuppliersPage from "./pages/SuppliersPage";
+import PurchaseOrdersPage from "./pages/PurchaseOrdersPage";
+import RequisitionsPage from "./pages/RequisitionsPage";
+import ProjectsPage from "./pages/ProjectsPage";
+import CategoriesPage from "./pages/CategoriesPage";
 import RequireActivation from "./components/RequireActivation";
+import RequireUnlock from "./components/RequireUnlock";
 
 function App() {
   return (
     <BrowserRouter>
-      <Routes>
-        <Route path="/activation" element={<ActivationPage />} />
+      <RequireUnlock>
+        <Routes>
+          <Route path="/activation" element={<ActivationPage />} />
 
-        <Route path="/" element={
-          <RequireActivation>
-            <Layout />
-          </RequireActivation>
-        }>
-          <Route index element={<DashboardPage />} />
-          <Route path="items" element={<ItemsPage />} />
-          <Route path="brands" element={<BrandsPage />} />
-          <Route path="models" element={<ModelsPage />} />
-          <Route path="sites" element={<SitesPage />} />
-          <Route path="inventory-vouchers" element={<StockEntryPage />} />
-          <Route path="transactions" element={<TransactionHistoryPage />} />
-          <Route path="stock-balance" element={<StockBalanceReport />} />
-          <Route path="stock-movements" element={<StockMovementReport />} />
-        </Route>
-      </Routes>
+          <Route path="/" element={
+            <RequireActivation>
+              <Layout />
+            </RequireActivation>
+          }>
+            <Route index element={<DashboardPage />} />
+            <Route path="items" element={<ItemsPage />} />
+            <Route path="categories" element={<CategoriesPage />} />
+            <Route path="brands" element={<BrandsPage />} />
+            <Route path="models" element={<ModelsPage />} />
+            <Route path="sites" element={<SitesPage />} />
+            <Route path="projects" element={<ProjectsPage />} />
+            <Route path="suppliers" element={<SuppliersPage />} />
+            <Route path="purchase-orders" element={<PurchaseOrdersPage />} />
+            <Route path="requisitions" element={<RequisitionsPage />} />
+            <Route path="inventory-vouchers" element={<StockEntryPage />} />
+            <Route path="transactions" element={<TransactionHistoryPage />} />
+            <Route path="stock-balance" element={<StockBalanceReport />} />
+            <Route path="stock-movements" element={<StockMovementReport />} />
+            <Route path="settings" element={<SettingsPage />} />
+            <Route path="companies" element={<CompaniesPage />} />
+            <Route path="sync" element={<SyncPage />} />
+          </Route>
+        </Routes>
+      </RequireUnlock>
     </BrowserRouter>
   );
 }
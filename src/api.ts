@@ -3,11 +3,15 @@ import { invoke } from "@tauri-apps/api/core";
 export interface Brand {
     id?: number;
     name: string;
+    is_active?: boolean;
 }
 
 export interface Model {
     id?: number;
     name: string;
+    brand_id?: number;
+    brand_name?: string;
+    is_active?: boolean;
 }
 
 export interface Item {
@@ -18,7 +22,41 @@ export interface Item {
     model_id?: number;
     brand_name?: string;
     model_name?: string;
+    category_id?: number;
+    category_name?: string;
+    aliases?: string;
     is_active: boolean;
+    is_kit?: boolean;
+    components?: ItemComponent[];
+    barcodes?: ItemBarcode[];
+    attributes?: AttributeValues;
+}
+
+export type BarcodeType = "EAN" | "Internal";
+
+export interface ItemBarcode {
+    id?: number;
+    item_id?: number;
+    barcode: string;
+    barcode_type: BarcodeType;
+    is_primary: boolean;
+}
+
+export interface ItemCategory {
+    id?: number;
+    name: string;
+    parent_id?: number;
+    path?: string;
+    depth?: number;
+}
+
+export interface ItemComponent {
+    id?: number;
+    kit_item_id?: number;
+    component_item_id: number;
+    component_code?: string;
+    component_name?: string;
+    quantity: number;
 }
 
 export interface Site {
@@ -28,6 +66,51 @@ export interface Site {
     address?: string;
     type: "Site" | "Warehouse";
     is_active: boolean;
+    attributes?: AttributeValues;
+}
+
+export interface Supplier {
+    id?: number;
+    code: string;
+    name: string;
+    gstin?: string;
+    contact_person?: string;
+    phone?: string;
+    email?: string;
+    address?: string;
+    is_active: boolean;
+}
+
+export type ProjectStatus = "Active" | "On Hold" | "Completed";
+
+export interface Project {
+    id?: number;
+    code: string;
+    name: string;
+    site_id: number;
+    site_name?: string;
+    start_date?: string;
+    end_date?: string;
+    status: ProjectStatus;
+    description?: string;
+}
+
+// Custom attribute values keyed by attribute name
+export type AttributeValues = Record<string, string>;
+
+export type AttributeEntity = "item" | "site" | "voucher";
+
+export type AttributeDataType = "text" | "number" | "date" | "enum";
+
+export interface AttributeDefinition {
+    id?: number;
+    entity: AttributeEntity;
+    name: string;
+    data_type: AttributeDataType;
+    is_required: boolean;
+    options?: string[];
+    sort_order?: number;
+    is_active: boolean;
 }
 
 export interface InventoryTransactionType {
@@ -37,11 +120,25 @@ export interface InventoryTransactionType {
 
 export interface InventoryVoucher {
     id?: number;
+    uuid?: string;
     transaction_number?: string;
     voucher_date: string;
     source_site_id?: number;
     destination_site_id?: number;
     voucher_type_id: number;
+    supplier_id?: number;
+    bill_number?: string;
+    bill_date?: string;
+    purchase_order_id?: number;
+    requisition_id?: number;
+    original_voucher_id?: number;
+    project_id?: number;
+    activity_code?: string;
+    challan_number?: string;
+    vehicle_number?: string;
+    driver_name?: string;
+    receiver_name?: string;
+    attributes?: AttributeValues;
     items: InventoryVoucherItem[];
     remarks?: string;
     created_at?: string;
@@ -58,15 +155,72 @@ export interface InventoryVoucherDisplay {
     destination_site_name?: string;
     voucher_type_id: number;
     voucher_type_name: string;
+    supplier_id?: number;
+    supplier_name?: string;
+    bill_number?: string;
+    bill_date?: string;
+    purchase_order_id?: number;
+    purchase_order_number?: string;
+    requisition_id?: number;
+    requisition_number?: string;
+    original_voucher_id?: number;
+    original_transaction_number?: string;
+    project_id?: number;
+    project_code?: string;
+    project_name?: string;
+    activity_code?: string;
+    challan_number?: string;
+    vehicle_number?: string;
+    attributes?: AttributeValues;
     remarks?: string;
     created_at: string;
+    created_by?: number;
+    status: VoucherStatus;
+}
+
+export type VoucherStatus = "Posted" | "Partially Returned" | "Returned";
+
+export interface InventoryVoucherFilters {
+    from_date?: string;
+    to_date?: string;
+    voucher_type_id?: number;
+    source_site_id?: number;
+    destination_site_id?: number;
+    item_id?: number;
+    transaction_number?: string;
+    remarks?: string;
+    created_by?: number;
+    status?: VoucherStatus;
+    attributes?: AttributeValues;
+    sort_by?: "voucher_date" | "transaction_number" | "created_at";
+    sort_dir?: "asc" | "desc";
 }
 
 export interface InventoryVoucherItem {
     id?: number;
+    uuid?: string;
     inventory_voucher_id?: number;
     item_id: number;
     quantity: number;
+    purchase_order_item_id?: number;
+    requisition_item_id?: number;
+    original_voucher_item_id?: number;
+    kit_item_id?: number;
+    expand_kit?: boolean;
+    remarks?: string;
+    returned_quantity?: number;
+    net_quantity?: number;
+}
+
+export interface VoucherAttachment {
+    id: number;
+    voucher_id: number;
+    file_name: string;
+    mime_type?: string;
+    size_bytes: number;
+    description?: string;
+    path: string;
+    created_at: string;
 }
 
 export interface StockBalance {
@@ -103,37 +257,111 @@ export interface StockMovementHistory {
     created_at: string;
 }
 
+// Query shared by the item, brand, model and site lists; omit page/limit (or pass limit -1) for every row
+export interface ListQuery {
+    search?: string;
+    is_active?: boolean;
+    brand_id?: number;
+    model_id?: number;
+    category_id?: number;
+    attributes?: AttributeValues;
+    sort_by?: string;
+    sort_dir?: "asc" | "desc";
+    page?: number;
+    limit?: number;
+}
+
 // Item API
 export const createItem = async (item: Item): Promise<number> => {
     return await invoke("create_item", { item });
 };
 
-export const getItems = async (): Promise<Item[]> => {
-    return await invoke("get_items");
+export const getItems = async (query?: ListQuery): Promise<PaginatedResponse<Item>> => {
+    return await invoke("get_items", { query });
+};
+
+export interface ItemSearchFilters {
+    category_id?: number;
+    brand_id?: number;
+    model_id?: number;
+    is_active?: boolean;
+    attributes?: AttributeValues;
+}
+
+// Ranked full-text search over code, name, brand, model and aliases
+export const searchItems = async (
+    query: string,
+    filters?: ItemSearchFilters,
+    page: number = 1,
+    limit: number = 20
+): Promise<PaginatedResponse<Item>> => {
+    return await invoke("search_items", { query, filters, page, limit });
 };
 
 export const updateItem = async (item: Item): Promise<void> => {
     return await invoke("update_item", { item });
 };
 
-export const deleteItem = async (id: number): Promise<void> => {
+export const deleteItem = async (id: number): Promise<DeleteOutcome> => {
     return await invoke("delete_item", { id });
 };
 
+export const getItemComponents = async (kitItemId: number): Promise<ItemComponent[]> => {
+    return await invoke("get_item_components", { kitItemId });
+};
+
+export const getItemBarcodes = async (itemId: number): Promise<ItemBarcode[]> => {
+    return await invoke("get_item_barcodes", { itemId });
+};
+
+export const lookupItemByBarcode = async (barcode: string): Promise<Item | null> => {
+    return await invoke("lookup_item_by_barcode", { barcode });
+};
+
+// Label Sheet API
+export interface LabelRequest {
+    item_ids: number[];
+    symbology: "code128" | "qr";
+    format: "svg" | "pdf";
+    copies?: number;
+}
+
+// Returns the path of the generated label sheet
+export const generateItemLabels = async (request: LabelRequest): Promise<string> => {
+    return await invoke("generate_item_labels", { request });
+};
+
+// Item Category API
+export const createItemCategory = async (category: ItemCategory): Promise<number> => {
+    return await invoke("create_item_category", { category });
+};
+
+export const getItemCategories = async (): Promise<ItemCategory[]> => {
+    return await invoke("get_item_categories");
+};
+
+export const updateItemCategory = async (category: ItemCategory): Promise<void> => {
+    return await invoke("update_item_category", { category });
+};
+
+export const deleteItemCategory = async (id: number): Promise<void> => {
+    return await invoke("delete_item_category", { id });
+};
+
 // Brand API
 export const createBrand = async (brand: Brand): Promise<number> => {
     return await invoke("create_brand", { brand });
 };
 
-export const getBrands = async (): Promise<Brand[]> => {
-    return await invoke("get_brands");
+export const getBrands = async (query?: ListQuery): Promise<PaginatedResponse<Brand>> => {
+    return await invoke("get_brands", { query });
 };
 
 export const updateBrand = async (brand: Brand): Promise<void> => {
     return await invoke("update_brand", { brand });
 };
 
-export const deleteBrand = async (id: number): Promise<void> => {
+export const deleteBrand = async (id: number): Promise<DeleteOutcome> => {
     return await invoke("delete_brand", { id });
 };
 
@@ -142,15 +370,19 @@ export const createModel = async (model: Model): Promise<number> => {
     return await invoke("create_model", { model });
 };
 
-export const getModels = async (): Promise<Model[]> => {
-    return await invoke("get_models");
+export const getModels = async (query?: ListQuery): Promise<PaginatedResponse<Model>> => {
+    return await invoke("get_models", { query });
+};
+
+export const getModelsByBrand = async (brandId: number): Promise<Model[]> => {
+    return await invoke("get_models_by_brand", { brandId });
 };
 
 export const updateModel = async (model: Model): Promise<void> => {
     return await invoke("update_model", { model });
 };
 
-export const deleteModel = async (id: number): Promise<void> => {
+export const deleteModel = async (id: number): Promise<DeleteOutcome> => {
     return await invoke("delete_model", { id });
 };
 
@@ -159,18 +391,236 @@ export const createSite = async (site: Site): Promise<number> => {
     return await invoke("create_site", { site });
 };
 
-export const getSites = async (): Promise<Site[]> => {
-    return await invoke("get_sites");
+export const getSites = async (query?: ListQuery): Promise<PaginatedResponse<Site>> => {
+    return await invoke("get_sites", { query });
 };
 
 export const updateSite = async (site: Site): Promise<void> => {
     return await invoke("update_site", { site });
 };
 
-export const deleteSite = async (id: number): Promise<void> => {
+export const deleteSite = async (id: number): Promise<DeleteOutcome> => {
     return await invoke("delete_site", { id });
 };
 
+// Master Usage and Merge API
+export type MasterEntity = "brand" | "model" | "item" | "site" | "supplier";
+
+export type DeleteOutcome = "Deleted" | "Deactivated";
+
+export interface MasterReference {
+    label: string;
+    count: number;
+}
+
+export interface MasterUsage {
+    entity: MasterEntity;
+    id: number;
+    name: string;
+    in_use: boolean;
+    references: MasterReference[];
+}
+
+export const getMasterUsage = async (entity: MasterEntity, id: number): Promise<MasterUsage> => {
+    return await invoke("get_master_usage", { entity, id });
+};
+
+export const mergeMasters = async (
+    entity: MasterEntity,
+    sourceId: number,
+    targetId: number
+): Promise<void> => {
+    return await invoke("merge_masters", { entity, sourceId, targetId });
+};
+
+// Custom Attribute API
+export const createAttributeDefinition = async (definition: AttributeDefinition): Promise<number> => {
+    return await invoke("create_attribute_definition", { definition });
+};
+
+export const getAttributeDefinitions = async (entity?: AttributeEntity): Promise<AttributeDefinition[]> => {
+    return await invoke("get_attribute_definitions", { entity });
+};
+
+export const updateAttributeDefinition = async (definition: AttributeDefinition): Promise<void> => {
+    return await invoke("update_attribute_definition", { definition });
+};
+
+export const deleteAttributeDefinition = async (id: number): Promise<void> => {
+    return await invoke("delete_attribute_definition", { id });
+};
+
+// Supplier API
+export const createSupplier = async (supplier: Supplier): Promise<number> => {
+    return await invoke("create_supplier", { supplier });
+};
+
+export const getSuppliers = async (): Promise<Supplier[]> => {
+    return await invoke("get_suppliers");
+};
+
+export const updateSupplier = async (supplier: Supplier): Promise<void> => {
+    return await invoke("update_supplier", { supplier });
+};
+
+export const deleteSupplier = async (id: number): Promise<DeleteOutcome> => {
+    return await invoke("delete_supplier", { id });
+};
+
+// Project API
+export const createProject = async (project: Project): Promise<number> => {
+    return await invoke("create_project", { project });
+};
+
+export const getProjects = async (siteId?: number): Promise<Project[]> => {
+    return await invoke("get_projects", { siteId });
+};
+
+export const updateProject = async (project: Project): Promise<void> => {
+    return await invoke("update_project", { project });
+};
+
+export const deleteProject = async (id: number): Promise<void> => {
+    return await invoke("delete_project", { id });
+};
+
+export type PurchaseOrderStatus = "Open" | "Partial" | "Closed" | "Cancelled";
+
+export interface PurchaseOrderItem {
+    id?: number;
+    purchase_order_id?: number;
+    item_id: number;
+    ordered_quantity: number;
+    rate?: number;
+    received_quantity?: number;
+}
+
+export interface PurchaseOrder {
+    id?: number;
+    po_number?: string;
+    po_date: string;
+    supplier_id: number;
+    delivery_site_id?: number;
+    expected_date?: string;
+    status?: PurchaseOrderStatus;
+    items: PurchaseOrderItem[];
+    remarks?: string;
+    created_at?: string;
+    created_by?: number;
+}
+
+export interface PurchaseOrderDisplay {
+    id: number;
+    po_number: string;
+    po_date: string;
+    supplier_id: number;
+    supplier_name: string;
+    delivery_site_id?: number;
+    delivery_site_name?: string;
+    expected_date?: string;
+    status: PurchaseOrderStatus;
+    ordered_quantity: number;
+    received_quantity: number;
+    order_value: number;
+    remarks?: string;
+    created_at: string;
+}
+
+export interface OutstandingPurchaseOrderLine {
+    purchase_order_id: number;
+    po_number: string;
+    po_date: string;
+    expected_date?: string;
+    status: PurchaseOrderStatus;
+    supplier_id: number;
+    supplier_name: string;
+    delivery_site_id?: number;
+    delivery_site_name?: string;
+    purchase_order_item_id: number;
+    item_id: number;
+    item_code: string;
+    item_name: string;
+    ordered_quantity: number;
+    received_quantity: number;
+    pending_quantity: number;
+    rate?: number;
+    pending_value?: number;
+}
+
+export type RequisitionStatus =
+    | "Pending"
+    | "Approved"
+    | "Rejected"
+    | "Partial"
+    | "Fulfilled"
+    | "Cancelled";
+
+export interface MaterialRequisitionItem {
+    id?: number;
+    requisition_id?: number;
+    item_id: number;
+    requested_quantity: number;
+    approved_quantity?: number;
+    fulfilled_quantity?: number;
+}
+
+export interface MaterialRequisition {
+    id?: number;
+    requisition_number?: string;
+    requisition_date: string;
+    site_id: number;
+    needed_by?: string;
+    status?: RequisitionStatus;
+    requested_by?: string;
+    items: MaterialRequisitionItem[];
+    remarks?: string;
+    approved_by?: number;
+    approved_at?: string;
+    rejection_reason?: string;
+    created_at?: string;
+    created_by?: number;
+}
+
+export interface MaterialRequisitionDisplay {
+    id: number;
+    requisition_number: string;
+    requisition_date: string;
+    site_id: number;
+    site_name: string;
+    needed_by?: string;
+    status: RequisitionStatus;
+    requested_by?: string;
+    requested_quantity: number;
+    approved_quantity: number;
+    fulfilled_quantity: number;
+    remarks?: string;
+    created_at: string;
+}
+
+export interface RequisitionApproval {
+    requisition_item_id: number;
+    approved_quantity: number;
+}
+
+export interface RequisitionStatusLine {
+    requisition_id: number;
+    requisition_number: string;
+    requisition_date: string;
+    needed_by?: string;
+    status: RequisitionStatus;
+    site_id: number;
+    site_code: string;
+    site_name: string;
+    requisition_item_id: number;
+    item_id: number;
+    item_code: string;
+    item_name: string;
+    requested_quantity: number;
+    approved_quantity?: number;
+    fulfilled_quantity: number;
+    pending_quantity: number;
+}
+
 export interface PaginatedResponse<T> {
     items: T[];
     total_count: number;
@@ -181,6 +631,98 @@ export interface PaginationParams {
     limit: number;
 }
 
+// Purchase Order API
+export const createPurchaseOrder = async (order: PurchaseOrder): Promise<number> => {
+    return await invoke("create_purchase_order", { order });
+};
+
+export const getPurchaseOrders = async (
+    supplierId?: number,
+    status?: PurchaseOrderStatus,
+    page: number = 1,
+    limit: number = 10
+): Promise<PaginatedResponse<PurchaseOrderDisplay>> => {
+    return await invoke("get_purchase_orders", { supplierId, status, page, limit });
+};
+
+export const getPurchaseOrder = async (id: number): Promise<PurchaseOrder> => {
+    return await invoke("get_purchase_order", { id });
+};
+
+export const updatePurchaseOrder = async (order: PurchaseOrder): Promise<void> => {
+    return await invoke("update_purchase_order", { order });
+};
+
+export const cancelPurchaseOrder = async (id: number): Promise<void> => {
+    return await invoke("cancel_purchase_order", { id });
+};
+
+export const deletePurchaseOrder = async (id: number): Promise<void> => {
+    return await invoke("delete_purchase_order", { id });
+};
+
+export const getOutstandingPurchaseOrders = async (
+    supplierId?: number,
+    itemId?: number,
+    siteId?: number
+): Promise<OutstandingPurchaseOrderLine[]> => {
+    return await invoke("get_outstanding_purchase_orders", { supplierId, itemId, siteId });
+};
+
+// Material Requisition API
+export const createMaterialRequisition = async (requisition: MaterialRequisition): Promise<number> => {
+    return await invoke("create_material_requisition", { requisition });
+};
+
+export const getMaterialRequisitions = async (
+    siteId?: number,
+    status?: RequisitionStatus,
+    page: number = 1,
+    limit: number = 10
+): Promise<PaginatedResponse<MaterialRequisitionDisplay>> => {
+    return await invoke("get_material_requisitions", { siteId, status, page, limit });
+};
+
+export const getMaterialRequisition = async (id: number): Promise<MaterialRequisition> => {
+    return await invoke("get_material_requisition", { id });
+};
+
+export const updateMaterialRequisition = async (requisition: MaterialRequisition): Promise<void> => {
+    return await invoke("update_material_requisition", { requisition });
+};
+
+export const approveMaterialRequisition = async (
+    id: number,
+    approvals: RequisitionApproval[] = [],
+    approvedBy?: number
+): Promise<void> => {
+    return await invoke("approve_material_requisition", { id, approvals, approvedBy });
+};
+
+export const rejectMaterialRequisition = async (
+    id: number,
+    reason?: string,
+    rejectedBy?: number
+): Promise<void> => {
+    return await invoke("reject_material_requisition", { id, reason, rejectedBy });
+};
+
+export const cancelMaterialRequisition = async (id: number): Promise<void> => {
+    return await invoke("cancel_material_requisition", { id });
+};
+
+export const deleteMaterialRequisition = async (id: number): Promise<void> => {
+    return await invoke("delete_material_requisition", { id });
+};
+
+export const getRequisitionStatusReport = async (
+    siteId?: number,
+    status?: RequisitionStatus,
+    pendingOnly: boolean = false
+): Promise<RequisitionStatusLine[]> => {
+    return await invoke("get_requisition_status_report", { siteId, status, pendingOnly });
+};
+
 // Inventory Transaction Type API
 export const getInventoryTransactionTypes = async (): Promise<InventoryTransactionType[]> => {
     return await invoke("get_inventory_transaction_types");
@@ -191,8 +733,12 @@ export const createInventoryVoucher = async (voucher: InventoryVoucher): Promise
     return await invoke("create_inventory_voucher", { voucher });
 };
 
-export const getInventoryVouchers = async (page: number = 1, limit: number = 10): Promise<PaginatedResponse<InventoryVoucherDisplay>> => {
-    return await invoke("get_inventory_vouchers", { page, limit });
+export const getInventoryVouchers = async (
+    page: number = 1,
+    limit: number = 10,
+    filters?: InventoryVoucherFilters
+): Promise<PaginatedResponse<InventoryVoucherDisplay>> => {
+    return await invoke("get_inventory_vouchers", { page, limit, filters });
 };
 
 export const getInventoryVoucher = async (id: number): Promise<InventoryVoucher> => {
@@ -207,6 +753,33 @@ export const deleteInventoryVoucher = async (id: number): Promise<void> => {
     return await invoke("delete_inventory_voucher", { id });
 };
 
+// File contents are sent base64-encoded; the backend keeps a copy in the app data folder
+export const addVoucherAttachment = async (
+    voucherId: number,
+    fileName: string,
+    data: string,
+    description?: string
+): Promise<VoucherAttachment> => {
+    return await invoke("add_voucher_attachment", { voucherId, fileName, data, description });
+};
+
+export const getVoucherAttachments = async (voucherId: number): Promise<VoucherAttachment[]> => {
+    return await invoke("get_voucher_attachments", { voucherId });
+};
+
+// Path of a local copy to open; in client mode the file is fetched from the host first
+export const getVoucherAttachmentFile = async (id: number): Promise<string> => {
+    return await invoke("get_voucher_attachment_file", { id });
+};
+
+export const deleteVoucherAttachment = async (id: number): Promise<void> => {
+    return await invoke("delete_voucher_attachment", { id });
+};
+
+export const generateVoucherDocument = async (voucherId: number): Promise<string> => {
+    return await invoke("generate_voucher_document", { voucherId });
+};
+
 export const getStockBalance = async (siteId: number, itemId: number): Promise<number> => {
     return await invoke("get_stock_balance", { siteId, itemId });
 };
@@ -215,9 +788,11 @@ export const getStockBalances = async (
     itemName?: string,
     siteId?: number,
     page: number = 1,
-    limit: number = 10
+    limit: number = 10,
+    categoryId?: number,
+    itemAttributes?: AttributeValues
 ): Promise<PaginatedResponse<StockBalance>> => {
-    return await invoke("get_stock_balances", { itemName, siteId, page, limit });
+    return await invoke("get_stock_balances", { itemName, siteId, categoryId, itemAttributes, page, limit });
 };
 
 export const getItemStockBySites = async (itemId: number): Promise<StockBalance[]> => {
@@ -235,12 +810,16 @@ export const getStockMovementHistory = async (
     fromDate?: string,
     toDate?: string,
     page: number = 1,
-    limit: number = 10
+    limit: number = 10,
+    categoryId?: number,
+    itemAttributes?: AttributeValues
 ): Promise<PaginatedResponse<StockMovementHistory>> => {
     return await invoke("get_stock_movement_history", {
         itemId,
         siteId,
         voucherTypeId,
+        categoryId,
+        itemAttributes,
         fromDate,
         toDate,
         page,
@@ -248,24 +827,453 @@ export const getStockMovementHistory = async (
     });
 };
 
+export interface PurchaseRegisterEntry {
+    voucher_id: number;
+    transaction_number: string;
+    voucher_date: string;
+    supplier_id?: number;
+    supplier_name?: string;
+    bill_number?: string;
+    bill_date?: string;
+    item_id: number;
+    item_code: string;
+    item_name: string;
+    brand_name?: string;
+    model_name?: string;
+    site_id?: number;
+    site_name?: string;
+    quantity: number;
+}
+
+export interface PurchaseSummary {
+    group_key: string;
+    group_name: string;
+    voucher_count: number;
+    total_quantity: number;
+}
+
+export const getPurchaseRegister = async (
+    supplierId?: number,
+    itemId?: number,
+    fromDate?: string,
+    toDate?: string,
+    page: number = 1,
+    limit: number = 10
+): Promise<PaginatedResponse<PurchaseRegisterEntry>> => {
+    return await invoke("get_purchase_register", {
+        supplierId,
+        itemId,
+        fromDate,
+        toDate,
+        page,
+        limit
+    });
+};
+
+export const getPurchaseSummary = async (
+    groupBy: "supplier" | "item" | "month",
+    supplierId?: number,
+    itemId?: number,
+    fromDate?: string,
+    toDate?: string
+): Promise<PurchaseSummary[]> => {
+    return await invoke("get_purchase_summary", {
+        groupBy,
+        supplierId,
+        itemId,
+        fromDate,
+        toDate
+    });
+};
+
+export interface ProjectConsumptionSummary {
+    group_key: string;
+    group_name: string;
+    voucher_count: number;
+    used_quantity: number;
+    damaged_quantity: number;
+    total_quantity: number;
+    total_value?: number;
+    unvalued_quantity: number;
+}
+
+export const getProjectConsumption = async (
+    groupBy: "project" | "activity" | "item" | "month",
+    projectId?: number,
+    siteId?: number,
+    itemId?: number,
+    fromDate?: string,
+    toDate?: string
+): Promise<ProjectConsumptionSummary[]> => {
+    return await invoke("get_project_consumption", {
+        groupBy,
+        projectId,
+        siteId,
+        itemId,
+        fromDate,
+        toDate
+    });
+};
+
+export interface CategoryTotal {
+    category_id?: number;
+    category_name: string;
+    parent_id?: number;
+    path: string;
+    depth: number;
+    item_count: number;
+    total_quantity: number;
+    total_value?: number;
+}
+
+export const getCategoryStockSummary = async (siteId?: number): Promise<CategoryTotal[]> => {
+    return await invoke("get_category_stock_summary", { siteId });
+};
+
+export const getCategoryConsumption = async (
+    projectId?: number,
+    siteId?: number,
+    fromDate?: string,
+    toDate?: string
+): Promise<CategoryTotal[]> => {
+    return await invoke("get_category_consumption", { projectId, siteId, fromDate, toDate });
+};
+
 export interface DashboardStats {
     active_items_count: number;
     active_sites_count: number;
     recent_transactions_count: number;
+    fiscal_year_start: string;
+    fiscal_year_transactions_count: number;
 }
 
 export const getDashboardStats = async (): Promise<DashboardStats> => {
     return await invoke("get_dashboard_stats");
 };
 
+export type DateFormat = "DD-MM-YYYY" | "DD/MM/YYYY" | "YYYY-MM-DD" | "MM/DD/YYYY";
+
+export interface Settings {
+    company_name: string;
+    company_address?: string;
+    company_phone?: string;
+    company_email?: string;
+    gstin?: string;
+    logo?: string; // data:image/png or data:image/jpeg URL
+    date_format: DateFormat;
+    default_godown_id?: number;
+    fiscal_year_start_month: number;
+    quantity_decimals: number;
+    voucher_number_prefix?: string;
+    daily_backup: boolean;
+    backup_retention_count: number;
+    compress_backups: boolean;
+    api_enabled: boolean;
+    api_port: number;
+    api_allow_lan: boolean;
+    api_token?: string; // generated when the API is enabled without one
+}
+
+export const getSettings = async (): Promise<Settings> => {
+    return await invoke("get_settings");
+};
+
+export const updateSettings = async (settings: Settings): Promise<Settings> => {
+    return await invoke("update_settings", { settings });
+};
+
+// Integrity API
+export interface IntegrityIssue {
+    check: "database" | "foreign_keys" | "movements" | "masters" | "balances";
+    message: string;
+    voucher_id?: number;
+    repairable: boolean;
+}
+
+export interface IntegrityReport {
+    is_ok: boolean;
+    voucher_count: number;
+    movement_count: number;
+    issues: IntegrityIssue[];
+}
+
+export const checkIntegrity = async (): Promise<IntegrityReport> => {
+    return await invoke("check_integrity");
+};
+
+// Returns the number of stock movements written
+export const rebuildStockMovements = async (): Promise<number> => {
+    return await invoke("rebuild_stock_movements");
+};
+
+// Company API
+export interface Company {
+    id: number;
+    name: string;
+    folder: string;
+    is_archived: boolean;
+    is_active: boolean;
+    created_at?: string;
+    last_opened_at?: string;
+}
+
+export const listCompanies = async (includeArchived?: boolean): Promise<Company[]> => {
+    return await invoke("list_companies", { includeArchived });
+};
+
+export const getActiveCompany = async (): Promise<Company> => {
+    return await invoke("get_active_company");
+};
+
+export const createCompany = async (name: string): Promise<Company> => {
+    return await invoke("create_company", { name });
+};
+
+export const openCompany = async (id: number): Promise<Company> => {
+    return await invoke("open_company", { id });
+};
+
+export const renameCompany = async (id: number, name: string): Promise<Company> => {
+    return await invoke("rename_company", { id, name });
+};
+
+export const archiveCompany = async (id: number, archived: boolean): Promise<Company> => {
+    return await invoke("archive_company", { id, archived });
+};
+
+// Backup API
+export type BackupKind = "Manual" | "Automatic" | "PreRestore";
+
+export interface BackupInfo {
+    file_name: string;
+    path: string;
+    kind: BackupKind;
+    compressed: boolean;
+    size_bytes: number;
+    created_at: string;
+}
+
+export const backupDatabase = async (compress?: boolean): Promise<BackupInfo> => {
+    return await invoke("backup_database", { compress });
+};
+
+export const listBackups = async (): Promise<BackupInfo[]> => {
+    return await invoke("list_backups");
+};
+
+// Set when the last automatic backup failed
+export interface BackupStatus {
+    error?: string;
+    failed_at?: string;
+}
+
+export const getBackupStatus = async (): Promise<BackupStatus> => {
+    return await invoke("get_backup_status");
+};
+
+// Returns the backup of the data that was replaced. The passphrase is only needed for an
+// encrypted backup made under a different passphrase.
+export const restoreBackup = async (fileName: string, passphrase?: string): Promise<BackupInfo> => {
+    return await invoke("restore_backup", { fileName, passphrase });
+};
+
+// API Server API
+export interface ApiServerStatus {
+    is_running: boolean;
+    address?: string;
+    lan_address?: string; // for other computers, when network access is allowed
+    error?: string;
+}
+
+export const getApiServerStatus = async (): Promise<ApiServerStatus> => {
+    return await invoke("get_api_server_status");
+};
+
+// LAN API
+export interface LanStatus {
+    client_enabled: boolean;
+    host_address?: string;
+    is_connected: boolean;
+    host_company?: string;
+    error?: string;
+    last_contact?: string;
+}
+
+export const getLanStatus = async (): Promise<LanStatus> => {
+    return await invoke("get_lan_status");
+};
+
+// Checks the host first; afterwards every data command runs on the host
+export const connectToHost = async (hostAddress: string, token: string): Promise<LanStatus> => {
+    return await invoke("connect_to_host", { hostAddress, token });
+};
+
+export const disconnectFromHost = async (): Promise<LanStatus> => {
+    return await invoke("disconnect_from_host");
+};
+
+// Sync API
+export interface SyncPeer {
+    node_id: string;
+    node_name: string;
+    last_exported_at?: string;
+    last_imported_at?: string;
+    pending_changes: number; // records changed since the last package sent to this node
+}
+
+export interface SyncConflict {
+    id: number;
+    entity: string; // table name, e.g. "items" or "inventory_vouchers"
+    entity_uuid: string;
+    node_id: string;
+    node_name?: string;
+    message: string;
+    local_values?: Record<string, any>;
+    remote_values?: Record<string, any>;
+    detected_at: string;
+}
+
+export interface SyncStatus {
+    node_id: string;
+    node_name: string;
+    packages_dir: string;
+    peers: SyncPeer[];
+    conflicts: SyncConflict[];
+}
+
+export interface SyncExportInfo {
+    file_name: string;
+    path: string;
+    masters: number;
+    vouchers: number;
+    deleted_vouchers: number;
+}
+
+export interface SyncImportReport {
+    node_name: string;
+    masters_added: number;
+    masters_updated: number;
+    vouchers_added: number;
+    vouchers_updated: number;
+    vouchers_deleted: number;
+    conflicts: number;
+    warnings: string[];
+}
+
+export const getSyncStatus = async (): Promise<SyncStatus> => {
+    return await invoke("get_sync_status");
+};
+
+export const setSyncNodeName = async (name: string): Promise<void> => {
+    return await invoke("set_sync_node_name", { name });
+};
+
+// Without a target node the package holds everything, for a node that has never synced
+export const exportSyncPackage = async (targetNodeId?: string): Promise<SyncExportInfo> => {
+    return await invoke("export_sync_package", { targetNodeId });
+};
+
+// data is the package file's content, base64 encoded
+export const importSyncPackage = async (data: string): Promise<SyncImportReport> => {
+    return await invoke("import_sync_package", { data });
+};
+
+export const resolveSyncConflict = async (id: number, useRemote: boolean): Promise<void> => {
+    return await invoke("resolve_sync_conflict", { id, useRemote });
+};
+
+// Encryption API
+export interface EncryptionStatus {
+    is_encrypted: boolean;
+    is_unlocked: boolean;
+}
+
+export const getEncryptionStatus = async (): Promise<EncryptionStatus> => {
+    return await invoke("get_encryption_status");
+};
+
+export const unlockDatabase = async (passphrase: string): Promise<EncryptionStatus> => {
+    return await invoke("unlock_database", { passphrase });
+};
+
+// Encrypts, changes the passphrase, or decrypts when no new passphrase is given
+export const setDatabasePassphrase = async (
+    currentPassphrase: string | undefined,
+    newPassphrase: string | undefined,
+): Promise<EncryptionStatus> => {
+    return await invoke("set_database_passphrase", { currentPassphrase, newPassphrase });
+};
+
 export interface ImportItem {
     code: string;
     name: string;
     brand_name: string;
     model_name: string;
+    attributes?: AttributeValues;
 }
 
 export const importItems = async (items: ImportItem[]): Promise<void> => {
     return await invoke("import_items", { items });
 };
 
+export const exportItems = async (): Promise<ImportItem[]> => {
+    return await invoke("export_items");
+};
+
+// Item import from a CSV, XLSX, XLS or ODS file read on the backend
+export interface ItemImportFile {
+    file_name: string; // the extension picks the format
+    data: string; // base64
+    sheet?: string;
+    header_row?: number; // 1-based; detected when missing
+}
+
+// Mapping keys are "code", "name", "brand", "model", "category", "aliases", "is_active"
+// and "attr.<name>" for item attributes; values are column indexes
+export interface ItemImportPreview {
+    sheets: string[];
+    sheet?: string;
+    header_row: number;
+    headers: string[];
+    fields: string[];
+    mapping: Record<string, number>;
+    sample_rows: string[][];
+    total_rows: number;
+}
+
+export interface ItemImportRequest {
+    file: ItemImportFile;
+    mapping?: Record<string, number>; // detected from the headers when missing
+    dry_run?: boolean;
+    skip_existing?: boolean; // otherwise items with a known code are updated
+    fuzzy_match?: boolean;
+    create_missing?: boolean; // brands and models that match nothing are created
+}
+
+export type ImportRowStatus = "Created" | "Updated" | "Skipped" | "Error";
+
+export interface ImportRowResult {
+    row: number; // row number in the sheet
+    code: string;
+    status: ImportRowStatus;
+    messages: string[];
+}
+
+export interface ItemImportReport {
+    dry_run: boolean;
+    sheet?: string;
+    created: number;
+    updated: number;
+    skipped: number;
+    errors: number;
+    rows: ImportRowResult[];
+}
+
+export const previewItemImport = async (file: ItemImportFile): Promise<ItemImportPreview> => {
+    return await invoke("preview_item_import", { file });
+};
+
+export const importItemsFile = async (request: ItemImportRequest): Promise<ItemImportReport> => {
+    return await invoke("import_items_file", { request });
+};
+
@@ -0,0 +1,51 @@
+import { useEffect, useState } from "react";
+import { listCompanies, openCompany, Company } from "../api";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+
+// Opening another company reloads the app so every page refetches from the new database
+export async function switchCompany(id: number) {
+    try {
+        await openCompany(id);
+        window.location.assign("/");
+    } catch (error) {
+        console.error("Failed to open company:", error);
+        alert("Error opening company: " + error);
+    }
+}
+
+export default function CompanySwitcher({ className }: { className?: string }) {
+    const [companies, setCompanies] = useState<Company[]>([]);
+
+    useEffect(() => {
+        listCompanies()
+            .then(setCompanies)
+            .catch((error) => console.error("Failed to fetch companies:", error));
+    }, []);
+
+    const active = companies.find((c) => c.is_active);
+    if (companies.length < 2) return null;
+
+    return (
+        <Select
+            value={active ? String(active.id) : undefined}
+            onValueChange={(value) => {
+                if (Number(value) !== active?.id) switchCompany(Number(value));
+            }}
+        >
+            <SelectTrigger className={className}>
+                <SelectValue placeholder="Select company" />
+            </SelectTrigger>
+            <SelectContent>
+                {companies.map((c) => (
+                    <SelectItem key={c.id} value={String(c.id)}>{c.name}</SelectItem>
+                ))}
+            </SelectContent>
+        </Select>
+    );
+}
@@ -0,0 +1,41 @@
+import { useEffect, useState } from "react";
+import { getLanStatus, LanStatus } from "../api";
+import { cn } from "@/lib/utils";
+
+const POLL_INTERVAL_MS = 5000;
+
+// Shown while this desktop works against a host's database; the backend keeps retrying the host
+export default function ConnectionIndicator({ className }: { className?: string }) {
+    const [status, setStatus] = useState<LanStatus | null>(null);
+
+    useEffect(() => {
+        const refresh = () =>
+            getLanStatus()
+                .then(setStatus)
+                .catch((error) => console.error("Failed to fetch connection status:", error));
+        refresh();
+        const timer = setInterval(refresh, POLL_INTERVAL_MS);
+        return () => clearInterval(timer);
+    }, []);
+
+    if (!status?.client_enabled) return null;
+
+    return (
+        <div
+            className={cn("flex items-center gap-2 text-sm", className)}
+            title={status.error ?? `Host ${status.host_address}`}
+        >
+            <span
+                className={cn(
+                    "h-2.5 w-2.5 rounded-full",
+                    status.is_connected ? "bg-green-500" : "bg-red-500 animate-pulse",
+                )}
+            />
+            {status.is_connected ? (
+                <span className="text-muted-foreground">Host: {status.host_company || status.host_address}</span>
+            ) : (
+                <span className="text-destructive">Offline, reconnecting...</span>
+            )}
+        </div>
+    );
+}
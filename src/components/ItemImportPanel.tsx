@@ -0,0 +1,303 @@
+import { useEffect, useState } from "react";
+import {
+    importItemsFile,
+    previewItemImport,
+    ItemImportFile,
+    ItemImportPreview,
+    ItemImportReport,
+} from "../api";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { X } from "lucide-react";
+
+const FIELD_LABELS: Record<string, string> = {
+    code: "Code",
+    name: "Name",
+    brand: "Brand",
+    model: "Model",
+    category: "Category",
+    aliases: "Aliases",
+    is_active: "Active",
+};
+
+const STATUS_CLASSES: Record<string, string> = {
+    Created: "text-green-600",
+    Updated: "text-blue-600",
+    Skipped: "text-muted-foreground",
+    Error: "text-destructive",
+};
+
+// Spreadsheet column letters: 0 -> A, 26 -> AA
+function columnLetter(index: number): string {
+    let letter = "";
+    for (let n = index + 1; n > 0; n = Math.floor((n - 1) / 26)) {
+        letter = String.fromCharCode(65 + ((n - 1) % 26)) + letter;
+    }
+    return letter;
+}
+
+interface ItemImportPanelProps {
+    file: File;
+    onClose: () => void;
+    onImported: () => void;
+}
+
+// Maps the file's columns to item fields and checks every row with a dry run before importing
+export default function ItemImportPanel({ file, onClose, onImported }: ItemImportPanelProps) {
+    const [source, setSource] = useState<ItemImportFile | null>(null);
+    const [preview, setPreview] = useState<ItemImportPreview | null>(null);
+    const [mapping, setMapping] = useState<Record<string, number>>({});
+    const [options, setOptions] = useState({ skip_existing: false, fuzzy_match: true, create_missing: false });
+    const [report, setReport] = useState<ItemImportReport | null>(null);
+    const [problemsOnly, setProblemsOnly] = useState(false);
+    const [isWorking, setIsWorking] = useState(false);
+
+    const loadPreview = async (next: ItemImportFile) => {
+        setIsWorking(true);
+        try {
+            const preview = await previewItemImport(next);
+            setSource({ ...next, sheet: preview.sheet, header_row: preview.header_row });
+            setPreview(preview);
+            setMapping(preview.mapping);
+            setReport(null);
+        } catch (error) {
+            console.error("Failed to read import file:", error);
+            alert("Error reading file: " + error);
+        } finally {
+            setIsWorking(false);
+        }
+    };
+
+    useEffect(() => {
+        const reader = new FileReader();
+        reader.onload = (evt) => {
+            // Strip the "data:<mime>;base64," prefix
+            const data = String(evt.target?.result || "").split(",")[1] || "";
+            loadPreview({ file_name: file.name, data });
+        };
+        reader.readAsDataURL(file);
+    }, [file]);
+
+    const handleRun = async (dryRun: boolean) => {
+        if (!source) return;
+        setIsWorking(true);
+        try {
+            const result = await importItemsFile({ file: source, mapping, dry_run: dryRun, ...options });
+            setReport(result);
+            if (!dryRun) {
+                onImported();
+            }
+        } catch (error) {
+            console.error("Failed to import items:", error);
+            alert("Error importing items: " + error);
+        } finally {
+            setIsWorking(false);
+        }
+    };
+
+    const setField = (field: string, value: string) => {
+        const next = { ...mapping };
+        if (value === "none") {
+            delete next[field];
+        } else {
+            next[field] = Number(value);
+        }
+        setMapping(next);
+        setReport(null);
+    };
+
+    const fieldLabel = (field: string) => FIELD_LABELS[field] || field.replace(/^attr\./, "");
+    const rows = report?.rows.filter((row) => !problemsOnly || row.status === "Error" || row.messages.length > 0) || [];
+
+    return (
+        <Card>
+            <CardHeader className="flex flex-row items-center justify-between space-y-0">
+                <CardTitle className="text-lg">Import {file.name}</CardTitle>
+                <Button variant="ghost" size="icon" title="Close" onClick={onClose}>
+                    <X className="h-4 w-4" />
+                </Button>
+            </CardHeader>
+            <CardContent className="space-y-6">
+                {!preview ? (
+                    <p className="text-sm text-muted-foreground">{isWorking ? "Reading file..." : "The file could not be read."}</p>
+                ) : (
+                    <>
+                        <div className="flex flex-row gap-4 items-end">
+                            {preview.sheets.length > 1 && (
+                                <div className="space-y-2 w-[200px]">
+                                    <Label>Sheet</Label>
+                                    <Select
+                                        value={preview.sheet}
+                                        onValueChange={(sheet) => source && loadPreview({ ...source, sheet, header_row: undefined })}
+                                    >
+                                        <SelectTrigger>
+                                            <SelectValue />
+                                        </SelectTrigger>
+                                        <SelectContent>
+                                            {preview.sheets.map((sheet) => (
+                                                <SelectItem key={sheet} value={sheet}>{sheet}</SelectItem>
+                                            ))}
+                                        </SelectContent>
+                                    </Select>
+                                </div>
+                            )}
+                            <div className="space-y-2 w-[120px]">
+                                <Label htmlFor="import-header-row">Header Row</Label>
+                                <Input
+                                    id="import-header-row"
+                                    type="number"
+                                    min={1}
+                                    value={preview.header_row}
+                                    onChange={(e) => source && Number(e.target.value) > 0
+                                        && loadPreview({ ...source, header_row: Number(e.target.value) })}
+                                />
+                            </div>
+                            <p className="text-sm text-muted-foreground pb-2">{preview.total_rows} rows</p>
+                        </div>
+
+                        <div className="grid grid-cols-2 md:grid-cols-4 gap-4">
+                            {preview.fields.map((field) => (
+                                <div key={field} className="space-y-2">
+                                    <Label>{fieldLabel(field)}{field === "code" ? " *" : ""}</Label>
+                                    <Select
+                                        value={mapping[field] === undefined ? "none" : String(mapping[field])}
+                                        onValueChange={(value) => setField(field, value)}
+                                    >
+                                        <SelectTrigger>
+                                            <SelectValue />
+                                        </SelectTrigger>
+                                        <SelectContent>
+                                            <SelectItem value="none">Not imported</SelectItem>
+                                            {preview.headers.map((header, index) => (
+                                                <SelectItem key={index} value={String(index)}>
+                                                    {columnLetter(index)}{header ? `: ${header}` : ""}
+                                                </SelectItem>
+                                            ))}
+                                        </SelectContent>
+                                    </Select>
+                                </div>
+                            ))}
+                        </div>
+
+                        {preview.sample_rows.length > 0 && (
+                            <div className="rounded-md border overflow-x-auto">
+                                <Table>
+                                    <TableHeader>
+                                        <TableRow>
+                                            {preview.fields.filter((field) => mapping[field] !== undefined).map((field) => (
+                                                <TableHead key={field}>{fieldLabel(field)}</TableHead>
+                                            ))}
+                                        </TableRow>
+                                    </TableHeader>
+                                    <TableBody>
+                                        {preview.sample_rows.map((row, index) => (
+                                            <TableRow key={index}>
+                                                {preview.fields.filter((field) => mapping[field] !== undefined).map((field) => (
+                                                    <TableCell key={field}>{row[mapping[field]] || ""}</TableCell>
+                                                ))}
+                                            </TableRow>
+                                        ))}
+                                    </TableBody>
+                                </Table>
+                            </div>
+                        )}
+
+                        <div className="flex flex-wrap gap-6 text-sm">
+                            <label className="flex items-center gap-2">
+                                <input
+                                    type="checkbox"
+                                    checked={options.skip_existing}
+                                    onChange={(e) => { setOptions({ ...options, skip_existing: e.target.checked }); setReport(null); }}
+                                />
+                                Skip items that already exist (otherwise they are updated)
+                            </label>
+                            <label className="flex items-center gap-2">
+                                <input
+                                    type="checkbox"
+                                    checked={options.fuzzy_match}
+                                    onChange={(e) => { setOptions({ ...options, fuzzy_match: e.target.checked }); setReport(null); }}
+                                />
+                                Match misspelt brand and model names
+                            </label>
+                            <label className="flex items-center gap-2">
+                                <input
+                                    type="checkbox"
+                                    checked={options.create_missing}
+                                    onChange={(e) => { setOptions({ ...options, create_missing: e.target.checked }); setReport(null); }}
+                                />
+                                Create brands and models that are not found
+                            </label>
+                        </div>
+
+                        <div className="flex gap-2">
+                            <Button variant="outline" disabled={isWorking || mapping.code === undefined} onClick={() => handleRun(true)}>
+                                Check Rows
+                            </Button>
+                            <Button
+                                disabled={isWorking || mapping.code === undefined || (!!report && !report.dry_run)}
+                                onClick={() => handleRun(false)}
+                            >
+                                {isWorking ? "Working..." : "Import"}
+                            </Button>
+                        </div>
+                    </>
+                )}
+
+                {report && (
+                    <div className="space-y-2">
+                        <div className="flex items-center justify-between text-sm">
+                            <p>
+                                <span className="font-medium">{report.dry_run ? "Check only, nothing saved:" : "Imported:"}</span>{" "}
+                                {report.created} created, {report.updated} updated, {report.skipped} skipped,{" "}
+                                <span className={report.errors > 0 ? "text-destructive" : ""}>{report.errors} errors</span>
+                            </p>
+                            <label className="flex items-center gap-2">
+                                <input type="checkbox" checked={problemsOnly} onChange={(e) => setProblemsOnly(e.target.checked)} />
+                                Only rows with messages
+                            </label>
+                        </div>
+                        <div className="rounded-md border max-h-[400px] overflow-y-auto">
+                            <Table>
+                                <TableHeader>
+                                    <TableRow>
+                                        <TableHead className="w-[80px]">Row</TableHead>
+                                        <TableHead>Code</TableHead>
+                                        <TableHead>Result</TableHead>
+                                        <TableHead>Details</TableHead>
+                                    </TableRow>
+                                </TableHeader>
+                                <TableBody>
+                                    {rows.map((row) => (
+                                        <TableRow key={row.row}>
+                                            <TableCell>{row.row}</TableCell>
+                                            <TableCell className="font-medium">{row.code || "-"}</TableCell>
+                                            <TableCell className={STATUS_CLASSES[row.status]}>{row.status}</TableCell>
+                                            <TableCell className="text-sm">{row.messages.join("; ")}</TableCell>
+                                        </TableRow>
+                                    ))}
+                                </TableBody>
+                            </Table>
+                        </div>
+                    </div>
+                )}
+            </CardContent>
+        </Card>
+    );
+}
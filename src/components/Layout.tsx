@@ -1,20 +1,54 @@
+import { useEffect, useState } from "react";
 import { Link, Outlet, useLocation } from "react-router-dom";
 import { Button } from "@/components/ui/button";
-import { cn } from "@/lib/utils";
+import { applySettings, cn } from "@/lib/utils";
+import { getSettings, Settings } from "../api";
+import CompanySwitcher from "./CompanySwitcher";
+import ConnectionIndicator from "./ConnectionIndicator";
+
+// Passed to pages through the outlet so saving settings updates the header and formatting
+export interface LayoutContext {
+    settings: Settings | null;
+    setSettings: (settings: Settings) => void;
+}
 
 function Layout() {
     const location = useLocation();
+    const [settings, setSettingsState] = useState<Settings | null>(null);
+    const [settingsLoaded, setSettingsLoaded] = useState(false);
+
+    const setSettings = (next: Settings) => {
+        applySettings(next);
+        setSettingsState(next);
+    };
+
+    const outletContext: LayoutContext = { settings, setSettings };
+
+    useEffect(() => {
+        getSettings()
+            .then(setSettings)
+            .catch((error) => console.error("Failed to load settings:", error))
+            .finally(() => setSettingsLoaded(true));
+    }, []);
 
     const navItems = [
         { path: "/", label: "Dashboard" },
         { path: "/items", label: "Items" },
+        { path: "/categories", label: "Categories" },
         { path: "/brands", label: "Brands" },
         { path: "/models", label: "Models" },
         { path: "/sites", label: "Sites" },
+        { path: "/projects", label: "Projects" },
+        { path: "/suppliers", label: "Suppliers" },
+        { path: "/purchase-orders", label: "Orders" },
+        { path: "/requisitions", label: "Requisitions" },
         { path: "/inventory-vouchers", label: "Entry" },
         { path: "/transactions", label: "History" },
         { path: "/stock-balance", label: "Stock Balance" },
         { path: "/stock-movements", label: "Stock Movement" },
+        { path: "/companies", label: "Companies" },
+        { path: "/sync", label: "Sync" },
+        { path: "/settings", label: "Settings" },
     ];
 
     return (
@@ -23,9 +57,15 @@ function Layout() {
                 <div className="container flex h-16 items-center justify-between px-8">
                     <div className="flex items-center gap-6 md:gap-10">
                         <Link to="/" className="flex items-center space-x-2">
+                            {settings?.logo && (
+                                <img src={settings.logo} alt="" className="h-8 w-auto" />
+                            )}
                             <span className="inline-block font-bold text-2xl text-primary">
                                 Stock Star
                             </span>
+                            {settings && (
+                                <span className="text-sm text-muted-foreground">{settings.company_name}</span>
+                            )}
                         </Link>
                         <nav className="flex items-center space-x-2 text-sm font-medium">
                             {navItems.map((item) => (
@@ -45,10 +85,14 @@ function Layout() {
                             ))}
                         </nav>
                     </div>
+                    <div className="flex items-center gap-4">
+                        <ConnectionIndicator />
+                        <CompanySwitcher className="w-[200px]" />
+                    </div>
                 </div>
             </nav>
             <main className="flex-1 overflow-y-auto p-8">
-                <Outlet />
+                {settingsLoaded && <Outlet context={outletContext} />}
             </main>
         </div>
     );
@@ -0,0 +1,88 @@
+import { useEffect, useState } from "react";
+import { getEncryptionStatus, unlockDatabase, EncryptionStatus } from "../api";
+import CompanySwitcher from "@/components/CompanySwitcher";
+
+// An encrypted company database has to be unlocked before anything else can read it
+export default function RequireUnlock({ children }: { children: React.ReactNode }) {
+    const [status, setStatus] = useState<EncryptionStatus | null>(null);
+    const [passphrase, setPassphrase] = useState("");
+    const [error, setError] = useState("");
+    const [unlocking, setUnlocking] = useState(false);
+
+    useEffect(() => {
+        getEncryptionStatus()
+            .then(setStatus)
+            .catch((error) => {
+                console.error("Failed to check encryption:", error);
+                setStatus({ is_encrypted: false, is_unlocked: true });
+            });
+    }, []);
+
+    const handleUnlock = async (e: React.FormEvent) => {
+        e.preventDefault();
+        setError("");
+        setUnlocking(true);
+        try {
+            setStatus(await unlockDatabase(passphrase));
+            setPassphrase("");
+        } catch (err: any) {
+            setError(err || "Unlock failed");
+        } finally {
+            setUnlocking(false);
+        }
+    };
+
+    if (!status) {
+        return (
+            <div className="flex items-center justify-center h-screen bg-gray-100">
+                <div className="text-xl font-semibold text-gray-600">Opening database...</div>
+            </div>
+        );
+    }
+
+    if (status.is_unlocked) {
+        return <>{children}</>;
+    }
+
+    return (
+        <div className="flex flex-col items-center justify-center min-h-screen bg-gray-100 p-4">
+            <form onSubmit={handleUnlock} className="bg-white p-8 rounded-lg shadow-lg max-w-md w-full">
+                <h1 className="text-2xl font-bold mb-6 text-center text-gray-800">Unlock Stock Star</h1>
+
+                <CompanySwitcher className="mb-4 w-full" />
+
+                <p className="text-gray-600 mb-6 text-center text-sm">
+                    This company's data is encrypted. Enter its passphrase to open it.
+                </p>
+
+                <div className="mb-4">
+                    <label className="block text-gray-700 text-sm font-bold mb-2" htmlFor="unlock-passphrase">
+                        Passphrase
+                    </label>
+                    <input
+                        id="unlock-passphrase"
+                        type="password"
+                        autoFocus
+                        className="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                        value={passphrase}
+                        onChange={(e) => setPassphrase(e.target.value)}
+                    />
+                </div>
+
+                {error && (
+                    <div className="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded relative mb-4 text-sm">
+                        {error}
+                    </div>
+                )}
+
+                <button
+                    type="submit"
+                    className={`w-full bg-blue-600 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline transition duration-150 ${unlocking ? "opacity-50 cursor-not-allowed" : ""}`}
+                    disabled={unlocking || !passphrase}
+                >
+                    {unlocking ? "Unlocking..." : "Unlock"}
+                </button>
+            </form>
+        </div>
+    );
+}
@@ -1,3 +1,15 @@
+import { getAppSettings } from "./utils";
+
+// Company name and address shown above printed reports
+const companyHeaderHTML = (): string => {
+  const settings = getAppSettings();
+  if (!settings) return '';
+  return `
+    <div class="company-name">${settings.company_name}</div>
+    ${settings.company_address ? `<div class="company-address">${settings.company_address.replace(/\n/g, ', ')}</div>` : ''}
+  `;
+};
+
 export interface PrintOptions {
   title: string;
   subtitle?: string;
@@ -89,6 +101,8 @@ export const generatePrintHTML = (options: PrintOptions): string => {
           padding-bottom: 10px;
         }
         .print-header h1 { font-size: 24px; margin-bottom: 5px; }
+        .company-name { font-size: 18px; font-weight: bold; }
+        .company-address { color: #666; font-size: 12px; margin-bottom: 8px; }
         .print-header p { color: #666; font-size: 14px; }
         .print-timestamp { 
           color: #999; 
@@ -146,6 +160,7 @@ export const generatePrintHTML = (options: PrintOptions): string => {
     </head>
     <body>
       <div class="print-header">
+        ${companyHeaderHTML()}
         <h1>${title}</h1>
         ${subtitle ? `<p>${subtitle}</p>` : ''}
       </div>
@@ -222,6 +237,8 @@ export const generateVoucherPrintHTML = (options: VoucherPrintOptions): string =
           padding-bottom: 10px;
         }
         .header h1 { font-size: 20px; font-weight: bold; text-transform: uppercase; }
+        .company-name { font-size: 18px; font-weight: bold; }
+        .company-address { color: #666; font-size: 12px; margin-bottom: 8px; }
         
         .details-table {
           width: 100%;
@@ -280,6 +297,7 @@ export const generateVoucherPrintHTML = (options: VoucherPrintOptions): string =
     </head>
     <body>
       <div class="header">
+        ${companyHeaderHTML()}
         <h1>${title}</h1>
       </div>
 
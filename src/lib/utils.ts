@@ -1,23 +1,51 @@
 import { clsx, type ClassValue } from "clsx"
 import { twMerge } from "tailwind-merge"
 import { format, parseISO } from "date-fns"
+import type { Settings } from "@/api"
 
 export function cn(...inputs: ClassValue[]) {
     return twMerge(clsx(inputs))
 }
 
+const DATE_FORMATS: Record<string, string> = {
+    "DD-MM-YYYY": "dd-MM-yyyy",
+    "DD/MM/YYYY": "dd/MM/yyyy",
+    "YYYY-MM-DD": "yyyy-MM-dd",
+    "MM/DD/YYYY": "MM/dd/yyyy",
+}
+
+let appSettings: Settings | null = null
+
 /**
- * Formats a date string to dd-MM-yyyy format
+ * Makes the loaded settings available to the formatting helpers
+ */
+export function applySettings(settings: Settings) {
+    appSettings = settings
+}
+
+export function getAppSettings(): Settings | null {
+    return appSettings
+}
+
+/**
+ * Formats a date string using the configured date format (dd-MM-yyyy by default)
  * @param dateString - ISO date string or yyyy-MM-dd format
- * @returns Formatted date string in dd-MM-yyyy format
+ * @returns Formatted date string
  */
 export function formatDate(dateString: string): string {
     if (!dateString) return "";
     try {
         // Handle both ISO strings and yyyy-MM-dd format
         const date = dateString.includes("T") ? parseISO(dateString) : parseISO(dateString);
-        return format(date, "dd-MM-yyyy");
+        return format(date, DATE_FORMATS[appSettings?.date_format ?? ""] ?? "dd-MM-yyyy");
     } catch {
         return dateString;
     }
 }
+
+/**
+ * Formats a quantity with the configured number of decimals (2 by default)
+ */
+export function formatQuantity(value: number): string {
+    return value.toFixed(appSettings?.quantity_decimals ?? 2);
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useNavigate } from "react-router-dom";
+import CompanySwitcher from "@/components/CompanySwitcher";
 
 export default function ActivationPage() {
     const [key, setKey] = useState("");
@@ -33,8 +34,10 @@ export default function ActivationPage() {
             <div className="bg-white p-8 rounded-lg shadow-lg max-w-md w-full">
                 <h1 className="text-2xl font-bold mb-6 text-center text-gray-800">Activate Stock Star</h1>
 
+                <CompanySwitcher className="mb-4 w-full" />
+
                 <p className="text-gray-600 mb-6 text-center text-sm">
-                    Your trial has expired or no license was found.<br />
+                    Your trial has expired or no license was found for this company.<br />
                     Please provide the System ID below to your administrator to receive an activation key.
                 </p>
 
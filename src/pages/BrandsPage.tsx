@@ -12,25 +12,44 @@ import {
 } from "@/components/ui/table";
 import { Card, CardContent } from "@/components/ui/card";
 import { Label } from "@/components/ui/label";
-import { Trash2, Pencil, X } from "lucide-react";
+import { Trash2, Pencil, X, ArrowUpDown } from "lucide-react";
+import { PaginationControls } from "@/components/ui/pagination-controls";
 
 function BrandsPage() {
     const [brands, setBrands] = useState<Brand[]>([]);
     const [formData, setFormData] = useState({ name: "" });
     const [editingId, setEditingId] = useState<number | null>(null);
 
+    const [search, setSearch] = useState("");
+    const [sortDir, setSortDir] = useState<"asc" | "desc">("asc");
+    const [currentPage, setCurrentPage] = useState(1);
+    const [pageSize, setPageSize] = useState(50);
+    const [totalCount, setTotalCount] = useState(0);
+
     const fetchBrands = async () => {
         try {
-            const data = await getBrands();
-            setBrands(data);
+            const data = await getBrands({
+                search: search.trim() || undefined,
+                sort_by: "name",
+                sort_dir: sortDir,
+                page: currentPage,
+                limit: pageSize,
+            });
+            setBrands(data.items);
+            setTotalCount(data.total_count);
         } catch (error) {
             console.error("Failed to fetch brands:", error);
         }
     };
 
     useEffect(() => {
-        fetchBrands();
-    }, []);
+        setCurrentPage(1);
+    }, [search, sortDir]);
+
+    useEffect(() => {
+        const timer = setTimeout(fetchBrands, 250);
+        return () => clearTimeout(timer);
+    }, [search, sortDir, currentPage, pageSize]);
 
     const handleSubmit = async (e: React.FormEvent) => {
         e.preventDefault();
@@ -62,7 +81,10 @@ function BrandsPage() {
     const handleDelete = async (id: number) => {
         if (confirm("Are you sure you want to delete this brand?")) {
             try {
-                await deleteBrand(id);
+                const outcome = await deleteBrand(id);
+                if (outcome === "Deactivated") {
+                    alert("This brand is in use, so it was deactivated instead of deleted.");
+                }
                 fetchBrands();
             } catch (error) {
                 console.error("Failed to delete brand:", error);
@@ -102,19 +124,37 @@ function BrandsPage() {
                 </CardContent>
             </Card>
 
+            <Input
+                value={search}
+                onChange={(e) => setSearch(e.target.value)}
+                placeholder="Search brands..."
+                className="max-w-sm"
+            />
+
             <div className="rounded-md border bg-card">
                 <Table>
                     <TableHeader>
                         <TableRow>
                             <TableHead className="w-[80px]">S.No</TableHead>
-                            <TableHead>Name</TableHead>
+                            <TableHead>
+                                <Button
+                                    variant="ghost"
+                                    className="px-0 hover:bg-transparent"
+                                    onClick={() => setSortDir(sortDir === "asc" ? "desc" : "asc")}
+                                >
+                                    Name
+                                    <ArrowUpDown className="h-4 w-4 ml-2" />
+                                </Button>
+                            </TableHead>
                             <TableHead className="text-right">Actions</TableHead>
                         </TableRow>
                     </TableHeader>
                     <TableBody>
                         {brands.map((brand, index) => (
                             <TableRow key={brand.id}>
-                                <TableCell className="font-medium">{index + 1}</TableCell>
+                                <TableCell className="font-medium">
+                                    {(pageSize === -1 ? 0 : (currentPage - 1) * pageSize) + index + 1}
+                                </TableCell>
                                 <TableCell>{brand.name}</TableCell>
                                 <TableCell className="text-right space-x-2">
                                     <Button
@@ -145,6 +185,18 @@ function BrandsPage() {
                         )}
                     </TableBody>
                 </Table>
+                <div className="border-t">
+                    <PaginationControls
+                        currentPage={currentPage}
+                        totalCount={totalCount}
+                        pageSize={pageSize}
+                        onPageChange={setCurrentPage}
+                        onPageSizeChange={(size) => {
+                            setPageSize(size);
+                            setCurrentPage(1);
+                        }}
+                    />
+                </div>
             </div>
         </div>
     );
@@ -0,0 +1,263 @@
+import { useEffect, useState } from "react";
+import {
+    createItemCategory,
+    deleteItemCategory,
+    getCategoryStockSummary,
+    getItemCategories,
+    getSites,
+    updateItemCategory,
+    CategoryTotal,
+    ItemCategory,
+    Site,
+} from "../api";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Combobox } from "@/components/ui/combobox";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Card, CardContent } from "@/components/ui/card";
+import { Trash2, Pencil, X, FolderPlus } from "lucide-react";
+
+const emptyCategory: ItemCategory = { name: "", parent_id: undefined };
+
+function CategoriesPage() {
+    const [categories, setCategories] = useState<ItemCategory[]>([]);
+    const [totals, setTotals] = useState<CategoryTotal[]>([]);
+    const [sites, setSites] = useState<Site[]>([]);
+    const [siteFilter, setSiteFilter] = useState("all");
+    const [formData, setFormData] = useState<ItemCategory>(emptyCategory);
+    const [editingId, setEditingId] = useState<number | null>(null);
+
+    const fetchCategories = async () => {
+        try {
+            setCategories(await getItemCategories());
+        } catch (error) {
+            console.error("Failed to fetch categories:", error);
+        }
+    };
+
+    const fetchTotals = async () => {
+        try {
+            setTotals(await getCategoryStockSummary(siteFilter === "all" ? undefined : Number(siteFilter)));
+        } catch (error) {
+            console.error("Failed to fetch category stock:", error);
+        }
+    };
+
+    useEffect(() => {
+        fetchCategories();
+        getSites()
+            .then((data) => setSites(data.items))
+            .catch((error) => console.error("Failed to fetch sites:", error));
+    }, []);
+
+    useEffect(() => {
+        fetchTotals();
+    }, [siteFilter]);
+
+    // A category cannot be moved under itself or anything below it
+    const isWithin = (categoryId: number | undefined, ancestorId: number): boolean => {
+        let current = categories.find(c => c.id === categoryId);
+        while (current) {
+            if (current.id === ancestorId) return true;
+            current = categories.find(c => c.id === current!.parent_id);
+        }
+        return false;
+    };
+
+    const parentOptions = [
+        { label: "(Top level)", value: "none" },
+        ...categories
+            .filter(c => !editingId || !isWithin(c.id, editingId))
+            .map(c => ({ label: c.path || c.name, value: String(c.id) })),
+    ];
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        try {
+            if (editingId) {
+                await updateItemCategory({ ...formData, id: editingId });
+            } else {
+                await createItemCategory(formData);
+            }
+            setFormData(emptyCategory);
+            setEditingId(null);
+            fetchCategories();
+            fetchTotals();
+        } catch (error) {
+            console.error("Failed to save category:", error);
+            alert("Error saving category: " + error);
+        }
+    };
+
+    const handleEdit = (category: ItemCategory) => {
+        setEditingId(category.id!);
+        setFormData({ name: category.name, parent_id: category.parent_id });
+    };
+
+    const handleAddChild = (category: ItemCategory) => {
+        setEditingId(null);
+        setFormData({ name: "", parent_id: category.id });
+    };
+
+    const handleCancel = () => {
+        setEditingId(null);
+        setFormData(emptyCategory);
+    };
+
+    const handleDelete = async (id: number) => {
+        if (confirm("Are you sure you want to delete this category?")) {
+            try {
+                await deleteItemCategory(id);
+                fetchCategories();
+                fetchTotals();
+            } catch (error) {
+                console.error("Failed to delete category:", error);
+                alert("Error deleting category: " + error);
+            }
+        }
+    };
+
+    const uncategorised = totals.find(t => t.category_id == null);
+
+    return (
+        <div className="space-y-6">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Item Categories</h2>
+            </div>
+
+            <Card>
+                <CardContent className="pt-6">
+                    <form onSubmit={handleSubmit} className="grid grid-cols-2 md:grid-cols-4 gap-4 items-end">
+                        <div>
+                            <Label htmlFor="name">Name</Label>
+                            <Input
+                                id="name"
+                                value={formData.name}
+                                onChange={(e) => setFormData({ ...formData, name: e.target.value })}
+                                required
+                                placeholder="Cement"
+                            />
+                        </div>
+                        <div className="col-span-2">
+                            <Label>Parent Category</Label>
+                            <Combobox
+                                options={parentOptions}
+                                value={formData.parent_id ? String(formData.parent_id) : "none"}
+                                onChange={(val) => setFormData({ ...formData, parent_id: val === "none" ? undefined : Number(val) })}
+                                placeholder="(Top level)"
+                            />
+                        </div>
+                        <div className="flex items-center gap-4 h-9">
+                            <Button type="submit">{editingId ? "Update Category" : "Add Category"}</Button>
+                            {(editingId || formData.parent_id) && (
+                                <Button type="button" variant="outline" onClick={handleCancel}>
+                                    <X className="h-4 w-4 mr-2" />
+                                    Cancel
+                                </Button>
+                            )}
+                        </div>
+                    </form>
+                </CardContent>
+            </Card>
+
+            <div className="w-[240px]">
+                <Combobox
+                    options={[
+                        { label: "Stock at all sites", value: "all" },
+                        ...sites.map(s => ({ label: s.name, value: String(s.id) })),
+                    ]}
+                    value={siteFilter}
+                    onChange={setSiteFilter}
+                    placeholder="Stock at all sites"
+                />
+            </div>
+
+            <div className="rounded-md border bg-card">
+                <Table>
+                    <TableHeader>
+                        <TableRow>
+                            <TableHead>Category</TableHead>
+                            <TableHead className="text-right">Items</TableHead>
+                            <TableHead className="text-right">Stock Qty</TableHead>
+                            <TableHead className="text-right">Stock Value</TableHead>
+                            <TableHead className="text-right">Actions</TableHead>
+                        </TableRow>
+                    </TableHeader>
+                    <TableBody>
+                        {categories.map((category) => {
+                            // Totals roll up everything in the subcategories
+                            const total = totals.find(t => t.category_id === category.id);
+                            return (
+                                <TableRow key={category.id}>
+                                    <TableCell style={{ paddingLeft: `${1 + (category.depth ?? 0) * 1.5}rem` }}>
+                                        <span className={category.depth ? "" : "font-medium"}>{category.name}</span>
+                                    </TableCell>
+                                    <TableCell className="text-right">{total?.item_count ?? 0}</TableCell>
+                                    <TableCell className="text-right">{total?.total_quantity ?? 0}</TableCell>
+                                    <TableCell className="text-right">
+                                        {total?.total_value != null ? total.total_value.toFixed(2) : "-"}
+                                    </TableCell>
+                                    <TableCell className="text-right space-x-2">
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            title="Add subcategory"
+                                            onClick={() => handleAddChild(category)}
+                                            className="text-primary hover:text-primary hover:bg-primary/10"
+                                        >
+                                            <FolderPlus className="h-4 w-4" />
+                                        </Button>
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            onClick={() => handleEdit(category)}
+                                            className="text-primary hover:text-primary hover:bg-primary/10"
+                                        >
+                                            <Pencil className="h-4 w-4" />
+                                        </Button>
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            onClick={() => handleDelete(category.id!)}
+                                            className="text-destructive hover:text-destructive hover:bg-destructive/10"
+                                        >
+                                            <Trash2 className="h-4 w-4" />
+                                        </Button>
+                                    </TableCell>
+                                </TableRow>
+                            );
+                        })}
+                        {uncategorised && (
+                            <TableRow>
+                                <TableCell className="italic text-muted-foreground">{uncategorised.category_name}</TableCell>
+                                <TableCell className="text-right">{uncategorised.item_count}</TableCell>
+                                <TableCell className="text-right">{uncategorised.total_quantity}</TableCell>
+                                <TableCell className="text-right">
+                                    {uncategorised.total_value != null ? uncategorised.total_value.toFixed(2) : "-"}
+                                </TableCell>
+                                <TableCell />
+                            </TableRow>
+                        )}
+                        {categories.length === 0 && (
+                            <TableRow>
+                                <TableCell colSpan={5} className="h-24 text-center text-muted-foreground">
+                                    No categories found.
+                                </TableCell>
+                            </TableRow>
+                        )}
+                    </TableBody>
+                </Table>
+            </div>
+        </div>
+    );
+}
+
+export default CategoriesPage;
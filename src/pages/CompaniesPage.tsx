@@ -0,0 +1,179 @@
+import { useEffect, useState } from "react";
+import {
+    archiveCompany,
+    createCompany,
+    listCompanies,
+    renameCompany,
+    Company,
+} from "../api";
+import { switchCompany } from "@/components/CompanySwitcher";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Card, CardContent } from "@/components/ui/card";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Archive, ArchiveRestore, FolderOpen, Pencil, X } from "lucide-react";
+import { formatDate } from "@/lib/utils";
+
+function CompaniesPage() {
+    const [companies, setCompanies] = useState<Company[]>([]);
+    const [name, setName] = useState("");
+    const [editingId, setEditingId] = useState<number | null>(null);
+
+    const fetchCompanies = async () => {
+        try {
+            setCompanies(await listCompanies(true));
+        } catch (error) {
+            console.error("Failed to fetch companies:", error);
+        }
+    };
+
+    useEffect(() => {
+        fetchCompanies();
+    }, []);
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        try {
+            if (editingId) {
+                await renameCompany(editingId, name);
+            } else {
+                const company = await createCompany(name);
+                if (confirm(`Company "${company.name}" created. Open it now?`)) {
+                    await switchCompany(company.id);
+                    return;
+                }
+            }
+            setName("");
+            setEditingId(null);
+            fetchCompanies();
+        } catch (error) {
+            console.error("Failed to save company:", error);
+            alert("Error saving company: " + error);
+        }
+    };
+
+    const handleEdit = (company: Company) => {
+        setEditingId(company.id);
+        setName(company.name);
+    };
+
+    const handleCancel = () => {
+        setEditingId(null);
+        setName("");
+    };
+
+    const handleArchive = async (company: Company) => {
+        const archived = !company.is_archived;
+        if (archived && !confirm(`Archive "${company.name}"? Its data is kept but it can no longer be opened until restored.`)) {
+            return;
+        }
+        try {
+            await archiveCompany(company.id, archived);
+            fetchCompanies();
+        } catch (error) {
+            console.error("Failed to archive company:", error);
+            alert("Error: " + error);
+        }
+    };
+
+    return (
+        <div className="space-y-6">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Companies</h2>
+            </div>
+
+            <Card>
+                <CardContent className="pt-6">
+                    <form onSubmit={handleSubmit} className="flex flex-row gap-4 items-end max-w-4xl">
+                        <div className="space-y-2 flex-1">
+                            <Label htmlFor="company-name">Name</Label>
+                            <Input
+                                id="company-name"
+                                value={name}
+                                onChange={(e) => setName(e.target.value)}
+                                required
+                                placeholder="Company Name"
+                            />
+                        </div>
+                        <div className="flex gap-2 shrink-0">
+                            <Button type="submit">{editingId ? "Rename Company" : "Add Company"}</Button>
+                            {editingId && (
+                                <Button type="button" variant="outline" onClick={handleCancel}>
+                                    <X className="h-4 w-4 mr-2" />
+                                    Cancel
+                                </Button>
+                            )}
+                        </div>
+                    </form>
+                </CardContent>
+            </Card>
+
+            <div className="rounded-md border bg-card">
+                <Table>
+                    <TableHeader>
+                        <TableRow>
+                            <TableHead>Name</TableHead>
+                            <TableHead>Status</TableHead>
+                            <TableHead>Last Opened</TableHead>
+                            <TableHead className="text-right">Actions</TableHead>
+                        </TableRow>
+                    </TableHeader>
+                    <TableBody>
+                        {companies.map((company) => (
+                            <TableRow key={company.id} className={company.is_archived ? "text-muted-foreground" : ""}>
+                                <TableCell className="font-medium">{company.name}</TableCell>
+                                <TableCell>
+                                    {company.is_active ? "Open" : company.is_archived ? "Archived" : "-"}
+                                </TableCell>
+                                <TableCell>{company.last_opened_at ? formatDate(company.last_opened_at.split(" ")[0]) : "-"}</TableCell>
+                                <TableCell className="text-right space-x-2">
+                                    {!company.is_active && !company.is_archived && (
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            title="Open"
+                                            onClick={() => switchCompany(company.id)}
+                                            className="text-primary hover:text-primary hover:bg-primary/10"
+                                        >
+                                            <FolderOpen className="h-4 w-4" />
+                                        </Button>
+                                    )}
+                                    <Button
+                                        variant="ghost"
+                                        size="icon"
+                                        title="Rename"
+                                        onClick={() => handleEdit(company)}
+                                        className="text-primary hover:text-primary hover:bg-primary/10"
+                                    >
+                                        <Pencil className="h-4 w-4" />
+                                    </Button>
+                                    {!company.is_active && (
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            title={company.is_archived ? "Restore" : "Archive"}
+                                            onClick={() => handleArchive(company)}
+                                            className="text-destructive hover:text-destructive hover:bg-destructive/10"
+                                        >
+                                            {company.is_archived ? <ArchiveRestore className="h-4 w-4" /> : <Archive className="h-4 w-4" />}
+                                        </Button>
+                                    )}
+                                </TableCell>
+                            </TableRow>
+                        ))}
+                    </TableBody>
+                </Table>
+            </div>
+        </div>
+    );
+}
+
+export default CompaniesPage;
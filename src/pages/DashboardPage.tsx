@@ -12,6 +12,7 @@ import {
     Package,
     MapPin,
     History,
+    CalendarRange,
     PlusCircle,
     BarChart3,
     ArrowRight
@@ -75,6 +76,14 @@ export default function DashboardPage() {
             color: "text-purple-600",
             bg: "bg-purple-50",
         },
+        {
+            title: "This Fiscal Year",
+            description: stats ? `Since ${formatDate(stats.fiscal_year_start)}` : undefined,
+            value: stats?.fiscal_year_transactions_count ?? 0,
+            icon: CalendarRange,
+            color: "text-orange-600",
+            bg: "bg-orange-50",
+        },
     ];
 
     return (
@@ -86,7 +95,7 @@ export default function DashboardPage() {
                 </p>
             </div>
 
-            <div className="grid gap-4 md:grid-cols-3">
+            <div className="grid gap-4 md:grid-cols-4">
                 {statCards.map((card) => (
                     <Card key={card.title} className="hover:shadow-md transition-shadow">
                         <CardHeader className="flex flex-row items-center justify-between space-y-0 pb-2">
@@ -1,5 +1,26 @@
 import { useEffect, useState, useRef } from "react";
-import { createItem, deleteItem, getItems, getBrands, getModels, updateItem, Brand, Model, Item, importItems, ImportItem } from "../api";
+import {
+    createItem,
+    deleteItem,
+    getItems,
+    getBrands,
+    getModels,
+    updateItem,
+    Brand,
+    Model,
+    Item,
+    exportItems,
+    getAttributeDefinitions,
+    AttributeDefinition,
+    getItemBarcodes,
+    ItemBarcode,
+    generateItemLabels,
+    getItemComponents,
+    ItemComponent,
+    getItemCategories,
+    ItemCategory,
+} from "../api";
+import { openPath } from "@tauri-apps/plugin-opener";
 import { Input } from "@/components/ui/input";
 import { Button } from "@/components/ui/button";
 import {
@@ -19,91 +40,195 @@ import {
 } from "@/components/ui/select";
 import { Card, CardContent } from "@/components/ui/card";
 import { Label } from "@/components/ui/label";
-import { Trash2, Pencil, X, Upload } from "lucide-react";
+import { Trash2, Pencil, X, Upload, Download, Tag, ArrowUpDown, Plus } from "lucide-react";
+import { Combobox } from "@/components/ui/combobox";
+import { PaginationControls } from "@/components/ui/pagination-controls";
+import ItemImportPanel from "@/components/ItemImportPanel";
 import * as XLSX from "xlsx";
 
 function ItemsPage() {
     const [items, setItems] = useState<Item[]>([]);
     const [brands, setBrands] = useState<Brand[]>([]);
     const [models, setModels] = useState<Model[]>([]);
+    const [attributeDefs, setAttributeDefs] = useState<AttributeDefinition[]>([]);
+    const [categories, setCategories] = useState<ItemCategory[]>([]);
     const [formData, setFormData] = useState<Omit<Item, "id">>({
         code: "",
         name: "",
         brand_id: undefined,
         model_id: undefined,
         is_active: true,
+        attributes: {},
     });
     const [editingId, setEditingId] = useState<number | null>(null);
+    const [barcodeText, setBarcodeText] = useState("");
+    // Components of the item being edited; an item with components is a kit
+    const [components, setComponents] = useState<ItemComponent[]>([]);
+    const [allItems, setAllItems] = useState<Item[]>([]);
+    const [selectedIds, setSelectedIds] = useState<number[]>([]);
+    const [labelSymbology, setLabelSymbology] = useState<"code128" | "qr">("code128");
+    const [searchQuery, setSearchQuery] = useState("");
+    const [brandFilter, setBrandFilter] = useState("all");
+    const [categoryFilter, setCategoryFilter] = useState("all");
+    const [statusFilter, setStatusFilter] = useState("all");
+    // No sort field while searching keeps the results ranked by relevance
+    const [sort, setSort] = useState<{ by?: string; dir: "asc" | "desc" }>({ dir: "asc" });
+    const [currentPage, setCurrentPage] = useState(1);
+    const [pageSize, setPageSize] = useState(50);
+    const [totalCount, setTotalCount] = useState(0);
+    const [importFile, setImportFile] = useState<File | null>(null);
     const fileInputRef = useRef<HTMLInputElement>(null);
 
     const fetchData = async () => {
         try {
-            const [itemsData, brandsData, modelsData] = await Promise.all([
-                getItems(),
+            const [itemsData, brandsData, modelsData, attributeData, categoriesData] = await Promise.all([
+                getItems({
+                    search: searchQuery.trim() || undefined,
+                    brand_id: brandFilter === "all" ? undefined : Number(brandFilter),
+                    category_id: categoryFilter === "all" ? undefined : Number(categoryFilter),
+                    is_active: statusFilter === "all" ? undefined : statusFilter === "active",
+                    sort_by: sort.by,
+                    sort_dir: sort.dir,
+                    page: currentPage,
+                    limit: pageSize,
+                }),
                 getBrands(),
                 getModels(),
+                getAttributeDefinitions("item"),
+                getItemCategories(),
             ]);
-            setItems(itemsData);
-            setBrands(brandsData);
-            setModels(modelsData);
+            setItems(itemsData.items);
+            setTotalCount(itemsData.total_count);
+            setBrands(brandsData.items);
+            setModels(modelsData.items);
+            setAttributeDefs(attributeData.filter((def) => def.is_active));
+            setCategories(categoriesData);
         } catch (error) {
             console.error("Failed to fetch data:", error);
         }
     };
 
+    const fetchAllItems = () =>
+        getItems()
+            .then((data) => setAllItems(data.items))
+            .catch((error) => console.error("Failed to fetch items:", error));
+
     useEffect(() => {
-        fetchData();
+        fetchAllItems();
     }, []);
 
+    useEffect(() => {
+        setCurrentPage(1);
+    }, [searchQuery, brandFilter, categoryFilter, statusFilter, sort]);
+
+    useEffect(() => {
+        const timer = setTimeout(fetchData, 250);
+        return () => clearTimeout(timer);
+    }, [searchQuery, brandFilter, categoryFilter, statusFilter, sort, currentPage, pageSize]);
+
+    const toggleSort = (by: string) => {
+        setSort((prev) => ({ by, dir: prev.by === by && prev.dir === "asc" ? "desc" : "asc" }));
+    };
+
+    const sortableHead = (by: string, label: string) => (
+        <Button variant="ghost" className="px-0 hover:bg-transparent" onClick={() => toggleSort(by)}>
+            {label}
+            <ArrowUpDown className="h-4 w-4 ml-2" />
+        </Button>
+    );
+
     const handleSubmit = async (e: React.FormEvent) => {
         e.preventDefault();
         try {
+            const barcodes = parseBarcodes(barcodeText);
+            const kitComponents = components.filter((c) => c.component_item_id > 0);
             if (editingId) {
-                await updateItem({ id: editingId, ...formData });
+                await updateItem({ id: editingId, ...formData, barcodes, components: kitComponents });
             } else {
-                await createItem(formData as Item);
+                await createItem({ ...formData, barcodes, components: kitComponents } as Item);
             }
+            setBarcodeText("");
+            setComponents([]);
             setFormData({
                 code: "",
                 name: "",
                 brand_id: undefined,
                 model_id: undefined,
                 is_active: true,
+                attributes: {},
             });
             setEditingId(null);
             fetchData();
+            fetchAllItems();
         } catch (error) {
             console.error("Failed to save item:", error);
             alert("Error saving item: " + error);
         }
     };
 
+    // Comma separated; 8/12/13/14 digit values are manufacturer EAN/UPC codes
+    const parseBarcodes = (text: string): ItemBarcode[] =>
+        text
+            .split(",")
+            .map((value) => value.trim())
+            .filter((value) => value)
+            .map((value, index): ItemBarcode => ({
+                barcode: value,
+                barcode_type: /^(\d{8}|\d{12,14})$/.test(value) ? "EAN" : "Internal",
+                is_primary: index === 0,
+            }));
+
     const handleEdit = (item: Item) => {
         setEditingId(item.id!);
+        setBarcodeText("");
+        getItemBarcodes(item.id!)
+            .then((barcodes) => setBarcodeText(barcodes.map((b) => b.barcode).join(", ")))
+            .catch((error) => console.error("Failed to load barcodes:", error));
+        setComponents([]);
+        getItemComponents(item.id!)
+            .then(setComponents)
+            .catch((error) => console.error("Failed to load kit components:", error));
         setFormData({
             code: item.code,
             name: item.name,
             brand_id: item.brand_id,
             model_id: item.model_id,
+            category_id: item.category_id,
+            aliases: item.aliases,
             is_active: item.is_active,
+            attributes: item.attributes ?? {},
         });
     };
 
     const handleCancel = () => {
         setEditingId(null);
+        setBarcodeText("");
+        setComponents([]);
         setFormData({
             code: "",
             name: "",
             brand_id: undefined,
             model_id: undefined,
             is_active: true,
+            attributes: {},
         });
     };
 
+    const updateComponent = (index: number, changes: Partial<ItemComponent>) => {
+        setComponents(components.map((c, i) => (i === index ? { ...c, ...changes } : c)));
+    };
+
+    const setAttribute = (name: string, value: string) => {
+        setFormData({ ...formData, attributes: { ...formData.attributes, [name]: value } });
+    };
+
     const handleDelete = async (id: number) => {
         if (confirm("Are you sure you want to delete this item?")) {
             try {
-                await deleteItem(id);
+                const outcome = await deleteItem(id);
+                if (outcome === "Deactivated") {
+                    alert("This item is in use, so it was deactivated instead of deleted.");
+                }
                 fetchData();
             } catch (error) {
                 console.error("Failed to delete item:", error);
@@ -115,47 +240,50 @@ function ItemsPage() {
         fileInputRef.current?.click();
     };
 
-    const handleFileChange = async (e: React.ChangeEvent<HTMLInputElement>) => {
+    const handleFileChange = (e: React.ChangeEvent<HTMLInputElement>) => {
         const file = e.target.files?.[0];
-        if (!file) return;
-
-        const reader = new FileReader();
-        reader.onload = async (evt) => {
-            try {
-                const bstr = evt.target?.result;
-                const wb = XLSX.read(bstr, { type: "binary" });
-                const wsname = wb.SheetNames[0];
-                const ws = wb.Sheets[wsname];
-                const data = XLSX.utils.sheet_to_json(ws);
+        // Reset file input so the same file can be picked again
+        e.target.value = "";
+        if (file) {
+            setImportFile(file);
+        }
+    };
 
-                // Map data to ImportItem format
-                // Expected headers: Code, Name, Brand, Model
-                const importData: ImportItem[] = data.map((row: any) => ({
-                    code: row.Code?.toString() || "",
-                    name: row.Name?.toString() || "",
-                    brand_name: row.Brand?.toString() || "",
-                    model_name: row.Model?.toString() || "",
-                })).filter(item => item.code && item.name); // Basic validation
+    const handleExport = async () => {
+        try {
+            const data = await exportItems();
+            const rows = data.map((item) => ({
+                Code: item.code,
+                Name: item.name,
+                Brand: item.brand_name,
+                Model: item.model_name,
+                ...Object.fromEntries(attributeDefs.map((def) => [def.name, item.attributes?.[def.name] ?? ""])),
+            }));
+            const wb = XLSX.utils.book_new();
+            XLSX.utils.book_append_sheet(wb, XLSX.utils.json_to_sheet(rows), "Items");
+            XLSX.writeFile(wb, "items.xlsx");
+        } catch (error) {
+            console.error("Failed to export items:", error);
+            alert("Error exporting items: " + error);
+        }
+    };
 
-                if (importData.length === 0) {
-                    alert("No valid items found in the file. Please check headers: Code, Name, Brand, Model");
-                    return;
-                }
+    const toggleSelected = (id: number) => {
+        setSelectedIds((prev) => (prev.includes(id) ? prev.filter((x) => x !== id) : [...prev, id]));
+    };
 
-                await importItems(importData);
-                alert("Items imported successfully!");
-                fetchData();
-            } catch (error) {
-                console.error("Failed to import items:", error);
-                alert("Error importing items: " + error);
-            } finally {
-                // Reset file input
-                if (fileInputRef.current) {
-                    fileInputRef.current.value = "";
-                }
-            }
-        };
-        reader.readAsBinaryString(file);
+    const handlePrintLabels = async () => {
+        try {
+            const path = await generateItemLabels({
+                item_ids: selectedIds,
+                symbology: labelSymbology,
+                format: "pdf",
+            });
+            await openPath(path);
+        } catch (error) {
+            console.error("Failed to generate labels:", error);
+            alert("Error generating labels: " + error);
+        }
     };
 
     const getBrandName = (id?: number) => brands.find((b) => b.id === id)?.name || "N/A";
@@ -165,21 +293,47 @@ function ItemsPage() {
         <div className="space-y-6">
             <div className="flex items-center justify-between">
                 <h2 className="text-3xl font-bold tracking-tight">Items Management</h2>
-                <div>
+                <div className="flex gap-2">
                     <input
                         type="file"
                         ref={fileInputRef}
                         className="hidden"
-                        accept=".xlsx, .xls"
+                        accept=".xlsx, .xls, .ods, .csv"
                         onChange={handleFileChange}
                     />
                     <Button onClick={handleImportClick} variant="outline" className="gap-2">
                         <Upload className="h-4 w-4" />
                         Import Items
                     </Button>
+                    <Button onClick={handleExport} variant="outline" className="gap-2">
+                        <Download className="h-4 w-4" />
+                        Export Items
+                    </Button>
+                    <Select value={labelSymbology} onValueChange={(value) => setLabelSymbology(value as "code128" | "qr")}>
+                        <SelectTrigger className="w-[120px]">
+                            <SelectValue />
+                        </SelectTrigger>
+                        <SelectContent>
+                            <SelectItem value="code128">Code 128</SelectItem>
+                            <SelectItem value="qr">QR Code</SelectItem>
+                        </SelectContent>
+                    </Select>
+                    <Button
+                        onClick={handlePrintLabels}
+                        variant="outline"
+                        className="gap-2"
+                        disabled={selectedIds.length === 0}
+                    >
+                        <Tag className="h-4 w-4" />
+                        Print Labels{selectedIds.length > 0 ? ` (${selectedIds.length})` : ""}
+                    </Button>
                 </div>
             </div>
 
+            {importFile && (
+                <ItemImportPanel file={importFile} onClose={() => setImportFile(null)} onImported={fetchData} />
+            )}
+
             <Card>
                 <CardContent className="pt-6">
                     <form onSubmit={handleSubmit} className="flex flex-row gap-4 items-end overflow-x-auto pb-2">
@@ -231,14 +385,80 @@ function ItemsPage() {
                                     <SelectValue placeholder="Select Model" />
                                 </SelectTrigger>
                                 <SelectContent>
-                                    {models.map((model) => (
-                                        <SelectItem key={model.id} value={model.id!.toString()}>
-                                            {model.name}
-                                        </SelectItem>
-                                    ))}
+                                    {models
+                                        .filter((model) => !formData.brand_id || !model.brand_id || model.brand_id === formData.brand_id)
+                                        .map((model) => (
+                                            <SelectItem key={model.id} value={model.id!.toString()}>
+                                                {model.name}
+                                            </SelectItem>
+                                        ))}
                                 </SelectContent>
                             </Select>
                         </div>
+                        <div className="flex-[1.5] min-w-[150px]">
+                            <Label htmlFor="category">Category</Label>
+                            <Combobox
+                                id="category"
+                                options={[
+                                    { label: "(None)", value: "none" },
+                                    ...categories.map((c) => ({ label: c.path || c.name, value: String(c.id) })),
+                                ]}
+                                value={formData.category_id ? String(formData.category_id) : "none"}
+                                onChange={(val) => setFormData({ ...formData, category_id: val === "none" ? undefined : Number(val) })}
+                                placeholder="Select Category"
+                            />
+                        </div>
+                        <div className="flex-[1.5] min-w-[150px]">
+                            <Label htmlFor="aliases">Aliases</Label>
+                            <Input
+                                id="aliases"
+                                value={formData.aliases || ""}
+                                onChange={(e) => setFormData({ ...formData, aliases: e.target.value })}
+                                placeholder="Other names"
+                            />
+                        </div>
+                        <div className="flex-[1.5] min-w-[150px]">
+                            <Label htmlFor="barcodes">Barcodes</Label>
+                            <Input
+                                id="barcodes"
+                                value={barcodeText}
+                                onChange={(e) => setBarcodeText(e.target.value)}
+                                placeholder="EAN, own code, ..."
+                            />
+                        </div>
+                        {attributeDefs.map((def) => (
+                            <div key={def.id} className="flex-[1] min-w-[120px]">
+                                <Label htmlFor={`attr-${def.id}`}>
+                                    {def.name}
+                                    {def.is_required && " *"}
+                                </Label>
+                                {def.data_type === "enum" ? (
+                                    <Select
+                                        value={formData.attributes?.[def.name] ?? ""}
+                                        onValueChange={(value) => setAttribute(def.name, value)}
+                                    >
+                                        <SelectTrigger id={`attr-${def.id}`}>
+                                            <SelectValue placeholder={`Select ${def.name}`} />
+                                        </SelectTrigger>
+                                        <SelectContent>
+                                            {def.options?.map((option) => (
+                                                <SelectItem key={option} value={option}>
+                                                    {option}
+                                                </SelectItem>
+                                            ))}
+                                        </SelectContent>
+                                    </Select>
+                                ) : (
+                                    <Input
+                                        id={`attr-${def.id}`}
+                                        type={def.data_type === "text" ? "text" : def.data_type}
+                                        value={formData.attributes?.[def.name] ?? ""}
+                                        onChange={(e) => setAttribute(def.name, e.target.value)}
+                                        required={def.is_required}
+                                    />
+                                )}
+                            </div>
+                        ))}
                         <div className="flex items-center space-x-2 px-2 h-9 mb-[2px]">
                             <input
                                 type="checkbox"
@@ -259,28 +479,160 @@ function ItemsPage() {
                             )}
                         </div>
                     </form>
+
+                    <div className="mt-4 space-y-2">
+                        <div className="flex items-center justify-between">
+                            <Label>Kit Components</Label>
+                            <Button
+                                type="button"
+                                variant="outline"
+                                size="sm"
+                                onClick={() => setComponents([...components, { component_item_id: 0, quantity: 1 }])}
+                            >
+                                <Plus className="h-4 w-4 mr-2" />
+                                Add Component
+                            </Button>
+                        </div>
+                        {components.length > 0 && (
+                            <Table>
+                                <TableHeader>
+                                    <TableRow>
+                                        <TableHead className="w-[50px]">#</TableHead>
+                                        <TableHead>Component</TableHead>
+                                        <TableHead className="w-[140px]">Qty per Kit</TableHead>
+                                        <TableHead className="w-[60px]" />
+                                    </TableRow>
+                                </TableHeader>
+                                <TableBody>
+                                    {components.map((component, index) => (
+                                        <TableRow key={index}>
+                                            <TableCell>{index + 1}</TableCell>
+                                            <TableCell>
+                                                <Combobox
+                                                    options={allItems
+                                                        .filter((i) => i.id !== editingId && !i.is_kit && (i.is_active || i.id === component.component_item_id))
+                                                        .map((i) => ({ label: `${i.name} (${i.code})`, value: String(i.id) }))}
+                                                    value={component.component_item_id ? String(component.component_item_id) : ""}
+                                                    onChange={(val) => updateComponent(index, { component_item_id: Number(val) })}
+                                                    placeholder="Select Item"
+                                                    className="h-8"
+                                                />
+                                            </TableCell>
+                                            <TableCell>
+                                                <Input
+                                                    type="number"
+                                                    value={component.quantity}
+                                                    onChange={(e) => updateComponent(index, { quantity: Number(e.target.value) })}
+                                                    className="h-8"
+                                                />
+                                            </TableCell>
+                                            <TableCell>
+                                                <Button
+                                                    type="button"
+                                                    variant="ghost"
+                                                    size="icon"
+                                                    onClick={() => setComponents(components.filter((_, i) => i !== index))}
+                                                    className="h-8 w-8 text-destructive hover:bg-destructive/10"
+                                                >
+                                                    <Trash2 className="h-4 w-4" />
+                                                </Button>
+                                            </TableCell>
+                                        </TableRow>
+                                    ))}
+                                </TableBody>
+                            </Table>
+                        )}
+                    </div>
                 </CardContent>
             </Card>
 
+            <div className="flex gap-4">
+                <Input
+                    value={searchQuery}
+                    onChange={(e) => setSearchQuery(e.target.value)}
+                    placeholder="Search by code, name, brand, model or alias..."
+                    className="max-w-md"
+                />
+                <Select value={brandFilter} onValueChange={setBrandFilter}>
+                    <SelectTrigger className="w-[180px]">
+                        <SelectValue placeholder="All Brands" />
+                    </SelectTrigger>
+                    <SelectContent>
+                        <SelectItem value="all">All Brands</SelectItem>
+                        {brands.map((brand) => (
+                            <SelectItem key={brand.id} value={brand.id!.toString()}>
+                                {brand.name}
+                            </SelectItem>
+                        ))}
+                    </SelectContent>
+                </Select>
+                <div className="w-[220px]">
+                    <Combobox
+                        options={[
+                            { label: "All Categories", value: "all" },
+                            ...categories.map((c) => ({ label: c.path || c.name, value: String(c.id) })),
+                        ]}
+                        value={categoryFilter}
+                        onChange={setCategoryFilter}
+                        placeholder="All Categories"
+                    />
+                </div>
+                <Select value={statusFilter} onValueChange={setStatusFilter}>
+                    <SelectTrigger className="w-[140px]">
+                        <SelectValue placeholder="All Statuses" />
+                    </SelectTrigger>
+                    <SelectContent>
+                        <SelectItem value="all">All Statuses</SelectItem>
+                        <SelectItem value="active">Active</SelectItem>
+                        <SelectItem value="inactive">Inactive</SelectItem>
+                    </SelectContent>
+                </Select>
+            </div>
+
             <div className="rounded-md border bg-card">
                 <Table>
                     <TableHeader>
                         <TableRow>
+                            <TableHead className="w-[40px]">
+                                <input
+                                    type="checkbox"
+                                    checked={items.length > 0 && selectedIds.length === items.length}
+                                    onChange={(e) => setSelectedIds(e.target.checked ? items.map((item) => item.id!) : [])}
+                                    className="h-4 w-4 rounded border-gray-300"
+                                />
+                            </TableHead>
                             <TableHead className="w-[60px]">S.No</TableHead>
-                            <TableHead className="w-[120px]">Code</TableHead>
-                            <TableHead>Name</TableHead>
-                            <TableHead>Brand</TableHead>
-                            <TableHead>Model</TableHead>
-                            <TableHead className="w-[100px]">Status</TableHead>
+                            <TableHead className="w-[120px]">{sortableHead("code", "Code")}</TableHead>
+                            <TableHead>{sortableHead("name", "Name")}</TableHead>
+                            <TableHead>{sortableHead("brand", "Brand")}</TableHead>
+                            <TableHead>{sortableHead("model", "Model")}</TableHead>
+                            <TableHead className="w-[100px]">{sortableHead("is_active", "Status")}</TableHead>
                             <TableHead className="text-right">Actions</TableHead>
                         </TableRow>
                     </TableHeader>
                     <TableBody>
                         {items.map((item, index) => (
                             <TableRow key={item.id}>
-                                <TableCell className="font-medium text-muted-foreground">{index + 1}</TableCell>
+                                <TableCell>
+                                    <input
+                                        type="checkbox"
+                                        checked={selectedIds.includes(item.id!)}
+                                        onChange={() => toggleSelected(item.id!)}
+                                        className="h-4 w-4 rounded border-gray-300"
+                                    />
+                                </TableCell>
+                                <TableCell className="font-medium text-muted-foreground">
+                                    {(pageSize === -1 ? 0 : (currentPage - 1) * pageSize) + index + 1}
+                                </TableCell>
                                 <TableCell className="font-medium">{item.code}</TableCell>
-                                <TableCell>{item.name}</TableCell>
+                                <TableCell>
+                                    {item.name}
+                                    {item.is_kit && (
+                                        <span className="ml-2 inline-flex items-center rounded-full bg-blue-100 px-2 py-0.5 text-xs font-medium text-blue-800">
+                                            Kit
+                                        </span>
+                                    )}
+                                </TableCell>
                                 <TableCell>{getBrandName(item.brand_id)}</TableCell>
                                 <TableCell>{getModelName(item.model_id)}</TableCell>
                                 <TableCell>
@@ -316,13 +668,25 @@ function ItemsPage() {
                         ))}
                         {items.length === 0 && (
                             <TableRow>
-                                <TableCell colSpan={6} className="h-24 text-center text-muted-foreground">
+                                <TableCell colSpan={8} className="h-24 text-center text-muted-foreground">
                                     No items found.
                                 </TableCell>
                             </TableRow>
                         )}
                     </TableBody>
                 </Table>
+                <div className="border-t">
+                    <PaginationControls
+                        currentPage={currentPage}
+                        totalCount={totalCount}
+                        pageSize={pageSize}
+                        onPageChange={setCurrentPage}
+                        onPageSizeChange={(size) => {
+                            setPageSize(size);
+                            setCurrentPage(1);
+                        }}
+                    />
+                </div>
             </div>
         </div>
     );
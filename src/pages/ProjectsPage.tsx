@@ -0,0 +1,456 @@
+import { useEffect, useState } from "react";
+import {
+    createProject,
+    deleteProject,
+    getProjectConsumption,
+    getProjects,
+    getSites,
+    updateProject,
+    Project,
+    ProjectConsumptionSummary,
+    ProjectStatus,
+    Site,
+} from "../api";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Combobox } from "@/components/ui/combobox";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Card, CardContent } from "@/components/ui/card";
+import { Trash2, Pencil, X } from "lucide-react";
+import { formatDate } from "@/lib/utils";
+
+type ConsumptionGroup = "project" | "activity" | "item" | "month";
+
+const emptyProject: Omit<Project, "id"> = {
+    code: "",
+    name: "",
+    site_id: 0,
+    start_date: "",
+    end_date: "",
+    status: "Active",
+    description: "",
+};
+
+const statusStyles: Record<ProjectStatus, string> = {
+    Active: "bg-green-100 text-green-800",
+    "On Hold": "bg-amber-100 text-amber-800",
+    Completed: "bg-gray-100 text-gray-800",
+};
+
+function ProjectsPage() {
+    const [view, setView] = useState<"projects" | "consumption">("projects");
+    const [projects, setProjects] = useState<Project[]>([]);
+    const [sites, setSites] = useState<Site[]>([]);
+    const [formData, setFormData] = useState<Omit<Project, "id">>(emptyProject);
+    const [editingId, setEditingId] = useState<number | null>(null);
+
+    const [groupBy, setGroupBy] = useState<ConsumptionGroup>("project");
+    const [projectFilter, setProjectFilter] = useState("all");
+    const [siteFilter, setSiteFilter] = useState("all");
+    const [fromDate, setFromDate] = useState("");
+    const [toDate, setToDate] = useState("");
+    const [consumption, setConsumption] = useState<ProjectConsumptionSummary[]>([]);
+
+    const fetchProjects = async () => {
+        try {
+            setProjects(await getProjects());
+        } catch (error) {
+            console.error("Failed to fetch projects:", error);
+        }
+    };
+
+    const fetchConsumption = async () => {
+        try {
+            setConsumption(await getProjectConsumption(
+                groupBy,
+                projectFilter === "all" ? undefined : Number(projectFilter),
+                siteFilter === "all" ? undefined : Number(siteFilter),
+                undefined,
+                fromDate || undefined,
+                toDate || undefined
+            ));
+        } catch (error) {
+            console.error("Failed to fetch project consumption:", error);
+        }
+    };
+
+    useEffect(() => {
+        fetchProjects();
+        getSites()
+            .then((data) => setSites(data.items))
+            .catch((error) => console.error("Failed to fetch sites:", error));
+    }, []);
+
+    useEffect(() => {
+        if (view === "consumption") {
+            fetchConsumption();
+        }
+    }, [view, groupBy, projectFilter, siteFilter, fromDate, toDate]);
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        if (!formData.site_id) {
+            alert("Please select the site the project belongs to");
+            return;
+        }
+        const project = {
+            ...formData,
+            start_date: formData.start_date || undefined,
+            end_date: formData.end_date || undefined,
+        };
+        try {
+            if (editingId) {
+                await updateProject({ id: editingId, ...project });
+            } else {
+                await createProject(project);
+            }
+            setFormData(emptyProject);
+            setEditingId(null);
+            fetchProjects();
+        } catch (error) {
+            console.error("Failed to save project:", error);
+            alert("Error saving project: " + error);
+        }
+    };
+
+    const handleEdit = (project: Project) => {
+        setEditingId(project.id!);
+        setFormData({
+            code: project.code,
+            name: project.name,
+            site_id: project.site_id,
+            start_date: project.start_date || "",
+            end_date: project.end_date || "",
+            status: project.status,
+            description: project.description || "",
+        });
+    };
+
+    const handleCancel = () => {
+        setEditingId(null);
+        setFormData(emptyProject);
+    };
+
+    const handleDelete = async (id: number) => {
+        if (confirm("Are you sure you want to delete this project?")) {
+            try {
+                await deleteProject(id);
+                fetchProjects();
+            } catch (error) {
+                console.error("Failed to delete project:", error);
+                alert("Error deleting project: " + error);
+            }
+        }
+    };
+
+    const siteOptions = sites
+        .filter(s => s.type === "Site")
+        .map(s => ({ label: s.name, value: String(s.id) }));
+
+    const groupLabels: Record<ConsumptionGroup, string> = {
+        project: "Project",
+        activity: "Project / Activity",
+        item: "Item",
+        month: "Month",
+    };
+
+    const totals = consumption.reduce(
+        (acc, row) => ({
+            used: acc.used + row.used_quantity,
+            damaged: acc.damaged + row.damaged_quantity,
+            total: acc.total + row.total_quantity,
+            value: acc.value + (row.total_value ?? 0),
+        }),
+        { used: 0, damaged: 0, total: 0, value: 0 }
+    );
+
+    return (
+        <div className="space-y-6">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Projects</h2>
+                <div className="flex gap-2">
+                    <Button
+                        variant={view === "projects" ? "default" : "outline"}
+                        onClick={() => setView("projects")}
+                    >
+                        Projects
+                    </Button>
+                    <Button
+                        variant={view === "consumption" ? "default" : "outline"}
+                        onClick={() => setView("consumption")}
+                    >
+                        Consumption
+                    </Button>
+                </div>
+            </div>
+
+            {view === "projects" ? (
+                <>
+                    <Card>
+                        <CardContent className="pt-6">
+                            <form onSubmit={handleSubmit} className="grid grid-cols-2 md:grid-cols-4 gap-4 items-end">
+                                <div>
+                                    <Label htmlFor="code">Code</Label>
+                                    <Input
+                                        id="code"
+                                        value={formData.code}
+                                        onChange={(e) => setFormData({ ...formData, code: e.target.value })}
+                                        required
+                                        placeholder="PRJ001"
+                                    />
+                                </div>
+                                <div>
+                                    <Label htmlFor="name">Name</Label>
+                                    <Input
+                                        id="name"
+                                        value={formData.name}
+                                        onChange={(e) => setFormData({ ...formData, name: e.target.value })}
+                                        required
+                                        placeholder="Tower A foundation"
+                                    />
+                                </div>
+                                <div>
+                                    <Label>Site</Label>
+                                    <Combobox
+                                        options={siteOptions}
+                                        value={formData.site_id ? String(formData.site_id) : ""}
+                                        onChange={(val) => setFormData({ ...formData, site_id: Number(val) })}
+                                        placeholder="Select Site"
+                                    />
+                                </div>
+                                <div>
+                                    <Label>Status</Label>
+                                    <Select
+                                        value={formData.status}
+                                        onValueChange={(val) => setFormData({ ...formData, status: val as ProjectStatus })}
+                                    >
+                                        <SelectTrigger>
+                                            <SelectValue />
+                                        </SelectTrigger>
+                                        <SelectContent>
+                                            {Object.keys(statusStyles).map(status => (
+                                                <SelectItem key={status} value={status}>{status}</SelectItem>
+                                            ))}
+                                        </SelectContent>
+                                    </Select>
+                                </div>
+                                <div>
+                                    <Label htmlFor="start_date">Start Date</Label>
+                                    <Input
+                                        id="start_date"
+                                        type="date"
+                                        value={formData.start_date}
+                                        onChange={(e) => setFormData({ ...formData, start_date: e.target.value })}
+                                    />
+                                </div>
+                                <div>
+                                    <Label htmlFor="end_date">End Date</Label>
+                                    <Input
+                                        id="end_date"
+                                        type="date"
+                                        value={formData.end_date}
+                                        onChange={(e) => setFormData({ ...formData, end_date: e.target.value })}
+                                    />
+                                </div>
+                                <div>
+                                    <Label htmlFor="description">Description</Label>
+                                    <Input
+                                        id="description"
+                                        value={formData.description}
+                                        onChange={(e) => setFormData({ ...formData, description: e.target.value })}
+                                    />
+                                </div>
+                                <div className="flex items-center gap-4 h-9">
+                                    <Button type="submit">{editingId ? "Update Project" : "Add Project"}</Button>
+                                    {editingId && (
+                                        <Button type="button" variant="outline" onClick={handleCancel}>
+                                            <X className="h-4 w-4 mr-2" />
+                                            Cancel
+                                        </Button>
+                                    )}
+                                </div>
+                            </form>
+                        </CardContent>
+                    </Card>
+
+                    <div className="rounded-md border bg-card">
+                        <Table>
+                            <TableHeader>
+                                <TableRow>
+                                    <TableHead className="w-[60px]">S.No</TableHead>
+                                    <TableHead className="w-[100px]">Code</TableHead>
+                                    <TableHead>Name</TableHead>
+                                    <TableHead>Site</TableHead>
+                                    <TableHead>Start</TableHead>
+                                    <TableHead>End</TableHead>
+                                    <TableHead className="w-[120px]">Status</TableHead>
+                                    <TableHead className="text-right">Actions</TableHead>
+                                </TableRow>
+                            </TableHeader>
+                            <TableBody>
+                                {projects.map((project, index) => (
+                                    <TableRow key={project.id}>
+                                        <TableCell className="font-medium text-muted-foreground">{index + 1}</TableCell>
+                                        <TableCell className="font-medium">{project.code}</TableCell>
+                                        <TableCell>{project.name}</TableCell>
+                                        <TableCell>{project.site_name}</TableCell>
+                                        <TableCell className="whitespace-nowrap">
+                                            {project.start_date ? formatDate(project.start_date) : "-"}
+                                        </TableCell>
+                                        <TableCell className="whitespace-nowrap">
+                                            {project.end_date ? formatDate(project.end_date) : "-"}
+                                        </TableCell>
+                                        <TableCell>
+                                            <span className={`inline-flex items-center rounded-full px-2.5 py-0.5 text-xs font-medium ${statusStyles[project.status]}`}>
+                                                {project.status}
+                                            </span>
+                                        </TableCell>
+                                        <TableCell className="text-right space-x-2">
+                                            <Button
+                                                variant="ghost"
+                                                size="icon"
+                                                onClick={() => handleEdit(project)}
+                                                className="text-primary hover:text-primary hover:bg-primary/10"
+                                            >
+                                                <Pencil className="h-4 w-4" />
+                                            </Button>
+                                            <Button
+                                                variant="ghost"
+                                                size="icon"
+                                                onClick={() => handleDelete(project.id!)}
+                                                className="text-destructive hover:text-destructive hover:bg-destructive/10"
+                                            >
+                                                <Trash2 className="h-4 w-4" />
+                                            </Button>
+                                        </TableCell>
+                                    </TableRow>
+                                ))}
+                                {projects.length === 0 && (
+                                    <TableRow>
+                                        <TableCell colSpan={8} className="h-24 text-center text-muted-foreground">
+                                            No projects found.
+                                        </TableCell>
+                                    </TableRow>
+                                )}
+                            </TableBody>
+                        </Table>
+                    </div>
+                </>
+            ) : (
+                <>
+                    <div className="flex flex-wrap gap-4 items-end">
+                        <div className="w-[180px]">
+                            <Label>Group By</Label>
+                            <Select value={groupBy} onValueChange={(val) => setGroupBy(val as ConsumptionGroup)}>
+                                <SelectTrigger>
+                                    <SelectValue />
+                                </SelectTrigger>
+                                <SelectContent>
+                                    {Object.entries(groupLabels).map(([value, label]) => (
+                                        <SelectItem key={value} value={value}>{label}</SelectItem>
+                                    ))}
+                                </SelectContent>
+                            </Select>
+                        </div>
+                        <div className="w-[240px]">
+                            <Label>Project</Label>
+                            <Combobox
+                                options={[
+                                    { label: "All Projects", value: "all" },
+                                    ...projects.map(p => ({ label: `${p.name} (${p.code})`, value: String(p.id) })),
+                                ]}
+                                value={projectFilter}
+                                onChange={setProjectFilter}
+                                placeholder="All Projects"
+                            />
+                        </div>
+                        <div className="w-[200px]">
+                            <Label>Site</Label>
+                            <Combobox
+                                options={[{ label: "All Sites", value: "all" }, ...siteOptions]}
+                                value={siteFilter}
+                                onChange={setSiteFilter}
+                                placeholder="All Sites"
+                            />
+                        </div>
+                        <div>
+                            <Label>From</Label>
+                            <Input type="date" value={fromDate} onChange={(e) => setFromDate(e.target.value)} />
+                        </div>
+                        <div>
+                            <Label>To</Label>
+                            <Input type="date" value={toDate} onChange={(e) => setToDate(e.target.value)} />
+                        </div>
+                    </div>
+
+                    <div className="rounded-md border bg-card">
+                        <Table>
+                            <TableHeader>
+                                <TableRow>
+                                    <TableHead>{groupLabels[groupBy]}</TableHead>
+                                    <TableHead className="text-right">Vouchers</TableHead>
+                                    <TableHead className="text-right">Used</TableHead>
+                                    <TableHead className="text-right">Damaged</TableHead>
+                                    <TableHead className="text-right">Total Qty</TableHead>
+                                    <TableHead className="text-right">Value</TableHead>
+                                </TableRow>
+                            </TableHeader>
+                            <TableBody>
+                                {consumption.map((row) => (
+                                    <TableRow key={row.group_key}>
+                                        <TableCell className="font-medium">{row.group_name}</TableCell>
+                                        <TableCell className="text-right">{row.voucher_count}</TableCell>
+                                        <TableCell className="text-right">{row.used_quantity}</TableCell>
+                                        <TableCell className="text-right">{row.damaged_quantity}</TableCell>
+                                        <TableCell className="text-right">{row.total_quantity}</TableCell>
+                                        <TableCell className="text-right">
+                                            {row.total_value != null ? row.total_value.toFixed(2) : "-"}
+                                            {row.unvalued_quantity > 0 && (
+                                                <span className="ml-1 text-xs text-muted-foreground" title="Quantity without a rate">
+                                                    (+{row.unvalued_quantity} unvalued)
+                                                </span>
+                                            )}
+                                        </TableCell>
+                                    </TableRow>
+                                ))}
+                                {consumption.length === 0 ? (
+                                    <TableRow>
+                                        <TableCell colSpan={6} className="h-24 text-center text-muted-foreground">
+                                            No consumption booked to projects.
+                                        </TableCell>
+                                    </TableRow>
+                                ) : (
+                                    <TableRow className="font-semibold">
+                                        <TableCell>Total</TableCell>
+                                        <TableCell />
+                                        <TableCell className="text-right">{totals.used}</TableCell>
+                                        <TableCell className="text-right">{totals.damaged}</TableCell>
+                                        <TableCell className="text-right">{totals.total}</TableCell>
+                                        <TableCell className="text-right">{totals.value.toFixed(2)}</TableCell>
+                                    </TableRow>
+                                )}
+                            </TableBody>
+                        </Table>
+                    </div>
+                </>
+            )}
+        </div>
+    );
+}
+
+export default ProjectsPage;
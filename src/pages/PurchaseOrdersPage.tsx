@@ -0,0 +1,546 @@
+import { useEffect, useState } from "react";
+import { useNavigate } from "react-router-dom";
+import {
+    cancelPurchaseOrder,
+    createPurchaseOrder,
+    deletePurchaseOrder,
+    getItems,
+    getOutstandingPurchaseOrders,
+    getPurchaseOrder,
+    getPurchaseOrders,
+    getSites,
+    getSuppliers,
+    updatePurchaseOrder,
+    Item,
+    OutstandingPurchaseOrderLine,
+    PurchaseOrder,
+    PurchaseOrderDisplay,
+    PurchaseOrderItem,
+    PurchaseOrderStatus,
+    Site,
+    Supplier,
+} from "../api";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Combobox } from "@/components/ui/combobox";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Ban, PackagePlus, Pencil, Plus, Save, Trash2, X } from "lucide-react";
+import { PaginationControls } from "@/components/ui/pagination-controls";
+import { formatDate } from "@/lib/utils";
+
+const today = () => new Date().toISOString().split("T")[0];
+
+const emptyOrder = (): PurchaseOrder => ({
+    po_date: today(),
+    supplier_id: 0,
+    items: [{ item_id: 0, ordered_quantity: 1 }],
+});
+
+const statusStyles: Record<PurchaseOrderStatus, string> = {
+    Open: "bg-blue-100 text-blue-800",
+    Partial: "bg-amber-100 text-amber-800",
+    Closed: "bg-green-100 text-green-800",
+    Cancelled: "bg-red-100 text-red-800",
+};
+
+function PurchaseOrdersPage() {
+    const navigate = useNavigate();
+    const [view, setView] = useState<"orders" | "outstanding">("orders");
+    const [suppliers, setSuppliers] = useState<Supplier[]>([]);
+    const [sites, setSites] = useState<Site[]>([]);
+    const [items, setItems] = useState<Item[]>([]);
+
+    const [orders, setOrders] = useState<PurchaseOrderDisplay[]>([]);
+    const [supplierFilter, setSupplierFilter] = useState("all");
+    const [statusFilter, setStatusFilter] = useState("all");
+    const [currentPage, setCurrentPage] = useState(1);
+    const [pageSize, setPageSize] = useState(50);
+    const [totalCount, setTotalCount] = useState(0);
+
+    const [outstanding, setOutstanding] = useState<OutstandingPurchaseOrderLine[]>([]);
+
+    // The order being raised or edited; null while the form is closed
+    const [order, setOrder] = useState<PurchaseOrder | null>(null);
+
+    useEffect(() => {
+        Promise.all([getSuppliers(), getSites(), getItems()])
+            .then(([suppliersData, sitesData, itemsData]) => {
+                setSuppliers(suppliersData);
+                setSites(sitesData.items);
+                setItems(itemsData.items);
+            })
+            .catch((error) => console.error("Failed to fetch master data:", error));
+    }, []);
+
+    const toId = (value: string) => (value === "all" ? undefined : Number(value));
+
+    const fetchOrders = async () => {
+        try {
+            const data = await getPurchaseOrders(
+                toId(supplierFilter),
+                statusFilter === "all" ? undefined : (statusFilter as PurchaseOrderStatus),
+                currentPage,
+                pageSize
+            );
+            setOrders(data.items);
+            setTotalCount(data.total_count);
+        } catch (error) {
+            console.error("Failed to fetch purchase orders:", error);
+        }
+    };
+
+    const fetchOutstanding = async () => {
+        try {
+            setOutstanding(await getOutstandingPurchaseOrders(toId(supplierFilter)));
+        } catch (error) {
+            console.error("Failed to fetch outstanding purchase orders:", error);
+        }
+    };
+
+    useEffect(() => {
+        setCurrentPage(1);
+    }, [supplierFilter, statusFilter]);
+
+    useEffect(() => {
+        if (view === "orders") {
+            fetchOrders();
+        } else {
+            fetchOutstanding();
+        }
+    }, [view, supplierFilter, statusFilter, currentPage, pageSize]);
+
+    const refresh = () => (view === "orders" ? fetchOrders() : fetchOutstanding());
+
+    const handleEdit = async (id: number) => {
+        try {
+            setOrder(await getPurchaseOrder(id));
+        } catch (error) {
+            console.error("Failed to load purchase order:", error);
+            alert("Error loading purchase order: " + error);
+        }
+    };
+
+    const updateLine = (index: number, changes: Partial<PurchaseOrderItem>) => {
+        if (!order) return;
+        const lines = order.items.map((line, i) => (i === index ? { ...line, ...changes } : line));
+        setOrder({ ...order, items: lines });
+    };
+
+    const handleSave = async () => {
+        if (!order) return;
+        if (!order.supplier_id) {
+            alert("Please select a supplier");
+            return;
+        }
+        const lines = order.items.filter(l => l.item_id > 0);
+        if (lines.length === 0) {
+            alert("Please add at least one item");
+            return;
+        }
+        try {
+            if (order.id) {
+                await updatePurchaseOrder({ ...order, items: lines });
+            } else {
+                await createPurchaseOrder({ ...order, items: lines });
+            }
+            setOrder(null);
+            refresh();
+        } catch (error) {
+            console.error("Failed to save purchase order:", error);
+            alert("Error saving purchase order: " + error);
+        }
+    };
+
+    const handleCancelOrder = async (id: number) => {
+        if (!confirm("Cancel this purchase order? Nothing more can be received against it.")) return;
+        try {
+            await cancelPurchaseOrder(id);
+            refresh();
+        } catch (error) {
+            console.error("Failed to cancel purchase order:", error);
+            alert("Error cancelling purchase order: " + error);
+        }
+    };
+
+    const handleDelete = async (id: number) => {
+        if (!confirm("Are you sure you want to delete this purchase order?")) return;
+        try {
+            await deletePurchaseOrder(id);
+            refresh();
+        } catch (error) {
+            console.error("Failed to delete purchase order:", error);
+            alert("Error deleting purchase order: " + error);
+        }
+    };
+
+    const handleReceive = (id: number) => {
+        navigate(`/inventory-vouchers?type=Purchase Inward&po=${id}`);
+    };
+
+    const statusBadge = (status: PurchaseOrderStatus) => (
+        <span className={`inline-flex items-center rounded-full px-2.5 py-0.5 text-xs font-medium ${statusStyles[status]}`}>
+            {status}
+        </span>
+    );
+
+    return (
+        <div className="space-y-6">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Purchase Orders</h2>
+                <div className="flex gap-2">
+                    <Button
+                        variant={view === "orders" ? "default" : "outline"}
+                        onClick={() => setView("orders")}
+                    >
+                        Orders
+                    </Button>
+                    <Button
+                        variant={view === "outstanding" ? "default" : "outline"}
+                        onClick={() => setView("outstanding")}
+                    >
+                        Outstanding
+                    </Button>
+                    {!order && (
+                        <Button onClick={() => setOrder(emptyOrder())}>
+                            <Plus className="mr-2 h-4 w-4" /> New Order
+                        </Button>
+                    )}
+                </div>
+            </div>
+
+            {order && (
+                <Card>
+                    <CardHeader>
+                        <CardTitle>{order.id ? `Purchase Order #${order.po_number}` : "New Purchase Order"}</CardTitle>
+                    </CardHeader>
+                    <CardContent className="space-y-4">
+                        <div className="grid grid-cols-2 md:grid-cols-5 gap-4">
+                            <div>
+                                <Label>PO Date</Label>
+                                <Input
+                                    type="date"
+                                    value={order.po_date}
+                                    onChange={(e) => setOrder({ ...order, po_date: e.target.value })}
+                                />
+                            </div>
+                            <div>
+                                <Label>Supplier</Label>
+                                <Combobox
+                                    options={suppliers
+                                        .filter(s => s.is_active || s.id === order.supplier_id)
+                                        .map(s => ({ label: `${s.name} (${s.code})`, value: String(s.id) }))}
+                                    value={order.supplier_id ? String(order.supplier_id) : ""}
+                                    onChange={(val) => setOrder({ ...order, supplier_id: Number(val) })}
+                                    placeholder="Select Supplier"
+                                />
+                            </div>
+                            <div>
+                                <Label>Delivery Site</Label>
+                                <Combobox
+                                    options={sites
+                                        .filter(s => s.is_active || s.id === order.delivery_site_id)
+                                        .map(s => ({ label: `${s.name} (${s.type})`, value: String(s.id) }))}
+                                    value={order.delivery_site_id ? String(order.delivery_site_id) : ""}
+                                    onChange={(val) => setOrder({ ...order, delivery_site_id: Number(val) })}
+                                    placeholder="Select Site"
+                                />
+                            </div>
+                            <div>
+                                <Label>Expected Date</Label>
+                                <Input
+                                    type="date"
+                                    value={order.expected_date || ""}
+                                    onChange={(e) => setOrder({ ...order, expected_date: e.target.value || undefined })}
+                                />
+                            </div>
+                            <div>
+                                <Label>Remarks</Label>
+                                <Input
+                                    value={order.remarks || ""}
+                                    onChange={(e) => setOrder({ ...order, remarks: e.target.value })}
+                                    placeholder="Optional remarks"
+                                />
+                            </div>
+                        </div>
+
+                        <Table>
+                            <TableHeader>
+                                <TableRow>
+                                    <TableHead className="w-[50px]">#</TableHead>
+                                    <TableHead>Item</TableHead>
+                                    <TableHead className="w-[140px]">Ordered Qty</TableHead>
+                                    <TableHead className="w-[140px]">Rate</TableHead>
+                                    <TableHead className="w-[120px]">Received</TableHead>
+                                    <TableHead className="w-[60px]" />
+                                </TableRow>
+                            </TableHeader>
+                            <TableBody>
+                                {order.items.map((line, index) => (
+                                    <TableRow key={index}>
+                                        <TableCell>{index + 1}</TableCell>
+                                        <TableCell>
+                                            <Combobox
+                                                options={items
+                                                    .filter(i => i.is_active || i.id === line.item_id)
+                                                    .map(i => ({ label: `${i.name} (${i.code})`, value: String(i.id) }))}
+                                                value={line.item_id ? String(line.item_id) : ""}
+                                                onChange={(val) => updateLine(index, { item_id: Number(val) })}
+                                                placeholder="Select Item"
+                                                className="h-8"
+                                            />
+                                        </TableCell>
+                                        <TableCell>
+                                            <Input
+                                                type="number"
+                                                value={line.ordered_quantity}
+                                                onChange={(e) => updateLine(index, { ordered_quantity: Number(e.target.value) })}
+                                                className="h-8"
+                                            />
+                                        </TableCell>
+                                        <TableCell>
+                                            <Input
+                                                type="number"
+                                                value={line.rate ?? ""}
+                                                onChange={(e) => updateLine(index, {
+                                                    rate: e.target.value === "" ? undefined : Number(e.target.value),
+                                                })}
+                                                placeholder="Optional"
+                                                className="h-8"
+                                            />
+                                        </TableCell>
+                                        <TableCell className="text-muted-foreground">
+                                            {line.received_quantity ?? 0}
+                                        </TableCell>
+                                        <TableCell>
+                                            <Button
+                                                variant="ghost"
+                                                size="icon"
+                                                onClick={() => setOrder({ ...order, items: order.items.filter((_, i) => i !== index) })}
+                                                disabled={order.items.length === 1 || (line.received_quantity ?? 0) > 0}
+                                                className="h-8 w-8 text-destructive hover:bg-destructive/10"
+                                            >
+                                                <Trash2 className="h-4 w-4" />
+                                            </Button>
+                                        </TableCell>
+                                    </TableRow>
+                                ))}
+                            </TableBody>
+                        </Table>
+
+                        <div className="flex justify-between">
+                            <Button
+                                variant="outline"
+                                onClick={() => setOrder({ ...order, items: [...order.items, { item_id: 0, ordered_quantity: 1 }] })}
+                            >
+                                <Plus className="mr-2 h-4 w-4" /> Add Line
+                            </Button>
+                            <div className="flex gap-2">
+                                <Button variant="outline" onClick={() => setOrder(null)}>
+                                    <X className="mr-2 h-4 w-4" /> Cancel
+                                </Button>
+                                <Button onClick={handleSave}>
+                                    <Save className="mr-2 h-4 w-4" /> {order.id ? "Update Order" : "Raise Order"}
+                                </Button>
+                            </div>
+                        </div>
+                    </CardContent>
+                </Card>
+            )}
+
+            <div className="flex gap-4">
+                <div className="w-[260px]">
+                    <Combobox
+                        options={[
+                            { label: "All Suppliers", value: "all" },
+                            ...suppliers.map(s => ({ label: `${s.name} (${s.code})`, value: String(s.id) })),
+                        ]}
+                        value={supplierFilter}
+                        onChange={setSupplierFilter}
+                        placeholder="All Suppliers"
+                    />
+                </div>
+                {view === "orders" && (
+                    <div className="w-[200px]">
+                        <Select value={statusFilter} onValueChange={setStatusFilter}>
+                            <SelectTrigger>
+                                <SelectValue placeholder="All Statuses" />
+                            </SelectTrigger>
+                            <SelectContent>
+                                <SelectItem value="all">All Statuses</SelectItem>
+                                <SelectItem value="Open">Open</SelectItem>
+                                <SelectItem value="Partial">Partial</SelectItem>
+                                <SelectItem value="Closed">Closed</SelectItem>
+                                <SelectItem value="Cancelled">Cancelled</SelectItem>
+                            </SelectContent>
+                        </Select>
+                    </div>
+                )}
+            </div>
+
+            {view === "orders" ? (
+                <div className="rounded-md border bg-card">
+                    <Table>
+                        <TableHeader>
+                            <TableRow>
+                                <TableHead>PO No</TableHead>
+                                <TableHead>Date</TableHead>
+                                <TableHead>Supplier</TableHead>
+                                <TableHead>Delivery Site</TableHead>
+                                <TableHead>Expected</TableHead>
+                                <TableHead className="text-right">Ordered</TableHead>
+                                <TableHead className="text-right">Received</TableHead>
+                                <TableHead>Status</TableHead>
+                                <TableHead className="text-right">Actions</TableHead>
+                            </TableRow>
+                        </TableHeader>
+                        <TableBody>
+                            {orders.map((o) => {
+                                const receivable = o.status === "Open" || o.status === "Partial";
+                                return (
+                                    <TableRow key={o.id}>
+                                        <TableCell className="font-medium">{o.po_number}</TableCell>
+                                        <TableCell className="whitespace-nowrap">{formatDate(o.po_date)}</TableCell>
+                                        <TableCell>{o.supplier_name}</TableCell>
+                                        <TableCell>{o.delivery_site_name || "-"}</TableCell>
+                                        <TableCell className="whitespace-nowrap">
+                                            {o.expected_date ? formatDate(o.expected_date) : "-"}
+                                        </TableCell>
+                                        <TableCell className="text-right">{o.ordered_quantity}</TableCell>
+                                        <TableCell className="text-right">{o.received_quantity}</TableCell>
+                                        <TableCell>{statusBadge(o.status)}</TableCell>
+                                        <TableCell className="text-right">
+                                            <div className="flex justify-end gap-1">
+                                                <Button
+                                                    variant="ghost"
+                                                    size="icon"
+                                                    title="Receive goods"
+                                                    onClick={() => handleReceive(o.id)}
+                                                    disabled={!receivable}
+                                                    className="h-8 w-8 text-green-600 hover:text-green-700 hover:bg-green-50"
+                                                >
+                                                    <PackagePlus className="h-4 w-4" />
+                                                </Button>
+                                                <Button
+                                                    variant="ghost"
+                                                    size="icon"
+                                                    title="Edit"
+                                                    onClick={() => handleEdit(o.id)}
+                                                    disabled={o.status === "Cancelled"}
+                                                    className="h-8 w-8 text-primary hover:bg-primary/10"
+                                                >
+                                                    <Pencil className="h-4 w-4" />
+                                                </Button>
+                                                <Button
+                                                    variant="ghost"
+                                                    size="icon"
+                                                    title="Cancel order"
+                                                    onClick={() => handleCancelOrder(o.id)}
+                                                    disabled={!receivable}
+                                                    className="h-8 w-8 text-orange-600 hover:text-orange-700 hover:bg-orange-50"
+                                                >
+                                                    <Ban className="h-4 w-4" />
+                                                </Button>
+                                                <Button
+                                                    variant="ghost"
+                                                    size="icon"
+                                                    title="Delete"
+                                                    onClick={() => handleDelete(o.id)}
+                                                    className="h-8 w-8 text-destructive hover:bg-destructive/10"
+                                                >
+                                                    <Trash2 className="h-4 w-4" />
+                                                </Button>
+                                            </div>
+                                        </TableCell>
+                                    </TableRow>
+                                );
+                            })}
+                            {orders.length === 0 && (
+                                <TableRow>
+                                    <TableCell colSpan={9} className="h-24 text-center text-muted-foreground">
+                                        No purchase orders found.
+                                    </TableCell>
+                                </TableRow>
+                            )}
+                        </TableBody>
+                    </Table>
+                    <div className="border-t">
+                        <PaginationControls
+                            currentPage={currentPage}
+                            totalCount={totalCount}
+                            pageSize={pageSize}
+                            onPageChange={setCurrentPage}
+                            onPageSizeChange={(size) => {
+                                setPageSize(size);
+                                setCurrentPage(1);
+                            }}
+                        />
+                    </div>
+                </div>
+            ) : (
+                <div className="rounded-md border bg-card">
+                    <Table>
+                        <TableHeader>
+                            <TableRow>
+                                <TableHead>PO No</TableHead>
+                                <TableHead>Date</TableHead>
+                                <TableHead>Expected</TableHead>
+                                <TableHead>Supplier</TableHead>
+                                <TableHead>Delivery Site</TableHead>
+                                <TableHead>Item</TableHead>
+                                <TableHead className="text-right">Ordered</TableHead>
+                                <TableHead className="text-right">Received</TableHead>
+                                <TableHead className="text-right">Pending</TableHead>
+                                <TableHead className="text-right">Pending Value</TableHead>
+                            </TableRow>
+                        </TableHeader>
+                        <TableBody>
+                            {outstanding.map((line) => (
+                                <TableRow key={line.purchase_order_item_id}>
+                                    <TableCell className="font-medium">{line.po_number}</TableCell>
+                                    <TableCell className="whitespace-nowrap">{formatDate(line.po_date)}</TableCell>
+                                    <TableCell className="whitespace-nowrap">
+                                        {line.expected_date ? formatDate(line.expected_date) : "-"}
+                                    </TableCell>
+                                    <TableCell>{line.supplier_name}</TableCell>
+                                    <TableCell>{line.delivery_site_name || "-"}</TableCell>
+                                    <TableCell>{line.item_name} ({line.item_code})</TableCell>
+                                    <TableCell className="text-right">{line.ordered_quantity}</TableCell>
+                                    <TableCell className="text-right">{line.received_quantity}</TableCell>
+                                    <TableCell className="text-right font-medium">{line.pending_quantity}</TableCell>
+                                    <TableCell className="text-right">
+                                        {line.pending_value != null ? line.pending_value.toFixed(2) : "-"}
+                                    </TableCell>
+                                </TableRow>
+                            ))}
+                            {outstanding.length === 0 && (
+                                <TableRow>
+                                    <TableCell colSpan={10} className="h-24 text-center text-muted-foreground">
+                                        Nothing is outstanding.
+                                    </TableCell>
+                                </TableRow>
+                            )}
+                        </TableBody>
+                    </Table>
+                </div>
+            )}
+        </div>
+    );
+}
+
+export default PurchaseOrdersPage;
@@ -0,0 +1,630 @@
+import { useEffect, useState } from "react";
+import { useNavigate } from "react-router-dom";
+import {
+    approveMaterialRequisition,
+    cancelMaterialRequisition,
+    createMaterialRequisition,
+    deleteMaterialRequisition,
+    getItems,
+    getMaterialRequisition,
+    getMaterialRequisitions,
+    getRequisitionStatusReport,
+    getSites,
+    rejectMaterialRequisition,
+    updateMaterialRequisition,
+    Item,
+    MaterialRequisition,
+    MaterialRequisitionDisplay,
+    MaterialRequisitionItem,
+    RequisitionStatus,
+    RequisitionStatusLine,
+    Site,
+} from "../api";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Combobox } from "@/components/ui/combobox";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Ban, CheckCircle, Pencil, Plus, Save, Trash2, Truck, X } from "lucide-react";
+import { PaginationControls } from "@/components/ui/pagination-controls";
+import { formatDate } from "@/lib/utils";
+
+const today = () => new Date().toISOString().split("T")[0];
+
+const emptyRequisition = (): MaterialRequisition => ({
+    requisition_date: today(),
+    site_id: 0,
+    items: [{ item_id: 0, requested_quantity: 1 }],
+});
+
+const statusStyles: Record<RequisitionStatus, string> = {
+    Pending: "bg-gray-100 text-gray-800",
+    Approved: "bg-blue-100 text-blue-800",
+    Rejected: "bg-red-100 text-red-800",
+    Partial: "bg-amber-100 text-amber-800",
+    Fulfilled: "bg-green-100 text-green-800",
+    Cancelled: "bg-red-100 text-red-800",
+};
+
+function RequisitionsPage() {
+    const navigate = useNavigate();
+    const [view, setView] = useState<"requisitions" | "report">("requisitions");
+    const [sites, setSites] = useState<Site[]>([]);
+    const [items, setItems] = useState<Item[]>([]);
+
+    const [requisitions, setRequisitions] = useState<MaterialRequisitionDisplay[]>([]);
+    const [siteFilter, setSiteFilter] = useState("all");
+    const [statusFilter, setStatusFilter] = useState("all");
+    const [pendingOnly, setPendingOnly] = useState(true);
+    const [currentPage, setCurrentPage] = useState(1);
+    const [pageSize, setPageSize] = useState(50);
+    const [totalCount, setTotalCount] = useState(0);
+
+    const [report, setReport] = useState<RequisitionStatusLine[]>([]);
+
+    // The requisition being raised or edited, or reviewed for approval
+    const [requisition, setRequisition] = useState<MaterialRequisition | null>(null);
+    const [reviewing, setReviewing] = useState(false);
+
+    useEffect(() => {
+        Promise.all([getSites(), getItems()])
+            .then(([sitesData, itemsData]) => {
+                setSites(sitesData.items);
+                setItems(itemsData.items);
+            })
+            .catch((error) => console.error("Failed to fetch master data:", error));
+    }, []);
+
+    const toId = (value: string) => (value === "all" ? undefined : Number(value));
+    const toStatus = (value: string) => (value === "all" ? undefined : (value as RequisitionStatus));
+
+    const fetchRequisitions = async () => {
+        try {
+            const data = await getMaterialRequisitions(toId(siteFilter), toStatus(statusFilter), currentPage, pageSize);
+            setRequisitions(data.items);
+            setTotalCount(data.total_count);
+        } catch (error) {
+            console.error("Failed to fetch requisitions:", error);
+        }
+    };
+
+    const fetchReport = async () => {
+        try {
+            setReport(await getRequisitionStatusReport(toId(siteFilter), toStatus(statusFilter), pendingOnly));
+        } catch (error) {
+            console.error("Failed to fetch requisition status report:", error);
+        }
+    };
+
+    useEffect(() => {
+        setCurrentPage(1);
+    }, [siteFilter, statusFilter]);
+
+    useEffect(() => {
+        if (view === "requisitions") {
+            fetchRequisitions();
+        } else {
+            fetchReport();
+        }
+    }, [view, siteFilter, statusFilter, pendingOnly, currentPage, pageSize]);
+
+    const refresh = () => (view === "requisitions" ? fetchRequisitions() : fetchReport());
+
+    const closeForm = () => {
+        setRequisition(null);
+        setReviewing(false);
+    };
+
+    const openRequisition = async (id: number, review: boolean) => {
+        try {
+            const data = await getMaterialRequisition(id);
+            // Approved quantities start at the requested quantities
+            setRequisition(review
+                ? { ...data, items: data.items.map(l => ({ ...l, approved_quantity: l.approved_quantity ?? l.requested_quantity })) }
+                : data);
+            setReviewing(review);
+        } catch (error) {
+            console.error("Failed to load requisition:", error);
+            alert("Error loading requisition: " + error);
+        }
+    };
+
+    const updateLine = (index: number, changes: Partial<MaterialRequisitionItem>) => {
+        if (!requisition) return;
+        const lines = requisition.items.map((line, i) => (i === index ? { ...line, ...changes } : line));
+        setRequisition({ ...requisition, items: lines });
+    };
+
+    const handleSave = async () => {
+        if (!requisition) return;
+        if (!requisition.site_id) {
+            alert("Please select the requesting site");
+            return;
+        }
+        const lines = requisition.items.filter(l => l.item_id > 0);
+        if (lines.length === 0) {
+            alert("Please add at least one item");
+            return;
+        }
+        try {
+            if (requisition.id) {
+                await updateMaterialRequisition({ ...requisition, items: lines });
+            } else {
+                await createMaterialRequisition({ ...requisition, items: lines });
+            }
+            closeForm();
+            refresh();
+        } catch (error) {
+            console.error("Failed to save requisition:", error);
+            alert("Error saving requisition: " + error);
+        }
+    };
+
+    const handleApprove = async () => {
+        if (!requisition?.id) return;
+        try {
+            await approveMaterialRequisition(
+                requisition.id,
+                requisition.items.map(l => ({
+                    requisition_item_id: l.id!,
+                    approved_quantity: l.approved_quantity ?? l.requested_quantity,
+                }))
+            );
+            closeForm();
+            refresh();
+        } catch (error) {
+            console.error("Failed to approve requisition:", error);
+            alert("Error approving requisition: " + error);
+        }
+    };
+
+    const handleReject = async () => {
+        if (!requisition?.id) return;
+        const reason = prompt("Reason for rejecting this requisition:");
+        if (reason === null) return;
+        try {
+            await rejectMaterialRequisition(requisition.id, reason.trim() || undefined);
+            closeForm();
+            refresh();
+        } catch (error) {
+            console.error("Failed to reject requisition:", error);
+            alert("Error rejecting requisition: " + error);
+        }
+    };
+
+    const handleCancelRequisition = async (id: number) => {
+        if (!confirm("Cancel this requisition? Nothing more can be issued against it.")) return;
+        try {
+            await cancelMaterialRequisition(id);
+            refresh();
+        } catch (error) {
+            console.error("Failed to cancel requisition:", error);
+            alert("Error cancelling requisition: " + error);
+        }
+    };
+
+    const handleDelete = async (id: number) => {
+        if (!confirm("Are you sure you want to delete this requisition?")) return;
+        try {
+            await deleteMaterialRequisition(id);
+            refresh();
+        } catch (error) {
+            console.error("Failed to delete requisition:", error);
+            alert("Error deleting requisition: " + error);
+        }
+    };
+
+    const handleIssue = (id: number) => {
+        navigate(`/inventory-vouchers?type=Godown → Site&requisition=${id}`);
+    };
+
+    const statusBadge = (status: RequisitionStatus) => (
+        <span className={`inline-flex items-center rounded-full px-2.5 py-0.5 text-xs font-medium ${statusStyles[status]}`}>
+            {status}
+        </span>
+    );
+
+    const siteOptions = sites
+        .filter(s => s.type === "Site")
+        .map(s => ({ label: s.name, value: String(s.id) }));
+
+    return (
+        <div className="space-y-6">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Material Requisitions</h2>
+                <div className="flex gap-2">
+                    <Button
+                        variant={view === "requisitions" ? "default" : "outline"}
+                        onClick={() => setView("requisitions")}
+                    >
+                        Requisitions
+                    </Button>
+                    <Button
+                        variant={view === "report" ? "default" : "outline"}
+                        onClick={() => setView("report")}
+                    >
+                        Status Report
+                    </Button>
+                    {!requisition && (
+                        <Button onClick={() => setRequisition(emptyRequisition())}>
+                            <Plus className="mr-2 h-4 w-4" /> New Requisition
+                        </Button>
+                    )}
+                </div>
+            </div>
+
+            {requisition && (
+                <Card>
+                    <CardHeader>
+                        <CardTitle>
+                            {reviewing
+                                ? `Approve Requisition #${requisition.requisition_number}`
+                                : requisition.id
+                                    ? `Requisition #${requisition.requisition_number}`
+                                    : "New Requisition"}
+                        </CardTitle>
+                    </CardHeader>
+                    <CardContent className="space-y-4">
+                        <div className="grid grid-cols-2 md:grid-cols-5 gap-4">
+                            <div>
+                                <Label>Date</Label>
+                                <Input
+                                    type="date"
+                                    value={requisition.requisition_date}
+                                    onChange={(e) => setRequisition({ ...requisition, requisition_date: e.target.value })}
+                                    disabled={reviewing}
+                                />
+                            </div>
+                            <div>
+                                <Label>Site</Label>
+                                <Combobox
+                                    options={siteOptions}
+                                    value={requisition.site_id ? String(requisition.site_id) : ""}
+                                    onChange={(val) => setRequisition({ ...requisition, site_id: Number(val) })}
+                                    placeholder="Select Site"
+                                    disabled={reviewing}
+                                />
+                            </div>
+                            <div>
+                                <Label>Needed By</Label>
+                                <Input
+                                    type="date"
+                                    value={requisition.needed_by || ""}
+                                    onChange={(e) => setRequisition({ ...requisition, needed_by: e.target.value || undefined })}
+                                    disabled={reviewing}
+                                />
+                            </div>
+                            <div>
+                                <Label>Requested By</Label>
+                                <Input
+                                    value={requisition.requested_by || ""}
+                                    onChange={(e) => setRequisition({ ...requisition, requested_by: e.target.value })}
+                                    placeholder="Site engineer"
+                                    disabled={reviewing}
+                                />
+                            </div>
+                            <div>
+                                <Label>Remarks</Label>
+                                <Input
+                                    value={requisition.remarks || ""}
+                                    onChange={(e) => setRequisition({ ...requisition, remarks: e.target.value })}
+                                    placeholder="Optional remarks"
+                                    disabled={reviewing}
+                                />
+                            </div>
+                        </div>
+
+                        <Table>
+                            <TableHeader>
+                                <TableRow>
+                                    <TableHead className="w-[50px]">#</TableHead>
+                                    <TableHead>Item</TableHead>
+                                    <TableHead className="w-[140px]">Requested Qty</TableHead>
+                                    {reviewing && <TableHead className="w-[140px]">Approved Qty</TableHead>}
+                                    {!reviewing && <TableHead className="w-[60px]" />}
+                                </TableRow>
+                            </TableHeader>
+                            <TableBody>
+                                {requisition.items.map((line, index) => (
+                                    <TableRow key={index}>
+                                        <TableCell>{index + 1}</TableCell>
+                                        <TableCell>
+                                            <Combobox
+                                                options={items
+                                                    .filter(i => i.is_active || i.id === line.item_id)
+                                                    .map(i => ({ label: `${i.name} (${i.code})`, value: String(i.id) }))}
+                                                value={line.item_id ? String(line.item_id) : ""}
+                                                onChange={(val) => updateLine(index, { item_id: Number(val) })}
+                                                placeholder="Select Item"
+                                                disabled={reviewing}
+                                                className="h-8"
+                                            />
+                                        </TableCell>
+                                        <TableCell>
+                                            <Input
+                                                type="number"
+                                                value={line.requested_quantity}
+                                                onChange={(e) => updateLine(index, { requested_quantity: Number(e.target.value) })}
+                                                disabled={reviewing}
+                                                className="h-8"
+                                            />
+                                        </TableCell>
+                                        {reviewing ? (
+                                            <TableCell>
+                                                <Input
+                                                    type="number"
+                                                    value={line.approved_quantity ?? ""}
+                                                    onChange={(e) => updateLine(index, { approved_quantity: Number(e.target.value) })}
+                                                    className="h-8"
+                                                />
+                                            </TableCell>
+                                        ) : (
+                                            <TableCell>
+                                                <Button
+                                                    variant="ghost"
+                                                    size="icon"
+                                                    onClick={() => setRequisition({ ...requisition, items: requisition.items.filter((_, i) => i !== index) })}
+                                                    disabled={requisition.items.length === 1}
+                                                    className="h-8 w-8 text-destructive hover:bg-destructive/10"
+                                                >
+                                                    <Trash2 className="h-4 w-4" />
+                                                </Button>
+                                            </TableCell>
+                                        )}
+                                    </TableRow>
+                                ))}
+                            </TableBody>
+                        </Table>
+
+                        <div className="flex justify-between">
+                            {reviewing ? (
+                                <div />
+                            ) : (
+                                <Button
+                                    variant="outline"
+                                    onClick={() => setRequisition({ ...requisition, items: [...requisition.items, { item_id: 0, requested_quantity: 1 }] })}
+                                >
+                                    <Plus className="mr-2 h-4 w-4" /> Add Line
+                                </Button>
+                            )}
+                            <div className="flex gap-2">
+                                <Button variant="outline" onClick={closeForm}>
+                                    <X className="mr-2 h-4 w-4" /> Close
+                                </Button>
+                                {reviewing ? (
+                                    <>
+                                        <Button variant="outline" onClick={handleReject} className="text-destructive hover:text-destructive">
+                                            <Ban className="mr-2 h-4 w-4" /> Reject
+                                        </Button>
+                                        <Button onClick={handleApprove}>
+                                            <CheckCircle className="mr-2 h-4 w-4" /> Approve
+                                        </Button>
+                                    </>
+                                ) : (
+                                    <Button onClick={handleSave}>
+                                        <Save className="mr-2 h-4 w-4" /> {requisition.id ? "Update" : "Raise Requisition"}
+                                    </Button>
+                                )}
+                            </div>
+                        </div>
+                    </CardContent>
+                </Card>
+            )}
+
+            <div className="flex gap-4 items-center">
+                <div className="w-[240px]">
+                    <Combobox
+                        options={[{ label: "All Sites", value: "all" }, ...siteOptions]}
+                        value={siteFilter}
+                        onChange={setSiteFilter}
+                        placeholder="All Sites"
+                    />
+                </div>
+                <div className="w-[200px]">
+                    <Select value={statusFilter} onValueChange={setStatusFilter}>
+                        <SelectTrigger>
+                            <SelectValue placeholder="All Statuses" />
+                        </SelectTrigger>
+                        <SelectContent>
+                            <SelectItem value="all">All Statuses</SelectItem>
+                            {Object.keys(statusStyles).map(status => (
+                                <SelectItem key={status} value={status}>{status}</SelectItem>
+                            ))}
+                        </SelectContent>
+                    </Select>
+                </div>
+                {view === "report" && (
+                    <div className="flex items-center space-x-2">
+                        <input
+                            type="checkbox"
+                            id="pending_only"
+                            checked={pendingOnly}
+                            onChange={(e) => setPendingOnly(e.target.checked)}
+                            className="h-4 w-4 rounded border-gray-300 text-primary focus:ring-primary"
+                        />
+                        <Label htmlFor="pending_only" className="cursor-pointer">Pending lines only</Label>
+                    </div>
+                )}
+            </div>
+
+            {view === "requisitions" ? (
+                <div className="rounded-md border bg-card">
+                    <Table>
+                        <TableHeader>
+                            <TableRow>
+                                <TableHead>Req. No</TableHead>
+                                <TableHead>Date</TableHead>
+                                <TableHead>Site</TableHead>
+                                <TableHead>Needed By</TableHead>
+                                <TableHead>Requested By</TableHead>
+                                <TableHead className="text-right">Requested</TableHead>
+                                <TableHead className="text-right">Approved</TableHead>
+                                <TableHead className="text-right">Issued</TableHead>
+                                <TableHead>Status</TableHead>
+                                <TableHead className="text-right">Actions</TableHead>
+                            </TableRow>
+                        </TableHeader>
+                        <TableBody>
+                            {requisitions.map((r) => {
+                                const issuable = r.status === "Approved" || r.status === "Partial";
+                                const cancellable = r.status === "Pending" || issuable;
+                                return (
+                                    <TableRow key={r.id}>
+                                        <TableCell className="font-medium">{r.requisition_number}</TableCell>
+                                        <TableCell className="whitespace-nowrap">{formatDate(r.requisition_date)}</TableCell>
+                                        <TableCell>{r.site_name}</TableCell>
+                                        <TableCell className="whitespace-nowrap">
+                                            {r.needed_by ? formatDate(r.needed_by) : "-"}
+                                        </TableCell>
+                                        <TableCell>{r.requested_by || "-"}</TableCell>
+                                        <TableCell className="text-right">{r.requested_quantity}</TableCell>
+                                        <TableCell className="text-right">{r.approved_quantity}</TableCell>
+                                        <TableCell className="text-right">{r.fulfilled_quantity}</TableCell>
+                                        <TableCell>{statusBadge(r.status)}</TableCell>
+                                        <TableCell className="text-right">
+                                            <div className="flex justify-end gap-1">
+                                                {r.status === "Pending" && (
+                                                    <>
+                                                        <Button
+                                                            variant="ghost"
+                                                            size="icon"
+                                                            title="Approve or reject"
+                                                            onClick={() => openRequisition(r.id, true)}
+                                                            className="h-8 w-8 text-green-600 hover:text-green-700 hover:bg-green-50"
+                                                        >
+                                                            <CheckCircle className="h-4 w-4" />
+                                                        </Button>
+                                                        <Button
+                                                            variant="ghost"
+                                                            size="icon"
+                                                            title="Edit"
+                                                            onClick={() => openRequisition(r.id, false)}
+                                                            className="h-8 w-8 text-primary hover:bg-primary/10"
+                                                        >
+                                                            <Pencil className="h-4 w-4" />
+                                                        </Button>
+                                                    </>
+                                                )}
+                                                {issuable && (
+                                                    <Button
+                                                        variant="ghost"
+                                                        size="icon"
+                                                        title="Issue from godown"
+                                                        onClick={() => handleIssue(r.id)}
+                                                        className="h-8 w-8 text-blue-600 hover:text-blue-700 hover:bg-blue-50"
+                                                    >
+                                                        <Truck className="h-4 w-4" />
+                                                    </Button>
+                                                )}
+                                                {cancellable && (
+                                                    <Button
+                                                        variant="ghost"
+                                                        size="icon"
+                                                        title="Cancel requisition"
+                                                        onClick={() => handleCancelRequisition(r.id)}
+                                                        className="h-8 w-8 text-orange-600 hover:text-orange-700 hover:bg-orange-50"
+                                                    >
+                                                        <Ban className="h-4 w-4" />
+                                                    </Button>
+                                                )}
+                                                <Button
+                                                    variant="ghost"
+                                                    size="icon"
+                                                    title="Delete"
+                                                    onClick={() => handleDelete(r.id)}
+                                                    className="h-8 w-8 text-destructive hover:bg-destructive/10"
+                                                >
+                                                    <Trash2 className="h-4 w-4" />
+                                                </Button>
+                                            </div>
+                                        </TableCell>
+                                    </TableRow>
+                                );
+                            })}
+                            {requisitions.length === 0 && (
+                                <TableRow>
+                                    <TableCell colSpan={10} className="h-24 text-center text-muted-foreground">
+                                        No requisitions found.
+                                    </TableCell>
+                                </TableRow>
+                            )}
+                        </TableBody>
+                    </Table>
+                    <div className="border-t">
+                        <PaginationControls
+                            currentPage={currentPage}
+                            totalCount={totalCount}
+                            pageSize={pageSize}
+                            onPageChange={setCurrentPage}
+                            onPageSizeChange={(size) => {
+                                setPageSize(size);
+                                setCurrentPage(1);
+                            }}
+                        />
+                    </div>
+                </div>
+            ) : (
+                <div className="rounded-md border bg-card">
+                    <Table>
+                        <TableHeader>
+                            <TableRow>
+                                <TableHead>Site</TableHead>
+                                <TableHead>Req. No</TableHead>
+                                <TableHead>Date</TableHead>
+                                <TableHead>Needed By</TableHead>
+                                <TableHead>Item</TableHead>
+                                <TableHead className="text-right">Requested</TableHead>
+                                <TableHead className="text-right">Approved</TableHead>
+                                <TableHead className="text-right">Issued</TableHead>
+                                <TableHead className="text-right">Pending</TableHead>
+                                <TableHead>Status</TableHead>
+                            </TableRow>
+                        </TableHeader>
+                        <TableBody>
+                            {report.map((line) => (
+                                <TableRow key={line.requisition_item_id}>
+                                    <TableCell>{line.site_name}</TableCell>
+                                    <TableCell className="font-medium">{line.requisition_number}</TableCell>
+                                    <TableCell className="whitespace-nowrap">{formatDate(line.requisition_date)}</TableCell>
+                                    <TableCell className="whitespace-nowrap">
+                                        {line.needed_by ? formatDate(line.needed_by) : "-"}
+                                    </TableCell>
+                                    <TableCell>{line.item_name} ({line.item_code})</TableCell>
+                                    <TableCell className="text-right">{line.requested_quantity}</TableCell>
+                                    <TableCell className="text-right">{line.approved_quantity ?? "-"}</TableCell>
+                                    <TableCell className="text-right">{line.fulfilled_quantity}</TableCell>
+                                    <TableCell className="text-right font-medium">{line.pending_quantity}</TableCell>
+                                    <TableCell>{statusBadge(line.status)}</TableCell>
+                                </TableRow>
+                            ))}
+                            {report.length === 0 && (
+                                <TableRow>
+                                    <TableCell colSpan={10} className="h-24 text-center text-muted-foreground">
+                                        No requisition lines found.
+                                    </TableCell>
+                                </TableRow>
+                            )}
+                        </TableBody>
+                    </Table>
+                </div>
+            )}
+        </div>
+    );
+}
+
+export default RequisitionsPage;
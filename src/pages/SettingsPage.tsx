@@ -0,0 +1,848 @@
+import { useEffect, useState } from "react";
+import { useOutletContext } from "react-router-dom";
+import {
+    backupDatabase,
+    checkIntegrity,
+    connectToHost,
+    disconnectFromHost,
+    getApiServerStatus,
+    getBackupStatus,
+    getEncryptionStatus,
+    getLanStatus,
+    getSites,
+    listBackups,
+    rebuildStockMovements,
+    restoreBackup,
+    setDatabasePassphrase,
+    updateSettings,
+    ApiServerStatus,
+    BackupInfo,
+    BackupKind,
+    BackupStatus,
+    DateFormat,
+    EncryptionStatus,
+    IntegrityReport,
+    LanStatus,
+    Settings,
+    Site,
+} from "../api";
+import { LayoutContext } from "@/components/Layout";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import {
+    Copy,
+    DatabaseBackup,
+    FolderOpen,
+    History,
+    Link2,
+    Link2Off,
+    Lock,
+    RefreshCw,
+    Save,
+    ShieldCheck,
+    Trash2,
+    Upload,
+    Wrench,
+} from "lucide-react";
+import { openPath } from "@tauri-apps/plugin-opener";
+import { formatDate } from "@/lib/utils";
+
+const MONTHS = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+const DATE_FORMATS: DateFormat[] = ["DD-MM-YYYY", "DD/MM/YYYY", "YYYY-MM-DD", "MM/DD/YYYY"];
+
+const DEFAULT_SETTINGS: Settings = {
+    company_name: "",
+    date_format: "DD-MM-YYYY",
+    fiscal_year_start_month: 4,
+    quantity_decimals: 2,
+    daily_backup: true,
+    backup_retention_count: 7,
+    compress_backups: true,
+    api_enabled: false,
+    api_port: 8787,
+    api_allow_lan: false,
+};
+
+const BACKUP_KIND_LABELS: Record<BackupKind, string> = {
+    Manual: "Manual",
+    Automatic: "Automatic",
+    PreRestore: "Before restore",
+};
+
+function formatSize(bytes: number) {
+    if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+    return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+function SettingsPage() {
+    const { settings, setSettings } = useOutletContext<LayoutContext>();
+    const [formData, setFormData] = useState<Settings>(settings ?? DEFAULT_SETTINGS);
+    const [godowns, setGodowns] = useState<Site[]>([]);
+    const [isSaving, setIsSaving] = useState(false);
+    const [backups, setBackups] = useState<BackupInfo[]>([]);
+    const [backupStatus, setBackupStatus] = useState<BackupStatus | null>(null);
+    const [isBackingUp, setIsBackingUp] = useState(false);
+    const [integrity, setIntegrity] = useState<IntegrityReport | null>(null);
+    const [isChecking, setIsChecking] = useState(false);
+    const [encryption, setEncryption] = useState<EncryptionStatus | null>(null);
+    const [passphrases, setPassphrases] = useState({ current: "", next: "", confirm: "" });
+    const [apiStatus, setApiStatus] = useState<ApiServerStatus | null>(null);
+    const [lanStatus, setLanStatus] = useState<LanStatus | null>(null);
+    const [hostForm, setHostForm] = useState({ address: "", token: "" });
+    const [isConnecting, setIsConnecting] = useState(false);
+    // As a LAN client, backups, encryption and the API server belong to the host
+    const isClient = lanStatus?.client_enabled ?? false;
+
+    const fetchApiStatus = async () => {
+        try {
+            setApiStatus(await getApiServerStatus());
+        } catch (error) {
+            console.error("Failed to fetch API server status:", error);
+        }
+    };
+
+    const fetchBackups = async () => {
+        try {
+            setBackups(await listBackups());
+            setBackupStatus(await getBackupStatus());
+        } catch (error) {
+            console.error("Failed to fetch backups:", error);
+        }
+    };
+
+    useEffect(() => {
+        getSites({ is_active: true })
+            .then((data) => setGodowns(data.items.filter((s) => s.type === "Warehouse")))
+            .catch((error) => console.error("Failed to fetch godowns:", error));
+        fetchBackups();
+        getEncryptionStatus()
+            .then(setEncryption)
+            .catch((error) => console.error("Failed to fetch encryption status:", error));
+        fetchApiStatus();
+        getLanStatus()
+            .then((status) => {
+                setLanStatus(status);
+                setHostForm((form) => ({ ...form, address: status.host_address ?? "" }));
+            })
+            .catch((error) => console.error("Failed to fetch LAN status:", error));
+    }, []);
+
+    useEffect(() => {
+        if (settings) setFormData(settings);
+    }, [settings]);
+
+    const handleLogoChange = (e: React.ChangeEvent<HTMLInputElement>) => {
+        const file = e.target.files?.[0];
+        e.target.value = "";
+        if (!file) return;
+        if (!["image/png", "image/jpeg"].includes(file.type)) {
+            alert("Logo must be a PNG or JPEG image.");
+            return;
+        }
+        const reader = new FileReader();
+        reader.onload = () => setFormData({ ...formData, logo: reader.result as string });
+        reader.readAsDataURL(file);
+    };
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        setIsSaving(true);
+        try {
+            const saved = await updateSettings(formData);
+            setSettings(saved);
+            fetchApiStatus();
+            alert("Settings saved successfully!");
+        } catch (error) {
+            console.error("Failed to save settings:", error);
+            alert("Error saving settings: " + error);
+        } finally {
+            setIsSaving(false);
+        }
+    };
+
+    const handleBackup = async () => {
+        setIsBackingUp(true);
+        try {
+            const backup = await backupDatabase();
+            alert(`Backup saved as ${backup.file_name}`);
+            fetchBackups();
+        } catch (error) {
+            console.error("Failed to back up:", error);
+            alert("Error creating backup: " + error);
+        } finally {
+            setIsBackingUp(false);
+        }
+    };
+
+    const handleRestore = async (backup: BackupInfo) => {
+        if (!confirm(`Restore the backup from ${formatDate(backup.created_at.split(" ")[0])} ${backup.created_at.split(" ")[1]}? All changes made since then will be replaced. The current data is backed up first.`)) {
+            return;
+        }
+        let passphrase: string | undefined;
+        while (true) {
+            try {
+                const safety = await restoreBackup(backup.file_name, passphrase);
+                alert(`Backup restored. The previous data was saved as ${safety.file_name}.`);
+                window.location.reload();
+                return;
+            } catch (error) {
+                // Encrypted backups made under another passphrase ask for it
+                if (String(error).includes("passphrase")) {
+                    const entered = prompt(`${error}\n\nPassphrase of the backup:`);
+                    if (entered) {
+                        passphrase = entered;
+                        continue;
+                    }
+                    return;
+                }
+                console.error("Failed to restore backup:", error);
+                alert("Error restoring backup: " + error);
+                return;
+            }
+        }
+    };
+
+    const handleOpenFolder = async (backup: BackupInfo) => {
+        try {
+            await openPath(backup.path.replace(/[\\/][^\\/]+$/, ""));
+        } catch (error) {
+            console.error("Failed to open backups folder:", error);
+        }
+    };
+
+    const handleCheckIntegrity = async () => {
+        setIsChecking(true);
+        try {
+            setIntegrity(await checkIntegrity());
+        } catch (error) {
+            console.error("Failed to check integrity:", error);
+            alert("Error checking data: " + error);
+        } finally {
+            setIsChecking(false);
+        }
+    };
+
+    const handleRebuild = async () => {
+        if (!confirm("Rebuild the stock ledger from all vouchers? Every stock movement is regenerated. Consider backing up first.")) {
+            return;
+        }
+        setIsChecking(true);
+        try {
+            const count = await rebuildStockMovements();
+            setIntegrity(await checkIntegrity());
+            alert(`Stock ledger rebuilt with ${count} movements.`);
+        } catch (error) {
+            console.error("Failed to rebuild stock movements:", error);
+            alert("Error rebuilding stock ledger: " + error);
+        } finally {
+            setIsChecking(false);
+        }
+    };
+
+    // Saving without a token makes the backend generate a new one
+    const handleRegenerateToken = async () => {
+        if (!confirm("Generate a new API token? Programs using the current token will stop working.")) {
+            return;
+        }
+        try {
+            const saved = await updateSettings({ ...formData, api_token: undefined });
+            setSettings(saved);
+            fetchApiStatus();
+        } catch (error) {
+            console.error("Failed to regenerate API token:", error);
+            alert("Error: " + error);
+        }
+    };
+
+    const handleCopyToken = async () => {
+        if (!settings?.api_token) return;
+        try {
+            await navigator.clipboard.writeText(settings.api_token);
+        } catch (error) {
+            console.error("Failed to copy API token:", error);
+        }
+    };
+
+    // Every page reads from the new database, so the app starts over
+    const handleConnect = async () => {
+        setIsConnecting(true);
+        try {
+            await connectToHost(hostForm.address, hostForm.token);
+            window.location.assign("/");
+        } catch (error) {
+            console.error("Failed to connect to host:", error);
+            alert("Error connecting: " + error);
+        } finally {
+            setIsConnecting(false);
+        }
+    };
+
+    const handleDisconnect = async () => {
+        if (!confirm("Disconnect from the host? This computer goes back to its own data.")) {
+            return;
+        }
+        try {
+            await disconnectFromHost();
+            window.location.assign("/");
+        } catch (error) {
+            console.error("Failed to disconnect from host:", error);
+            alert("Error: " + error);
+        }
+    };
+
+    // A blank new passphrase removes the encryption
+    const handlePassphrase = async () => {
+        const { current, next, confirm: confirmation } = passphrases;
+        if (next !== confirmation) {
+            alert("The new passphrases do not match.");
+            return;
+        }
+        if (!next && !confirm("Remove the encryption? The database will be stored unencrypted.")) {
+            return;
+        }
+        try {
+            setEncryption(await setDatabasePassphrase(current || undefined, next || undefined));
+            setPassphrases({ current: "", next: "", confirm: "" });
+            alert(
+                !next
+                    ? "Encryption removed."
+                    : encryption?.is_encrypted
+                        ? "Passphrase changed. Backups made earlier still need the old passphrase."
+                        : "Database encrypted. Keep the passphrase safe: the data cannot be recovered without it. Backups made earlier are not encrypted.",
+            );
+        } catch (error) {
+            console.error("Failed to change passphrase:", error);
+            alert("Error: " + error);
+        }
+    };
+
+    return (
+        <form onSubmit={handleSubmit} className="space-y-6 max-w-4xl">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Settings</h2>
+                <Button type="submit" disabled={isSaving} className="gap-2">
+                    <Save className="h-4 w-4" /> Save
+                </Button>
+            </div>
+
+            <Card>
+                <CardHeader>
+                    <CardTitle className="text-lg">Company Profile</CardTitle>
+                </CardHeader>
+                <CardContent className="grid gap-4 md:grid-cols-2">
+                    <div className="md:col-span-2">
+                        <Label htmlFor="company-name">Company Name</Label>
+                        <Input
+                            id="company-name"
+                            value={formData.company_name}
+                            onChange={(e) => setFormData({ ...formData, company_name: e.target.value })}
+                            required
+                        />
+                    </div>
+                    <div className="md:col-span-2">
+                        <Label htmlFor="company-address">Address</Label>
+                        <textarea
+                            id="company-address"
+                            value={formData.company_address || ""}
+                            onChange={(e) => setFormData({ ...formData, company_address: e.target.value })}
+                            rows={3}
+                            className="flex w-full rounded-md border border-input bg-transparent px-3 py-2 text-sm shadow-sm placeholder:text-muted-foreground focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+                        />
+                    </div>
+                    <div>
+                        <Label htmlFor="company-phone">Phone</Label>
+                        <Input
+                            id="company-phone"
+                            value={formData.company_phone || ""}
+                            onChange={(e) => setFormData({ ...formData, company_phone: e.target.value })}
+                        />
+                    </div>
+                    <div>
+                        <Label htmlFor="company-email">Email</Label>
+                        <Input
+                            id="company-email"
+                            type="email"
+                            value={formData.company_email || ""}
+                            onChange={(e) => setFormData({ ...formData, company_email: e.target.value })}
+                        />
+                    </div>
+                    <div>
+                        <Label htmlFor="gstin">GSTIN</Label>
+                        <Input
+                            id="gstin"
+                            value={formData.gstin || ""}
+                            onChange={(e) => setFormData({ ...formData, gstin: e.target.value.toUpperCase() })}
+                            maxLength={15}
+                            placeholder="15 characters"
+                        />
+                    </div>
+                    <div>
+                        <Label>Logo</Label>
+                        <div className="flex items-center gap-3 h-9">
+                            {formData.logo && (
+                                <img src={formData.logo} alt="Company logo" className="h-9 w-auto border rounded" />
+                            )}
+                            <Button type="button" variant="outline" size="sm" className="gap-2" asChild>
+                                <label>
+                                    <Upload className="h-4 w-4" /> {formData.logo ? "Replace" : "Upload"}
+                                    <input type="file" accept="image/png,image/jpeg" className="hidden" onChange={handleLogoChange} />
+                                </label>
+                            </Button>
+                            {formData.logo && (
+                                <Button
+                                    type="button"
+                                    variant="ghost"
+                                    size="sm"
+                                    className="text-destructive"
+                                    onClick={() => setFormData({ ...formData, logo: undefined })}
+                                >
+                                    <Trash2 className="h-4 w-4" />
+                                </Button>
+                            )}
+                        </div>
+                    </div>
+                </CardContent>
+            </Card>
+
+            <Card>
+                <CardHeader>
+                    <CardTitle className="text-lg">Preferences</CardTitle>
+                </CardHeader>
+                <CardContent className="grid gap-4 md:grid-cols-2">
+                    <div>
+                        <Label>Date Format</Label>
+                        <Select
+                            value={formData.date_format}
+                            onValueChange={(value: DateFormat) => setFormData({ ...formData, date_format: value })}
+                        >
+                            <SelectTrigger>
+                                <SelectValue />
+                            </SelectTrigger>
+                            <SelectContent>
+                                {DATE_FORMATS.map((f) => (
+                                    <SelectItem key={f} value={f}>{f}</SelectItem>
+                                ))}
+                            </SelectContent>
+                        </Select>
+                    </div>
+                    <div>
+                        <Label>Fiscal Year Starts In</Label>
+                        <Select
+                            value={String(formData.fiscal_year_start_month)}
+                            onValueChange={(value) => setFormData({ ...formData, fiscal_year_start_month: Number(value) })}
+                        >
+                            <SelectTrigger>
+                                <SelectValue />
+                            </SelectTrigger>
+                            <SelectContent>
+                                {MONTHS.map((m, i) => (
+                                    <SelectItem key={m} value={String(i + 1)}>{m}</SelectItem>
+                                ))}
+                            </SelectContent>
+                        </Select>
+                    </div>
+                    <div>
+                        <Label>Quantity Decimals</Label>
+                        <Select
+                            value={String(formData.quantity_decimals)}
+                            onValueChange={(value) => setFormData({ ...formData, quantity_decimals: Number(value) })}
+                        >
+                            <SelectTrigger>
+                                <SelectValue />
+                            </SelectTrigger>
+                            <SelectContent>
+                                {[0, 1, 2, 3, 4].map((d) => (
+                                    <SelectItem key={d} value={String(d)}>{d}</SelectItem>
+                                ))}
+                            </SelectContent>
+                        </Select>
+                    </div>
+                    <div>
+                        <Label>Default Godown</Label>
+                        <Select
+                            value={formData.default_godown_id ? String(formData.default_godown_id) : "none"}
+                            onValueChange={(value) =>
+                                setFormData({ ...formData, default_godown_id: value === "none" ? undefined : Number(value) })
+                            }
+                        >
+                            <SelectTrigger>
+                                <SelectValue />
+                            </SelectTrigger>
+                            <SelectContent>
+                                <SelectItem value="none">None</SelectItem>
+                                {godowns.map((g) => (
+                                    <SelectItem key={g.id} value={String(g.id)}>{g.name} ({g.code})</SelectItem>
+                                ))}
+                            </SelectContent>
+                        </Select>
+                    </div>
+                    <div>
+                        <Label htmlFor="number-prefix">Voucher Number Prefix</Label>
+                        <Input
+                            id="number-prefix"
+                            value={formData.voucher_number_prefix || ""}
+                            onChange={(e) => setFormData({ ...formData, voucher_number_prefix: e.target.value.toUpperCase() })}
+                            maxLength={10}
+                            placeholder="e.g. SS- (blank for plain numbers)"
+                        />
+                    </div>
+                </CardContent>
+            </Card>
+
+            {!isClient && (
+                <>
+                    <Card>
+                        <CardHeader className="flex flex-row items-center justify-between space-y-0">
+                            <CardTitle className="text-lg">Backups</CardTitle>
+                            <Button type="button" variant="outline" disabled={isBackingUp} onClick={handleBackup} className="gap-2">
+                                <DatabaseBackup className="h-4 w-4" /> Back Up Now
+                            </Button>
+                        </CardHeader>
+                        <CardContent className="space-y-4">
+                            {backupStatus?.error && (
+                                <p className="text-sm text-destructive">
+                                    The automatic backup at {backupStatus.failed_at} failed: {backupStatus.error}
+                                </p>
+                            )}
+                            <div className="grid gap-4 md:grid-cols-3 items-end">
+                                <label className="flex items-center gap-2 text-sm h-9">
+                                    <input
+                                        type="checkbox"
+                                        checked={formData.daily_backup}
+                                        onChange={(e) => setFormData({ ...formData, daily_backup: e.target.checked })}
+                                    />
+                                    Back up automatically every day
+                                </label>
+                                <div>
+                                    <Label htmlFor="backup-retention">Automatic Backups to Keep</Label>
+                                    <Input
+                                        id="backup-retention"
+                                        type="number"
+                                        min={1}
+                                        max={365}
+                                        value={formData.backup_retention_count}
+                                        onChange={(e) => setFormData({ ...formData, backup_retention_count: Number(e.target.value) })}
+                                    />
+                                </div>
+                                <label className="flex items-center gap-2 text-sm h-9">
+                                    <input
+                                        type="checkbox"
+                                        checked={formData.compress_backups}
+                                        onChange={(e) => setFormData({ ...formData, compress_backups: e.target.checked })}
+                                    />
+                                    Compress backups (zip)
+                                </label>
+                            </div>
+
+                            <div className="rounded-md border">
+                                <Table>
+                                    <TableHeader>
+                                        <TableRow>
+                                            <TableHead>Created</TableHead>
+                                            <TableHead>Type</TableHead>
+                                            <TableHead>Size</TableHead>
+                                            <TableHead className="text-right">Actions</TableHead>
+                                        </TableRow>
+                                    </TableHeader>
+                                    <TableBody>
+                                        {backups.length === 0 ? (
+                                            <TableRow>
+                                                <TableCell colSpan={4} className="text-center text-muted-foreground">
+                                                    No backups yet
+                                                </TableCell>
+                                            </TableRow>
+                                        ) : (
+                                            backups.map((backup) => (
+                                                <TableRow key={backup.file_name}>
+                                                    <TableCell>
+                                                        {formatDate(backup.created_at.split(" ")[0])} {backup.created_at.split(" ")[1]}
+                                                    </TableCell>
+                                                    <TableCell>{BACKUP_KIND_LABELS[backup.kind]}</TableCell>
+                                                    <TableCell>{formatSize(backup.size_bytes)}</TableCell>
+                                                    <TableCell className="text-right space-x-2">
+                                                        <Button
+                                                            type="button"
+                                                            variant="ghost"
+                                                            size="icon"
+                                                            title="Show in folder"
+                                                            onClick={() => handleOpenFolder(backup)}
+                                                            className="text-primary hover:text-primary hover:bg-primary/10"
+                                                        >
+                                                            <FolderOpen className="h-4 w-4" />
+                                                        </Button>
+                                                        <Button
+                                                            type="button"
+                                                            variant="ghost"
+                                                            size="icon"
+                                                            title="Restore"
+                                                            onClick={() => handleRestore(backup)}
+                                                            className="text-destructive hover:text-destructive hover:bg-destructive/10"
+                                                        >
+                                                            <History className="h-4 w-4" />
+                                                        </Button>
+                                                    </TableCell>
+                                                </TableRow>
+                                            ))
+                                        )}
+                                    </TableBody>
+                                </Table>
+                            </div>
+                        </CardContent>
+                    </Card>
+
+                    <Card>
+                        <CardHeader className="flex flex-row items-center justify-between space-y-0">
+                            <CardTitle className="text-lg">Encryption</CardTitle>
+                            <Button
+                                type="button"
+                                variant="outline"
+                                disabled={!passphrases.next && !encryption?.is_encrypted}
+                                onClick={handlePassphrase}
+                                className="gap-2"
+                            >
+                                <Lock className="h-4 w-4" />
+                                {!encryption?.is_encrypted
+                                    ? "Encrypt Database"
+                                    : passphrases.next
+                                        ? "Change Passphrase"
+                                        : "Remove Encryption"}
+                            </Button>
+                        </CardHeader>
+                        <CardContent className="space-y-4">
+                            <p className="text-sm text-muted-foreground">
+                                {encryption?.is_encrypted
+                                    ? "This company's database and its backups are encrypted. The passphrase is asked for when the app starts."
+                                    : "Encrypt this company's database and its backups with a passphrase, asked for when the app starts."}
+                            </p>
+                            <div className="grid gap-4 md:grid-cols-3">
+                                {encryption?.is_encrypted && (
+                                    <div>
+                                        <Label htmlFor="current-passphrase">Current Passphrase</Label>
+                                        <Input
+                                            id="current-passphrase"
+                                            type="password"
+                                            value={passphrases.current}
+                                            onChange={(e) => setPassphrases({ ...passphrases, current: e.target.value })}
+                                        />
+                                    </div>
+                                )}
+                                <div>
+                                    <Label htmlFor="new-passphrase">New Passphrase</Label>
+                                    <Input
+                                        id="new-passphrase"
+                                        type="password"
+                                        value={passphrases.next}
+                                        onChange={(e) => setPassphrases({ ...passphrases, next: e.target.value })}
+                                        placeholder="At least 8 characters"
+                                    />
+                                </div>
+                                <div>
+                                    <Label htmlFor="confirm-passphrase">Confirm Passphrase</Label>
+                                    <Input
+                                        id="confirm-passphrase"
+                                        type="password"
+                                        value={passphrases.confirm}
+                                        onChange={(e) => setPassphrases({ ...passphrases, confirm: e.target.value })}
+                                    />
+                                </div>
+                            </div>
+                        </CardContent>
+                    </Card>
+
+                    <Card>
+                        <CardHeader>
+                            <CardTitle className="text-lg">API Server</CardTitle>
+                        </CardHeader>
+                        <CardContent className="space-y-4">
+                            <div className="grid gap-4 md:grid-cols-3 items-end">
+                                <label className="flex items-center gap-2 text-sm h-9">
+                                    <input
+                                        type="checkbox"
+                                        checked={formData.api_enabled}
+                                        onChange={(e) => setFormData({ ...formData, api_enabled: e.target.checked })}
+                                    />
+                                    Enable the local HTTP API
+                                </label>
+                                <div>
+                                    <Label htmlFor="api-port">Port</Label>
+                                    <Input
+                                        id="api-port"
+                                        type="number"
+                                        min={1024}
+                                        max={65535}
+                                        value={formData.api_port}
+                                        onChange={(e) => setFormData({ ...formData, api_port: Number(e.target.value) })}
+                                    />
+                                </div>
+                                <label className="flex items-center gap-2 text-sm h-9">
+                                    <input
+                                        type="checkbox"
+                                        checked={formData.api_allow_lan}
+                                        onChange={(e) => setFormData({ ...formData, api_allow_lan: e.target.checked })}
+                                    />
+                                    Allow other computers on the network
+                                </label>
+                            </div>
+
+                            {settings?.api_token && (
+                                <div>
+                                    <Label htmlFor="api-token">Access Token</Label>
+                                    <div className="flex gap-2">
+                                        <Input id="api-token" readOnly value={settings.api_token} className="font-mono" />
+                                        <Button type="button" variant="outline" size="icon" title="Copy" onClick={handleCopyToken}>
+                                            <Copy className="h-4 w-4" />
+                                        </Button>
+                                        <Button type="button" variant="outline" size="icon" title="Generate a new token" onClick={handleRegenerateToken}>
+                                            <RefreshCw className="h-4 w-4" />
+                                        </Button>
+                                    </div>
+                                    <p className="text-xs text-muted-foreground mt-1">
+                                        Send it as <span className="font-mono">Authorization: Bearer &lt;token&gt;</span> with every request.
+                                    </p>
+                                </div>
+                            )}
+
+                            <div className="text-sm">
+                                {apiStatus?.is_running ? (
+                                    <span className="text-green-600">
+                                        Running at http://{apiStatus.lan_address ?? apiStatus.address}/api
+                                        {" · "}the endpoints are described at /api/openapi.json
+                                    </span>
+                                ) : apiStatus?.error ? (
+                                    <span className="text-destructive">{apiStatus.error}</span>
+                                ) : (
+                                    <span className="text-muted-foreground">Not running. Changes apply when the settings are saved.</span>
+                                )}
+                            </div>
+                        </CardContent>
+                    </Card>
+                </>
+            )}
+
+            <Card>
+                <CardHeader className="flex flex-row items-center justify-between space-y-0">
+                    <CardTitle className="text-lg">Network (LAN)</CardTitle>
+                    {isClient ? (
+                        <Button type="button" variant="outline" onClick={handleDisconnect} className="gap-2">
+                            <Link2Off className="h-4 w-4" /> Disconnect
+                        </Button>
+                    ) : (
+                        <Button
+                            type="button"
+                            variant="outline"
+                            disabled={isConnecting || !hostForm.address || !hostForm.token}
+                            onClick={handleConnect}
+                            className="gap-2"
+                        >
+                            <Link2 className="h-4 w-4" /> {isConnecting ? "Connecting..." : "Connect to Host"}
+                        </Button>
+                    )}
+                </CardHeader>
+                <CardContent className="space-y-4 text-sm">
+                    {isClient ? (
+                        <p>
+                            This computer works on the database of{" "}
+                            <span className="font-medium">{lanStatus?.host_company || "the host"}</span> at{" "}
+                            <span className="font-mono">{lanStatus?.host_address}</span>.{" "}
+                            {lanStatus?.is_connected ? (
+                                <span className="text-green-600">Connected.</span>
+                            ) : (
+                                <span className="text-destructive">{lanStatus?.error ?? "Not connected."}</span>
+                            )}
+                        </p>
+                    ) : (
+                        <>
+                            <p className="text-muted-foreground">
+                                To share this computer's data, enable the API server with network access and give the
+                                other computers its address and access token. To use another computer's data, connect to it here.
+                            </p>
+                            <div className="grid gap-4 md:grid-cols-2">
+                                <div>
+                                    <Label htmlFor="host-address">Host Address</Label>
+                                    <Input
+                                        id="host-address"
+                                        placeholder="192.168.1.20:8787"
+                                        value={hostForm.address}
+                                        onChange={(e) => setHostForm({ ...hostForm, address: e.target.value })}
+                                    />
+                                </div>
+                                <div>
+                                    <Label htmlFor="host-token">Host Access Token</Label>
+                                    <Input
+                                        id="host-token"
+                                        type="password"
+                                        value={hostForm.token}
+                                        onChange={(e) => setHostForm({ ...hostForm, token: e.target.value })}
+                                    />
+                                </div>
+                            </div>
+                        </>
+                    )}
+                </CardContent>
+            </Card>
+
+            <Card>
+                <CardHeader className="flex flex-row items-center justify-between space-y-0">
+                    <CardTitle className="text-lg">Data Integrity</CardTitle>
+                    <div className="flex gap-2">
+                        <Button type="button" variant="outline" disabled={isChecking} onClick={handleCheckIntegrity} className="gap-2">
+                            <ShieldCheck className="h-4 w-4" /> Check Data
+                        </Button>
+                        <Button type="button" variant="outline" disabled={isChecking} onClick={handleRebuild} className="gap-2">
+                            <Wrench className="h-4 w-4" /> Rebuild Stock Ledger
+                        </Button>
+                    </div>
+                </CardHeader>
+                <CardContent className="space-y-2 text-sm">
+                    {!integrity ? (
+                        <p className="text-muted-foreground">
+                            Checks the database file, references between records and that every voucher's stock movements add up.
+                        </p>
+                    ) : integrity.is_ok ? (
+                        <p>
+                            No problems found in {integrity.voucher_count} vouchers and {integrity.movement_count} stock movements.
+                        </p>
+                    ) : (
+                        <>
+                            <p>
+                                {integrity.issues.length} problem(s) found.
+                                {integrity.issues.some((i) => i.repairable) &&
+                                    ` ${integrity.issues.filter((i) => i.repairable).length} can be fixed by rebuilding the stock ledger.`}
+                            </p>
+                            <ul className="list-disc pl-5 space-y-1 max-h-64 overflow-y-auto">
+                                {integrity.issues.map((issue, index) => (
+                                    <li key={index} className={issue.repairable ? "" : "text-destructive"}>
+                                        {issue.message}
+                                    </li>
+                                ))}
+                            </ul>
+                        </>
+                    )}
+                </CardContent>
+            </Card>
+        </form>
+    );
+}
+
+export default SettingsPage;
@@ -20,7 +20,8 @@ import {
 } from "@/components/ui/select";
 import { Card, CardContent } from "@/components/ui/card";
 import { Label } from "@/components/ui/label";
-import { Trash2, Pencil, X, PackagePlus, PackageMinus, Settings2 } from "lucide-react";
+import { Trash2, Pencil, X, PackagePlus, PackageMinus, Settings2, ArrowUpDown } from "lucide-react";
+import { PaginationControls } from "@/components/ui/pagination-controls";
 import {
     Tooltip,
     TooltipContent,
@@ -40,18 +41,47 @@ function SitesPage() {
     const [editingId, setEditingId] = useState<number | null>(null);
     const navigate = useNavigate();
 
+    const [search, setSearch] = useState("");
+    const [sort, setSort] = useState<{ by: string; dir: "asc" | "desc" }>({ by: "code", dir: "asc" });
+    const [currentPage, setCurrentPage] = useState(1);
+    const [pageSize, setPageSize] = useState(50);
+    const [totalCount, setTotalCount] = useState(0);
+
     const fetchSites = async () => {
         try {
-            const data = await getSites();
-            setSites(data);
+            const data = await getSites({
+                search: search.trim() || undefined,
+                sort_by: sort.by,
+                sort_dir: sort.dir,
+                page: currentPage,
+                limit: pageSize,
+            });
+            setSites(data.items);
+            setTotalCount(data.total_count);
         } catch (error) {
             console.error("Failed to fetch sites:", error);
         }
     };
 
     useEffect(() => {
-        fetchSites();
-    }, []);
+        setCurrentPage(1);
+    }, [search, sort]);
+
+    useEffect(() => {
+        const timer = setTimeout(fetchSites, 250);
+        return () => clearTimeout(timer);
+    }, [search, sort, currentPage, pageSize]);
+
+    const toggleSort = (by: string) => {
+        setSort((prev) => ({ by, dir: prev.by === by && prev.dir === "asc" ? "desc" : "asc" }));
+    };
+
+    const sortableHead = (by: string, label: string) => (
+        <Button variant="ghost" className="px-0 hover:bg-transparent" onClick={() => toggleSort(by)}>
+            {label}
+            <ArrowUpDown className="h-4 w-4 ml-2" />
+        </Button>
+    );
 
     const handleSubmit = async (e: React.FormEvent) => {
         e.preventDefault();
@@ -101,7 +131,10 @@ function SitesPage() {
     const handleDelete = async (id: number) => {
         if (confirm("Are you sure you want to delete this site/warehouse?")) {
             try {
-                await deleteSite(id);
+                const outcome = await deleteSite(id);
+                if (outcome === "Deactivated") {
+                    alert("This site is in use, so it was deactivated instead of deleted.");
+                }
                 fetchSites();
             } catch (error) {
                 console.error("Failed to delete site:", error);
@@ -188,24 +221,33 @@ function SitesPage() {
                     </CardContent>
                 </Card>
 
+                <Input
+                    value={search}
+                    onChange={(e) => setSearch(e.target.value)}
+                    placeholder="Search by code, name or address..."
+                    className="max-w-sm"
+                />
+
                 <div className="rounded-md border bg-card">
                     <Table>
                         <TableHeader>
                             <TableRow>
                                 <TableHead className="w-[60px]">S.No</TableHead>
-                                <TableHead className="w-[100px]">Code</TableHead>
-                                <TableHead>Name</TableHead>
-                                <TableHead>Type</TableHead>
+                                <TableHead className="w-[100px]">{sortableHead("code", "Code")}</TableHead>
+                                <TableHead>{sortableHead("name", "Name")}</TableHead>
+                                <TableHead>{sortableHead("type", "Type")}</TableHead>
                                 <TableHead>Address</TableHead>
                                 <TableHead className="w-[150px]">Stock Actions</TableHead>
-                                <TableHead className="w-[100px]">Status</TableHead>
+                                <TableHead className="w-[100px]">{sortableHead("is_active", "Status")}</TableHead>
                                 <TableHead className="text-right">Actions</TableHead>
                             </TableRow>
                         </TableHeader>
                         <TableBody>
                             {sites.map((site, index) => (
                                 <TableRow key={site.id}>
-                                    <TableCell className="font-medium text-muted-foreground">{index + 1}</TableCell>
+                                    <TableCell className="font-medium text-muted-foreground">
+                                        {(pageSize === -1 ? 0 : (currentPage - 1) * pageSize) + index + 1}
+                                    </TableCell>
                                     <TableCell className="font-medium">{site.code}</TableCell>
                                     <TableCell>{site.name}</TableCell>
                                     <TableCell>{site.type}</TableCell>
@@ -294,6 +336,18 @@ function SitesPage() {
                             )}
                         </TableBody>
                     </Table>
+                    <div className="border-t">
+                        <PaginationControls
+                            currentPage={currentPage}
+                            totalCount={totalCount}
+                            pageSize={pageSize}
+                            onPageChange={setCurrentPage}
+                            onPageSizeChange={(size) => {
+                                setPageSize(size);
+                                setCurrentPage(1);
+                            }}
+                        />
+                    </div>
                 </div>
             </div>
         </TooltipProvider>
@@ -21,6 +21,7 @@ import {
 } from "@/components/ui/select";
 import { PaginationControls } from "@/components/ui/pagination-controls";
 import { generatePrintHTML, openPrintWindow } from "@/lib/printUtils";
+import { formatQuantity } from "@/lib/utils";
 
 function StockBalanceReport() {
     const [balances, setBalances] = useState<StockBalance[]>([]);
@@ -47,8 +48,8 @@ function StockBalanceReport() {
                 getSites(),
                 getItems()
             ]);
-            setSites(sitesData);
-            setItems(itemsData);
+            setSites(sitesData.items);
+            setItems(itemsData.items);
         } catch (error) {
             console.error("Failed to fetch master data:", error);
         }
@@ -119,7 +120,7 @@ function StockBalanceReport() {
                     header: 'Balance',
                     accessor: 'balance',
                     align: 'right',
-                    format: (val) => (val ?? 0).toFixed(2),
+                    format: (val) => formatQuantity(val ?? 0),
                     width: '12%',
                 },
             ],
@@ -206,7 +207,7 @@ function StockBalanceReport() {
                                     <TableCell>{balance.model_name || "N/A"}</TableCell>
                                     <TableCell>{balance.site_name}</TableCell>
                                     <TableCell className="text-right font-semibold">
-                                        {balance.balance.toFixed(2)}
+                                        {formatQuantity(balance.balance)}
                                     </TableCell>
                                 </TableRow>
                             ))}
@@ -215,7 +216,7 @@ function StockBalanceReport() {
                                 <TableRow className="bg-muted/50 font-bold">
                                     <TableCell colSpan={5} className="text-right">Total Balance (Visible Page):</TableCell>
                                     <TableCell className="text-right">
-                                        {balances.reduce((sum, b) => sum + b.balance, 0).toFixed(2)}
+                                        {formatQuantity(balances.reduce((sum, b) => sum + b.balance, 0))}
                                     </TableCell>
                                 </TableRow>
                             )}
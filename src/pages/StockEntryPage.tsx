@@ -5,19 +5,35 @@ import {
     getInventoryTransactionTypes,
     getSites,
     getItems,
+    getSuppliers,
+    getOutstandingPurchaseOrders,
+    getRequisitionStatusReport,
+    getProjects,
 
     createInventoryVoucher,
     updateInventoryVoucher,
     getInventoryVoucher,
     getStockBalance,
+    lookupItemByBarcode,
+    addVoucherAttachment,
+    getVoucherAttachments,
+    getVoucherAttachmentFile,
+    deleteVoucherAttachment,
+    generateVoucherDocument,
     InventoryTransactionType,
     Site,
     Item,
+    Supplier,
+    OutstandingPurchaseOrderLine,
+    RequisitionStatusLine,
+    Project,
     InventoryVoucher,
-    InventoryVoucherItem
+    InventoryVoucherItem,
+    VoucherAttachment
 } from "../api";
+import { openPath } from "@tauri-apps/plugin-opener";
 import { generateVoucherPrintHTML, openPrintWindow } from "@/lib/printUtils";
-import { formatDate } from "@/lib/utils";
+import { formatDate, getAppSettings } from "@/lib/utils";
 import { Input } from "@/components/ui/input";
 import { Button } from "@/components/ui/button";
 import {
@@ -30,14 +46,23 @@ import {
 } from "@/components/ui/table";
 import { Card, CardContent } from "@/components/ui/card";
 import { Label } from "@/components/ui/label";
-import { Trash2, Plus, Save, Printer, RotateCcw, Clock, X, Upload } from "lucide-react";
+import { Trash2, Plus, Save, Printer, RotateCcw, Clock, X, Upload, ScanLine, Paperclip, FileText } from "lucide-react";
 import * as XLSX from "xlsx";
 import { Combobox } from "@/components/ui/combobox";
 
+// Pending lines of requisitions that can still be issued from the godown
+const fetchIssuableRequisitionLines = async () =>
+    (await getRequisitionStatusReport(undefined, undefined, true))
+        .filter(l => l.status === "Approved" || l.status === "Partial");
+
 function StockEntryPage() {
     const [transactionTypes, setTransactionTypes] = useState<InventoryTransactionType[]>([]);
     const [sites, setSites] = useState<Site[]>([]);
     const [items, setItems] = useState<Item[]>([]);
+    const [suppliers, setSuppliers] = useState<Supplier[]>([]);
+    const [orderLines, setOrderLines] = useState<OutstandingPurchaseOrderLine[]>([]);
+    const [requisitionLines, setRequisitionLines] = useState<RequisitionStatusLine[]>([]);
+    const [projects, setProjects] = useState<Project[]>([]);
     const navigate = useNavigate();
     const [searchParams] = useSearchParams();
     const isEditMode = searchParams.get("edit_id") != null;
@@ -45,6 +70,9 @@ function StockEntryPage() {
     const { handleKeyDown } = useEnterKeyNavigation();
     const [focusNewRow, setFocusNewRow] = useState(false);
     const fileInputRef = useRef<HTMLInputElement>(null);
+    const [scanValue, setScanValue] = useState("");
+    const attachmentInputRef = useRef<HTMLInputElement>(null);
+    const [attachments, setAttachments] = useState<VoucherAttachment[]>([]);
 
     const [voucher, setVoucher] = useState<Partial<InventoryVoucher>>({
         voucher_date: new Date().toISOString().split('T')[0],
@@ -68,14 +96,22 @@ function StockEntryPage() {
 
     useEffect(() => {
         const fetchData = async () => {
-            const [typesData, sitesData, itemsData] = await Promise.all([
+            const [typesData, sitesData, itemsData, suppliersData, orderLinesData, requisitionLinesData, projectsData] = await Promise.all([
                 getInventoryTransactionTypes(),
                 getSites(),
-                getItems()
+                getItems(),
+                getSuppliers(),
+                getOutstandingPurchaseOrders(),
+                fetchIssuableRequisitionLines(),
+                getProjects()
             ]);
             setTransactionTypes(typesData);
-            setSites(sitesData);
-            setItems(itemsData);
+            setSites(sitesData.items);
+            setItems(itemsData.items);
+            setSuppliers(suppliersData);
+            setOrderLines(orderLinesData);
+            setRequisitionLines(requisitionLinesData);
+            setProjects(projectsData);
 
             // Handle URL Search Params
             const editId = searchParams.get("edit_id");
@@ -86,6 +122,7 @@ function StockEntryPage() {
                 try {
                     const voucherData = await getInventoryVoucher(Number(targetId));
                     setVoucher(voucherData);
+                    setAttachments(await getVoucherAttachments(voucherData.id!));
                     // Fetch stock balances for existing items
                     if (voucherData.source_site_id) {
                         voucherData.items.forEach(async (item) => {
@@ -101,6 +138,8 @@ function StockEntryPage() {
                 const typeParam = searchParams.get("type");
                 const sourceParam = searchParams.get("source");
                 const destParam = searchParams.get("destination");
+                const orderParam = searchParams.get("po");
+                const requisitionParam = searchParams.get("requisition");
 
                 if (typeParam) {
                     const foundType = typesData.find(t => t.name === typeParam || String(t.id) === typeParam);
@@ -117,6 +156,12 @@ function StockEntryPage() {
                 if (destParam) {
                     setVoucher(prev => ({ ...prev, destination_site_id: Number(destParam) }));
                 }
+                if (orderParam) {
+                    applyPurchaseOrder(orderLinesData, Number(orderParam));
+                }
+                if (requisitionParam) {
+                    applyRequisition(requisitionLinesData, Number(requisitionParam));
+                }
             }
         };
         fetchData();
@@ -126,6 +171,18 @@ function StockEntryPage() {
 
     const showSource = selectedType && !["Purchase Inward", "Opening Stock"].includes(selectedType.name);
     const showDestination = selectedType && !["Material Usage", "Damaged Stock"].includes(selectedType.name);
+    const isPurchase = selectedType && ["Purchase Inward", "Purchase Return"].includes(selectedType.name);
+    const isIssue = selectedType?.name === "Godown → Site";
+    const isConsumption = selectedType && ["Material Usage", "Damaged Stock"].includes(selectedType.name);
+
+    // Consumption is booked to an open project of the site it is taken from
+    const projectOptions = [
+        { label: "No project", value: "none" },
+        ...projects
+            .filter(p => p.id === voucher.project_id ||
+                (p.status !== "Completed" && (!voucher.source_site_id || p.site_id === voucher.source_site_id)))
+            .map(p => ({ label: `${p.name} (${p.code})`, value: String(p.id) }))
+    ];
 
     // Filter sites based on transaction type
     const getSourceSites = () => {
@@ -158,6 +215,101 @@ function StockEntryPage() {
         }
     };
 
+    // Prefill the configured default godown on the godown side of the new transaction type
+    const handleTypeChange = (typeId: number) => {
+        const typeName = transactionTypes.find(t => t.id === typeId)?.name;
+        const godownId = getAppSettings()?.default_godown_id;
+        const next = { ...voucher, voucher_type_id: typeId };
+        if (godownId) {
+            if (typeName === "Godown → Site" && !next.source_site_id) {
+                next.source_site_id = godownId;
+            }
+            if (["Purchase Inward", "Opening Stock", "Site → Godown"].includes(typeName ?? "") && !next.destination_site_id) {
+                next.destination_site_id = godownId;
+            }
+        }
+        setVoucher(next);
+    };
+
+    // Receiving against an order fills in its supplier, delivery site and every line still pending
+    const applyPurchaseOrder = (lines: OutstandingPurchaseOrderLine[], orderId?: number) => {
+        const pending = lines.filter(l => l.purchase_order_id === orderId);
+        if (!orderId || pending.length === 0) {
+            setVoucher(prev => ({
+                ...prev,
+                purchase_order_id: undefined,
+                items: (prev.items || []).map(i => ({ ...i, purchase_order_item_id: undefined }))
+            }));
+            return;
+        }
+        setVoucher(prev => ({
+            ...prev,
+            purchase_order_id: orderId,
+            supplier_id: pending[0].supplier_id,
+            destination_site_id: pending[0].delivery_site_id ?? prev.destination_site_id,
+            items: pending.map(l => ({
+                item_id: l.item_id,
+                quantity: l.pending_quantity,
+                purchase_order_item_id: l.purchase_order_item_id
+            }))
+        }));
+    };
+
+    const orderOptions = () => {
+        const options = [{ label: "No purchase order", value: "none" }];
+        const seen = new Set<number>();
+        orderLines
+            .filter(l => !voucher.supplier_id || l.supplier_id === voucher.supplier_id)
+            .forEach(l => {
+                if (seen.has(l.purchase_order_id)) return;
+                seen.add(l.purchase_order_id);
+                options.push({ label: `PO #${l.po_number} - ${l.supplier_name}`, value: String(l.purchase_order_id) });
+            });
+        if (voucher.purchase_order_id && !seen.has(voucher.purchase_order_id)) {
+            options.push({ label: "Linked purchase order", value: String(voucher.purchase_order_id) });
+        }
+        return options;
+    };
+
+    // Issuing against a requisition fills in the requesting site and every approved line still pending
+    const applyRequisition = (lines: RequisitionStatusLine[], requisitionId?: number) => {
+        const pending = lines.filter(l => l.requisition_id === requisitionId);
+        if (!requisitionId || pending.length === 0) {
+            setVoucher(prev => ({
+                ...prev,
+                requisition_id: undefined,
+                items: (prev.items || []).map(i => ({ ...i, requisition_item_id: undefined }))
+            }));
+            return;
+        }
+        setVoucher(prev => ({
+            ...prev,
+            requisition_id: requisitionId,
+            destination_site_id: pending[0].site_id,
+            items: pending.map(l => ({
+                item_id: l.item_id,
+                quantity: l.pending_quantity,
+                requisition_item_id: l.requisition_item_id
+            }))
+        }));
+    };
+
+    const requisitionOptions = () => {
+        const options = [{ label: "No requisition", value: "none" }];
+        const seen = new Set<number>();
+        requisitionLines
+            .filter(l => !voucher.destination_site_id || l.site_id === voucher.destination_site_id)
+            .forEach(l => {
+                if (seen.has(l.requisition_id)) return;
+                seen.add(l.requisition_id);
+                options.push({ label: `Req #${l.requisition_number} - ${l.site_name}`, value: String(l.requisition_id) });
+            });
+        if (voucher.requisition_id && !seen.has(voucher.requisition_id)) {
+            options.push({ label: "Linked requisition", value: String(voucher.requisition_id) });
+        }
+        return options;
+    };
+
     const filteredSourceSites = getSourceSites();
     const filteredDestinationSites = getDestinationSites();
 
@@ -197,6 +349,35 @@ function StockEntryPage() {
         }
     };
 
+    // A scanned item adds one to its existing line, or fills the empty row / appends a new line
+    const handleScan = async (e: React.KeyboardEvent<HTMLInputElement>) => {
+        if (e.key !== "Enter" || !scanValue.trim()) return;
+        e.preventDefault();
+        try {
+            const item = await lookupItemByBarcode(scanValue);
+            if (!item || !item.id) {
+                alert(`No item found for barcode ${scanValue}`);
+                return;
+            }
+            setVoucher(prev => {
+                const currentItems = (prev.items || []).filter(i => i.item_id !== 0);
+                const existing = currentItems.findIndex(i => i.item_id === item.id);
+                const newItems = existing >= 0
+                    ? currentItems.map((i, index) => index === existing ? { ...i, quantity: i.quantity + 1 } : i)
+                    : [...currentItems, { item_id: item.id!, quantity: 1 } as any];
+                return { ...prev, items: newItems };
+            });
+            if (voucher.source_site_id) {
+                fetchStockBalance(item.id, voucher.source_site_id);
+            }
+        } catch (error) {
+            console.error("Failed to look up barcode:", error);
+            alert("Error looking up barcode: " + error);
+        } finally {
+            setScanValue("");
+        }
+    };
+
     const handleSave = async () => {
         if (!voucher.voucher_type_id) {
             alert("Please select a transaction type");
@@ -233,6 +414,8 @@ function StockEntryPage() {
                     items: validItems
                 } as InventoryVoucher);
                 alert("Voucher saved successfully!");
+                getOutstandingPurchaseOrders().then(setOrderLines).catch(console.error);
+                fetchIssuableRequisitionLines().then(setRequisitionLines).catch(console.error);
                 setVoucher({
                     voucher_date: new Date().toISOString().split('T')[0],
                     voucher_type_id: transactionTypes[0]?.id,
@@ -263,7 +446,19 @@ function StockEntryPage() {
         }
     };
 
-    const handlePrint = () => {
+    const handlePrint = async () => {
+        // Saved vouchers print from the stored record; unsaved drafts fall back to the browser preview
+        if (voucher.id) {
+            try {
+                const path = await generateVoucherDocument(voucher.id);
+                await openPath(path);
+            } catch (error) {
+                console.error("Failed to print voucher:", error);
+                alert("Error generating voucher document: " + error);
+            }
+            return;
+        }
+
         if (!voucher.voucher_type_id) {
             alert("No transaction type selected.");
             return;
@@ -301,6 +496,20 @@ function StockEntryPage() {
 
         if (sourceName) details.push({ label: "Source", value: sourceName });
         if (destName) details.push({ label: "Destination", value: destName });
+        const supplierName = suppliers.find(s => s.id === voucher.supplier_id)?.name;
+        if (isPurchase && supplierName) details.push({ label: "Supplier", value: supplierName });
+        const project = projects.find(p => p.id === voucher.project_id);
+        if (isConsumption && project) {
+            details.push({
+                label: "Project",
+                value: voucher.activity_code ? `${project.code} / ${voucher.activity_code}` : project.code
+            });
+        }
+        if (isPurchase && voucher.bill_number) details.push({ label: "Bill No", value: voucher.bill_number });
+        if (voucher.challan_number) details.push({ label: "Challan No", value: voucher.challan_number });
+        if (voucher.vehicle_number) details.push({ label: "Vehicle No", value: voucher.vehicle_number });
+        if (voucher.driver_name) details.push({ label: "Driver", value: voucher.driver_name });
+        if (voucher.receiver_name) details.push({ label: "Received By", value: voucher.receiver_name });
 
         const html = generateVoucherPrintHTML({
             title: typeName.toUpperCase(),
@@ -397,6 +606,37 @@ function StockEntryPage() {
         reader.readAsBinaryString(file);
     };
 
+    const handleAttachmentChange = async (e: React.ChangeEvent<HTMLInputElement>) => {
+        const file = e.target.files?.[0];
+        e.target.value = "";
+        if (!file || !voucher.id) return;
+
+        const reader = new FileReader();
+        reader.onload = async (evt) => {
+            try {
+                // Strip the "data:<mime>;base64," prefix
+                const data = String(evt.target?.result || "").split(",")[1] || "";
+                const attachment = await addVoucherAttachment(voucher.id!, file.name, data);
+                setAttachments(prev => [...prev, attachment]);
+            } catch (error) {
+                console.error("Failed to attach file:", error);
+                alert("Error attaching file: " + error);
+            }
+        };
+        reader.readAsDataURL(file);
+    };
+
+    const handleDeleteAttachment = async (id: number) => {
+        if (!confirm("Remove this attachment?")) return;
+        try {
+            await deleteVoucherAttachment(id);
+            setAttachments(prev => prev.filter(a => a.id !== id));
+        } catch (error) {
+            console.error("Failed to delete attachment:", error);
+            alert("Error removing attachment: " + error);
+        }
+    };
+
     return (
         <div className="flex flex-col h-[calc(100vh-140px)]">
             <div className="flex-1 space-y-6 overflow-y-auto pb-20 pr-1">
@@ -438,7 +678,7 @@ function StockEntryPage() {
                                 id="type-input"
                                 options={transactionTypes.map(t => ({ label: t.name, value: String(t.id) }))}
                                 value={voucher.voucher_type_id ? String(voucher.voucher_type_id) : ""}
-                                onChange={(val) => setVoucher({ ...voucher, voucher_type_id: Number(val) })}
+                                onChange={(val) => handleTypeChange(Number(val))}
                                 onKeyDown={(e) => handleKeyDown(e, showSource ? "#source-input" : (showDestination ? "#dest-input" : "#remarks-input"))}
                                 placeholder="Select type"
                                 disabled={isViewMode}
@@ -509,6 +749,146 @@ function StockEntryPage() {
 
                         </div>
                     </CardContent>
+                    {isPurchase && (
+                        <CardContent className="grid grid-cols-2 md:grid-cols-4 gap-4 pt-0 pb-4">
+                            <div className={selectedType?.name === "Purchase Inward" ? "" : "col-span-2"}>
+                                <Label>Supplier</Label>
+                                <Combobox
+                                    id="supplier-input"
+                                    options={suppliers
+                                        .filter(s => s.is_active || s.id === voucher.supplier_id)
+                                        .map(s => ({ label: `${s.name} (${s.code})`, value: String(s.id) }))}
+                                    value={voucher.supplier_id ? String(voucher.supplier_id) : ""}
+                                    onChange={(val) => setVoucher({ ...voucher, supplier_id: Number(val) })}
+                                    placeholder="Select Supplier"
+                                    disabled={isViewMode}
+                                    className="h-8 w-full"
+                                />
+                            </div>
+                            {selectedType?.name === "Purchase Inward" && (
+                                <div>
+                                    <Label>Purchase Order</Label>
+                                    <Combobox
+                                        id="po-input"
+                                        options={orderOptions()}
+                                        value={voucher.purchase_order_id ? String(voucher.purchase_order_id) : "none"}
+                                        onChange={(val) => applyPurchaseOrder(orderLines, val === "none" ? undefined : Number(val))}
+                                        placeholder="No purchase order"
+                                        disabled={isViewMode || isEditMode}
+                                        className="h-8 w-full"
+                                    />
+                                </div>
+                            )}
+                            <div>
+                                <Label>Bill No</Label>
+                                <Input
+                                    value={voucher.bill_number || ""}
+                                    onChange={(e) => setVoucher({ ...voucher, bill_number: e.target.value })}
+                                    placeholder="Supplier invoice"
+                                    disabled={isViewMode}
+                                    className="h-8 w-full"
+                                />
+                            </div>
+                            <div>
+                                <Label>Bill Date</Label>
+                                <Input
+                                    type="date"
+                                    value={voucher.bill_date || ""}
+                                    onChange={(e) => setVoucher({ ...voucher, bill_date: e.target.value || undefined })}
+                                    disabled={isViewMode}
+                                    className="h-8 w-full"
+                                />
+                            </div>
+                        </CardContent>
+                    )}
+                    {isConsumption && (
+                        <CardContent className="grid grid-cols-2 md:grid-cols-4 gap-4 pt-0 pb-4">
+                            <div className="col-span-2">
+                                <Label>Project</Label>
+                                <Combobox
+                                    id="project-input"
+                                    options={projectOptions}
+                                    value={voucher.project_id ? String(voucher.project_id) : "none"}
+                                    onChange={(val) => setVoucher({
+                                        ...voucher,
+                                        project_id: val === "none" ? undefined : Number(val),
+                                        activity_code: val === "none" ? undefined : voucher.activity_code
+                                    })}
+                                    placeholder="No project"
+                                    disabled={isViewMode}
+                                    className="h-8 w-full"
+                                />
+                            </div>
+                            <div>
+                                <Label>Activity Code</Label>
+                                <Input
+                                    value={voucher.activity_code || ""}
+                                    onChange={(e) => setVoucher({ ...voucher, activity_code: e.target.value })}
+                                    placeholder="e.g. FDN-01"
+                                    disabled={isViewMode || !voucher.project_id}
+                                    className="h-8 w-full"
+                                />
+                            </div>
+                        </CardContent>
+                    )}
+                    {isIssue && (
+                        <CardContent className="grid grid-cols-2 md:grid-cols-4 gap-4 pt-0 pb-4">
+                            <div className="col-span-2">
+                                <Label>Requisition</Label>
+                                <Combobox
+                                    id="requisition-input"
+                                    options={requisitionOptions()}
+                                    value={voucher.requisition_id ? String(voucher.requisition_id) : "none"}
+                                    onChange={(val) => applyRequisition(requisitionLines, val === "none" ? undefined : Number(val))}
+                                    placeholder="No requisition"
+                                    disabled={isViewMode || isEditMode}
+                                    className="h-8 w-full"
+                                />
+                            </div>
+                        </CardContent>
+                    )}
+                    <CardContent className="grid grid-cols-2 md:grid-cols-4 gap-4 pt-0 pb-4">
+                        <div>
+                            <Label>Challan No</Label>
+                            <Input
+                                value={voucher.challan_number || ""}
+                                onChange={(e) => setVoucher({ ...voucher, challan_number: e.target.value })}
+                                placeholder="Delivery challan"
+                                disabled={isViewMode}
+                                className="h-8 w-full"
+                            />
+                        </div>
+                        <div>
+                            <Label>Vehicle No</Label>
+                            <Input
+                                value={voucher.vehicle_number || ""}
+                                onChange={(e) => setVoucher({ ...voucher, vehicle_number: e.target.value })}
+                                placeholder="Vehicle registration"
+                                disabled={isViewMode}
+                                className="h-8 w-full"
+                            />
+                        </div>
+                        <div>
+                            <Label>Driver</Label>
+                            <Input
+                                value={voucher.driver_name || ""}
+                                onChange={(e) => setVoucher({ ...voucher, driver_name: e.target.value })}
+                                placeholder="Driver name"
+                                disabled={isViewMode}
+                                className="h-8 w-full"
+                            />
+                        </div>
+                        <div>
+                            <Label>Received By</Label>
+                            <Input
+                                value={voucher.receiver_name || ""}
+                                onChange={(e) => setVoucher({ ...voucher, receiver_name: e.target.value })}
+                                placeholder="Receiver name"
+                                disabled={isViewMode}
+                                className="h-8 w-full"
+                            />
+                        </div>
+                    </CardContent>
                 </Card>
 
                 <Card>
@@ -521,19 +901,33 @@ function StockEntryPage() {
                                         <div className="flex items-center justify-between">
                                             <span>Item Name</span>
                                             {!isViewMode && (
-                                                <Button
-                                                    variant="ghost"
-                                                    size="sm"
-                                                    onClick={handleImportClick}
-                                                    className="h-6 gap-1 text-xs"
-                                                >
-                                                    <Upload className="h-3 w-3" />
-                                                    Import
-                                                </Button>
+                                                <div className="flex items-center gap-2">
+                                                    <div className="relative">
+                                                        <ScanLine className="absolute left-2 top-1.5 h-3 w-3 text-muted-foreground" />
+                                                        <Input
+                                                            id="scan-input"
+                                                            value={scanValue}
+                                                            onChange={(e) => setScanValue(e.target.value)}
+                                                            onKeyDown={handleScan}
+                                                            placeholder="Scan barcode"
+                                                            className="h-6 w-[160px] pl-6 text-xs"
+                                                        />
+                                                    </div>
+                                                    <Button
+                                                        variant="ghost"
+                                                        size="sm"
+                                                        onClick={handleImportClick}
+                                                        className="h-6 gap-1 text-xs"
+                                                    >
+                                                        <Upload className="h-3 w-3" />
+                                                        Import
+                                                    </Button>
+                                                </div>
                                             )}
                                         </div>
                                     </TableHead>
                                     <TableHead className="w-[120px] py-1">Quantity</TableHead>
+                                    <TableHead className="w-[220px] py-1">Notes</TableHead>
                                     <TableHead className="w-[100px] text-right py-1">Action</TableHead>
                                 </TableRow>
                             </TableHeader>
@@ -589,6 +983,15 @@ function StockEntryPage() {
                                                 )}
                                             </div>
                                         </TableCell>
+                                        <TableCell className="py-1">
+                                            <Input
+                                                value={vi.remarks || ""}
+                                                onChange={(e) => updateItemRow(index, "remarks", e.target.value)}
+                                                placeholder="Line note"
+                                                disabled={isViewMode}
+                                                className="h-8"
+                                            />
+                                        </TableCell>
                                         <TableCell className="text-right py-1 space-x-1">
                                             <Button
                                                 variant="ghost"
@@ -615,6 +1018,64 @@ function StockEntryPage() {
                         </Table>
                     </CardContent>
                 </Card>
+
+                {voucher.id && (
+                    <Card>
+                        <CardContent className="pt-4 pb-4 space-y-3">
+                            <div className="flex items-center justify-between">
+                                <Label className="flex items-center gap-2">
+                                    <Paperclip className="h-4 w-4" /> Attachments
+                                </Label>
+                                <Button
+                                    variant="outline"
+                                    size="sm"
+                                    onClick={() => attachmentInputRef.current?.click()}
+                                    className="gap-1"
+                                >
+                                    <Upload className="h-3 w-3" /> Attach File
+                                </Button>
+                                <input
+                                    type="file"
+                                    ref={attachmentInputRef}
+                                    className="hidden"
+                                    accept="image/*,.pdf"
+                                    onChange={handleAttachmentChange}
+                                />
+                            </div>
+                            {attachments.length === 0 ? (
+                                <p className="text-sm text-muted-foreground">
+                                    No attachments. Add photos of delivery challans or signed receipts.
+                                </p>
+                            ) : (
+                                <ul className="space-y-1">
+                                    {attachments.map(a => (
+                                        <li key={a.id} className="flex items-center justify-between text-sm">
+                                            <button
+                                                type="button"
+                                                onClick={() => getVoucherAttachmentFile(a.id).then(openPath).catch(err => alert("Error opening file: " + err))}
+                                                className="flex items-center gap-2 text-primary hover:underline"
+                                            >
+                                                <FileText className="h-4 w-4" />
+                                                {a.file_name}
+                                                <span className="text-muted-foreground">
+                                                    ({Math.max(1, Math.round(a.size_bytes / 1024))} KB)
+                                                </span>
+                                            </button>
+                                            <Button
+                                                variant="ghost"
+                                                size="icon"
+                                                onClick={() => handleDeleteAttachment(a.id)}
+                                                className="h-7 w-7 text-destructive hover:bg-destructive/10"
+                                            >
+                                                <Trash2 className="h-4 w-4" />
+                                            </Button>
+                                        </li>
+                                    ))}
+                                </ul>
+                            )}
+                        </CardContent>
+                    </Card>
+                )}
             </div>
 
             {/* Sticky Bottom Section */}
@@ -1,6 +1,6 @@
 import { useEffect, useState } from "react";
 import { getStockMovementHistory, getItems, getSites, getInventoryTransactionTypes, StockMovementHistory, Item, Site, InventoryTransactionType } from "../api";
-import { formatDate } from "@/lib/utils";
+import { formatDate, formatQuantity } from "@/lib/utils";
 import {
     Table,
     TableBody,
@@ -48,8 +48,8 @@ function StockMovementReport() {
                 getSites(),
                 getInventoryTransactionTypes(),
             ]);
-            setItems(itemsData);
-            setSites(sitesData);
+            setItems(itemsData.items);
+            setSites(sitesData.items);
             setTransactionTypes(typesData);
         } catch (error) {
             console.error("Failed to fetch master data:", error);
@@ -117,13 +117,13 @@ function StockMovementReport() {
                 { header: 'Brand', accessor: 'brand_name', width: '10%' },
                 { header: 'Model', accessor: 'model_name', width: '10%' },
                 { header: 'Site', accessor: 'site_name', width: '12%' },
-                { header: 'In', accessor: 'stock_in', align: 'right', format: (val) => val > 0 ? val.toFixed(2) : '-', width: '7%' },
-                { header: 'Out', accessor: 'stock_out', align: 'right', format: (val) => val > 0 ? val.toFixed(2) : '-', width: '7%' },
+                { header: 'In', accessor: 'stock_in', align: 'right', format: (val) => val > 0 ? formatQuantity(val) : '-', width: '7%' },
+                { header: 'Out', accessor: 'stock_out', align: 'right', format: (val) => val > 0 ? formatQuantity(val) : '-', width: '7%' },
                 {
                     header: 'Balance',
                     accessor: 'running_balance',
                     align: 'right',
-                    format: (val) => val ? val.toFixed(2) : '-',
+                    format: (val) => val ? formatQuantity(val) : '-',
                     width: '9%'
                 },
             ],
@@ -248,13 +248,13 @@ function StockMovementReport() {
                                         {movement.remarks || "-"}
                                     </TableCell>
                                     <TableCell className="text-right text-green-600 font-medium">
-                                        {movement.stock_in > 0 ? movement.stock_in.toFixed(2) : "-"}
+                                        {movement.stock_in > 0 ? formatQuantity(movement.stock_in) : "-"}
                                     </TableCell>
                                     <TableCell className="text-right text-red-600 font-medium">
-                                        {movement.stock_out > 0 ? movement.stock_out.toFixed(2) : "-"}
+                                        {movement.stock_out > 0 ? formatQuantity(movement.stock_out) : "-"}
                                     </TableCell>
                                     <TableCell className="text-right font-semibold">
-                                        {filters.itemId !== "all" ? movement.running_balance.toFixed(2) : "-"}
+                                        {filters.itemId !== "all" ? formatQuantity(movement.running_balance) : "-"}
                                     </TableCell>
                                 </TableRow>
                             ))}
@@ -0,0 +1,275 @@
+import { useEffect, useState } from "react";
+import { createSupplier, deleteSupplier, getSuppliers, updateSupplier, Supplier } from "../api";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Card, CardContent } from "@/components/ui/card";
+import { Label } from "@/components/ui/label";
+import { Trash2, Pencil, X } from "lucide-react";
+
+const emptySupplier: Omit<Supplier, "id"> = {
+    code: "",
+    name: "",
+    gstin: "",
+    contact_person: "",
+    phone: "",
+    email: "",
+    address: "",
+    is_active: true,
+};
+
+function SuppliersPage() {
+    const [suppliers, setSuppliers] = useState<Supplier[]>([]);
+    const [formData, setFormData] = useState<Omit<Supplier, "id">>(emptySupplier);
+    const [editingId, setEditingId] = useState<number | null>(null);
+    const [search, setSearch] = useState("");
+
+    const fetchSuppliers = async () => {
+        try {
+            setSuppliers(await getSuppliers());
+        } catch (error) {
+            console.error("Failed to fetch suppliers:", error);
+        }
+    };
+
+    useEffect(() => {
+        fetchSuppliers();
+    }, []);
+
+    const term = search.trim().toLowerCase();
+    const filteredSuppliers = suppliers.filter(s =>
+        !term ||
+        s.code.toLowerCase().includes(term) ||
+        s.name.toLowerCase().includes(term) ||
+        (s.gstin || "").toLowerCase().includes(term) ||
+        (s.contact_person || "").toLowerCase().includes(term)
+    );
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        try {
+            if (editingId) {
+                await updateSupplier({ id: editingId, ...formData });
+            } else {
+                await createSupplier(formData as Supplier);
+            }
+            setFormData(emptySupplier);
+            setEditingId(null);
+            fetchSuppliers();
+        } catch (error) {
+            console.error("Failed to save supplier:", error);
+            alert("Error saving supplier: " + error);
+        }
+    };
+
+    const handleEdit = (supplier: Supplier) => {
+        setEditingId(supplier.id!);
+        setFormData({
+            code: supplier.code,
+            name: supplier.name,
+            gstin: supplier.gstin || "",
+            contact_person: supplier.contact_person || "",
+            phone: supplier.phone || "",
+            email: supplier.email || "",
+            address: supplier.address || "",
+            is_active: supplier.is_active,
+        });
+    };
+
+    const handleCancel = () => {
+        setEditingId(null);
+        setFormData(emptySupplier);
+    };
+
+    const handleDelete = async (id: number) => {
+        if (confirm("Are you sure you want to delete this supplier?")) {
+            try {
+                const outcome = await deleteSupplier(id);
+                if (outcome === "Deactivated") {
+                    alert("This supplier is in use, so it was deactivated instead of deleted.");
+                }
+                fetchSuppliers();
+            } catch (error) {
+                console.error("Failed to delete supplier:", error);
+                alert("Error deleting supplier: " + error);
+            }
+        }
+    };
+
+    return (
+        <div className="space-y-6">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Suppliers</h2>
+            </div>
+
+            <Card>
+                <CardContent className="pt-6">
+                    <form onSubmit={handleSubmit} className="grid grid-cols-2 md:grid-cols-4 gap-4 items-end">
+                        <div>
+                            <Label htmlFor="code">Code</Label>
+                            <Input
+                                id="code"
+                                value={formData.code}
+                                onChange={(e) => setFormData({ ...formData, code: e.target.value })}
+                                required
+                                placeholder="SUP001"
+                            />
+                        </div>
+                        <div>
+                            <Label htmlFor="name">Name</Label>
+                            <Input
+                                id="name"
+                                value={formData.name}
+                                onChange={(e) => setFormData({ ...formData, name: e.target.value })}
+                                required
+                                placeholder="Supplier name"
+                            />
+                        </div>
+                        <div>
+                            <Label htmlFor="gstin">GSTIN</Label>
+                            <Input
+                                id="gstin"
+                                value={formData.gstin}
+                                onChange={(e) => setFormData({ ...formData, gstin: e.target.value })}
+                                placeholder="22AAAAA0000A1Z5"
+                            />
+                        </div>
+                        <div>
+                            <Label htmlFor="contact_person">Contact Person</Label>
+                            <Input
+                                id="contact_person"
+                                value={formData.contact_person}
+                                onChange={(e) => setFormData({ ...formData, contact_person: e.target.value })}
+                            />
+                        </div>
+                        <div>
+                            <Label htmlFor="phone">Phone</Label>
+                            <Input
+                                id="phone"
+                                value={formData.phone}
+                                onChange={(e) => setFormData({ ...formData, phone: e.target.value })}
+                            />
+                        </div>
+                        <div>
+                            <Label htmlFor="email">Email</Label>
+                            <Input
+                                id="email"
+                                type="email"
+                                value={formData.email}
+                                onChange={(e) => setFormData({ ...formData, email: e.target.value })}
+                            />
+                        </div>
+                        <div>
+                            <Label htmlFor="address">Address</Label>
+                            <Input
+                                id="address"
+                                value={formData.address}
+                                onChange={(e) => setFormData({ ...formData, address: e.target.value })}
+                            />
+                        </div>
+                        <div className="flex items-center gap-4 h-9">
+                            <div className="flex items-center space-x-2">
+                                <input
+                                    type="checkbox"
+                                    id="is_active"
+                                    checked={formData.is_active}
+                                    onChange={(e) => setFormData({ ...formData, is_active: e.target.checked })}
+                                    className="h-4 w-4 rounded border-gray-300 text-primary focus:ring-primary"
+                                />
+                                <Label htmlFor="is_active" className="cursor-pointer">Active</Label>
+                            </div>
+                            <Button type="submit">{editingId ? "Update Supplier" : "Add Supplier"}</Button>
+                            {editingId && (
+                                <Button type="button" variant="outline" onClick={handleCancel}>
+                                    <X className="h-4 w-4 mr-2" />
+                                    Cancel
+                                </Button>
+                            )}
+                        </div>
+                    </form>
+                </CardContent>
+            </Card>
+
+            <Input
+                value={search}
+                onChange={(e) => setSearch(e.target.value)}
+                placeholder="Search by code, name, GSTIN or contact..."
+                className="max-w-sm"
+            />
+
+            <div className="rounded-md border bg-card">
+                <Table>
+                    <TableHeader>
+                        <TableRow>
+                            <TableHead className="w-[60px]">S.No</TableHead>
+                            <TableHead className="w-[100px]">Code</TableHead>
+                            <TableHead>Name</TableHead>
+                            <TableHead>GSTIN</TableHead>
+                            <TableHead>Contact</TableHead>
+                            <TableHead>Phone</TableHead>
+                            <TableHead className="w-[100px]">Status</TableHead>
+                            <TableHead className="text-right">Actions</TableHead>
+                        </TableRow>
+                    </TableHeader>
+                    <TableBody>
+                        {filteredSuppliers.map((supplier, index) => (
+                            <TableRow key={supplier.id}>
+                                <TableCell className="font-medium text-muted-foreground">{index + 1}</TableCell>
+                                <TableCell className="font-medium">{supplier.code}</TableCell>
+                                <TableCell>{supplier.name}</TableCell>
+                                <TableCell>{supplier.gstin}</TableCell>
+                                <TableCell>{supplier.contact_person}</TableCell>
+                                <TableCell>{supplier.phone}</TableCell>
+                                <TableCell>
+                                    {supplier.is_active ? (
+                                        <span className="inline-flex items-center rounded-full bg-green-100 px-2.5 py-0.5 text-xs font-medium text-green-800">
+                                            Active
+                                        </span>
+                                    ) : (
+                                        <span className="inline-flex items-center rounded-full bg-red-100 px-2.5 py-0.5 text-xs font-medium text-red-800">
+                                            Inactive
+                                        </span>
+                                    )}
+                                </TableCell>
+                                <TableCell className="text-right space-x-2">
+                                    <Button
+                                        variant="ghost"
+                                        size="icon"
+                                        onClick={() => handleEdit(supplier)}
+                                        className="text-primary hover:text-primary hover:bg-primary/10"
+                                    >
+                                        <Pencil className="h-4 w-4" />
+                                    </Button>
+                                    <Button
+                                        variant="ghost"
+                                        size="icon"
+                                        onClick={() => handleDelete(supplier.id!)}
+                                        className="text-destructive hover:text-destructive hover:bg-destructive/10"
+                                    >
+                                        <Trash2 className="h-4 w-4" />
+                                    </Button>
+                                </TableCell>
+                            </TableRow>
+                        ))}
+                        {filteredSuppliers.length === 0 && (
+                            <TableRow>
+                                <TableCell colSpan={8} className="h-24 text-center text-muted-foreground">
+                                    No suppliers found.
+                                </TableCell>
+                            </TableRow>
+                        )}
+                    </TableBody>
+                </Table>
+            </div>
+        </div>
+    );
+}
+
+export default SuppliersPage;
@@ -0,0 +1,329 @@
+import { useEffect, useRef, useState } from "react";
+import {
+    exportSyncPackage,
+    getLanStatus,
+    getSyncStatus,
+    importSyncPackage,
+    resolveSyncConflict,
+    setSyncNodeName,
+    SyncConflict,
+    SyncImportReport,
+    SyncStatus,
+} from "../api";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Check, Download, FolderOpen, Upload, X } from "lucide-react";
+import { openPath } from "@tauri-apps/plugin-opener";
+
+const ENTITY_LABELS: Record<string, string> = {
+    item_categories: "Category",
+    brands: "Brand",
+    models: "Model",
+    sites: "Site",
+    suppliers: "Supplier",
+    items: "Item",
+    inventory_vouchers: "Voucher",
+};
+
+// Names the record and lists the fields the two versions disagree on
+function describeConflict(conflict: SyncConflict) {
+    const local = conflict.local_values || {};
+    const remote = conflict.remote_values || {};
+    const record = { ...local, ...remote };
+    const name = record.transaction_number || record.code || record.name || conflict.entity_uuid.slice(0, 8);
+    const fields = Object.keys(record).filter(
+        (key) => conflict.local_values && conflict.remote_values
+            && JSON.stringify(local[key]) !== JSON.stringify(remote[key])
+    );
+    return { name, fields };
+}
+
+function formatValue(value: any) {
+    if (value === null || value === undefined || value === "") return "-";
+    return typeof value === "object" ? JSON.stringify(value) : String(value);
+}
+
+function SyncPage() {
+    const [status, setStatus] = useState<SyncStatus | null>(null);
+    const [nodeName, setNodeName] = useState("");
+    const [report, setReport] = useState<SyncImportReport | null>(null);
+    const [isWorking, setIsWorking] = useState(false);
+    const [isClient, setIsClient] = useState(false);
+    const fileInputRef = useRef<HTMLInputElement>(null);
+
+    const fetchStatus = async () => {
+        try {
+            const status = await getSyncStatus();
+            setStatus(status);
+            setNodeName(status.node_name);
+        } catch (error) {
+            console.error("Failed to fetch sync status:", error);
+        }
+    };
+
+    useEffect(() => {
+        fetchStatus();
+        getLanStatus()
+            .then((lan) => setIsClient(lan.client_enabled))
+            .catch((error) => console.error("Failed to fetch LAN status:", error));
+    }, []);
+
+    const handleRename = async (e: React.FormEvent) => {
+        e.preventDefault();
+        try {
+            await setSyncNodeName(nodeName);
+            fetchStatus();
+        } catch (error) {
+            console.error("Failed to rename node:", error);
+            alert("Error: " + error);
+        }
+    };
+
+    const handleExport = async (targetNodeId?: string) => {
+        setIsWorking(true);
+        try {
+            const info = await exportSyncPackage(targetNodeId);
+            fetchStatus();
+            if (confirm(
+                `Sync package ${info.file_name} created with ${info.masters} master records, ` +
+                `${info.vouchers} vouchers and ${info.deleted_vouchers} deletions.\n\nOpen its folder?`
+            )) {
+                await openPath(info.path.replace(/[\\/][^\\/]+$/, ""));
+            }
+        } catch (error) {
+            console.error("Failed to export sync package:", error);
+            alert("Error: " + error);
+        } finally {
+            setIsWorking(false);
+        }
+    };
+
+    const handleImport = (e: React.ChangeEvent<HTMLInputElement>) => {
+        const file = e.target.files?.[0];
+        e.target.value = "";
+        if (!file) return;
+
+        const reader = new FileReader();
+        reader.onload = async (evt) => {
+            setIsWorking(true);
+            try {
+                // Strip the "data:<mime>;base64," prefix
+                const data = String(evt.target?.result || "").split(",")[1] || "";
+                setReport(await importSyncPackage(data));
+                fetchStatus();
+            } catch (error) {
+                console.error("Failed to import sync package:", error);
+                alert("Error: " + error);
+            } finally {
+                setIsWorking(false);
+            }
+        };
+        reader.readAsDataURL(file);
+    };
+
+    const handleResolve = async (conflict: SyncConflict, useRemote: boolean) => {
+        try {
+            await resolveSyncConflict(conflict.id, useRemote);
+            fetchStatus();
+        } catch (error) {
+            console.error("Failed to resolve conflict:", error);
+            alert("Error: " + error);
+        }
+    };
+
+    if (isClient) {
+        return (
+            <div className="space-y-6">
+                <h2 className="text-3xl font-bold tracking-tight">Sync</h2>
+                <p className="text-muted-foreground">
+                    This computer works on the host's database over the network. Sync packages are exported and imported on the host.
+                </p>
+            </div>
+        );
+    }
+
+    return (
+        <div className="space-y-6">
+            <div className="flex items-center justify-between">
+                <h2 className="text-3xl font-bold tracking-tight">Sync</h2>
+                <div className="flex gap-2">
+                    <Button variant="outline" disabled={isWorking} onClick={() => handleExport()} className="gap-2">
+                        <Download className="h-4 w-4" /> Export for New Node
+                    </Button>
+                    <Button disabled={isWorking} onClick={() => fileInputRef.current?.click()} className="gap-2">
+                        <Upload className="h-4 w-4" /> Import Package
+                    </Button>
+                    <input
+                        type="file"
+                        ref={fileInputRef}
+                        className="hidden"
+                        accept=".gz"
+                        onChange={handleImport}
+                    />
+                </div>
+            </div>
+
+            <Card>
+                <CardContent className="pt-6">
+                    <form onSubmit={handleRename} className="flex flex-row gap-4 items-end max-w-4xl">
+                        <div className="space-y-2 flex-1">
+                            <Label htmlFor="node-name">This Node</Label>
+                            <Input
+                                id="node-name"
+                                value={nodeName}
+                                onChange={(e) => setNodeName(e.target.value)}
+                                required
+                                placeholder="e.g. Head Office"
+                            />
+                        </div>
+                        <Button type="submit" variant="outline">Rename</Button>
+                        {status && (
+                            <Button
+                                type="button"
+                                variant="ghost"
+                                title="Open package folder"
+                                onClick={() => openPath(status.packages_dir).catch(err => alert("Error opening folder: " + err))}
+                            >
+                                <FolderOpen className="h-4 w-4" />
+                            </Button>
+                        )}
+                    </form>
+                    <p className="text-xs text-muted-foreground mt-2">
+                        Node ID <span className="font-mono">{status?.node_id}</span>. Each computer that syncs needs its own database; a copied database cannot sync with the original.
+                    </p>
+                </CardContent>
+            </Card>
+
+            {report && (
+                <Card>
+                    <CardHeader className="flex flex-row items-center justify-between space-y-0">
+                        <CardTitle className="text-lg">Imported from {report.node_name}</CardTitle>
+                        <Button variant="ghost" size="icon" title="Close" onClick={() => setReport(null)}>
+                            <X className="h-4 w-4" />
+                        </Button>
+                    </CardHeader>
+                    <CardContent className="text-sm space-y-1">
+                        <p>Master records: {report.masters_added} added, {report.masters_updated} updated</p>
+                        <p>
+                            Vouchers: {report.vouchers_added} added, {report.vouchers_updated} updated, {report.vouchers_deleted} deleted
+                        </p>
+                        {report.conflicts > 0 && (
+                            <p className="text-destructive">{report.conflicts} records could not be applied; see Conflicts below.</p>
+                        )}
+                        {report.warnings.map((warning) => (
+                            <p key={warning} className="text-amber-600">{warning}</p>
+                        ))}
+                    </CardContent>
+                </Card>
+            )}
+
+            <div className="rounded-md border bg-card">
+                <Table>
+                    <TableHeader>
+                        <TableRow>
+                            <TableHead>Node</TableHead>
+                            <TableHead>Last Sent</TableHead>
+                            <TableHead>Last Received</TableHead>
+                            <TableHead>Changes to Send</TableHead>
+                            <TableHead className="text-right">Actions</TableHead>
+                        </TableRow>
+                    </TableHeader>
+                    <TableBody>
+                        {status?.peers.length === 0 && (
+                            <TableRow>
+                                <TableCell colSpan={5} className="text-center text-muted-foreground">
+                                    No other nodes yet. Export a package for a new node, or import one from it.
+                                </TableCell>
+                            </TableRow>
+                        )}
+                        {status?.peers.map((peer) => (
+                            <TableRow key={peer.node_id}>
+                                <TableCell className="font-medium">{peer.node_name}</TableCell>
+                                <TableCell>{peer.last_exported_at || "-"}</TableCell>
+                                <TableCell>{peer.last_imported_at || "-"}</TableCell>
+                                <TableCell>{peer.pending_changes}</TableCell>
+                                <TableCell className="text-right">
+                                    <Button
+                                        variant="ghost"
+                                        size="icon"
+                                        title="Export changes for this node"
+                                        disabled={isWorking}
+                                        onClick={() => handleExport(peer.node_id)}
+                                        className="text-primary hover:text-primary hover:bg-primary/10"
+                                    >
+                                        <Download className="h-4 w-4" />
+                                    </Button>
+                                </TableCell>
+                            </TableRow>
+                        ))}
+                    </TableBody>
+                </Table>
+            </div>
+
+            {status && status.conflicts.length > 0 && (
+                <Card>
+                    <CardHeader>
+                        <CardTitle className="text-lg">Conflicts</CardTitle>
+                    </CardHeader>
+                    <CardContent>
+                        <Table>
+                            <TableHeader>
+                                <TableRow>
+                                    <TableHead>Record</TableHead>
+                                    <TableHead>From</TableHead>
+                                    <TableHead>Problem</TableHead>
+                                    <TableHead className="text-right">Actions</TableHead>
+                                </TableRow>
+                            </TableHeader>
+                            <TableBody>
+                                {status.conflicts.map((conflict) => {
+                                    const { name, fields } = describeConflict(conflict);
+                                    return (
+                                        <TableRow key={conflict.id}>
+                                            <TableCell>
+                                                <span className="text-muted-foreground">{ENTITY_LABELS[conflict.entity] || conflict.entity}</span>{" "}
+                                                <span className="font-medium">{name}</span>
+                                            </TableCell>
+                                            <TableCell>{conflict.node_name || conflict.node_id.slice(0, 6)}</TableCell>
+                                            <TableCell className="text-sm">
+                                                <div>{conflict.message}</div>
+                                                {fields.map((field) => (
+                                                    <div key={field} className="text-xs text-muted-foreground">
+                                                        {field}: {formatValue(conflict.local_values?.[field])} here,{" "}
+                                                        {formatValue(conflict.remote_values?.[field])} there
+                                                    </div>
+                                                ))}
+                                            </TableCell>
+                                            <TableCell className="text-right space-x-2 whitespace-nowrap">
+                                                <Button variant="outline" size="sm" onClick={() => handleResolve(conflict, false)} className="gap-1">
+                                                    <Check className="h-4 w-4" /> Keep Mine
+                                                </Button>
+                                                {conflict.remote_values && (
+                                                    <Button size="sm" onClick={() => handleResolve(conflict, true)}>
+                                                        Take Theirs
+                                                    </Button>
+                                                )}
+                                            </TableCell>
+                                        </TableRow>
+                                    );
+                                })}
+                            </TableBody>
+                        </Table>
+                    </CardContent>
+                </Card>
+            )}
+        </div>
+    );
+}
+
+export default SyncPage;
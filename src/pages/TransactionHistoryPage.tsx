@@ -4,15 +4,27 @@ import {
     InventoryVoucherDisplay,
     getInventoryVouchers,
     deleteInventoryVoucher,
-    getInventoryVoucher,
+    generateVoucherDocument,
     getSites,
     getItems,
     getInventoryTransactionTypes,
-    // Site, // Unused
-    // Item, // Unused
-    // InventoryTransactionType // Unused
+    Site,
+    Item,
+    InventoryTransactionType,
+    InventoryVoucherFilters,
+    VoucherStatus,
 } from "../api";
 import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Combobox } from "@/components/ui/combobox";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
 import { formatDate } from "@/lib/utils";
 import {
     Table,
@@ -23,9 +35,9 @@ import {
     TableRow,
 } from "@/components/ui/table";
 import { Card, CardContent, CardHeader, CardTitle, CardFooter } from "@/components/ui/card";
-import { Edit, Trash2, Plus, Printer, Eye } from "lucide-react";
+import { Edit, Trash2, Plus, Printer, Eye, X } from "lucide-react";
 import { PaginationControls } from "@/components/ui/pagination-controls";
-import { generateVoucherPrintHTML, openPrintWindow } from "@/lib/printUtils";
+import { openPath } from "@tauri-apps/plugin-opener";
 
 export default function TransactionHistoryPage() {
     const [transactions, setTransactions] = useState<InventoryVoucherDisplay[]>([]);
@@ -34,15 +46,64 @@ export default function TransactionHistoryPage() {
     const [totalCount, setTotalCount] = useState(0);
     const [isPrinting, setIsPrinting] = useState(false);
 
+    const [sites, setSites] = useState<Site[]>([]);
+    const [items, setItems] = useState<Item[]>([]);
+    const [transactionTypes, setTransactionTypes] = useState<InventoryTransactionType[]>([]);
+
+    const emptyFilters = {
+        fromDate: "",
+        toDate: "",
+        voucherTypeId: "all",
+        sourceSiteId: "all",
+        destinationSiteId: "all",
+        itemId: "all",
+        transactionNumber: "",
+        remarks: "",
+        status: "all",
+        sort: "created_at:desc",
+    };
+    const [filters, setFilters] = useState(emptyFilters);
+
     const navigate = useNavigate();
 
     useEffect(() => {
-        loadTransactions();
-    }, [currentPage, pageSize]);
+        Promise.all([getSites(), getItems(), getInventoryTransactionTypes()])
+            .then(([sitesData, itemsData, typesData]) => {
+                setSites(sitesData.items);
+                setItems(itemsData.items);
+                setTransactionTypes(typesData);
+            })
+            .catch((error) => console.error("Failed to fetch master data:", error));
+    }, []);
+
+    useEffect(() => {
+        setCurrentPage(1);
+    }, [filters]);
+
+    useEffect(() => {
+        const timer = setTimeout(loadTransactions, 250);
+        return () => clearTimeout(timer);
+    }, [filters, currentPage, pageSize]);
+
+    const toId = (value: string) => (value === "all" ? undefined : Number(value));
 
     const loadTransactions = async () => {
         try {
-            const data = await getInventoryVouchers(currentPage, pageSize);
+            const [sortBy, sortDir] = filters.sort.split(":");
+            const query: InventoryVoucherFilters = {
+                from_date: filters.fromDate || undefined,
+                to_date: filters.toDate || undefined,
+                voucher_type_id: toId(filters.voucherTypeId),
+                source_site_id: toId(filters.sourceSiteId),
+                destination_site_id: toId(filters.destinationSiteId),
+                item_id: toId(filters.itemId),
+                transaction_number: filters.transactionNumber || undefined,
+                remarks: filters.remarks || undefined,
+                status: filters.status === "all" ? undefined : (filters.status as VoucherStatus),
+                sort_by: sortBy as InventoryVoucherFilters["sort_by"],
+                sort_dir: sortDir as "asc" | "desc",
+            };
+            const data = await getInventoryVouchers(currentPage, pageSize, query);
             setTransactions(data.items);
             setTotalCount(data.total_count);
         } catch (error) {
@@ -74,61 +135,11 @@ export default function TransactionHistoryPage() {
         if (isPrinting) return;
         setIsPrinting(true);
         try {
-            // Fetch all necessary data
-            // In a better optimized app, we would have an API endpoint that returns the full voucher with expanded names.
-            // For now, we fetch master data to resolve names.
-            const [voucher, sites, items, types] = await Promise.all([
-                getInventoryVoucher(id),
-                getSites(),
-                getItems(),
-                getInventoryTransactionTypes()
-            ]);
-
-            const typeName = types.find(t => t.id === voucher.voucher_type_id)?.name || "Voucher";
-            const sourceName = sites.find(s => s.id === voucher.source_site_id)?.name;
-            const destName = sites.find(s => s.id === voucher.destination_site_id)?.name;
-
-            // Filter valid items
-            const printItems = (voucher.items || [])
-                .filter(vi => vi.item_id && vi.item_id > 0)
-                .map(vi => {
-                    const item = items.find(i => i.id === vi.item_id);
-                    return {
-                        item_code: item?.code || "",
-                        item_name: item?.name || "Unknown Item",
-                        brand_name: item?.brand_name,
-                        model_name: item?.model_name,
-                        quantity: vi.quantity,
-                        unit: "Unit" // Default unit as it's not in Item interface
-                    };
-                });
-
-            if (printItems.length === 0) {
-                alert("No items to print.");
-                return;
-            }
-
-            const details = [
-                { label: "Voucher No", value: voucher.transaction_number || String(voucher.id) },
-                { label: "Date", value: formatDate(voucher.voucher_date) },
-                { label: "Type", value: typeName },
-                { label: "Remarks", value: voucher.remarks || "-" },
-            ];
-
-            if (sourceName) details.push({ label: "Source", value: sourceName });
-            if (destName) details.push({ label: "Destination", value: destName });
-
-            const html = generateVoucherPrintHTML({
-                title: typeName.toUpperCase(),
-                details,
-                items: printItems
-            });
-
-            openPrintWindow(html);
-
+            const path = await generateVoucherDocument(id);
+            await openPath(path);
         } catch (error) {
             console.error("Failed to print voucher:", error);
-            alert("Failed to generated print view.");
+            alert("Error generating voucher document: " + error);
         } finally {
             setIsPrinting(false);
         }
@@ -145,6 +156,128 @@ export default function TransactionHistoryPage() {
                 </Button>
             </div>
 
+            <Card>
+                <CardContent className="pt-6">
+                    <div className="grid grid-cols-1 md:grid-cols-3 lg:grid-cols-6 gap-4">
+                        <div className="space-y-2">
+                            <Label>From Date</Label>
+                            <Input
+                                type="date"
+                                value={filters.fromDate}
+                                onChange={(e) => setFilters({ ...filters, fromDate: e.target.value })}
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>To Date</Label>
+                            <Input
+                                type="date"
+                                value={filters.toDate}
+                                onChange={(e) => setFilters({ ...filters, toDate: e.target.value })}
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Transaction Type</Label>
+                            <Combobox
+                                options={[
+                                    { label: "All Types", value: "all" },
+                                    ...transactionTypes.map(t => ({ label: t.name, value: String(t.id) }))
+                                ]}
+                                value={filters.voucherTypeId}
+                                onChange={(val) => setFilters({ ...filters, voucherTypeId: val })}
+                                placeholder="All Types"
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Source</Label>
+                            <Combobox
+                                options={[
+                                    { label: "All Sites", value: "all" },
+                                    ...sites.map(s => ({ label: `${s.name} (${s.type})`, value: String(s.id) }))
+                                ]}
+                                value={filters.sourceSiteId}
+                                onChange={(val) => setFilters({ ...filters, sourceSiteId: val })}
+                                placeholder="All Sites"
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Destination</Label>
+                            <Combobox
+                                options={[
+                                    { label: "All Sites", value: "all" },
+                                    ...sites.map(s => ({ label: `${s.name} (${s.type})`, value: String(s.id) }))
+                                ]}
+                                value={filters.destinationSiteId}
+                                onChange={(val) => setFilters({ ...filters, destinationSiteId: val })}
+                                placeholder="All Sites"
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Item</Label>
+                            <Combobox
+                                options={[
+                                    { label: "All Items", value: "all" },
+                                    ...items.map(i => ({ label: `${i.name} (${i.code})`, value: String(i.id) }))
+                                ]}
+                                value={filters.itemId}
+                                onChange={(val) => setFilters({ ...filters, itemId: val })}
+                                placeholder="All Items"
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Trans. No</Label>
+                            <Input
+                                value={filters.transactionNumber}
+                                onChange={(e) => setFilters({ ...filters, transactionNumber: e.target.value })}
+                                placeholder="Any"
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Remarks</Label>
+                            <Input
+                                value={filters.remarks}
+                                onChange={(e) => setFilters({ ...filters, remarks: e.target.value })}
+                                placeholder="Contains..."
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Status</Label>
+                            <Select value={filters.status} onValueChange={(val) => setFilters({ ...filters, status: val })}>
+                                <SelectTrigger>
+                                    <SelectValue placeholder="All Statuses" />
+                                </SelectTrigger>
+                                <SelectContent>
+                                    <SelectItem value="all">All Statuses</SelectItem>
+                                    <SelectItem value="Posted">Posted</SelectItem>
+                                    <SelectItem value="Partially Returned">Partially Returned</SelectItem>
+                                    <SelectItem value="Returned">Returned</SelectItem>
+                                </SelectContent>
+                            </Select>
+                        </div>
+                        <div className="space-y-2">
+                            <Label>Sort By</Label>
+                            <Select value={filters.sort} onValueChange={(val) => setFilters({ ...filters, sort: val })}>
+                                <SelectTrigger>
+                                    <SelectValue />
+                                </SelectTrigger>
+                                <SelectContent>
+                                    <SelectItem value="created_at:desc">Recently Entered</SelectItem>
+                                    <SelectItem value="voucher_date:desc">Date (Newest)</SelectItem>
+                                    <SelectItem value="voucher_date:asc">Date (Oldest)</SelectItem>
+                                    <SelectItem value="transaction_number:desc">Trans. No (High-Low)</SelectItem>
+                                    <SelectItem value="transaction_number:asc">Trans. No (Low-High)</SelectItem>
+                                </SelectContent>
+                            </Select>
+                        </div>
+                        <div className="flex items-end">
+                            <Button variant="outline" onClick={() => setFilters(emptyFilters)} className="w-full">
+                                <X className="h-4 w-4 mr-2" />
+                                Clear Filters
+                            </Button>
+                        </div>
+                    </div>
+                </CardContent>
+            </Card>
+
             <Card className="flex-1 overflow-hidden flex flex-col">
                 <CardHeader>
                     <CardTitle>Recent Transactions</CardTitle>
@@ -159,13 +292,14 @@ export default function TransactionHistoryPage() {
                                 <TableHead>Source</TableHead>
                                 <TableHead>Destination</TableHead>
                                 <TableHead className="w-[200px]">Remarks</TableHead>
+                                <TableHead>Status</TableHead>
                                 <TableHead className="text-right">Actions</TableHead>
                             </TableRow>
                         </TableHeader>
                         <TableBody>
                             {transactions.length === 0 ? (
                                 <TableRow>
-                                    <TableCell colSpan={7} className="text-center h-24 text-muted-foreground">
+                                    <TableCell colSpan={8} className="text-center h-24 text-muted-foreground">
                                         No transactions found.
                                     </TableCell>
                                 </TableRow>
@@ -182,6 +316,7 @@ export default function TransactionHistoryPage() {
                                         <TableCell className="truncate max-w-[200px]" title={t.remarks || ""}>
                                             {t.remarks}
                                         </TableCell>
+                                        <TableCell className="whitespace-nowrap">{t.status}</TableCell>
                                         <TableCell className="text-right">
                                             <div className="flex justify-end gap-2">
                                                 <Button